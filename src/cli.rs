@@ -1,3 +1,4 @@
+use crate::logging::Level;
 use std::env;
 use std::path::PathBuf;
 
@@ -8,7 +9,16 @@ pub struct Args {
     pub search: Option<String>,
     pub doctor: bool,
     pub fix: bool,
+    pub lint: bool,
     pub hud: bool,
+    /// Which HUD from `hud.conf` to launch; the default one when unset.
+    pub hud_instance: Option<String>,
+    pub which_key: bool,
+    /// Submap a one-shot which-key popup is shown for.
+    pub which_key_submap: Option<String>,
+    pub launcher: bool,
+    pub verbose: bool,
+    pub log_level: Option<Level>,
 }
 
 impl Args {
@@ -27,31 +37,62 @@ impl Args {
         let mut search = None;
         let mut doctor = false;
         let mut fix = false;
+        let mut lint = false;
         let mut hud = false;
+        let mut hud_instance = None;
+        let mut which_key = false;
+        let mut which_key_submap = None;
+        let mut launcher = false;
+        let mut verbose = false;
+        let mut log_level = None;
 
-        let mut args_iter = args.into_iter().skip(1);
+        let mut args_iter = args.into_iter().skip(1).map(Into::into).peekable();
         while let Some(arg) = args_iter.next() {
-            let arg_str: String = arg.into();
+            let arg_str: String = arg;
             match arg_str.as_str() {
                 "-c" | "--config" => {
                     if let Some(path) = args_iter.next() {
-                        config = Some(PathBuf::from(path.into()));
+                        config = Some(PathBuf::from(path));
                     }
                 }
                 "-b" | "--backup-path" => {
                     if let Some(path) = args_iter.next() {
-                        backup_path = Some(PathBuf::from(path.into()));
+                        backup_path = Some(PathBuf::from(path));
                     }
                 }
                 "-p" | "--print" => print = true,
                 "-s" | "--search" => {
                     if let Some(term) = args_iter.next() {
-                        search = Some(term.into());
+                        search = Some(term);
                     }
                 }
                 "--doctor" => doctor = true,
                 "--fix" => fix = true,
+                "--lint" => lint = true,
                 "--hud" => hud = true,
+                "--hud-instance" => {
+                    if let Some(name) = args_iter.next() {
+                        hud = true;
+                        hud_instance = Some(name);
+                    }
+                }
+                "--which-key" => {
+                    which_key = true;
+                    which_key_submap = args_iter.next_if(|next| !next.starts_with('-'));
+                }
+                "--launcher" => launcher = true,
+                "--verbose" => verbose = true,
+                "--log-level" => {
+                    if let Some(level) = args_iter.next() {
+                        log_level = Level::parse(&level);
+                        if log_level.is_none() {
+                            eprintln!(
+                                "Unknown log level '{}', expected error, warn, info, debug or trace",
+                                level
+                            );
+                        }
+                    }
+                }
                 "-h" | "--help" => {
                     println!("hyprKCS - Hyprland Keybind Cheat Sheet");
                     println!("\nUsage: hyprkcs [OPTIONS]");
@@ -70,7 +111,25 @@ impl Args {
                         "  --doctor                  Check system compatibility and report issues"
                     );
                     println!("  --fix                     Attempt to automatically fix issues found by --doctor");
+                    println!(
+                        "  --lint                    Check keybinds for invalid dispatchers and arguments"
+                    );
                     println!("  --hud                     Launch the Wallpaper HUD");
+                    println!(
+                        "  --hud-instance <NAME>     Launch another HUD configured in hud.conf"
+                    );
+                    println!(
+                        "  --which-key [SUBMAP]      Show the binds of each submap as it is entered"
+                    );
+                    println!(
+                        "  --launcher                Search the binds of the current submap and run one"
+                    );
+                    println!(
+                        "  --verbose                 Log debug messages and echo the log to stderr"
+                    );
+                    println!(
+                        "  --log-level <LEVEL>       Log level: error, warn, info, debug or trace"
+                    );
                     println!("  -h, --help                Print this help message");
                     std::process::exit(0);
                 }
@@ -89,7 +148,14 @@ impl Args {
             search,
             doctor,
             fix,
+            lint,
             hud,
+            hud_instance,
+            which_key,
+            which_key_submap,
+            launcher,
+            verbose,
+            log_level,
         }
     }
 }
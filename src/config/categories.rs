@@ -0,0 +1,133 @@
+//! Keybind categories, stored as named search queries in
+//! `~/.config/hyprkcs/categories.json`.
+//!
+//! They fill the category dropdown of the main list, group the Markdown
+//! export and can be used by `category:` routing rules. The file is created
+//! with [`default_categories`] the first time it is needed.
+
+use crate::json::{self, JsonValue};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Names the main list's dropdown always uses for itself.
+pub const RESERVED_NAMES: &[&str] = &["All", "Favorites"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Category {
+    pub name: String,
+    /// A query in the search language, matched without the fuzzy fallback.
+    pub query: String,
+}
+
+impl Category {
+    fn new(name: &str, query: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            query: query.to_string(),
+        }
+    }
+}
+
+/// The categories hyprKCS ships with.
+pub fn default_categories() -> Vec<Category> {
+    vec![
+        Category::new("Workspace", "action:workspace"),
+        Category::new(
+            "Window",
+            "action:window OR action:active OR action:focus OR action:fullscreen \
+             OR action:group OR action:split OR action:pin",
+        ),
+        Category::new(
+            "Media",
+            "args:volume OR args:brightness OR args:playerctl OR action:audio",
+        ),
+        Category::new("Custom", "action:/^exec$/"),
+        Category::new("Mouse", "key:mouse"),
+    ]
+}
+
+pub fn get_categories_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| {
+        d.join(super::constants::HYPRKCS_DIR)
+            .join(super::constants::CATEGORIES_JSON)
+    })
+}
+
+/// Parses the categories file. Entries without a name or query, reserved
+/// names and repeated names are skipped; `None` if it is not valid JSON.
+pub fn parse_categories(content: &str) -> Option<Vec<Category>> {
+    let root = json::parse(content)?;
+    let mut categories: Vec<Category> = Vec::new();
+    for entry in root
+        .get("categories")
+        .and_then(JsonValue::as_array)
+        .unwrap_or_default()
+    {
+        let name = entry.str_field("name").trim().to_string();
+        let query = entry.str_field("query").trim().to_string();
+        if name.is_empty()
+            || query.is_empty()
+            || RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(&name))
+            || find_category(&categories, &name).is_some()
+        {
+            continue;
+        }
+        categories.push(Category { name, query });
+    }
+    Some(categories)
+}
+
+/// The user's categories, writing the defaults when there is no file yet.
+/// Falls back to the defaults if the file can't be read.
+pub fn load_categories() -> Vec<Category> {
+    let Some(path) = get_categories_path() else {
+        return default_categories();
+    };
+    if !path.exists() {
+        let defaults = default_categories();
+        if let Err(e) = save_categories(&defaults) {
+            crate::log_error!("config", "Failed to write {}: {}", path.display(), e);
+        }
+        return defaults;
+    }
+    match fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| parse_categories(&c))
+    {
+        Some(categories) => categories,
+        None => {
+            crate::log_error!("config", "Invalid categories file {}", path.display());
+            default_categories()
+        }
+    }
+}
+
+pub fn save_categories(categories: &[Category]) -> std::io::Result<()> {
+    let Some(path) = get_categories_path() else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let entries = categories
+        .iter()
+        .map(|c| {
+            let mut map = BTreeMap::new();
+            map.insert("name".to_string(), JsonValue::String(c.name.clone()));
+            map.insert("query".to_string(), JsonValue::String(c.query.clone()));
+            JsonValue::Object(map)
+        })
+        .collect();
+    let mut root = BTreeMap::new();
+    root.insert("categories".to_string(), JsonValue::Array(entries));
+    fs::write(path, JsonValue::Object(root).to_pretty_string())
+}
+
+/// Looks a category up by name, ignoring case.
+pub fn find_category<'a>(categories: &'a [Category], name: &str) -> Option<&'a Category> {
+    categories
+        .iter()
+        .find(|c| c.name.eq_ignore_ascii_case(name.trim()))
+}
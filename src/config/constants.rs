@@ -1,11 +1,16 @@
 pub const HYPRKCS_DIR: &str = "hyprkcs";
 pub const HYPRKCS_CONF: &str = "hyprkcs.conf";
 pub const FAVORITES_JSON: &str = "favorites.json";
+pub const SEARCHES_JSON: &str = "searches.json";
+pub const CATEGORIES_JSON: &str = "categories.json";
 pub const HUD_CONF: &str = "hud.conf";
 pub const HUD_PID: &str = "hyprkcs-hud.pid";
+pub const WHICH_KEY_PID: &str = "hyprkcs-which-key.pid";
+pub const LAYOUTS_DIR: &str = "layouts";
 
 pub const HYPR_DIR: &str = "hypr";
 pub const HYPRLAND_CONF: &str = "hyprland.conf";
 pub const BACKUP_DIR: &str = "backups";
+pub const SCRIPTS_DIR: &str = "scripts";
 
 pub const TOAST_TIMEOUT: u32 = 2;
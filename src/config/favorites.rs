@@ -1,8 +1,28 @@
+//! Per-bind metadata such as favorites, stored in
+//! `~/.config/hyprkcs/favorites.json`.
+//!
+//! Entries are keyed by a [`BindId`] built from what the bind does, so they
+//! survive the bind moving around in the config. Code that rewrites binds
+//! calls [`follow_edits`] afterwards to carry the metadata over to the new
+//! identity. Files in the old `mods|key|submap|dispatcher|args` line format
+//! are converted on load.
+
+use crate::json::{self, JsonValue};
+use crate::parser::Keybind;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct FavoriteKeybind {
+/// Version written to the `version` field of the file.
+pub const METADATA_VERSION: u32 = 2;
+
+/// Keys of an entry that make up its [`BindId`].
+const ID_FIELDS: &[&str] = &["mods", "key", "submap", "dispatcher", "args"];
+
+/// What identifies a bind: its resolved modifiers, key, submap and action.
+/// Modifiers are compared in upper case and in any order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BindId {
     pub mods: String,
     pub key: String,
     pub submap: String,
@@ -10,6 +30,243 @@ pub struct FavoriteKeybind {
     pub args: String,
 }
 
+impl BindId {
+    pub fn new(mods: &str, key: &str, submap: &str, dispatcher: &str, args: &str) -> Self {
+        let mut mod_list: Vec<String> = mods.split_whitespace().map(|m| m.to_uppercase()).collect();
+        mod_list.sort();
+        Self {
+            mods: mod_list.join(" "),
+            key: key.trim().to_string(),
+            submap: submap.trim().to_string(),
+            dispatcher: dispatcher.trim().to_string(),
+            args: args.trim().to_string(),
+        }
+    }
+
+    pub fn of(kb: &Keybind) -> Self {
+        Self::new(
+            &kb.clean_mods,
+            &kb.key,
+            kb.submap.as_deref().unwrap_or(""),
+            &kb.dispatcher,
+            &kb.args,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindMetadata {
+    pub id: BindId,
+    pub favorite: bool,
+    /// Fields this version doesn't know about, kept as they are.
+    pub extra: BTreeMap<String, JsonValue>,
+}
+
+impl BindMetadata {
+    fn new(id: BindId) -> Self {
+        Self {
+            id,
+            favorite: false,
+            extra: BTreeMap::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.favorite && self.extra.is_empty()
+    }
+
+    /// Combines two entries that ended up with the same identity.
+    fn merge(&mut self, other: BindMetadata) {
+        self.favorite |= other.favorite;
+        for (key, value) in other.extra {
+            self.extra.entry(key).or_insert(value);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetadataStore {
+    pub entries: Vec<BindMetadata>,
+}
+
+impl MetadataStore {
+    pub fn get(&self, id: &BindId) -> Option<&BindMetadata> {
+        self.entries.iter().find(|e| e.id == *id)
+    }
+
+    fn entry(&mut self, id: &BindId) -> &mut BindMetadata {
+        let index = match self.entries.iter().position(|e| e.id == *id) {
+            Some(index) => index,
+            None => {
+                self.entries.push(BindMetadata::new(id.clone()));
+                self.entries.len() - 1
+            }
+        };
+        &mut self.entries[index]
+    }
+
+    pub fn is_favorite(&self, id: &BindId) -> bool {
+        self.get(id).is_some_and(|e| e.favorite)
+    }
+
+    /// Flips the favorite state of `id` and returns the new state.
+    pub fn toggle_favorite(&mut self, id: &BindId) -> bool {
+        let entry = self.entry(id);
+        entry.favorite = !entry.favorite;
+        let state = entry.favorite;
+        self.prune();
+        state
+    }
+
+    /// Moves the metadata of `old` to `new`. Returns whether anything moved.
+    pub fn migrate(&mut self, old: &BindId, new: &BindId) -> bool {
+        if old == new {
+            return false;
+        }
+        let Some(index) = self.entries.iter().position(|e| e.id == *old) else {
+            return false;
+        };
+        let mut moved = self.entries.remove(index);
+        moved.id = new.clone();
+        self.entry(new).merge(moved);
+        true
+    }
+
+    /// Gives `to` a copy of the metadata of `from`, e.g. for a cloned bind.
+    pub fn copy(&mut self, from: &BindId, to: &BindId) -> bool {
+        if from == to {
+            return false;
+        }
+        let Some(mut copied) = self.get(from).cloned() else {
+            return false;
+        };
+        copied.id = to.clone();
+        self.entry(to).merge(copied);
+        true
+    }
+
+    pub fn remove(&mut self, id: &BindId) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.id != *id);
+        self.entries.len() != before
+    }
+
+    fn prune(&mut self) {
+        self.entries.retain(|e| !e.is_empty());
+    }
+
+    pub fn to_json(&self) -> String {
+        let binds = self
+            .entries
+            .iter()
+            .filter(|e| !e.is_empty())
+            .map(|e| {
+                let mut map = e.extra.clone();
+                for (key, value) in ID_FIELDS.iter().zip([
+                    &e.id.mods,
+                    &e.id.key,
+                    &e.id.submap,
+                    &e.id.dispatcher,
+                    &e.id.args,
+                ]) {
+                    map.insert(key.to_string(), JsonValue::String(value.clone()));
+                }
+                if e.favorite {
+                    map.insert("favorite".to_string(), JsonValue::Bool(true));
+                }
+                JsonValue::Object(map)
+            })
+            .collect();
+        let mut root = BTreeMap::new();
+        root.insert(
+            "version".to_string(),
+            JsonValue::Number(METADATA_VERSION as f64),
+        );
+        root.insert("binds".to_string(), JsonValue::Array(binds));
+        JsonValue::Object(root).to_pretty_string()
+    }
+}
+
+/// Reads the `mods|key|submap|dispatcher|args` lines of the first format,
+/// where every line was a favorite.
+fn parse_legacy(content: &str) -> MetadataStore {
+    let mut store = MetadataStore::default();
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.len() < 5 {
+            continue;
+        }
+        // Arguments may contain '|' themselves
+        let id = BindId::new(
+            parts[0],
+            parts[1],
+            parts[2],
+            parts[3],
+            &parts[4..].join("|"),
+        );
+        store.entry(&id).favorite = true;
+    }
+    store
+}
+
+/// Parses the metadata file. The second value is true when the content was
+/// in the old line format and should be written back as JSON.
+pub fn parse_metadata(content: &str) -> (MetadataStore, bool) {
+    let Some(root) = json::parse(content) else {
+        let store = parse_legacy(content);
+        let converted = !store.entries.is_empty();
+        return (store, converted);
+    };
+
+    let version = root
+        .get("version")
+        .and_then(JsonValue::as_f64)
+        .unwrap_or(0.0);
+    if version > METADATA_VERSION as f64 {
+        crate::log_warn!(
+            "config",
+            "favorites.json has version {}, newer than {}",
+            version,
+            METADATA_VERSION
+        );
+    }
+
+    let mut store = MetadataStore::default();
+    for entry in root
+        .get("binds")
+        .and_then(JsonValue::as_array)
+        .unwrap_or_default()
+    {
+        let Some(fields) = entry.as_object() else {
+            continue;
+        };
+        let id = BindId::new(
+            &entry.str_field("mods"),
+            &entry.str_field("key"),
+            &entry.str_field("submap"),
+            &entry.str_field("dispatcher"),
+            &entry.str_field("args"),
+        );
+        if id.key.is_empty() && id.dispatcher.is_empty() {
+            continue;
+        }
+        let metadata = BindMetadata {
+            id: id.clone(),
+            favorite: entry
+                .get("favorite")
+                .and_then(JsonValue::as_bool)
+                .unwrap_or(false),
+            extra: fields
+                .iter()
+                .filter(|(k, _)| !ID_FIELDS.contains(&k.as_str()) && *k != "favorite")
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        };
+        store.entry(&id).merge(metadata);
+    }
+    (store, false)
+}
+
 pub fn get_favorites_path() -> Option<PathBuf> {
     dirs::config_dir().map(|d: PathBuf| {
         d.join(super::constants::HYPRKCS_DIR)
@@ -17,78 +274,99 @@ pub fn get_favorites_path() -> Option<PathBuf> {
     })
 }
 
-pub fn load_favorites() -> Vec<FavoriteKeybind> {
-    if let Some(path) = get_favorites_path() {
-        if path.exists() {
-            if let Ok(content) = fs::read_to_string(path) {
-                // Try simple manual line-based parsing
-                // Format: mods|key|submap|dispatcher|args
-                return content
-                    .lines()
-                    .filter_map(|line| {
-                        let parts: Vec<&str> = line.split('|').collect();
-                        if parts.len() >= 5 {
-                            Some(FavoriteKeybind {
-                                mods: parts[0].to_string(),
-                                key: parts[1].to_string(),
-                                submap: parts[2].to_string(),
-                                dispatcher: parts[3].to_string(),
-                                args: parts[4..].join("|"), // handle | in args just in case
-                            })
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-            }
+/// Loads the metadata, converting a file in the old format to JSON.
+pub fn load_metadata() -> MetadataStore {
+    let Some(path) = get_favorites_path() else {
+        return MetadataStore::default();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return MetadataStore::default();
+    };
+    let (store, converted) = parse_metadata(&content);
+    if converted {
+        match save_metadata(&store) {
+            Ok(()) => crate::log_info!(
+                "config",
+                "Converted {} favorites to the JSON format",
+                store.entries.len()
+            ),
+            Err(e) => crate::log_error!("config", "Failed to convert favorites: {}", e),
         }
     }
-    Vec::new()
+    store
 }
 
-pub fn save_favorites(favorites: &[FavoriteKeybind]) -> std::io::Result<()> {
+pub fn save_metadata(store: &MetadataStore) -> std::io::Result<()> {
     if let Some(path) = get_favorites_path() {
         if let Some(parent) = path.parent() {
             if !parent.exists() {
                 fs::create_dir_all(parent)?;
             }
         }
-        let mut content = String::new();
-        for f in favorites {
-            content.push_str(&format!(
-                "{}|{}|{}|{}|{}
-",
-                f.mods, f.key, f.submap, f.dispatcher, f.args
-            ));
-        }
-        fs::write(path, content)?;
+        fs::write(path, store.to_json())?;
     }
     Ok(())
 }
 
-pub fn is_favorite(
-    favs: &[FavoriteKeybind],
-    mods: &str,
-    key: &str,
-    submap: &str,
-    dispatcher: &str,
-    args: &str,
-) -> bool {
-    favs.iter().any(|f| {
-        f.mods == mods
-            && f.key == key
-            && f.submap == submap
-            && f.dispatcher == dispatcher
-            && f.args == args
-    })
+/// How a bind was changed, for [`follow_edits`].
+#[derive(Debug, Clone)]
+pub enum BindEdit {
+    /// The bind that was `old` is now the one at `file`:`line` (0-based).
+    Moved {
+        old: BindId,
+        file: PathBuf,
+        line: usize,
+    },
+    /// The bind at `file`:`line` is a copy of `source`.
+    Copied {
+        source: BindId,
+        file: PathBuf,
+        line: usize,
+    },
+    /// The bind was deleted.
+    Deleted(BindId),
 }
 
-pub fn toggle_favorite(favs: &mut Vec<FavoriteKeybind>, item: FavoriteKeybind) -> bool {
-    if let Some(pos) = favs.iter().position(|f| *f == item) {
-        favs.remove(pos);
-        false
-    } else {
-        favs.push(item);
-        true
+/// Carries metadata over after binds were written, looking up what is now
+/// at each edited location. Metadata is only moved away from an identity
+/// when no other bind still has it.
+pub fn follow_edits(edits: &[BindEdit]) {
+    let mut store = load_metadata();
+    if store.entries.is_empty() || edits.is_empty() {
+        return;
+    }
+    let keybinds = match crate::parser::parse_config() {
+        Ok(keybinds) => keybinds,
+        Err(e) => {
+            crate::log_error!("config", "Failed to update favorites: {}", e);
+            return;
+        }
+    };
+    let at = |file: &Path, line: usize| {
+        keybinds
+            .iter()
+            .find(|kb| kb.line_number == line && kb.file_path == file)
+            .map(BindId::of)
+    };
+    let still_used = |id: &BindId| keybinds.iter().any(|kb| BindId::of(kb) == *id);
+
+    let mut changed = false;
+    for edit in edits {
+        changed |= match edit {
+            BindEdit::Moved { old, file, line } => match at(file, *line) {
+                Some(new) if still_used(old) => store.copy(old, &new),
+                Some(new) => store.migrate(old, &new),
+                None => false,
+            },
+            BindEdit::Copied { source, file, line } => {
+                at(file, *line).is_some_and(|new| store.copy(source, &new))
+            }
+            BindEdit::Deleted(id) => !still_used(id) && store.remove(id),
+        };
+    }
+    if changed {
+        if let Err(e) = save_metadata(&store) {
+            crate::log_error!("config", "Failed to save favorites: {}", e);
+        }
     }
 }
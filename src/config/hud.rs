@@ -58,10 +58,82 @@ impl HudPosition {
     }
 }
 
+/// What a HUD shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HudContent {
+    /// The hand-picked `keybinds`.
+    #[default]
+    Manual,
+    Favorites,
+    /// Binds matching `query`.
+    Search,
+    /// Binds of the submap that is currently active, hidden outside one.
+    Submap,
+    /// Binds run most often from hyprKCS, from the execution log.
+    MostUsed,
+}
+
+impl FromStr for HudContent {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "manual" => Ok(Self::Manual),
+            "favorites" => Ok(Self::Favorites),
+            "search" => Ok(Self::Search),
+            "submap" => Ok(Self::Submap),
+            "most-used" => Ok(Self::MostUsed),
+            _ => Err(()),
+        }
+    }
+}
+
+impl HudContent {
+    pub const ALL: [HudContent; 5] = [
+        Self::Manual,
+        Self::Favorites,
+        Self::Search,
+        Self::Submap,
+        Self::MostUsed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Favorites => "favorites",
+            Self::Search => "search",
+            Self::Submap => "submap",
+            Self::MostUsed => "most-used",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Manual => "Selected Keybinds",
+            Self::Favorites => "Favorites",
+            Self::Search => "Search Query",
+            Self::Submap => "Active Submap",
+            Self::MostUsed => "Most Used",
+        }
+    }
+}
+
+/// Name of the HUD configured at the top of `hud.conf`.
+pub const DEFAULT_HUD: &str = "default";
+
 #[derive(Debug, Clone)]
 pub struct HudConfig {
+    /// [`DEFAULT_HUD`], or the `[name]` of the section it was read from.
+    pub name: String,
     pub enabled: bool,
     pub position: HudPosition,
+    /// Connector name like `DP-1`; the compositor picks one when unset.
+    pub monitor: Option<String>,
+    pub content: HudContent,
+    /// Search query for [`HudContent::Search`].
+    pub query: String,
+    /// Most binds shown by the dynamic content modes, 0 for no limit.
+    pub limit: usize,
     pub keybinds: Vec<HudKeybind>,
     pub opacity: f64,
     pub border_radius: i32,
@@ -71,8 +143,13 @@ pub struct HudConfig {
 impl Default for HudConfig {
     fn default() -> Self {
         Self {
+            name: DEFAULT_HUD.to_string(),
             enabled: false,
             position: HudPosition::TopRight,
+            monitor: None,
+            content: HudContent::Manual,
+            query: String::new(),
+            limit: 10,
             keybinds: Vec::new(),
             opacity: 0.8,
             border_radius: 12,
@@ -81,6 +158,89 @@ impl Default for HudConfig {
     }
 }
 
+impl HudConfig {
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn apply_line(&mut self, line: &str) {
+        if let Some(value) = line.strip_prefix("enabled=") {
+            self.enabled = value == "true";
+        } else if let Some(value) = line.strip_prefix("position=") {
+            self.position = HudPosition::from_str(value).unwrap_or_default();
+        } else if let Some(value) = line.strip_prefix("monitor=") {
+            self.monitor = Some(value.trim().to_string()).filter(|m| !m.is_empty());
+        } else if let Some(value) = line.strip_prefix("content=") {
+            self.content = HudContent::from_str(value).unwrap_or_default();
+        } else if let Some(value) = line.strip_prefix("query=") {
+            self.query = value.to_string();
+        } else if let Some(value) = line.strip_prefix("limit=") {
+            self.limit = value.parse().unwrap_or(10);
+        } else if let Some(value) = line.strip_prefix("opacity=") {
+            self.opacity = value.parse().unwrap_or(0.8);
+        } else if let Some(value) = line.strip_prefix("border_radius=") {
+            self.border_radius = value.parse().unwrap_or(12);
+        } else if let Some(value) = line.strip_prefix("font_size=") {
+            self.font_size = value.parse().unwrap_or(14);
+        } else if line.contains('|') {
+            let mut parts = line.splitn(4, '|');
+            if let (Some(mods), Some(key), Some(disp), Some(args)) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            {
+                self.keybinds.push(HudKeybind::new(mods, key, disp, args));
+            }
+        }
+    }
+
+    fn write_to(&self, content: &mut String) {
+        content.push_str("enabled=");
+        content.push_str(if self.enabled { "true" } else { "false" });
+        content.push('\n');
+        content.push_str("position=");
+        content.push_str(self.position.as_str());
+        content.push('\n');
+        if let Some(monitor) = &self.monitor {
+            content.push_str("monitor=");
+            content.push_str(monitor);
+            content.push('\n');
+        }
+        content.push_str("content=");
+        content.push_str(self.content.as_str());
+        content.push('\n');
+        if !self.query.is_empty() {
+            content.push_str("query=");
+            content.push_str(&self.query);
+            content.push('\n');
+        }
+        content.push_str("limit=");
+        content.push_str(&self.limit.to_string());
+        content.push('\n');
+        content.push_str("opacity=");
+        content.push_str(&self.opacity.to_string());
+        content.push('\n');
+        content.push_str("border_radius=");
+        content.push_str(&self.border_radius.to_string());
+        content.push('\n');
+        content.push_str("font_size=");
+        content.push_str(&self.font_size.to_string());
+        content.push('\n');
+
+        for k in &self.keybinds {
+            content.push_str(&k.mods);
+            content.push('|');
+            content.push_str(&k.key);
+            content.push('|');
+            content.push_str(&k.dispatcher);
+            content.push('|');
+            content.push_str(&k.args);
+            content.push('\n');
+        }
+    }
+}
+
 static HUD_CONFIG_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
 
 #[inline]
@@ -95,16 +255,10 @@ pub fn get_hud_config_path() -> Option<&'static PathBuf> {
         .as_ref()
 }
 
-pub fn load_hud_config() -> HudConfig {
-    let Some(path) = get_hud_config_path() else {
-        return HudConfig::default();
-    };
-
-    let Ok(content) = fs::read_to_string(path) else {
-        return HudConfig::default();
-    };
-
-    let mut config = HudConfig::default();
+/// Parses `hud.conf`. The lines before the first `[name]` header configure
+/// the default HUD, which is always first; each header starts another one.
+pub fn parse_hud_configs(content: &str) -> Vec<HudConfig> {
+    let mut configs = vec![HudConfig::default()];
 
     for line in content.lines() {
         let line = line.trim();
@@ -112,29 +266,56 @@ pub fn load_hud_config() -> HudConfig {
             continue;
         }
 
-        if let Some(value) = line.strip_prefix("enabled=") {
-            config.enabled = value == "true";
-        } else if let Some(value) = line.strip_prefix("position=") {
-            config.position = HudPosition::from_str(value).unwrap_or_default();
-        } else if let Some(value) = line.strip_prefix("opacity=") {
-            config.opacity = value.parse().unwrap_or(0.8);
-        } else if let Some(value) = line.strip_prefix("border_radius=") {
-            config.border_radius = value.parse().unwrap_or(12);
-        } else if let Some(value) = line.strip_prefix("font_size=") {
-            config.font_size = value.parse().unwrap_or(14);
-        } else if line.contains('|') {
-            let mut parts = line.splitn(4, '|');
-            if let (Some(mods), Some(key), Some(disp), Some(args)) =
-                (parts.next(), parts.next(), parts.next(), parts.next())
-            {
-                config.keybinds.push(HudKeybind::new(mods, key, disp, args));
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let name = name.trim();
+            if !name.is_empty() && name != DEFAULT_HUD {
+                configs.push(HudConfig::named(name));
             }
+            continue;
+        }
+        if let Some(config) = configs.last_mut() {
+            config.apply_line(line);
         }
     }
-    config
+    configs
 }
 
-pub fn save_hud_config(config: &HudConfig) -> std::io::Result<()> {
+pub fn format_hud_configs(configs: &[HudConfig]) -> String {
+    let mut content = String::new();
+    let default = configs
+        .iter()
+        .find(|c| c.name == DEFAULT_HUD)
+        .cloned()
+        .unwrap_or_default();
+    default.write_to(&mut content);
+
+    for config in configs.iter().filter(|c| c.name != DEFAULT_HUD) {
+        content.push_str("\n[");
+        content.push_str(&config.name);
+        content.push_str("]\n");
+        config.write_to(&mut content);
+    }
+    content
+}
+
+/// Every configured HUD, the default one first.
+pub fn load_hud_configs() -> Vec<HudConfig> {
+    get_hud_config_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|content| parse_hud_configs(&content))
+        .unwrap_or_else(|| vec![HudConfig::default()])
+}
+
+/// The default HUD.
+pub fn load_hud_config() -> HudConfig {
+    load_hud_configs().swap_remove(0)
+}
+
+pub fn load_hud_instance(name: &str) -> Option<HudConfig> {
+    load_hud_configs().into_iter().find(|c| c.name == name)
+}
+
+pub fn save_hud_configs(configs: &[HudConfig]) -> std::io::Result<()> {
     let Some(path) = get_hud_config_path() else {
         return Ok(());
     };
@@ -145,57 +326,73 @@ pub fn save_hud_config(config: &HudConfig) -> std::io::Result<()> {
         }
     }
 
-    // Pre-calculate capacity: "enabled=true\n" + keybinds
-    let capacity = 16
-        + config
-            .keybinds
-            .iter()
-            .map(|k| k.mods.len() + k.key.len() + k.dispatcher.len() + k.args.len() + 4)
-            .sum::<usize>();
-
-    let mut content = String::with_capacity(capacity);
-    content.push_str("enabled=");
-    content.push_str(if config.enabled { "true" } else { "false" });
-    content.push('\n');
-    content.push_str("position=");
-    content.push_str(config.position.as_str());
-    content.push('\n');
-    content.push_str("opacity=");
-    content.push_str(&config.opacity.to_string());
-    content.push('\n');
-    content.push_str("border_radius=");
-    content.push_str(&config.border_radius.to_string());
-    content.push('\n');
-    content.push_str("font_size=");
-    content.push_str(&config.font_size.to_string());
-    content.push('\n');
-
-    for k in &config.keybinds {
-        content.push_str(&k.mods);
-        content.push('|');
-        content.push_str(&k.key);
-        content.push('|');
-        content.push_str(&k.dispatcher);
-        content.push('|');
-        content.push_str(&k.args);
-        content.push('\n');
-    }
-
     let mut tmp_path = path.clone();
     tmp_path.set_extension("tmp");
-    fs::write(&tmp_path, content)?;
+    fs::write(&tmp_path, format_hud_configs(configs))?;
     fs::rename(tmp_path, path)
 }
 
+/// Saves one HUD, keeping the others in the file as they are.
+pub fn save_hud_config(config: &HudConfig) -> std::io::Result<()> {
+    let mut configs = load_hud_configs();
+    match configs.iter_mut().find(|c| c.name == config.name) {
+        Some(existing) => *existing = config.clone(),
+        None => configs.push(config.clone()),
+    }
+    save_hud_configs(&configs)
+}
+
+pub fn remove_hud_instance(name: &str) -> std::io::Result<()> {
+    let mut configs = load_hud_configs();
+    configs.retain(|c| c.name != name || c.name == DEFAULT_HUD);
+    save_hud_configs(&configs)
+}
+
 pub fn get_hud_pid_path() -> Option<PathBuf> {
+    get_hud_instance_pid_path(DEFAULT_HUD)
+}
+
+/// Every HUD process writes its own PID file, so several can run at once.
+pub fn get_hud_instance_pid_path(name: &str) -> Option<PathBuf> {
+    let file = if name == DEFAULT_HUD {
+        super::constants::HUD_PID.to_string()
+    } else {
+        let stem = super::constants::HUD_PID.trim_end_matches(".pid");
+        let safe: String = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("{}-{}.pid", stem, safe)
+    };
+    runtime_path(&file)
+}
+
+/// PID file of the `--which-key` overlay.
+pub fn get_which_key_pid_path() -> Option<PathBuf> {
+    runtime_path(super::constants::WHICH_KEY_PID)
+}
+
+fn runtime_path(file: &str) -> Option<PathBuf> {
     std::env::var_os("XDG_RUNTIME_DIR")
         .map(PathBuf::from)
         .or_else(|| dirs::config_dir().map(|d| d.join(super::constants::HYPRKCS_DIR)))
-        .map(|d| d.join(super::constants::HUD_PID))
+        .map(|d| d.join(file))
 }
 
 pub fn is_hud_running() -> bool {
-    if let Some(pid_path) = get_hud_pid_path() {
+    is_hud_instance_running(DEFAULT_HUD)
+}
+
+pub fn is_hud_instance_running(name: &str) -> bool {
+    is_pid_file_alive(get_hud_instance_pid_path(name))
+}
+
+pub fn is_which_key_running() -> bool {
+    is_pid_file_alive(get_which_key_pid_path())
+}
+
+fn is_pid_file_alive(pid_path: Option<PathBuf>) -> bool {
+    if let Some(pid_path) = pid_path {
         if let Ok(pid_str) = fs::read_to_string(&pid_path) {
             if let Ok(pid) = pid_str.trim().parse::<i32>() {
                 // Check if process exists (signal 0)
@@ -1,6 +1,9 @@
+pub mod categories;
 pub mod constants;
 pub mod favorites;
 pub mod hud;
+pub mod routing;
+pub mod searches;
 
 use std::collections::HashMap;
 use std::fs;
@@ -37,6 +40,8 @@ pub struct StyleConfig {
     pub alternative_config_path: Option<String>,
     pub alternative_backup_path: Option<String>,
     pub default_submap: Option<String>,
+    /// Which file new binds are written to, see [`routing`].
+    pub bind_routes: Vec<routing::RouteRule>,
 
     pub errors: Vec<String>,
 }
@@ -70,6 +75,7 @@ impl Default for StyleConfig {
             alternative_config_path: None,
             alternative_backup_path: None,
             default_submap: None,
+            bind_routes: Vec::new(),
 
             errors: Vec::new(),
         }
@@ -139,13 +145,14 @@ maxBackupsCount = 10
 alternativeConfigPath = 
 alternativeBackupPath = 
 defaultSubmap = 
+bindRoutes = 
 
 # Spacing
 monitorMargin = 12px
 rowPadding = 2px
 "#;
             if let Err(e) = fs::write(&config_path, default_content) {
-                eprintln!("Failed to write default config: {}", e);
+                crate::log_error!("config", "Failed to write default config: {}", e);
             }
         }
 
@@ -346,6 +353,15 @@ rowPadding = 2px
                     config.default_submap = Some(val.clone());
                 }
             }
+            if let Some(val) = vars.get("bindRoutes") {
+                let (rules, invalid) = routing::parse_rules(val);
+                config.bind_routes = rules;
+                for rule in invalid {
+                    config
+                        .errors
+                        .push(format!("Invalid bind route '{}'. Ignoring it.", rule));
+                }
+            }
         }
 
         if let Ok(mut cache) = CONFIG_CACHE.lock() {
@@ -396,6 +412,7 @@ maxBackupsCount = {}
 alternativeConfigPath = {}
 alternativeBackupPath = {}
 defaultSubmap = {}
+bindRoutes = {}
 
 # Spacing
 monitorMargin = {}px
@@ -424,6 +441,7 @@ rowPadding = {}px
                 self.alternative_config_path.as_deref().unwrap_or(""),
                 self.alternative_backup_path.as_deref().unwrap_or(""),
                 self.default_submap.as_deref().unwrap_or(""),
+                routing::format_rules(&self.bind_routes),
                 self.monitor_margin,
                 self.row_padding
             );
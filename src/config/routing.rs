@@ -0,0 +1,185 @@
+//! Rules deciding which config file new keybinds are written to.
+//!
+//! Stored in `hyprkcs.conf` as one `bindRoutes` line, for example
+//! `bindRoutes = dispatcher:exec -> apps.conf; submap:* -> submaps/{submap}.conf`.
+//! The first matching rule wins; binds no rule matches go to the file the
+//! caller would have used anyway.
+
+use super::categories::{find_category, Category};
+use crate::parser::Keybind;
+use crate::ui::utils::search::{KeybindSearch, SearchQuery};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteMatch {
+    /// Binds using this dispatcher, e.g. `exec`.
+    Dispatcher(String),
+    /// Binds inside this submap, or inside any submap for `*`.
+    Submap(String),
+    /// Binds in the category with this name, see [`super::categories`].
+    Category(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteRule {
+    pub matcher: RouteMatch,
+    /// Destination, relative to the Hyprland config directory unless
+    /// absolute. `{submap}` is replaced with the bind's submap.
+    pub file: String,
+}
+
+impl RouteRule {
+    /// Parses `<kind>:<value> -> <file>`.
+    pub fn parse(rule: &str) -> Option<RouteRule> {
+        let (matcher, file) = rule.split_once("->")?;
+        let (kind, value) = matcher.trim().split_once(':')?;
+        let value = value.trim().to_string();
+        let file = file.trim().to_string();
+        if value.is_empty() || file.is_empty() {
+            return None;
+        }
+
+        let matcher = match kind.trim().to_lowercase().as_str() {
+            "dispatcher" | "action" => RouteMatch::Dispatcher(value),
+            "submap" => RouteMatch::Submap(value),
+            "category" => RouteMatch::Category(value.to_lowercase()),
+            _ => return None,
+        };
+        Some(RouteRule { matcher, file })
+    }
+
+    pub fn matches(
+        &self,
+        categories: &[Category],
+        dispatcher: &str,
+        args: &str,
+        key: &str,
+        submap: Option<&str>,
+    ) -> bool {
+        let submap = submap.filter(|s| !s.is_empty() && *s != "reset");
+        match &self.matcher {
+            RouteMatch::Dispatcher(d) => dispatcher.trim().eq_ignore_ascii_case(d),
+            RouteMatch::Submap(s) if s == "*" => submap.is_some(),
+            RouteMatch::Submap(s) => submap == Some(s.as_str()),
+            RouteMatch::Category(c) => {
+                let Some(category) = find_category(categories, c) else {
+                    return false;
+                };
+                let keybind = Keybind {
+                    mods: "".into(),
+                    clean_mods: "".into(),
+                    flags: "".into(),
+                    key: key.trim().into(),
+                    dispatcher: dispatcher.trim().into(),
+                    args: args.trim().into(),
+                    description: None,
+                    submap: submap.map(Into::into),
+                    line_number: 0,
+                    file_path: PathBuf::new(),
+                };
+                SearchQuery::parse(&category.query).matches_exact(&KeybindSearch {
+                    keybind: &keybind,
+                    conflicted: false,
+                    broken: false,
+                    favorite: false,
+                })
+            }
+        }
+    }
+
+    /// Where a matching bind goes, with `{submap}` filled in.
+    pub fn destination(&self, config_dir: &Path, submap: Option<&str>) -> PathBuf {
+        let file = self.file.replace("{submap}", submap.unwrap_or("global"));
+        if let Some(rest) = file.strip_prefix("~/") {
+            if let Some(home) = dirs::home_dir() {
+                return home.join(rest);
+            }
+        }
+        let path = PathBuf::from(&file);
+        if path.is_absolute() {
+            path
+        } else {
+            config_dir.join(path)
+        }
+    }
+}
+
+impl std::fmt::Display for RouteRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (kind, value) = match &self.matcher {
+            RouteMatch::Dispatcher(v) => ("dispatcher", v),
+            RouteMatch::Submap(v) => ("submap", v),
+            RouteMatch::Category(v) => ("category", v),
+        };
+        write!(f, "{}:{} -> {}", kind, value, self.file)
+    }
+}
+
+/// Parses a `bindRoutes` value, returning the rules and the parts that
+/// could not be parsed.
+pub fn parse_rules(value: &str) -> (Vec<RouteRule>, Vec<String>) {
+    let mut rules = Vec::new();
+    let mut invalid = Vec::new();
+    for part in value.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        match RouteRule::parse(part) {
+            Some(rule) => rules.push(rule),
+            None => invalid.push(part.to_string()),
+        }
+    }
+    (rules, invalid)
+}
+
+pub fn format_rules(rules: &[RouteRule]) -> String {
+    rules
+        .iter()
+        .map(|r| r.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The destination of the first rule matching the bind, if any.
+/// `categories` are the ones `category:` rules are looked up in.
+pub fn route(
+    rules: &[RouteRule],
+    categories: &[Category],
+    config_dir: &Path,
+    dispatcher: &str,
+    args: &str,
+    key: &str,
+    submap: Option<&str>,
+) -> Option<PathBuf> {
+    rules
+        .iter()
+        .find(|r| r.matches(categories, dispatcher, args, key, submap))
+        .map(|r| r.destination(config_dir, submap))
+}
+
+/// Where a new bind should be written according to the rules in
+/// `hyprkcs.conf`, or `fallback` when none matches.
+pub fn destination_for(
+    dispatcher: &str,
+    args: &str,
+    key: &str,
+    submap: Option<&str>,
+    fallback: PathBuf,
+) -> PathBuf {
+    let rules = super::StyleConfig::load().bind_routes;
+    if rules.is_empty() {
+        return fallback;
+    }
+    let Ok(main_path) = crate::parser::get_config_path() else {
+        return fallback;
+    };
+    let config_dir = main_path.parent().unwrap_or(Path::new("."));
+    let categories = super::categories::load_categories();
+    route(
+        &rules,
+        &categories,
+        config_dir,
+        dispatcher,
+        args,
+        key,
+        submap,
+    )
+    .unwrap_or(fallback)
+}
@@ -0,0 +1,79 @@
+//! Named search queries, stored in `~/.config/hyprkcs/searches.json`.
+
+use crate::json::{self, JsonValue};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    /// Shown in the category dropdown of the main list.
+    pub pinned: bool,
+}
+
+pub fn get_searches_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| {
+        d.join(super::constants::HYPRKCS_DIR)
+            .join(super::constants::SEARCHES_JSON)
+    })
+}
+
+pub fn parse_saved_searches(content: &str) -> Vec<SavedSearch> {
+    let Some(root) = json::parse(content) else {
+        return Vec::new();
+    };
+    root.get("searches")
+        .and_then(JsonValue::as_array)
+        .unwrap_or_default()
+        .iter()
+        .map(|entry| SavedSearch {
+            name: entry.str_field("name"),
+            query: entry.str_field("query"),
+            pinned: entry
+                .get("pinned")
+                .and_then(JsonValue::as_bool)
+                .unwrap_or(false),
+        })
+        .filter(|s| !s.name.is_empty())
+        .collect()
+}
+
+pub fn load_saved_searches() -> Vec<SavedSearch> {
+    get_searches_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|content| parse_saved_searches(&content))
+        .unwrap_or_default()
+}
+
+pub fn save_saved_searches(searches: &[SavedSearch]) -> std::io::Result<()> {
+    let Some(path) = get_searches_path() else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let entries = searches
+        .iter()
+        .map(|s| {
+            let mut map = BTreeMap::new();
+            map.insert("name".to_string(), JsonValue::String(s.name.clone()));
+            map.insert("query".to_string(), JsonValue::String(s.query.clone()));
+            map.insert("pinned".to_string(), JsonValue::Bool(s.pinned));
+            JsonValue::Object(map)
+        })
+        .collect();
+    let mut root = BTreeMap::new();
+    root.insert("searches".to_string(), JsonValue::Array(entries));
+    fs::write(path, JsonValue::Object(root).to_pretty_string())
+}
+
+/// Adds `search`, replacing a saved search with the same name.
+pub fn upsert_saved_search(searches: &mut Vec<SavedSearch>, search: SavedSearch) {
+    match searches.iter_mut().find(|s| s.name == search.name) {
+        Some(existing) => *existing = search,
+        None => searches.push(search),
+    }
+}
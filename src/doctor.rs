@@ -1,4 +1,4 @@
-use crate::parser::{get_config_path, parse_config};
+use crate::parser::{get_config_path, parse_config_with_diagnostics};
 use gtk::glib;
 use gtk4 as gtk;
 use libadwaita as adw;
@@ -130,13 +130,25 @@ pub fn run_doctor(fix: bool) {
                     Err(e) => println!("{} Permissions: Check failed ({})", warn, e),
                 }
 
-                match parse_config() {
-                    Ok(binds) => {
+                match parse_config_with_diagnostics() {
+                    Ok((binds, diagnostics)) => {
                         println!(
                             "{} Parser: Successfully parsed {} keybinds",
                             pass,
                             binds.len()
                         );
+                        if diagnostics.is_empty() {
+                            println!("{} Parser: No malformed lines", pass);
+                        } else {
+                            println!(
+                                "{} Parser: {} problem(s) in config",
+                                warn,
+                                diagnostics.len()
+                            );
+                            for diagnostic in &diagnostics {
+                                println!("     {}", diagnostic);
+                            }
+                        }
                     }
                     Err(e) => println!("{} Parser: Failed to parse config ({})", fail, e),
                 }
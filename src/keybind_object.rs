@@ -1,9 +1,11 @@
+use crate::config::favorites::BindId;
 use crate::parser::Keybind;
-use crate::ui::utils::SearchQuery;
+use crate::ui::utils::search::{Field, SearchQuery, SearchTarget};
 use fuzzy_matcher::FuzzyMatcher;
 use glib::subclass::prelude::*;
 use gtk::glib;
 use gtk4 as gtk;
+use std::borrow::Cow;
 use std::sync::Arc;
 
 glib::wrapper! {
@@ -81,6 +83,13 @@ impl KeybindObject {
         obj
     }
 
+    /// Links the bind to the application its exec command launches.
+    pub fn set_app(&self, name: Option<Arc<str>>, icon: Option<Arc<str>>) {
+        let mut data = self.imp().data.borrow_mut();
+        data.app_name = name;
+        data.app_icon = icon;
+    }
+
     pub fn with_data<F, R>(&self, f: F) -> R
     where
         F: FnOnce(&imp::KeybindData) -> R,
@@ -89,119 +98,71 @@ impl KeybindObject {
         f(&data)
     }
 
+    /// Reconstructs the parser-level keybind this object was created from.
+    pub fn to_keybind(&self) -> Keybind {
+        self.with_data(|d| Keybind {
+            mods: d.mods.clone(),
+            clean_mods: d.clean_mods.clone(),
+            flags: d.flags.clone(),
+            key: d.key.clone(),
+            dispatcher: d.dispatcher.clone(),
+            args: d.args.clone().unwrap_or_else(|| "".into()),
+            description: d.description.clone(),
+            submap: d.submap.clone(),
+            line_number: d.line_number as usize,
+            file_path: std::path::PathBuf::from(d.file_path.as_ref()),
+        })
+    }
+
+    /// The identity favorites and other metadata are stored under.
+    pub fn bind_id(&self) -> BindId {
+        self.with_data(|d| {
+            BindId::new(
+                &d.clean_mods,
+                &d.key,
+                d.submap.as_deref().unwrap_or(""),
+                &d.dispatcher,
+                d.args.as_deref().unwrap_or(""),
+            )
+        })
+    }
+
+    /// Whether the bind is in `category` (matched exactly, see
+    /// [`SearchQuery::matches_exact`]) and matches the search `query`.
     pub fn matches_query(
         &self,
         query: &SearchQuery,
-        category: u32,
+        category: &SearchQuery,
         matcher: &impl FuzzyMatcher,
     ) -> bool {
         let data = self.imp().data.borrow();
+        category.matches_exact(&*data) && query.matches(&*data, matcher)
+    }
+}
 
-        let dispatcher_lower = &data.dispatcher_lower;
-        let key_lower = &data.key_lower;
-
-        let category_match = match category {
-            0 => true,
-            1 => {
-                dispatcher_lower.contains("workspace")
-                    || dispatcher_lower.contains("movetoworkspace")
-            }
-            2 => {
-                dispatcher_lower.contains("window")
-                    || dispatcher_lower.contains("active")
-                    || dispatcher_lower.contains("focus")
-                    || dispatcher_lower.contains("fullscreen")
-                    || dispatcher_lower.contains("group")
-                    || dispatcher_lower.contains("split")
-                    || dispatcher_lower.contains("pin")
-            }
-            3 => {
-                data.args_lower.as_ref().is_some_and(|a| {
-                    a.contains("volume") || a.contains("brightness") || a.contains("playerctl")
-                }) || dispatcher_lower.contains("audio")
-            }
-            4 => dispatcher_lower.as_ref() == "exec",
-            5 => key_lower.contains("mouse"),
-            6 => data.is_favorite,
-            _ => true,
-        };
-
-        if !category_match {
-            return false;
-        }
-
-        if let Some(ref q_mods) = query.mods {
-            if !data.mods_lower.contains(q_mods) && !data.clean_mods_lower.contains(q_mods) {
-                return false;
-            }
-        }
-        if let Some(ref q_key) = query.key {
-            if !data.key_lower.contains(q_key) {
-                return false;
-            }
-        }
-        if let Some(ref q_action) = query.action {
-            if !data.dispatcher_lower.contains(q_action) {
-                return false;
-            }
-        }
-        if let Some(ref q_args) = query.args {
-            if !data.args_lower.as_ref().is_some_and(|a| a.contains(q_args)) {
-                return false;
-            }
-        }
-        if let Some(ref q_desc) = query.description {
-            if !data
-                .description_lower
-                .as_ref()
-                .is_some_and(|d| d.contains(q_desc))
-            {
-                return false;
-            }
-        }
-
-        if query.general_query.is_empty() {
-            return true;
+impl SearchTarget for imp::KeybindData {
+    fn text(&self, field: Field) -> Cow<'_, str> {
+        match field {
+            Field::Mods => Cow::Borrowed(&self.mods_lower),
+            Field::CleanMods => Cow::Borrowed(&self.clean_mods_lower),
+            Field::Key => Cow::Borrowed(&self.key_lower),
+            Field::Action => Cow::Borrowed(&self.dispatcher_lower),
+            Field::Args => Cow::Borrowed(self.args_lower.as_deref().unwrap_or("")),
+            Field::Description => Cow::Borrowed(self.description_lower.as_deref().unwrap_or("")),
+            Field::Flag => Cow::Borrowed(&self.flags),
+            Field::Submap => Cow::Owned(self.submap.as_deref().unwrap_or("").to_lowercase()),
+            Field::File => Cow::Owned(self.file_path.to_lowercase()),
+            Field::Conflict | Field::Broken | Field::Favorite => Cow::Borrowed(""),
         }
+    }
 
-        let text_to_match: &str = query.general_query.as_ref();
-
-        if data.mods_lower.contains(text_to_match)
-            || data.clean_mods_lower.contains(text_to_match)
-            || data.key_lower.contains(text_to_match)
-            || data.dispatcher_lower.contains(text_to_match)
-            || data
-                .args_lower
-                .as_ref()
-                .is_some_and(|a| a.contains(text_to_match))
-            || data
-                .description_lower
-                .as_ref()
-                .is_some_and(|d| d.contains(text_to_match))
-        {
-            return true;
+    fn state(&self, field: Field) -> bool {
+        match field {
+            Field::Conflict => self.is_conflicted,
+            Field::Broken => self.is_broken,
+            Field::Favorite => self.is_favorite,
+            _ => false,
         }
-
-        matcher
-            .fuzzy_match(&data.mods_lower, text_to_match)
-            .is_some()
-            || matcher
-                .fuzzy_match(&data.clean_mods_lower, text_to_match)
-                .is_some()
-            || matcher
-                .fuzzy_match(&data.key_lower, text_to_match)
-                .is_some()
-            || matcher
-                .fuzzy_match(&data.dispatcher_lower, text_to_match)
-                .is_some()
-            || data
-                .args_lower
-                .as_ref()
-                .is_some_and(|a| matcher.fuzzy_match(a, text_to_match).is_some())
-            || data
-                .description_lower
-                .as_ref()
-                .is_some_and(|d| matcher.fuzzy_match(d, text_to_match).is_some())
     }
 }
 
@@ -230,6 +191,8 @@ pub mod imp {
         pub is_favorite: bool,
         pub is_broken: bool,
         pub broken_reason: Option<Arc<str>>,
+        pub app_name: Option<Arc<str>>,
+        pub app_icon: Option<Arc<str>>,
 
         pub mods_lower: Arc<str>,
         pub clean_mods_lower: Arc<str>,
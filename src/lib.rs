@@ -2,7 +2,10 @@
 pub mod cli;
 pub mod config;
 pub mod doctor;
+pub mod json;
 pub mod keybind_object;
+pub mod lint;
+pub mod logging;
 pub mod parser;
 pub mod ui;
 pub mod watcher;
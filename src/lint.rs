@@ -0,0 +1,98 @@
+use crate::parser::dispatchers::{lookup, validate_dispatcher};
+use crate::parser::{parse_config_with_diagnostics, Diagnostic, Keybind, Severity};
+use std::path::PathBuf;
+
+/// A problem found in a keybind, pointing at its source line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    pub file: PathBuf,
+    /// 1-based line number.
+    pub line: usize,
+    /// A note for dispatchers missing from the catalog, a warning otherwise.
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl std::fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.file.display(), self.line, self.message)
+    }
+}
+
+impl From<LintIssue> for Diagnostic {
+    fn from(issue: LintIssue) -> Self {
+        Diagnostic {
+            file: issue.file,
+            line: issue.line,
+            severity: issue.severity,
+            message: issue.message,
+        }
+    }
+}
+
+/// Checks every keybind's dispatcher and arguments against the dispatcher
+/// catalog. Dispatchers the catalog doesn't know are only noted, since
+/// Hyprland adds new ones between releases.
+pub fn lint_keybinds(keybinds: &[Keybind]) -> Vec<LintIssue> {
+    keybinds
+        .iter()
+        .filter_map(|kb| {
+            validate_dispatcher(&kb.dispatcher, &kb.args).map(|message| LintIssue {
+                file: kb.file_path.clone(),
+                line: kb.line_number + 1,
+                severity: if lookup(&kb.dispatcher).is_some() {
+                    Severity::Warning
+                } else {
+                    Severity::Note
+                },
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Parser diagnostics followed by lint issues for `keybinds`.
+pub fn collect_problems(keybinds: &[Keybind], diagnostics: &[Diagnostic]) -> Vec<Diagnostic> {
+    let mut problems = diagnostics.to_vec();
+    problems.extend(lint_keybinds(keybinds).into_iter().map(Diagnostic::from));
+    problems
+}
+
+/// Prints parser diagnostics and lint results for the current config.
+/// Returns `true` when nothing but notes were found.
+pub fn run_lint() -> bool {
+    let (keybinds, diagnostics) = match parse_config_with_diagnostics() {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Error parsing config: {}", e);
+            return false;
+        }
+    };
+
+    let problems = collect_problems(&keybinds, &diagnostics);
+    for problem in &problems {
+        println!("{}", problem);
+    }
+
+    let notes = problems
+        .iter()
+        .filter(|p| p.severity == Severity::Note)
+        .count();
+    match problems.len() - notes {
+        0 => {
+            println!("No problems found in {} keybinds", keybinds.len());
+            if notes > 0 {
+                println!("{} note(s)", notes);
+            }
+            true
+        }
+        1 => {
+            println!("\n1 problem found");
+            false
+        }
+        n => {
+            println!("\n{} problems found", n);
+            false
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Application log.
+//!
+//! Records go to `$XDG_STATE_HOME/hyprkcs/hyprkcs.log`, which is rotated once
+//! it grows past [`MAX_LOG_BYTES`], and to an in-memory buffer that backs the
+//! log viewer in settings. Warnings and errors are also printed to stderr, as
+//! is everything else with `--verbose`.
+
+use crate::config::constants::HYPRKCS_DIR;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const LOG_FILE: &str = "hyprkcs.log";
+pub const MAX_LOG_BYTES: u64 = 512 * 1024;
+/// Rotated files kept next to the current one (`hyprkcs.1.log`, ...).
+pub const KEEP_ROTATED: usize = 3;
+const RECENT_CAPACITY: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+pub const LEVELS: &[Level] = &[
+    Level::Error,
+    Level::Warn,
+    Level::Info,
+    Level::Debug,
+    Level::Trace,
+];
+
+impl Level {
+    pub fn parse(s: &str) -> Option<Level> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" => Some(Level::Error),
+            "warn" | "warning" => Some(Level::Warn),
+            "info" => Some(Level::Info),
+            "debug" => Some(Level::Debug),
+            "trace" => Some(Level::Trace),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+/// One log line: `<timestamp> <LEVEL> [<target>] <message>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub timestamp: String,
+    pub level: Level,
+    /// Area the record comes from, e.g. `parser`, `write` or `hyprctl`.
+    pub target: String,
+    pub message: String,
+}
+
+impl LogRecord {
+    pub fn to_line(&self) -> String {
+        // Keep one record per line so the file stays greppable
+        let message = self.message.replace('\n', "\\n");
+        format!(
+            "{} {:<5} [{}] {}",
+            self.timestamp,
+            self.level.as_str(),
+            self.target,
+            message
+        )
+    }
+
+    pub fn parse_line(line: &str) -> Option<LogRecord> {
+        let (timestamp, rest) = line.split_once(' ')?;
+        let (level, rest) = rest.trim_start().split_once(' ')?;
+        let rest = rest.trim_start().strip_prefix('[')?;
+        let (target, message) = rest.split_once("] ")?;
+        Some(LogRecord {
+            timestamp: timestamp.to_string(),
+            level: Level::parse(level)?,
+            target: target.to_string(),
+            message: message.replace("\\n", "\n"),
+        })
+    }
+}
+
+struct Logger {
+    max_level: Level,
+    verbose: bool,
+    file: Option<PathBuf>,
+    recent: VecDeque<LogRecord>,
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger {
+    max_level: Level::Info,
+    verbose: false,
+    file: None,
+    recent: VecDeque::new(),
+});
+
+fn logger() -> std::sync::MutexGuard<'static, Logger> {
+    match LOGGER.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// `$XDG_STATE_HOME/hyprkcs/`
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::state_dir().map(|dir| dir.join(HYPRKCS_DIR))
+}
+
+/// The file records are currently written to, once [`init`] has run.
+pub fn log_file() -> Option<PathBuf> {
+    logger().file.clone()
+}
+
+/// The `n`th rotated copy of `path`: `hyprkcs.1.log` for `hyprkcs.log`,
+/// `executions.1.jsonl` for `executions.jsonl`.
+pub fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map_or("log".to_string(), |e| e.to_string_lossy().to_string());
+    path.with_file_name(format!("{}.{}.{}", stem, n, extension))
+}
+
+/// Shifts `hyprkcs.log` to `hyprkcs.1.log`, `hyprkcs.1.log` to
+/// `hyprkcs.2.log` and so on, dropping the oldest file past `keep`.
+pub fn rotate(path: &Path, keep: usize) {
+    if keep == 0 {
+        let _ = std::fs::remove_file(path);
+        return;
+    }
+    let _ = std::fs::remove_file(rotated_path(path, keep));
+    for n in (1..keep).rev() {
+        let _ = std::fs::rename(rotated_path(path, n), rotated_path(path, n + 1));
+    }
+    let _ = std::fs::rename(path, rotated_path(path, 1));
+}
+
+/// Rotates `path` once it is larger than [`MAX_LOG_BYTES`], keeping
+/// [`KEEP_ROTATED`] old copies.
+pub fn rotate_if_large(path: &Path) {
+    if std::fs::metadata(path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        rotate(path, KEEP_ROTATED);
+    }
+}
+
+/// Starts logging into `dir`, rotating the existing log if it is too large.
+pub fn init_in(dir: &Path, max_level: Level, verbose: bool) {
+    let path = dir.join(LOG_FILE);
+    if std::fs::create_dir_all(dir).is_ok() {
+        rotate_if_large(&path);
+    }
+    let mut logger = logger();
+    logger.max_level = max_level;
+    logger.verbose = verbose;
+    logger.file = Some(path);
+}
+
+/// Starts logging into [`log_dir`].
+pub fn init(max_level: Level, verbose: bool) {
+    match log_dir() {
+        Some(dir) => init_in(&dir, max_level, verbose),
+        None => {
+            let mut logger = logger();
+            logger.max_level = max_level;
+            logger.verbose = verbose;
+        }
+    }
+}
+
+pub fn max_level() -> Level {
+    logger().max_level
+}
+
+pub fn enabled(level: Level) -> bool {
+    level <= logger().max_level
+}
+
+pub fn log(level: Level, target: &str, message: impl Into<String>) {
+    let mut logger = logger();
+    if level > logger.max_level {
+        return;
+    }
+
+    let record = LogRecord {
+        timestamp: chrono::Local::now()
+            .format("%Y-%m-%dT%H:%M:%S%.3f%:z")
+            .to_string(),
+        level,
+        target: target.to_string(),
+        message: message.into(),
+    };
+
+    if logger.verbose || level <= Level::Warn {
+        eprintln!("[{}] {}", record.target, record.message);
+    }
+
+    if let Some(path) = &logger.file {
+        rotate_if_large(path);
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+        {
+            let _ = writeln!(file, "{}", record.to_line());
+        }
+    }
+
+    if logger.recent.len() == RECENT_CAPACITY {
+        logger.recent.pop_front();
+    }
+    logger.recent.push_back(record);
+}
+
+/// Records logged by this process, oldest first.
+pub fn recent_records() -> Vec<LogRecord> {
+    logger().recent.iter().cloned().collect()
+}
+
+/// Records in a log file, including ones from other processes like the HUD.
+pub fn read_log_file(path: &Path) -> Vec<LogRecord> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(LogRecord::parse_line)
+        .collect()
+}
+
+/// Records at `level` or more severe, optionally from one target only.
+pub fn filter_records<'a>(
+    records: &'a [LogRecord],
+    level: Level,
+    target: Option<&str>,
+) -> Vec<&'a LogRecord> {
+    records
+        .iter()
+        .filter(|r| r.level <= level)
+        .filter(|r| target.is_none_or(|t| r.target == t))
+        .collect()
+}
+
+/// Empties the current log file and the in-memory buffer.
+pub fn clear() {
+    let mut logger = logger();
+    logger.recent.clear();
+    if let Some(path) = &logger.file {
+        let _ = std::fs::write(path, "");
+    }
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::logging::log($crate::logging::Level::Error, $target, format!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::logging::log($crate::logging::Level::Warn, $target, format!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::logging::log($crate::logging::Level::Info, $target, format!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($target:expr, $($arg:tt)*) => {
+        if $crate::logging::enabled($crate::logging::Level::Debug) {
+            $crate::logging::log($crate::logging::Level::Debug, $target, format!($($arg)*))
+        }
+    };
+}
@@ -1,8 +1,6 @@
-use fuzzy_matcher::skim::SkimMatcherV2;
-use fuzzy_matcher::FuzzyMatcher;
 use gtk::{glib, prelude::*};
 use gtk4 as gtk;
-use hyprKCS::{cli, parser, ui};
+use hyprKCS::{cli, logging, parser, ui};
 use libadwaita as adw;
 
 const APP_ID: &str = "com.github.hyprkcs";
@@ -10,6 +8,13 @@ const APP_ID: &str = "com.github.hyprkcs";
 fn main() -> glib::ExitCode {
     let args = cli::Args::parse();
 
+    let default_level = if args.verbose {
+        logging::Level::Debug
+    } else {
+        logging::Level::Info
+    };
+    logging::init(args.log_level.unwrap_or(default_level), args.verbose);
+
     if let Some(config_path) = args.config {
         std::env::set_var("HYPRKCS_CONFIG", config_path);
     }
@@ -23,69 +28,38 @@ fn main() -> glib::ExitCode {
         return glib::ExitCode::SUCCESS;
     }
 
+    if args.lint {
+        return if hyprKCS::lint::run_lint() {
+            glib::ExitCode::SUCCESS
+        } else {
+            glib::ExitCode::FAILURE
+        };
+    }
+
     if args.hud {
-        ui::hud::run_hud();
+        ui::hud::run_hud(args.hud_instance.as_deref());
+        return glib::ExitCode::SUCCESS;
+    }
+
+    if args.which_key {
+        ui::which_key::run_which_key(args.which_key_submap.as_deref());
+        return glib::ExitCode::SUCCESS;
+    }
+
+    if args.launcher {
+        ui::launcher::run_launcher();
         return glib::ExitCode::SUCCESS;
     }
 
     if args.print || args.search.is_some() {
-        match parser::parse_config() {
-            Ok(binds) => {
+        match parser::parse_config_with_diagnostics() {
+            Ok((binds, diagnostics)) => {
                 let binds = if let Some(term) = args.search {
-                    let matcher = SkimMatcherV2::default();
                     let query = ui::utils::SearchQuery::parse(&term);
-
-                    binds
-                        .into_iter()
-                        .filter(|b| {
-                            let mods = b.mods.to_lowercase();
-                            let key = b.key.to_lowercase();
-                            let dispatcher = b.dispatcher.to_lowercase();
-
-                            let args_str = b.args.to_lowercase();
-                            let desc_str = b
-                                .description
-                                .as_ref()
-                                .map(|s| s.to_lowercase())
-                                .unwrap_or_default();
-
-                            if let Some(ref q_mods) = query.mods {
-                                if !mods.contains(q_mods) {
-                                    return false;
-                                }
-                            }
-                            if let Some(ref q_key) = query.key {
-                                if !key.contains(q_key) {
-                                    return false;
-                                }
-                            }
-                            if let Some(ref q_action) = query.action {
-                                if !dispatcher.contains(q_action) {
-                                    return false;
-                                }
-                            }
-                            if let Some(ref q_args) = query.args {
-                                if !args_str.contains(q_args) {
-                                    return false;
-                                }
-                            }
-                            if let Some(ref q_desc) = query.description {
-                                if !desc_str.contains(q_desc) {
-                                    return false;
-                                }
-                            }
-
-                            if query.general_query.is_empty() {
-                                return true;
-                            }
-                            let text_to_match: &str = query.general_query.as_ref();
-
-                            matcher.fuzzy_match(&mods, text_to_match).is_some()
-                                || matcher.fuzzy_match(&key, text_to_match).is_some()
-                                || matcher.fuzzy_match(&dispatcher, text_to_match).is_some()
-                                || matcher.fuzzy_match(&args_str, text_to_match).is_some()
-                        })
-                        .collect::<Vec<_>>()
+                    for error in &query.errors {
+                        eprintln!("warning: {}", error);
+                    }
+                    ui::utils::search::filter_keybinds(binds, &query)
                 } else {
                     binds
                 };
@@ -117,6 +91,13 @@ fn main() -> glib::ExitCode {
                         bind.mods, bind.key, bind.dispatcher, bind.args
                     );
                 }
+
+                if !diagnostics.is_empty() {
+                    eprintln!();
+                    for diagnostic in &diagnostics {
+                        eprintln!("{}", diagnostic);
+                    }
+                }
             }
             Err(e) => eprintln!("Error parsing config: {}", e),
         }
@@ -0,0 +1,210 @@
+use crate::json::{self, JsonValue};
+use crate::parser::input::InputConfig;
+use anyhow::Result;
+
+/// A keyboard as reported by `hyprctl -j devices`, with any per-device
+/// overrides from the config already applied.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyboardDevice {
+    pub name: String,
+    pub layout: String,
+    pub variant: String,
+    pub model: String,
+    pub options: String,
+    pub active_keymap: String,
+    pub main: bool,
+    /// Custom keymap file set through `kb_file` in the device block, if any.
+    pub kb_file: Option<String>,
+}
+
+/// A `device { name = ...; kb_layout = ... }` block from the Hyprland config.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceOverride {
+    pub name: String,
+    pub kb_layout: Option<String>,
+    pub kb_variant: Option<String>,
+    pub kb_model: Option<String>,
+    pub kb_options: Option<String>,
+    pub kb_file: Option<String>,
+}
+
+/// Extracts the keyboards array from `hyprctl -j devices` output.
+pub fn parse_devices_json(content: &str) -> Vec<KeyboardDevice> {
+    let Some(root) = json::parse(content) else {
+        return Vec::new();
+    };
+
+    root.get("keyboards")
+        .and_then(JsonValue::as_array)
+        .unwrap_or_default()
+        .iter()
+        .filter(|kb| kb.get("name").is_some())
+        .map(|kb| KeyboardDevice {
+            name: kb.str_field("name"),
+            layout: kb.str_field("layout"),
+            variant: kb.str_field("variant"),
+            model: kb.str_field("model"),
+            options: kb.str_field("options"),
+            active_keymap: kb.str_field("active_keymap"),
+            main: kb.get("main").and_then(JsonValue::as_bool).unwrap_or(false),
+            kb_file: None,
+        })
+        .collect()
+}
+
+/// Collects `device` blocks from config content.
+///
+/// Both the current `device { name = foo }` syntax and the legacy
+/// `device:foo { }` category syntax are understood.
+pub fn parse_device_blocks(content: &str) -> Vec<DeviceOverride> {
+    let mut devices = Vec::new();
+    let mut current: Option<DeviceOverride> = None;
+    let mut depth = 0;
+
+    for line in content.lines() {
+        let trimmed = line.split('#').next().unwrap_or("").trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if current.is_none() {
+            if let Some(header) = trimmed.strip_suffix('{') {
+                let header = header.trim();
+                if header == "device" {
+                    current = Some(DeviceOverride::default());
+                    depth = 1;
+                } else if let Some(name) = header.strip_prefix("device:") {
+                    current = Some(DeviceOverride {
+                        name: name.trim().to_string(),
+                        ..Default::default()
+                    });
+                    depth = 1;
+                }
+            }
+            continue;
+        }
+
+        if trimmed.ends_with('{') {
+            depth += 1;
+            continue;
+        }
+        if trimmed == "}" {
+            depth -= 1;
+            if depth == 0 {
+                if let Some(dev) = current.take() {
+                    if !dev.name.is_empty() {
+                        devices.push(dev);
+                    }
+                }
+            }
+            continue;
+        }
+
+        if depth != 1 {
+            continue;
+        }
+
+        if let (Some(dev), Some((key, val))) = (current.as_mut(), trimmed.split_once('=')) {
+            let val = val.trim().to_string();
+            match key.trim() {
+                "name" => dev.name = val,
+                "kb_layout" => dev.kb_layout = Some(val),
+                "kb_variant" => dev.kb_variant = Some(val),
+                "kb_model" => dev.kb_model = Some(val),
+                "kb_options" => dev.kb_options = Some(val),
+                "kb_file" => dev.kb_file = Some(val).filter(|v| !v.is_empty()),
+                _ => {}
+            }
+        }
+    }
+
+    devices
+}
+
+/// Reads device blocks from every loaded config file.
+pub fn load_device_overrides() -> Result<Vec<DeviceOverride>> {
+    let files = match super::get_loaded_files() {
+        Ok(f) if !f.is_empty() => f,
+        _ => vec![super::get_config_path()?],
+    };
+
+    let mut overrides = Vec::new();
+    for path in files {
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            overrides.extend(parse_device_blocks(&content));
+        }
+    }
+    Ok(overrides)
+}
+
+/// Fills in the effective keymap for each keyboard.
+///
+/// Config overrides win over what Hyprland reports (they may not have been
+/// reloaded yet), and empty fields fall back to the global `input` block.
+pub fn apply_overrides(
+    keyboards: &mut [KeyboardDevice],
+    overrides: &[DeviceOverride],
+    global: &InputConfig,
+) {
+    for kb in keyboards.iter_mut() {
+        if let Some(ov) = overrides.iter().rev().find(|o| o.name == kb.name) {
+            if let Some(v) = &ov.kb_layout {
+                kb.layout = v.clone();
+            }
+            if let Some(v) = &ov.kb_variant {
+                kb.variant = v.clone();
+            }
+            if let Some(v) = &ov.kb_model {
+                kb.model = v.clone();
+            }
+            if let Some(v) = &ov.kb_options {
+                kb.options = v.clone();
+            }
+            if ov.kb_file.is_some() {
+                kb.kb_file = ov.kb_file.clone();
+            }
+        }
+
+        if kb.layout.is_empty() {
+            kb.layout = global.kb_layout.clone();
+            if kb.variant.is_empty() {
+                kb.variant = global.kb_variant.clone();
+            }
+        }
+        if kb.model.is_empty() {
+            kb.model = global.kb_model.clone();
+        }
+        if kb.options.is_empty() {
+            kb.options = global.kb_options.clone();
+        }
+    }
+}
+
+/// Returns the raw `hyprctl -j devices` output.
+///
+/// `HYPRKCS_DEVICES_JSON` can point to a file to use instead, which keeps
+/// tests and offline setups independent of a running compositor.
+pub fn read_devices_json() -> Option<String> {
+    if let Ok(path) = std::env::var("HYPRKCS_DEVICES_JSON") {
+        return std::fs::read_to_string(path).ok();
+    }
+
+    let output = std::process::Command::new("hyprctl")
+        .args(["-j", "devices"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Lists connected keyboards with their effective layouts.
+pub fn list_keyboards(global: &InputConfig) -> Vec<KeyboardDevice> {
+    let mut keyboards = read_devices_json()
+        .map(|s| parse_devices_json(&s))
+        .unwrap_or_default();
+    let overrides = load_device_overrides().unwrap_or_default();
+    apply_overrides(&mut keyboards, &overrides, global);
+    keyboards
+}
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    /// Worth knowing, but not a problem on its own.
+    Note,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+}
+
+/// A config line the parser could not make sense of, or only partly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    /// 1-based line number, or 0 when the whole file is affected.
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(file: &std::path::Path, line: usize, message: impl Into<String>) -> Self {
+        Self {
+            file: file.to_path_buf(),
+            line,
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(file: &std::path::Path, line: usize, message: impl Into<String>) -> Self {
+        Self {
+            file: file.to_path_buf(),
+            line,
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+
+    pub fn note(file: &std::path::Path, line: usize, message: impl Into<String>) -> Self {
+        Self {
+            file: file.to_path_buf(),
+            line,
+            severity: Severity::Note,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if self.line > 0 {
+            write!(f, ":{}", self.line)?;
+        }
+        write!(f, ": {}: {}", self.severity.as_str(), self.message)
+    }
+}
+
+/// Flags Hyprland accepts after `bind`.
+const BIND_FLAGS: &str = "lrcgoenmtisdp";
+
+/// Checks the flags of a `bind<flags> =` keyword, returning why they are
+/// invalid.
+pub fn check_bind_flags(flags: &str) -> Option<(Severity, String)> {
+    if !flags.chars().all(|c| c.is_alphabetic()) {
+        return Some((Severity::Error, format!("Invalid bind flags '{}'", flags)));
+    }
+    let unknown: String = flags.chars().filter(|c| !BIND_FLAGS.contains(*c)).collect();
+    if unknown.is_empty() {
+        None
+    } else {
+        Some((
+            Severity::Warning,
+            format!("Unknown bind flag '{}' in 'bind{}'", unknown, flags),
+        ))
+    }
+}
@@ -0,0 +1,607 @@
+/// The argument grammar of a dispatcher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    /// Takes no arguments.
+    None,
+    /// A shell command.
+    Command,
+    /// Free text that is passed through unchecked (layout messages, names).
+    Text,
+    /// A workspace specifier: `3`, `+1`, `m-1`, `r+1`, `e+1`, `name:web`,
+    /// `special[:name]`, `previous`, `empty`.
+    Workspace,
+    /// A workspace specifier optionally followed by `,window`.
+    WorkspaceWithWindow,
+    /// A window selector: `class:regex`, `title:regex`, `pid:123`,
+    /// `address:0x...`, or a bare class regex.
+    Window,
+    /// `l`, `r`, `u` or `d`.
+    Direction,
+    /// A direction or `mon:NAME`, optionally followed by `silent`.
+    DirectionOrMonitor,
+    /// A monitor: name, ID, direction, `+1`/`-1` or `current`.
+    Monitor,
+    /// Two deltas like `10 -10` or `exact 50% 50%`.
+    Deltas,
+    /// Deltas followed by `,window`.
+    DeltasWithWindow,
+    /// `+0.1`, `-0.1` or `exact 0.5`.
+    Ratio,
+    /// An integer within a range.
+    Integer(i64, i64),
+    /// Two integers separated by a space.
+    Point,
+    /// One of a fixed set of words.
+    Choice(&'static [&'static str]),
+    /// One of a fixed set of words optionally followed by `,window`.
+    ChoiceWithWindow(&'static [&'static str]),
+    /// One of a fixed set of words optionally followed by a monitor.
+    ChoiceWithMonitor(&'static [&'static str]),
+    /// Any subset of a fixed set of words, separated by spaces.
+    Flags(&'static [&'static str]),
+    /// `b`, `f` or a 1-based index.
+    GroupIndex,
+    /// `[+-]tag` optionally followed by `,window`.
+    Tag,
+    /// A workspace followed by a monitor.
+    WorkspaceAndMonitor,
+    /// Two monitors separated by a space.
+    MonitorPair,
+    /// A workspace ID followed by a new name.
+    WorkspaceRename,
+    /// `appid:name` of a global shortcut.
+    Global,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DispatcherSpec {
+    pub name: &'static str,
+    pub arg: ArgKind,
+    /// Whether the arguments may be left empty.
+    pub optional: bool,
+    /// Short argument syntax shown next to the arguments field.
+    pub usage: &'static str,
+}
+
+const TOGGLE: &[&str] = &["on", "off", "toggle"];
+const LOCK: &[&str] = &["lock", "unlock", "toggle"];
+
+const fn spec(
+    name: &'static str,
+    arg: ArgKind,
+    optional: bool,
+    usage: &'static str,
+) -> DispatcherSpec {
+    DispatcherSpec {
+        name,
+        arg,
+        optional,
+        usage,
+    }
+}
+
+/// Every dispatcher hyprKCS knows about, with its argument grammar.
+pub const DISPATCHERS: &[DispatcherSpec] = &[
+    spec("exec", ArgKind::Command, false, "command"),
+    spec("execr", ArgKind::Command, false, "command"),
+    spec("pass", ArgKind::Window, false, "window"),
+    spec("sendshortcut", ArgKind::Text, false, "mod, key[, window]"),
+    spec(
+        "sendkeystate",
+        ArgKind::Text,
+        false,
+        "mod, key, state, window",
+    ),
+    spec("killactive", ArgKind::None, false, "no arguments"),
+    spec("forcekillactive", ArgKind::None, false, "no arguments"),
+    spec("closewindow", ArgKind::Window, false, "window"),
+    spec("killwindow", ArgKind::Window, false, "window"),
+    spec("signal", ArgKind::Integer(1, 64), false, "signal number"),
+    spec(
+        "signalwindow",
+        ArgKind::Text,
+        false,
+        "window, signal number",
+    ),
+    spec(
+        "workspace",
+        ArgKind::Workspace,
+        false,
+        "ID, +1/-1, m+1, e+1, name:NAME, special[:NAME], previous, empty",
+    ),
+    spec(
+        "movetoworkspace",
+        ArgKind::WorkspaceWithWindow,
+        false,
+        "workspace[, window]",
+    ),
+    spec(
+        "movetoworkspacesilent",
+        ArgKind::WorkspaceWithWindow,
+        false,
+        "workspace[, window]",
+    ),
+    spec("togglefloating", ArgKind::Window, true, "[window]"),
+    spec("setfloating", ArgKind::Window, true, "[window]"),
+    spec("settiled", ArgKind::Window, true, "[window]"),
+    spec(
+        "fullscreen",
+        ArgKind::Integer(0, 2),
+        true,
+        "[0 fullscreen, 1 maximize, 2 no client update]",
+    ),
+    spec("fakefullscreen", ArgKind::None, false, "no arguments"),
+    spec(
+        "dpms",
+        ArgKind::ChoiceWithMonitor(TOGGLE),
+        false,
+        "on|off|toggle [monitor]",
+    ),
+    spec("forceidle", ArgKind::Text, false, "seconds"),
+    spec("pin", ArgKind::Window, true, "[window]"),
+    spec("movefocus", ArgKind::Direction, false, "l|r|u|d"),
+    // Without arguments, movewindow and resizewindow are the mouse drag actions of bindm
+    spec(
+        "movewindow",
+        ArgKind::DirectionOrMonitor,
+        true,
+        "l|r|u|d or mon:NAME [silent]",
+    ),
+    spec(
+        "resizewindow",
+        ArgKind::Text,
+        true,
+        "[1 to keep aspect ratio]",
+    ),
+    spec("swapwindow", ArgKind::Direction, false, "l|r|u|d"),
+    spec(
+        "centerwindow",
+        ArgKind::Integer(1, 1),
+        true,
+        "[1 to respect reserved area]",
+    ),
+    spec(
+        "resizeactive",
+        ArgKind::Deltas,
+        false,
+        "X Y, e.g. 10 -10 or exact 50% 50%",
+    ),
+    spec(
+        "moveactive",
+        ArgKind::Deltas,
+        false,
+        "X Y, e.g. 10 -10 or exact 100 100",
+    ),
+    spec(
+        "resizewindowpixel",
+        ArgKind::DeltasWithWindow,
+        false,
+        "X Y, window",
+    ),
+    spec(
+        "movewindowpixel",
+        ArgKind::DeltasWithWindow,
+        false,
+        "X Y, window",
+    ),
+    spec(
+        "cyclenext",
+        ArgKind::Flags(&["prev", "next", "b", "tiled", "floating", "visible", "hist"]),
+        true,
+        "[prev] [tiled|floating] [visible] [hist]",
+    ),
+    spec("swapnext", ArgKind::Choice(&["prev"]), true, "[prev]"),
+    spec("focuswindow", ArgKind::Window, false, "window"),
+    spec("focusmonitor", ArgKind::Monitor, false, "monitor"),
+    spec(
+        "splitratio",
+        ArgKind::Ratio,
+        false,
+        "+0.1, -0.1 or exact 0.5",
+    ),
+    spec("toggleopaque", ArgKind::None, false, "no arguments"),
+    spec(
+        "movecursortocorner",
+        ArgKind::Integer(0, 3),
+        false,
+        "0-3 (clockwise from bottom left)",
+    ),
+    spec("movecursor", ArgKind::Point, false, "X Y"),
+    spec(
+        "workspaceopt",
+        ArgKind::Choice(&["allfloat", "allpseudo"]),
+        false,
+        "allfloat|allpseudo",
+    ),
+    spec(
+        "renameworkspace",
+        ArgKind::WorkspaceRename,
+        false,
+        "ID NAME",
+    ),
+    spec("exit", ArgKind::None, false, "no arguments"),
+    spec("forcerendererreload", ArgKind::None, false, "no arguments"),
+    spec(
+        "movecurrentworkspacetomonitor",
+        ArgKind::Monitor,
+        false,
+        "monitor",
+    ),
+    spec(
+        "focusworkspaceoncurrentmonitor",
+        ArgKind::Workspace,
+        false,
+        "workspace",
+    ),
+    spec(
+        "moveworkspacetomonitor",
+        ArgKind::WorkspaceAndMonitor,
+        false,
+        "workspace monitor",
+    ),
+    spec(
+        "swapactiveworkspaces",
+        ArgKind::MonitorPair,
+        false,
+        "monitor monitor",
+    ),
+    spec("togglespecialworkspace", ArgKind::Text, true, "[name]"),
+    spec("fullscreenstate", ArgKind::Text, false, "internal client"),
+    spec("focusurgentorlast", ArgKind::None, false, "no arguments"),
+    spec("focuscurrentorlast", ArgKind::None, false, "no arguments"),
+    spec("bringactivetotop", ArgKind::None, false, "no arguments"),
+    spec(
+        "alterzorder",
+        ArgKind::ChoiceWithWindow(&["top", "bottom"]),
+        false,
+        "top|bottom[, window]",
+    ),
+    spec("togglegroup", ArgKind::None, false, "no arguments"),
+    spec(
+        "changegroupactive",
+        ArgKind::GroupIndex,
+        false,
+        "b|f or index",
+    ),
+    spec("swapprev", ArgKind::None, false, "no arguments"),
+    spec(
+        "lockgroups",
+        ArgKind::Choice(LOCK),
+        false,
+        "lock|unlock|toggle",
+    ),
+    spec(
+        "lockactivegroup",
+        ArgKind::Choice(LOCK),
+        false,
+        "lock|unlock|toggle",
+    ),
+    spec("moveintogroup", ArgKind::Direction, false, "l|r|u|d"),
+    spec("moveoutofgroup", ArgKind::Window, true, "[window]"),
+    spec("movewindoworgroup", ArgKind::Direction, false, "l|r|u|d"),
+    spec(
+        "movegroupwindow",
+        ArgKind::Choice(&["b", "f"]),
+        true,
+        "[b|f]",
+    ),
+    spec(
+        "denywindowfromgroup",
+        ArgKind::Choice(TOGGLE),
+        false,
+        "on|off|toggle",
+    ),
+    spec(
+        "setignoregrouplock",
+        ArgKind::Choice(TOGGLE),
+        false,
+        "on|off|toggle",
+    ),
+    spec("pseudo", ArgKind::Window, true, "[window]"),
+    spec("togglesplit", ArgKind::None, false, "no arguments"),
+    spec("swapsplit", ArgKind::None, false, "no arguments"),
+    spec("tagwindow", ArgKind::Tag, false, "[+-]tag[, window]"),
+    spec("layoutmsg", ArgKind::Text, false, "layout message"),
+    spec("submap", ArgKind::Text, false, "submap name or reset"),
+    spec("global", ArgKind::Global, false, "appid:name"),
+    spec("event", ArgKind::Text, false, "data"),
+    spec("setprop", ArgKind::Text, false, "window property value"),
+    spec("toggleswallow", ArgKind::None, false, "no arguments"),
+];
+
+/// Looks a dispatcher up by name (case-insensitive).
+pub fn lookup(name: &str) -> Option<&'static DispatcherSpec> {
+    let name = name.trim();
+    DISPATCHERS
+        .iter()
+        .find(|d| d.name.eq_ignore_ascii_case(name))
+}
+
+fn is_number(s: &str) -> bool {
+    let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+    !s.is_empty() && s.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+fn is_integer(s: &str) -> bool {
+    s.parse::<i64>().is_ok()
+}
+
+fn is_direction(s: &str) -> bool {
+    matches!(
+        s.to_lowercase().as_str(),
+        "l" | "r" | "u" | "d" | "t" | "b" | "left" | "right" | "up" | "down" | "top" | "bottom"
+    )
+}
+
+fn check_workspace(s: &str) -> Result<(), String> {
+    let s = s.trim();
+    let lower = s.to_lowercase();
+
+    if s.is_empty() {
+        return Err("a workspace is required".into());
+    }
+    if let Ok(id) = s.parse::<i64>() {
+        return if id == 0 {
+            Err("workspace IDs start at 1".into())
+        } else {
+            Ok(())
+        };
+    }
+    if let Some(name) = s.strip_prefix("name:") {
+        return if name.trim().is_empty() {
+            Err("'name:' needs a workspace name".into())
+        } else {
+            Ok(())
+        };
+    }
+    if lower == "special" || lower.starts_with("special:") {
+        return Ok(());
+    }
+    if matches!(lower.as_str(), "previous" | "previous_per_monitor") {
+        return Ok(());
+    }
+    if let Some(flags) = lower.strip_prefix("empty") {
+        return if flags.chars().all(|c| c == 'm' || c == 'n') {
+            Ok(())
+        } else {
+            Err(format!("unknown 'empty' flags '{}' (use m and n)", flags))
+        };
+    }
+    if s.starts_with(['+', '-']) {
+        return if is_integer(&s[1..]) {
+            Ok(())
+        } else {
+            Err(format!("'{}' is not a relative workspace like +1", s))
+        };
+    }
+    if let Some(rest) = lower.strip_prefix(['m', 'r', 'e']) {
+        if let Some(n) = rest.strip_prefix(['+', '-', '~']) {
+            return if is_integer(n) {
+                Ok(())
+            } else {
+                Err(format!("'{}' is not a relative workspace like m+1", s))
+            };
+        }
+    }
+    Err(format!("'{}' is not a workspace specifier", s))
+}
+
+fn check_window(s: &str) -> Result<(), String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err("a window is required".into());
+    }
+    if let Some(pid) = s.strip_prefix("pid:") {
+        return if pid.trim().parse::<u32>().is_ok() {
+            Ok(())
+        } else {
+            Err(format!("'{}' is not a process ID", pid.trim()))
+        };
+    }
+    if let Some(addr) = s.strip_prefix("address:") {
+        let hex = addr.trim().trim_start_matches("0x");
+        return if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            Ok(())
+        } else {
+            Err(format!(
+                "'{}' is not a window address like 0x1234",
+                addr.trim()
+            ))
+        };
+    }
+    for prefix in ["class:", "title:", "initialclass:", "initialtitle:", "tag:"] {
+        if let Some(value) = s.strip_prefix(prefix) {
+            if value.trim().is_empty() {
+                return Err(format!("'{}' needs a value", prefix));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_deltas(s: &str) -> Result<(), String> {
+    let s = s.trim();
+    let rest = s.strip_prefix("exact").map(str::trim_start).unwrap_or(s);
+    let parts: Vec<&str> = rest.split_whitespace().collect();
+    if parts.len() != 2 {
+        return Err("expected two values, e.g. 10 -10".into());
+    }
+    for part in parts {
+        if !is_number(part.strip_suffix('%').unwrap_or(part)) {
+            return Err(format!("'{}' is not a number or percentage", part));
+        }
+    }
+    Ok(())
+}
+
+fn check_monitor(s: &str) -> Result<(), String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err("a monitor is required".into());
+    }
+    if s.starts_with(['+', '-']) && !is_integer(&s[1..]) {
+        return Err(format!("'{}' is not a relative monitor like +1", s));
+    }
+    Ok(())
+}
+
+fn check_choice(s: &str, choices: &[&str]) -> Result<(), String> {
+    if choices.iter().any(|c| c.eq_ignore_ascii_case(s.trim())) {
+        Ok(())
+    } else {
+        Err(format!("expected {}", choices.join(", ")))
+    }
+}
+
+fn check_with_window(s: &str, first: impl Fn(&str) -> Result<(), String>) -> Result<(), String> {
+    match s.split_once(',') {
+        Some((head, window)) => {
+            first(head)?;
+            check_window(window)
+        }
+        None => first(s),
+    }
+}
+
+/// Checks `args` against the grammar of a known dispatcher.
+pub fn check_args(spec: &DispatcherSpec, args: &str) -> Result<(), String> {
+    let args = args.trim();
+    if args.is_empty() {
+        return match spec.arg {
+            ArgKind::None => Ok(()),
+            _ if spec.optional => Ok(()),
+            _ => Err("arguments are required".into()),
+        };
+    }
+
+    match spec.arg {
+        ArgKind::None => Err("takes no arguments".into()),
+        ArgKind::Command | ArgKind::Text => Ok(()),
+        ArgKind::Workspace => check_workspace(args),
+        ArgKind::WorkspaceWithWindow => check_with_window(args, check_workspace),
+        ArgKind::Window => check_window(args),
+        ArgKind::Direction => {
+            if is_direction(args) {
+                Ok(())
+            } else {
+                Err(format!("'{}' is not a direction (l, r, u, d)", args))
+            }
+        }
+        ArgKind::DirectionOrMonitor => {
+            let target = args.strip_suffix("silent").map(str::trim).unwrap_or(args);
+            match target.strip_prefix("mon:") {
+                Some(monitor) => check_monitor(monitor),
+                None if is_direction(target) => Ok(()),
+                None => Err(format!(
+                    "'{}' is not a direction (l, r, u, d) or mon:NAME",
+                    target
+                )),
+            }
+        }
+        ArgKind::Monitor => check_monitor(args),
+        ArgKind::Deltas => check_deltas(args),
+        ArgKind::DeltasWithWindow => match args.split_once(',') {
+            Some((deltas, window)) => check_deltas(deltas).and_then(|_| check_window(window)),
+            None => Err("expected deltas followed by ,window".into()),
+        },
+        ArgKind::Ratio => {
+            let value = args.strip_prefix("exact").map(str::trim).unwrap_or(args);
+            if is_number(value) {
+                Ok(())
+            } else {
+                Err(format!("'{}' is not a ratio like +0.1 or exact 0.5", args))
+            }
+        }
+        ArgKind::Integer(min, max) => match args.parse::<i64>() {
+            Ok(n) if (min..=max).contains(&n) => Ok(()),
+            Ok(n) => Err(format!("{} is outside {}-{}", n, min, max)),
+            Err(_) => Err(format!("'{}' is not a whole number", args)),
+        },
+        ArgKind::Point => {
+            let parts: Vec<&str> = args.split_whitespace().collect();
+            if parts.len() == 2 && parts.iter().all(|p| is_integer(p)) {
+                Ok(())
+            } else {
+                Err("expected two whole numbers, e.g. 100 200".into())
+            }
+        }
+        ArgKind::Choice(choices) => check_choice(args, choices),
+        ArgKind::ChoiceWithWindow(choices) => {
+            check_with_window(args, |head| check_choice(head, choices))
+        }
+        ArgKind::ChoiceWithMonitor(choices) => {
+            let mut parts = args.splitn(2, char::is_whitespace);
+            check_choice(parts.next().unwrap_or(""), choices)?;
+            match parts.next() {
+                Some(monitor) => check_monitor(monitor),
+                None => Ok(()),
+            }
+        }
+        ArgKind::Flags(flags) => match args
+            .split_whitespace()
+            .find(|word| !flags.iter().any(|f| f.eq_ignore_ascii_case(word)))
+        {
+            Some(word) => Err(format!("unknown option '{}'", word)),
+            None => Ok(()),
+        },
+        ArgKind::GroupIndex => {
+            if matches!(args, "b" | "f") || args.parse::<u32>().is_ok_and(|n| n > 0) {
+                Ok(())
+            } else {
+                Err("expected b, f or an index starting at 1".into())
+            }
+        }
+        ArgKind::Tag => check_with_window(args, |tag| {
+            let name = tag.trim().trim_start_matches(['+', '-']);
+            if name.is_empty() {
+                Err("a tag name is required".into())
+            } else {
+                Ok(())
+            }
+        }),
+        ArgKind::WorkspaceAndMonitor => match args.split_once(char::is_whitespace) {
+            Some((ws, monitor)) => check_workspace(ws).and_then(|_| check_monitor(monitor)),
+            None => Err("expected a workspace and a monitor".into()),
+        },
+        ArgKind::MonitorPair => {
+            let parts: Vec<&str> = args.split_whitespace().collect();
+            if parts.len() == 2 {
+                parts.iter().try_for_each(|m| check_monitor(m))
+            } else {
+                Err("expected two monitors".into())
+            }
+        }
+        ArgKind::WorkspaceRename => match args.split_once(char::is_whitespace) {
+            Some((id, _)) if is_integer(id) => Ok(()),
+            Some((id, _)) => Err(format!("'{}' is not a workspace ID", id)),
+            None if is_integer(args) => Ok(()),
+            None => Err(format!("'{}' is not a workspace ID", args)),
+        },
+        ArgKind::Global => match args.split_once(':') {
+            Some((app, name)) if !app.trim().is_empty() && !name.trim().is_empty() => Ok(()),
+            _ => Err("expected appid:name".into()),
+        },
+    }
+}
+
+/// Validates a dispatcher and its arguments, returning the reason when they
+/// are invalid.
+///
+/// Plugin dispatchers (`plugin:name`) and arguments that still contain
+/// `$variables` are not checked. A name missing from [`DISPATCHERS`] may be
+/// one Hyprland added since, so callers should treat it as a notice.
+pub fn validate_dispatcher(dispatcher: &str, args: &str) -> Option<String> {
+    let dispatcher = dispatcher.trim();
+    if dispatcher.is_empty() || dispatcher.contains(':') || dispatcher.starts_with('$') {
+        return None;
+    }
+    let Some(spec) = lookup(dispatcher) else {
+        return Some(format!("Dispatcher '{}' is not in the catalog", dispatcher));
+    };
+    if args.contains('$') {
+        return None;
+    }
+    check_args(spec, args)
+        .err()
+        .map(|reason| format!("Invalid arguments for {}: {}", spec.name, reason))
+}
@@ -0,0 +1,213 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// One `source = ...` line, or a sourced directory, and the files it pulled
+/// in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceEdge {
+    pub from: PathBuf,
+    /// 1-based line of the `source` keyword, 0 for directory entries.
+    pub line: usize,
+    /// The sourced path after variable expansion, possibly a glob.
+    pub pattern: String,
+    /// The path as written, when it pointed into `~/.config/hypr` and was
+    /// redirected into the directory of an alternative config.
+    pub remapped_from: Option<String>,
+    pub targets: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncludeKind {
+    /// A file that was parsed.
+    File,
+    /// A file sourced again after it was already parsed; the parser skips it.
+    Duplicate,
+    /// A file sourcing one of its own includers; the parser skips it.
+    Cycle,
+    /// A glob pattern, with the files it matched below it.
+    Glob,
+    /// A sourced path that doesn't exist.
+    Missing,
+}
+
+/// One row of the include tree, see [`SourceGraph::tree`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncludeNode {
+    pub kind: IncludeKind,
+    /// The file, or the pattern for globs and missing targets.
+    pub path: PathBuf,
+    pub depth: usize,
+    /// The `source` line in the including file, 0 for the root and for
+    /// directory entries.
+    pub line: usize,
+    pub remapped_from: Option<String>,
+    /// Files a glob matched.
+    pub matched: usize,
+}
+
+/// Where each loaded file is read, for comparing lines across files in the
+/// order the parser reads them. See [`SourceGraph::load_order`].
+#[derive(Debug, Clone, Default)]
+pub struct LoadOrder {
+    /// For every file, the 0-based `source` line and target index of each
+    /// include leading to it from the root.
+    prefixes: HashMap<PathBuf, Vec<usize>>,
+}
+
+impl LoadOrder {
+    /// A key for 0-based `line` of `path` that sorts in parse order, `None`
+    /// if the file isn't loaded.
+    pub fn position(&self, path: &Path, line: usize) -> Option<Vec<usize>> {
+        let mut key = self.prefixes.get(path)?.clone();
+        key.push(line);
+        Some(key)
+    }
+}
+
+/// Which config file sources which, as seen by the last parse.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceGraph {
+    pub root: PathBuf,
+    pub edges: Vec<SourceEdge>,
+}
+
+impl SourceGraph {
+    /// Every file reached from the root, the root first.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut seen = HashSet::new();
+        let mut files = Vec::new();
+        if seen.insert(self.root.clone()) {
+            files.push(self.root.clone());
+        }
+        for target in self.edges.iter().flat_map(|e| &e.targets) {
+            if seen.insert(target.clone()) {
+                files.push(target.clone());
+            }
+        }
+        files
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.root == path
+            || self
+                .edges
+                .iter()
+                .any(|e| e.targets.iter().any(|t| t == path))
+    }
+
+    /// Edges going out of `path`, in file order.
+    pub fn sources_of(&self, path: &Path) -> Vec<&SourceEdge> {
+        self.edges.iter().filter(|e| e.from == path).collect()
+    }
+
+    /// The include tree in parse order, flattened depth first.
+    pub fn tree(&self) -> Vec<IncludeNode> {
+        let mut nodes = Vec::new();
+        let mut stack = Vec::new();
+        let mut seen = HashSet::new();
+        self.walk(&self.root, 0, None, &mut stack, &mut seen, &mut nodes);
+        nodes
+    }
+
+    fn walk(
+        &self,
+        path: &Path,
+        depth: usize,
+        edge: Option<&SourceEdge>,
+        stack: &mut Vec<PathBuf>,
+        seen: &mut HashSet<PathBuf>,
+        nodes: &mut Vec<IncludeNode>,
+    ) {
+        let kind = if stack.iter().any(|p| p == path) {
+            IncludeKind::Cycle
+        } else if !seen.insert(path.to_path_buf()) {
+            IncludeKind::Duplicate
+        } else {
+            IncludeKind::File
+        };
+        nodes.push(IncludeNode {
+            kind,
+            path: path.to_path_buf(),
+            depth,
+            line: edge.map_or(0, |e| e.line),
+            remapped_from: edge.and_then(|e| e.remapped_from.clone()),
+            matched: 0,
+        });
+        if kind != IncludeKind::File {
+            return;
+        }
+
+        stack.push(path.to_path_buf());
+        for edge in self.sources_of(path) {
+            if super::is_glob_pattern(&edge.pattern) {
+                nodes.push(IncludeNode {
+                    kind: IncludeKind::Glob,
+                    path: PathBuf::from(&edge.pattern),
+                    depth: depth + 1,
+                    line: edge.line,
+                    remapped_from: edge.remapped_from.clone(),
+                    matched: edge.targets.len(),
+                });
+                for target in &edge.targets {
+                    self.walk(target, depth + 2, None, stack, seen, nodes);
+                }
+            } else if edge.targets.is_empty() {
+                nodes.push(IncludeNode {
+                    kind: IncludeKind::Missing,
+                    path: PathBuf::from(&edge.pattern),
+                    depth: depth + 1,
+                    line: edge.line,
+                    remapped_from: edge.remapped_from.clone(),
+                    matched: 0,
+                });
+            } else {
+                for target in &edge.targets {
+                    self.walk(target, depth + 1, Some(edge), stack, seen, nodes);
+                }
+            }
+        }
+        stack.pop();
+    }
+
+    /// The order the parser reads the loaded files' lines in. A sourced file
+    /// is read where its `source` line is, and only the first time.
+    pub fn load_order(&self) -> LoadOrder {
+        let mut order = LoadOrder::default();
+        order.prefixes.insert(self.root.clone(), Vec::new());
+        let mut queue = vec![self.root.clone()];
+        while let Some(path) = queue.pop() {
+            let prefix = order.prefixes[&path].clone();
+            for edge in self.sources_of(&path) {
+                for (i, target) in edge.targets.iter().enumerate() {
+                    let mut key = prefix.clone();
+                    key.extend([edge.line.saturating_sub(1), i]);
+                    let earlier = order.prefixes.get(target).is_some_and(|k| *k <= key);
+                    if !earlier {
+                        order.prefixes.insert(target.clone(), key);
+                        queue.push(target.clone());
+                    }
+                }
+            }
+        }
+        order
+    }
+
+    /// Whether a change to `path` can change the parsed config: it is one of
+    /// the loaded files, or a new file a glob or sourced directory would pick
+    /// up.
+    pub fn is_relevant(&self, path: &Path) -> bool {
+        self.contains(path) || !self.includers_of_new(path).is_empty()
+    }
+
+    /// Files whose `source` globs or directories match `path`.
+    pub fn includers_of_new(&self, path: &Path) -> Vec<PathBuf> {
+        self.edges
+            .iter()
+            .filter(|e| {
+                glob::Pattern::new(&e.pattern).is_ok_and(|p| p.matches_path(path))
+                    || (e.line == 0 && path.parent() == Some(e.from.as_path()))
+            })
+            .map(|e| e.from.clone())
+            .collect()
+    }
+}
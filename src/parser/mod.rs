@@ -2,10 +2,22 @@ use anyhow::{Context, Result};
 use dirs::config_dir;
 use glob::glob;
 use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
 
+pub mod devices;
+pub mod diagnostics;
+pub mod dispatchers;
+pub mod graph;
 pub mod input;
+pub mod refactor;
+pub mod submaps;
+pub mod variables;
+
+use diagnostics::check_bind_flags;
+pub use diagnostics::{Diagnostic, Severity};
+pub use graph::{IncludeKind, IncludeNode, LoadOrder, SourceEdge, SourceGraph};
 
 #[derive(Debug, Clone)]
 pub struct Keybind {
@@ -132,6 +144,76 @@ fn split_comment(line: &str) -> (&str, &str) {
     }
 }
 
+/// Whether binds with these flags have a description field, like `bindd` or
+/// `binded`.
+pub fn bind_has_description(flags: &str) -> bool {
+    flags.contains('d')
+}
+
+fn trimmed_range(text: &str, range: Range<usize>) -> Range<usize> {
+    let field = &text[range.clone()];
+    let start = range.start + (field.len() - field.trim_start().len());
+    start..start + field.trim().len()
+}
+
+/// Splits the text after a bind's `=` into modifiers, key, the description
+/// when `has_description`, dispatcher and arguments. Commas in quotes don't
+/// split, and the arguments keep any further commas. Returns the trimmed byte
+/// range of each field in `code` and whether a quote was left open. An empty
+/// last field is left out unless it is the arguments.
+pub fn split_bind_fields(code: &str, has_description: bool) -> (Vec<Range<usize>>, bool) {
+    let limit = if has_description { 4 } else { 3 };
+    let mut fields = Vec::with_capacity(limit + 1);
+    let mut in_quote = false;
+    let mut start = 0;
+    for (i, c) in code.char_indices() {
+        if fields.len() == limit {
+            break;
+        }
+        if c == '"' {
+            in_quote = !in_quote;
+        } else if c == ',' && !in_quote {
+            fields.push(trimmed_range(code, start..i));
+            start = i + 1;
+        }
+    }
+    let last = trimmed_range(code, start..code.len());
+    if !last.is_empty() || fields.len() == limit {
+        fields.push(last);
+    }
+    (fields, in_quote)
+}
+
+/// Byte ranges of the fields of a `bind<flags> = ...` line that edits need.
+pub struct BindLineFields {
+    pub mods: Range<usize>,
+    pub dispatcher: Range<usize>,
+    /// `None` when the line ends after the dispatcher.
+    pub args: Option<Range<usize>>,
+}
+
+/// The fields of a bind line as byte ranges into `line`, comments left out.
+/// `None` if it isn't a bind line or has no dispatcher.
+pub fn bind_line_fields(line: &str) -> Option<BindLineFields> {
+    let trimmed = line.trim_start();
+    let after_bind = trimmed.strip_prefix("bind")?;
+    let eq = after_bind.find('=')?;
+    let flags = after_bind[..eq].trim();
+    if !flags.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let start = line.len() - trimmed.len() + "bind".len() + eq + 1;
+    let (code, _) = split_comment(&line[start..]);
+    let (fields, _) = split_bind_fields(code, bind_has_description(flags));
+    let at = |i: usize| fields.get(i).map(|r| start + r.start..start + r.end);
+    let dispatcher = if bind_has_description(flags) { 3 } else { 2 };
+    Some(BindLineFields {
+        mods: at(0)?,
+        dispatcher: at(dispatcher)?,
+        args: at(dispatcher + 1),
+    })
+}
+
 fn expand_path(
     path_str: &str,
     current_file: &Path,
@@ -185,15 +267,61 @@ impl ParserContext {
     }
 }
 
+/// A config file as last read from disk. Kept between parses so a change to
+/// one file doesn't re-read and re-parse all the others.
+struct CachedFile {
+    content: Arc<String>,
+    mtime: std::time::SystemTime,
+    size: u64,
+    /// Parsed lines and the fingerprint of the variables they were parsed
+    /// with.
+    items: Option<(u64, Arc<Vec<LineItem>>)>,
+}
+
+/// A line of a config file that matters to the keybind pass.
+#[derive(Debug)]
+enum LineItem {
+    /// A bind with variables substituted; its submap is filled in when the
+    /// files are put together.
+    Bind(Keybind),
+    Submap(Option<Arc<str>>),
+    Source {
+        line: usize,
+        path_str: String,
+    },
+    Problem(Diagnostic),
+}
+
+static FILE_CACHE: LazyLock<Mutex<HashMap<PathBuf, CachedFile>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn lock_file_cache() -> MutexGuard<'static, HashMap<PathBuf, CachedFile>> {
+    match FILE_CACHE.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn variables_fingerprint(variables: &HashMap<String, String>) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut pairs: Vec<_> = variables.iter().collect();
+    pairs.sort();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    pairs.hash(&mut hasher);
+    hasher.finish()
+}
+
 struct ConfigData {
     variables: HashMap<String, String>,
     defined_variables: Vec<Variable>,
     file_cache: HashMap<PathBuf, Arc<String>>,
     mtimes: HashMap<PathBuf, std::time::SystemTime>,
     sizes: HashMap<PathBuf, u64>,
+    /// Files that had to be read from disk again.
+    reread: usize,
 }
 
-fn load_config_data() -> Result<ConfigData> {
+fn load_config_data(files: &mut HashMap<PathBuf, CachedFile>) -> Result<ConfigData> {
     let main_path = get_config_path()?;
     let mut ctx = ParserContext::new();
 
@@ -215,8 +343,11 @@ fn load_config_data() -> Result<ConfigData> {
     let mut defined_variables = Vec::new();
     let mut mtimes: HashMap<PathBuf, std::time::SystemTime> = HashMap::new();
     let mut sizes: HashMap<PathBuf, u64> = HashMap::new();
+    let mut reread = 0;
 
     struct CollectState<'a> {
+        files: &'a mut HashMap<PathBuf, CachedFile>,
+        reread: &'a mut usize,
         file_cache: &'a mut HashMap<PathBuf, Arc<String>>,
         path_cache: &'a mut HashMap<PathBuf, Arc<PathBuf>>,
         defined_variables: &'a mut Vec<Variable>,
@@ -276,8 +407,30 @@ fn load_config_data() -> Result<ConfigData> {
             state.mtimes.insert(path.clone(), mtime);
             state.sizes.insert(path.clone(), metadata.len());
 
-            let s = std::fs::read_to_string(&path).unwrap_or_default();
-            let rc = Arc::new(s);
+            let rc = match state.files.get(&path) {
+                Some(file) if file.mtime == mtime && file.size == metadata.len() => {
+                    file.content.clone()
+                }
+                _ => {
+                    // Unreadable files are reported by the bind pass
+                    let Ok(s) = std::fs::read_to_string(&path) else {
+                        state.files.remove(&path);
+                        return Ok(());
+                    };
+                    *state.reread += 1;
+                    let rc = Arc::new(s);
+                    state.files.insert(
+                        path.clone(),
+                        CachedFile {
+                            content: rc.clone(),
+                            mtime,
+                            size: metadata.len(),
+                            items: None,
+                        },
+                    );
+                    rc
+                }
+            };
             state.file_cache.insert(path.clone(), rc.clone());
             rc
         };
@@ -385,6 +538,8 @@ fn load_config_data() -> Result<ConfigData> {
     let active_root = main_path.parent().unwrap_or(Path::new(".")).to_path_buf();
 
     let mut state = CollectState {
+        files,
+        reread: &mut reread,
         file_cache: &mut file_cache,
         path_cache: &mut path_cache,
         defined_variables: &mut defined_variables,
@@ -395,20 +550,26 @@ fn load_config_data() -> Result<ConfigData> {
     };
 
     collect_recursive(main_path, &mut ctx, &mut state)?;
+    // Forget files that are no longer sourced
+    files.retain(|path, _| file_cache.contains_key(path));
+
     Ok(ConfigData {
         variables: ctx.variables,
         defined_variables,
         file_cache,
         mtimes,
         sizes,
+        reread,
     })
 }
 
 struct CacheState {
     keybinds: Vec<Keybind>,
+    diagnostics: Vec<Diagnostic>,
     variables: HashMap<String, String>,
     defined_variables: Vec<Variable>,
     loaded_files: Vec<PathBuf>,
+    graph: SourceGraph,
     mtimes: HashMap<PathBuf, std::time::SystemTime>,
     sizes: HashMap<PathBuf, u64>,
     main_path: PathBuf,
@@ -416,10 +577,51 @@ struct CacheState {
 
 static GLOBAL_CACHE: Mutex<Option<Arc<CacheState>>> = Mutex::new(None);
 
+/// Forgets everything parsed, including the contents of every file. Writes
+/// made by hyprKCS itself use [`mark_files_changed`] instead.
 pub fn invalidate_parser_cache() {
     if let Ok(mut cache) = GLOBAL_CACHE.lock() {
         *cache = None;
     }
+    lock_file_cache().clear();
+}
+
+/// Marks `paths` as changed on disk, e.g. from file watcher events or an
+/// in-app save, so the next parse re-reads and re-parses them while reusing
+/// the contents and parsed lines of every other file. Files that source a
+/// changed file need nothing of their own: their `source` lines are
+/// expanded again on every parse.
+pub fn mark_files_changed(paths: &[PathBuf]) {
+    if let Ok(mut cache) = GLOBAL_CACHE.lock() {
+        *cache = None;
+    }
+    let mut files = lock_file_cache();
+    for path in paths {
+        files.remove(path);
+    }
+}
+
+/// Whether a change to `path` can affect the parsed config. Assumes it can
+/// when nothing has been parsed yet.
+pub fn affects_config(path: &Path) -> bool {
+    match GLOBAL_CACHE.lock() {
+        Ok(guard) => guard
+            .as_ref()
+            .is_none_or(|cache| cache.graph.is_relevant(path)),
+        Err(_) => true,
+    }
+}
+
+/// The `source` graph of the current config.
+pub fn get_source_graph() -> Result<SourceGraph> {
+    parse_config_with_diagnostics()?;
+    match get_valid_cache()? {
+        Some(cache) => Ok(cache.graph.clone()),
+        None => Ok(SourceGraph {
+            root: get_config_path()?,
+            edges: Vec::new(),
+        }),
+    }
 }
 
 fn get_valid_cache() -> Result<Option<Arc<CacheState>>> {
@@ -459,12 +661,24 @@ fn get_valid_cache() -> Result<Option<Arc<CacheState>>> {
 }
 
 pub fn parse_config() -> Result<Vec<Keybind>> {
+    parse_config_with_diagnostics().map(|(keybinds, _)| keybinds)
+}
+
+/// Problems found in the config by the last parse.
+pub fn get_diagnostics() -> Result<Vec<Diagnostic>> {
+    parse_config_with_diagnostics().map(|(_, diagnostics)| diagnostics)
+}
+
+/// Parses the config, also returning the lines that could not be parsed and
+/// `source` targets that could not be loaded.
+pub fn parse_config_with_diagnostics() -> Result<(Vec<Keybind>, Vec<Diagnostic>)> {
     if let Some(cache) = get_valid_cache()? {
-        return Ok(cache.keybinds.clone());
+        return Ok((cache.keybinds.clone(), cache.diagnostics.clone()));
     }
 
     let main_path = get_config_path()?;
-    let data = load_config_data()?;
+    let mut files = lock_file_cache();
+    let data = load_config_data(&mut files)?;
     let variables = data.variables;
     let file_cache = data.file_cache;
     let mtimes = data.mtimes;
@@ -474,291 +688,438 @@ pub fn parse_config() -> Result<Vec<Keybind>> {
     let mut sorted_keys: Vec<_> = variables.keys().cloned().collect();
     sorted_keys.sort_by_key(|b: &String| std::cmp::Reverse(b.len()));
 
-    let mut keybinds = Vec::new();
-    let mut visited = HashSet::new();
-    let mut current_submap: Option<Arc<str>> = None;
+    let system_root = config_dir()
+        .unwrap_or_default()
+        .join(crate::config::constants::HYPR_DIR);
+    let active_root = main_path
+        .parent()
+        .unwrap_or(&PathBuf::from("."))
+        .to_path_buf();
 
-    fn parse_recursive(
-        path: PathBuf,
-        keybinds: &mut Vec<Keybind>,
-        ctx: &RecursiveParseContext,
-        visited: &mut HashSet<PathBuf>,
-        current_submap: &mut Option<Arc<str>>,
-    ) -> Result<()> {
-        if visited.contains(&path) {
-            return Ok(());
-        }
-        if !path.exists() {
-            return Ok(());
+    let ctx = RecursiveParseContext {
+        variables: &variables,
+        sorted_keys: &sorted_keys,
+        fingerprint: variables_fingerprint(&variables),
+        system_root: &system_root,
+        active_root: &active_root,
+    };
+
+    let mut out = ParseOutput::default();
+    parse_recursive(main_path.clone(), &mut out, &ctx, &mut files)?;
+    drop(files);
+
+    let ParseOutput {
+        keybinds,
+        diagnostics,
+        edges,
+        reparsed,
+        ..
+    } = out;
+
+    for diagnostic in &diagnostics {
+        crate::log_info!("parser", "{}", diagnostic);
+    }
+
+    crate::log_debug!(
+        "parser",
+        "Parsed {} keybinds from {} files ({} re-read, {} re-parsed)",
+        keybinds.len(),
+        file_cache.len(),
+        data.reread,
+        reparsed
+    );
+
+    let loaded_files = file_cache.keys().cloned().collect();
+    let cache_state = Arc::new(CacheState {
+        keybinds: keybinds.clone(),
+        diagnostics: diagnostics.clone(),
+        variables: variables.clone(),
+        defined_variables,
+        loaded_files,
+        graph: SourceGraph {
+            root: main_path.clone(),
+            edges,
+        },
+        mtimes,
+        sizes,
+        main_path,
+    });
+
+    if let Ok(mut guard) = GLOBAL_CACHE.lock() {
+        *guard = Some(cache_state);
+    }
+
+    Ok((keybinds, diagnostics))
+}
+
+/// Keybinds, diagnostics and `source` edges collected while walking the
+/// config, in file order.
+#[derive(Default)]
+struct ParseOutput {
+    keybinds: Vec<Keybind>,
+    diagnostics: Vec<Diagnostic>,
+    edges: Vec<SourceEdge>,
+    visited: HashSet<PathBuf>,
+    current_submap: Option<Arc<str>>,
+    /// Files whose lines had to be parsed again rather than reused.
+    reparsed: usize,
+}
+
+fn parse_recursive(
+    path: PathBuf,
+    out: &mut ParseOutput,
+    ctx: &RecursiveParseContext,
+    files: &mut HashMap<PathBuf, CachedFile>,
+) -> Result<()> {
+    if out.visited.contains(&path) {
+        return Ok(());
+    }
+    if !path.exists() {
+        return Ok(());
+    }
+    out.visited.insert(path.clone());
+
+    if path.is_dir() {
+        if let Ok(entries) = std::fs::read_dir(&path) {
+            let mut paths: Vec<_> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+            paths.sort();
+            out.edges.push(SourceEdge {
+                from: path.clone(),
+                line: 0,
+                pattern: path.to_string_lossy().to_string(),
+                remapped_from: None,
+                targets: paths.clone(),
+            });
+            for sub_path in paths {
+                let _ = parse_recursive(sub_path, out, ctx, files);
+            }
         }
-        visited.insert(path.clone());
+        return Ok(());
+    }
 
-        if path.is_dir() {
-            if let Ok(entries) = std::fs::read_dir(&path) {
-                let mut paths: Vec<_> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
-                paths.sort();
-                for sub_path in paths {
-                    let _ = parse_recursive(sub_path, keybinds, ctx, visited, current_submap);
-                }
+    // Lines are reused as long as the file and the variables are unchanged
+    let items = match files.get_mut(&path) {
+        Some(file) => match &file.items {
+            Some((fingerprint, items)) if *fingerprint == ctx.fingerprint => items.clone(),
+            _ => {
+                let items = Arc::new(parse_lines(&path, &file.content, ctx));
+                file.items = Some((ctx.fingerprint, items.clone()));
+                out.reparsed += 1;
+                items
+            }
+        },
+        None => match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                out.reparsed += 1;
+                Arc::new(parse_lines(&path, &content, ctx))
+            }
+            Err(e) => {
+                out.diagnostics.push(Diagnostic::error(
+                    &path,
+                    0,
+                    format!("Could not read file: {}", e),
+                ));
+                return Ok(());
+            }
+        },
+    };
+
+    for item in items.iter() {
+        match item {
+            LineItem::Bind(kb) => {
+                let mut kb = kb.clone();
+                kb.submap = out.current_submap.clone();
+                out.keybinds.push(kb);
+            }
+            LineItem::Submap(submap) => out.current_submap = submap.clone(),
+            LineItem::Problem(diagnostic) => out.diagnostics.push(diagnostic.clone()),
+            LineItem::Source { line, path_str } => {
+                parse_source(&path, *line, path_str, out, ctx, files);
             }
-            return Ok(());
         }
+    }
+    Ok(())
+}
 
-        let content = if let Some(cached) = ctx.file_cache.get(&path) {
-            cached.clone()
-        } else {
-            Arc::new(std::fs::read_to_string(&path).unwrap_or_default())
-        };
+/// Follows a `source = ...` line, recording the files it pulled in.
+fn parse_source(
+    path: &Path,
+    line: usize,
+    path_str: &str,
+    out: &mut ParseOutput,
+    ctx: &RecursiveParseContext,
+    files: &mut HashMap<PathBuf, CachedFile>,
+) {
+    let mut sourced_path = expand_path(path_str, path, ctx.variables, ctx.sorted_keys);
+
+    if !sourced_path.exists() && sourced_path.ends_with(".conf") {
+        if let Some(parent) = sourced_path.parent() {
+            sourced_path = parent.join("*.conf");
+        }
+    }
 
-        let mut prev_line_trimmed: Option<&str> = None;
+    let mut remapped_from = None;
+    if ctx.system_root != ctx.active_root {
+        if let Ok(suffix) = sourced_path.strip_prefix(ctx.system_root) {
+            let remapped = ctx.active_root.join(suffix);
+            let remapped_str = remapped.to_string_lossy();
+            let is_glob = is_glob_pattern(&remapped_str);
+
+            if remapped.exists()
+                || (is_glob && glob(&remapped_str).is_ok_and(|mut p| p.next().is_some()))
+            {
+                remapped_from = Some(sourced_path.to_string_lossy().to_string());
+                sourced_path = remapped;
+            }
+        }
+    }
 
-        for (index, line) in content.lines().enumerate() {
-            let line_trimmed = line.trim();
+    let pattern = sourced_path.to_string_lossy().to_string();
+    let mut edge = SourceEdge {
+        from: path.to_path_buf(),
+        line,
+        pattern: pattern.clone(),
+        remapped_from,
+        targets: Vec::new(),
+    };
 
-            if !line_trimmed.is_empty() && !line_trimmed.starts_with('#') {
-                if let Some(rest) = line_trimmed.strip_prefix("submap") {
-                    let rest_trimmed = rest.trim_start();
-                    if let Some(val) = rest_trimmed.strip_prefix('=') {
-                        let name = val.split('#').next().unwrap_or("").trim();
-                        if name == "reset" {
-                            *current_submap = None;
-                        } else {
-                            *current_submap = Some(Arc::from(name));
-                        }
+    if !is_glob_pattern(&pattern) {
+        if sourced_path.exists() {
+            edge.targets.push(sourced_path.clone());
+        } else {
+            out.diagnostics.push(Diagnostic::error(
+                path,
+                line,
+                format!("Sourced file not found: {}", sourced_path.display()),
+            ));
+        }
+        out.edges.push(edge);
+        let _ = parse_recursive(sourced_path, out, ctx, files);
+    } else if let Ok(paths) = glob(&pattern) {
+        edge.targets = paths.flatten().collect();
+        if edge.targets.is_empty() {
+            out.diagnostics.push(Diagnostic::warning(
+                path,
+                line,
+                format!("Source pattern matched no files: {}", pattern),
+            ));
+        }
+        let targets = edge.targets.clone();
+        out.edges.push(edge);
+        for p in targets {
+            let _ = parse_recursive(p, out, ctx, files);
+        }
+    }
+}
+
+/// Parses the binds, submap switches and `source` lines of one file. The
+/// result only depends on the file's content and the variables, so it is
+/// cached per file.
+fn parse_lines(path: &Path, content: &str, ctx: &RecursiveParseContext) -> Vec<LineItem> {
+    let mut items = Vec::new();
+    let mut prev_line_trimmed: Option<&str> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_trimmed = line.trim();
+
+        if !line_trimmed.is_empty() && !line_trimmed.starts_with('#') {
+            if let Some(rest) = line_trimmed.strip_prefix("submap") {
+                let rest_trimmed = rest.trim_start();
+                if let Some(val) = rest_trimmed.strip_prefix('=') {
+                    let name = val.split('#').next().unwrap_or("").trim();
+                    if name == "reset" {
+                        items.push(LineItem::Submap(None));
+                    } else {
+                        items.push(LineItem::Submap(Some(Arc::from(name))));
                     }
-                } else if let Some(rest) = line_trimmed.strip_prefix("bind") {
-                    let rest = rest.trim_start();
-                    let flags: &str;
-                    let mut remaining = rest;
-
-                    if let Some(eq_idx) = remaining.find('=') {
-                        let potential_flags = remaining[..eq_idx].trim();
-                        if potential_flags.chars().all(|c| c.is_alphabetic()) {
-                            flags = potential_flags;
-                            remaining = &remaining[eq_idx + 1..];
-                        } else {
+                }
+            } else if let Some(rest) = line_trimmed.strip_prefix("bind") {
+                let rest = rest.trim_start();
+                let flags: &str;
+                let mut remaining = rest;
+
+                // `binds { ... }` and `binds:option = ...` set config
+                // options, they aren't binds
+                if rest
+                    .strip_prefix('s')
+                    .is_some_and(|r| r.trim_start().starts_with(['{', ':']))
+                {
+                    prev_line_trimmed = Some(line_trimmed);
+                    continue;
+                }
+
+                if let Some(eq_idx) = remaining.find('=') {
+                    let potential_flags = remaining[..eq_idx].trim();
+                    if let Some((severity, message)) = check_bind_flags(potential_flags) {
+                        items.push(LineItem::Problem(Diagnostic {
+                            file: path.to_path_buf(),
+                            line: index + 1,
+                            severity,
+                            message,
+                        }));
+                        if severity == Severity::Error {
                             prev_line_trimmed = Some(line_trimmed);
                             continue;
                         }
-                    } else {
-                        prev_line_trimmed = Some(line_trimmed);
-                        continue;
                     }
+                    flags = potential_flags;
+                    remaining = &remaining[eq_idx + 1..];
+                } else {
+                    items.push(LineItem::Problem(Diagnostic::error(
+                        path,
+                        index + 1,
+                        "Bind line has no '='",
+                    )));
+                    prev_line_trimmed = Some(line_trimmed);
+                    continue;
+                }
 
-                    let raw_content = remaining.trim();
-                    let mut description = None;
+                let raw_content = remaining.trim();
+                let mut description = None;
+
+                if let Some(idx) = line.find('#') {
+                    let comment = line[idx + 1..].trim();
+                    if !comment.is_empty() {
+                        description = Some(Arc::from(comment));
+                    }
+                }
 
-                    if let Some(idx) = line.find('#') {
-                        let comment = line[idx + 1..].trim();
+                if description.is_none() && index > 0 {
+                    let prev_line = prev_line_trimmed.unwrap_or("");
+                    if prev_line.starts_with('#') {
+                        let comment = prev_line.trim_start_matches('#').trim();
                         if !comment.is_empty() {
                             description = Some(Arc::from(comment));
                         }
                     }
+                }
 
-                    if description.is_none() && index > 0 {
-                        let prev_line = prev_line_trimmed.unwrap_or("");
-                        if prev_line.starts_with('#') {
-                            let comment = prev_line.trim_start_matches('#').trim();
-                            if !comment.is_empty() {
-                                description = Some(Arc::from(comment));
-                            }
-                        }
-                    }
+                let resolved_content =
+                    resolve_variables(raw_content, ctx.variables, ctx.sorted_keys);
+                let content_clean = resolved_content.split('#').next().unwrap_or("").trim();
 
-                    let resolved_content =
-                        resolve_variables(raw_content, ctx.variables, ctx.sorted_keys);
-                    let content_clean = resolved_content.split('#').next().unwrap_or("").trim();
-
-                    let mut parts = Vec::with_capacity(5);
-                    let mut current_part = String::with_capacity(32);
-                    let mut in_quote = false;
-                    let mut parts_count = 0;
-                    let is_bindd = flags == "d";
-                    let limit = if is_bindd { 4 } else { 3 };
-
-                    for c in content_clean.chars() {
-                        if parts_count < limit {
-                            if c == '"' {
-                                in_quote = !in_quote;
-                                current_part.push(c);
-                            } else if c == ',' && !in_quote {
-                                parts.push(current_part.trim().to_string());
-                                current_part.clear();
-                                parts_count += 1;
-                            } else {
-                                current_part.push(c);
-                            }
-                        } else {
-                            current_part.push(c);
-                        }
-                    }
+                let is_bindd = bind_has_description(flags);
+                let (ranges, in_quote) = split_bind_fields(content_clean, is_bindd);
+                let parts: Vec<&str> = ranges.into_iter().map(|r| &content_clean[r]).collect();
 
-                    if !current_part.trim().is_empty() || parts_count >= limit {
-                        parts.push(current_part.trim().to_string());
-                    }
+                if in_quote {
+                    items.push(LineItem::Problem(Diagnostic::warning(
+                        path,
+                        index + 1,
+                        "Unterminated quote",
+                    )));
+                }
 
-                    if parts.len() >= 3 {
-                        let mods: Arc<str>;
-                        let key: Arc<str>;
-                        let dispatcher: Arc<str>;
-                        let args: Arc<str>;
-
-                        if is_bindd {
-                            mods = Arc::from(parts[0].as_str());
-                            key = Arc::from(parts[1].as_str());
-                            if parts.len() > 2 {
-                                let desc_str = parts[2].trim();
-                                if !desc_str.is_empty() {
-                                    description = Some(Arc::from(desc_str));
-                                }
+                if parts.len() < 3 {
+                    items.push(LineItem::Problem(Diagnostic::error(
+                        path,
+                        index + 1,
+                        format!(
+                            "Expected modifiers, key and dispatcher but found {} field{}",
+                            parts.len(),
+                            if parts.len() == 1 { "" } else { "s" }
+                        ),
+                    )));
+                } else {
+                    let mods: Arc<str>;
+                    let key: Arc<str>;
+                    let dispatcher: Arc<str>;
+                    let args: Arc<str>;
+
+                    if is_bindd {
+                        mods = Arc::from(parts[0]);
+                        key = Arc::from(parts[1]);
+                        if parts.len() > 2 {
+                            let desc_str = parts[2].trim();
+                            if !desc_str.is_empty() {
+                                description = Some(Arc::from(desc_str));
                             }
-                            dispatcher = if parts.len() > 3 {
-                                Arc::from(parts[3].as_str())
-                            } else {
-                                Arc::from("")
-                            };
-                            args = if parts.len() > 4 {
-                                Arc::from(parts[4].as_str())
-                            } else {
-                                Arc::from("")
-                            };
-                        } else {
-                            mods = Arc::from(parts[0].as_str());
-                            key = Arc::from(parts[1].as_str());
-                            dispatcher = Arc::from(parts[2].as_str());
-                            args = if parts.len() > 3 {
-                                Arc::from(parts[3].as_str())
-                            } else {
-                                Arc::from("")
-                            };
                         }
-
-                        keybinds.push(Keybind {
-                            mods: mods.clone(),
-                            clean_mods: mods,
-                            flags: Arc::from(flags),
-                            key,
-                            dispatcher,
-                            args,
-                            description,
-                            submap: current_submap.clone(),
-                            line_number: index,
-                            file_path: path.clone(),
-                        });
+                        dispatcher = if parts.len() > 3 {
+                            Arc::from(parts[3])
+                        } else {
+                            Arc::from("")
+                        };
+                        args = if parts.len() > 4 {
+                            Arc::from(parts[4])
+                        } else {
+                            Arc::from("")
+                        };
+                    } else {
+                        mods = Arc::from(parts[0]);
+                        key = Arc::from(parts[1]);
+                        dispatcher = Arc::from(parts[2]);
+                        args = if parts.len() > 3 {
+                            Arc::from(parts[3])
+                        } else {
+                            Arc::from("")
+                        };
                     }
-                } else if let Some(rest) = line_trimmed.strip_prefix("source") {
-                    let trimmed_rest = rest.trim_start();
-                    if let Some(path_part) = trimmed_rest.strip_prefix('=') {
-                        let path_str = path_part
-                            .split('#')
-                            .next()
-                            .unwrap_or("")
-                            .trim()
-                            .trim_matches('"');
-
-                        let mut sourced_path =
-                            expand_path(path_str, &path, ctx.variables, ctx.sorted_keys);
-
-                        if !sourced_path.exists() && sourced_path.ends_with(".conf") {
-                            if let Some(parent) = sourced_path.parent() {
-                                sourced_path = parent.join("*.conf");
-                            }
-                        }
-
-                        if ctx.system_root != ctx.active_root {
-                            if let Ok(suffix) = sourced_path.strip_prefix(ctx.system_root) {
-                                let remapped = ctx.active_root.join(suffix);
-                                let remapped_str = remapped.to_string_lossy();
-                                let is_glob = is_glob_pattern(&remapped_str);
-
-                                if remapped.exists()
-                                    || (is_glob
-                                        && glob(&remapped_str)
-                                            .is_ok_and(|mut p| p.next().is_some()))
-                                {
-                                    sourced_path = remapped;
-                                }
-                            }
-                        }
 
-                        let pattern = sourced_path.to_string_lossy();
-                        if !is_glob_pattern(&pattern) {
-                            let _ = parse_recursive(
-                                sourced_path,
-                                keybinds,
-                                ctx,
-                                visited,
-                                current_submap,
-                            );
-                        } else if let Ok(paths) = glob(&pattern) {
-                            for p in paths.flatten() {
-                                let _ = parse_recursive(p, keybinds, ctx, visited, current_submap);
-                            }
-                        }
+                    if dispatcher.trim().is_empty() {
+                        items.push(LineItem::Problem(Diagnostic::warning(
+                            path,
+                            index + 1,
+                            "Bind has no dispatcher",
+                        )));
                     }
+
+                    items.push(LineItem::Bind(Keybind {
+                        mods: mods.clone(),
+                        clean_mods: mods,
+                        flags: Arc::from(flags),
+                        key,
+                        dispatcher,
+                        args,
+                        description,
+                        submap: None,
+                        line_number: index,
+                        file_path: path.to_path_buf(),
+                    }));
+                }
+            } else if let Some(rest) = line_trimmed.strip_prefix("source") {
+                let trimmed_rest = rest.trim_start();
+                if let Some(path_part) = trimmed_rest.strip_prefix('=') {
+                    let path_str = path_part
+                        .split('#')
+                        .next()
+                        .unwrap_or("")
+                        .trim()
+                        .trim_matches('"');
+                    items.push(LineItem::Source {
+                        line: index + 1,
+                        path_str: path_str.to_string(),
+                    });
                 }
             }
-
-            prev_line_trimmed = Some(line_trimmed);
         }
-        Ok(())
-    }
 
-    let system_root = config_dir()
-        .unwrap_or_default()
-        .join(crate::config::constants::HYPR_DIR);
-    let active_root = main_path
-        .parent()
-        .unwrap_or(&PathBuf::from("."))
-        .to_path_buf();
-
-    let ctx = RecursiveParseContext {
-        variables: &variables,
-        sorted_keys: &sorted_keys,
-        file_cache: &file_cache,
-        system_root: &system_root,
-        active_root: &active_root,
-    };
-
-    parse_recursive(
-        main_path.clone(),
-        &mut keybinds,
-        &ctx,
-        &mut visited,
-        &mut current_submap,
-    )?;
-
-    let loaded_files = file_cache.keys().cloned().collect();
-    let cache_state = Arc::new(CacheState {
-        keybinds: keybinds.clone(),
-        variables: variables.clone(),
-        defined_variables,
-        loaded_files,
-        mtimes,
-        sizes,
-        main_path,
-    });
-
-    if let Ok(mut guard) = GLOBAL_CACHE.lock() {
-        *guard = Some(cache_state);
+        prev_line_trimmed = Some(line_trimmed);
     }
-
-    Ok(keybinds)
+    items
 }
 
 pub fn get_variables() -> Result<HashMap<String, String>> {
     if let Some(cache) = get_valid_cache()? {
         return Ok(cache.variables.clone());
     }
-    let data = load_config_data()?;
+    let data = load_config_data(&mut lock_file_cache())?;
     Ok(data.variables)
 }
 
+/// Substitutes `$variables` in `input` the way the parser does, longest name
+/// first.
+pub fn substitute_variables(input: &str, vars: &HashMap<String, String>) -> String {
+    let mut sorted_keys: Vec<_> = vars.keys().cloned().collect();
+    sorted_keys.sort_by_key(|b: &String| std::cmp::Reverse(b.len()));
+    resolve_variables(input, vars, &sorted_keys)
+}
+
 pub fn get_defined_variables() -> Result<Vec<Variable>> {
     if let Some(cache) = get_valid_cache()? {
         return Ok(cache.defined_variables.clone());
     }
-    let data = load_config_data()?;
+    let data = load_config_data(&mut lock_file_cache())?;
     Ok(data.defined_variables)
 }
 
@@ -766,7 +1127,7 @@ pub fn get_loaded_files() -> Result<Vec<PathBuf>> {
     if let Some(cache) = get_valid_cache()? {
         return Ok(cache.loaded_files.clone());
     }
-    let data = load_config_data()?;
+    let data = load_config_data(&mut lock_file_cache())?;
     Ok(data.file_cache.keys().cloned().collect())
 }
 
@@ -836,7 +1197,7 @@ fn replace_variable_in_content(content: &str, old_name: &str, new_name: &str) ->
 pub fn rename_variable_references(old_name: &str, new_name: &str) -> Result<usize> {
     let files = get_loaded_files()?;
     let mut count = 0;
-    let mut modified_any = false;
+    let mut changed = Vec::new();
 
     for path in files {
         if !path.exists() {
@@ -848,14 +1209,13 @@ pub fn rename_variable_references(old_name: &str, new_name: &str) -> Result<usiz
 
         if modified {
             std::fs::write(&path, new_content)?;
+            crate::log_info!("write", "Updated variable references in {}", path.display());
             count += 1;
-            modified_any = true;
+            changed.push(path);
         }
     }
 
-    if modified_any {
-        invalidate_parser_cache();
-    }
+    mark_files_changed(&changed);
     Ok(count)
 }
 
@@ -889,7 +1249,7 @@ pub fn count_variable_references(name: &str) -> Result<usize> {
 pub fn inline_variable_references(name: &str, value: &str) -> Result<usize> {
     let files = get_loaded_files()?;
     let mut count = 0;
-    let mut modified_any = false;
+    let mut changed = Vec::new();
 
     // We are replacing $name with value
     let search_term = format!("${}", name.trim_start_matches('$'));
@@ -928,14 +1288,13 @@ pub fn inline_variable_references(name: &str, value: &str) -> Result<usize> {
 
         if modified {
             std::fs::write(&path, new_content)?;
+            crate::log_info!("write", "Updated variable references in {}", path.display());
             count += 1;
-            modified_any = true;
+            changed.push(path);
         }
     }
 
-    if modified_any {
-        invalidate_parser_cache();
-    }
+    mark_files_changed(&changed);
     Ok(count)
 }
 
@@ -1021,21 +1380,20 @@ pub fn write_lines<P: AsRef<Path>>(path: P, lines: &[String]) -> Result<()> {
     if !content.is_empty() {
         content.push('\n');
     }
-    std::fs::write(path, content).context("Failed to write to file")?;
-    invalidate_parser_cache();
+    std::fs::write(&path, content).context("Failed to write to file")?;
+    crate::log_info!(
+        "write",
+        "Wrote {} lines to {}",
+        lines.len(),
+        path.as_ref().display()
+    );
+    mark_files_changed(&[path.as_ref().to_path_buf()]);
     Ok(())
 }
 
-pub fn add_variable(path: PathBuf, name: &str, value: &str) -> Result<()> {
-    let content = std::fs::read_to_string(&path).unwrap_or_default();
-    let mut lines: Vec<String> = if content.is_empty() {
-        vec![]
-    } else {
-        content.lines().map(|s| s.to_string()).collect()
-    };
-
-    let new_line = format!("${} = {}", name.trim_start_matches('$'), value);
-
+/// Where a new variable definition goes: after the first block of
+/// variables, or at the top below any leading comments.
+fn variable_insert_index(lines: &[String]) -> usize {
     // Try to find a block of variables to append to
     let mut insert_idx = 0;
     let mut found_vars = false;
@@ -1050,7 +1408,7 @@ pub fn add_variable(path: PathBuf, name: &str, value: &str) -> Result<()> {
     }
 
     if found_vars {
-        lines.insert(insert_idx, new_line);
+        insert_idx
     } else {
         // No variables found, insert at top or after comments
         let mut top_idx = 0;
@@ -1060,8 +1418,20 @@ pub fn add_variable(path: PathBuf, name: &str, value: &str) -> Result<()> {
                 break;
             }
         }
-        lines.insert(top_idx, new_line);
+        top_idx
     }
+}
+
+pub fn add_variable(path: PathBuf, name: &str, value: &str) -> Result<()> {
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+    let mut lines: Vec<String> = if content.is_empty() {
+        vec![]
+    } else {
+        content.lines().map(|s| s.to_string()).collect()
+    };
+
+    let new_line = format!("${} = {}", name.trim_start_matches('$'), value);
+    lines.insert(variable_insert_index(&lines), new_line);
 
     write_lines(&path, &lines)
 }
@@ -1101,7 +1471,7 @@ pub fn delete_variable(path: PathBuf, line_number: usize) -> Result<()> {
 struct RecursiveParseContext<'a> {
     variables: &'a HashMap<String, String>,
     sorted_keys: &'a [String],
-    file_cache: &'a HashMap<PathBuf, Arc<String>>,
+    fingerprint: u64,
     system_root: &'a Path,
     active_root: &'a Path,
 }
@@ -1136,7 +1506,7 @@ pub fn update_line(
         if let Some(_eq_idx) = after_bind.find('=') {
             let current_flags = after_bind[.._eq_idx].trim();
             let flags = new_flags.unwrap_or(current_flags);
-            let is_bindd = flags == "d";
+            let is_bindd = bind_has_description(flags);
 
             let mut new_line = if is_bindd {
                 let desc_str = description.as_deref().unwrap_or("");
@@ -1183,6 +1553,71 @@ pub fn update_line(
     }
 }
 
+/// Makes sure `path` is part of the config, creating it and appending a
+/// `source` line to the main config when it isn't loaded yet. Returns whether
+/// a `source` line was added.
+pub fn ensure_sourced(path: &Path) -> Result<bool> {
+    let main_path = get_config_path()?;
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    let target = canonical(path);
+    if canonical(&main_path) == target
+        || get_loaded_files()
+            .unwrap_or_default()
+            .iter()
+            .any(|f| canonical(f) == target)
+    {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create directory")?;
+    }
+    if !path.exists() {
+        std::fs::write(path, "").context("Failed to create file")?;
+    }
+
+    let config_dir = main_path.parent().unwrap_or(Path::new("."));
+    let source_path = match path.strip_prefix(config_dir) {
+        Ok(relative) => relative.display().to_string(),
+        Err(_) => match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok()) {
+            Some(rest) => format!("~/{}", rest.display()),
+            None => path.display().to_string(),
+        },
+    };
+
+    let content = std::fs::read_to_string(&main_path).unwrap_or_default();
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    lines.push(format!("source = {}", source_path));
+    write_lines(&main_path, &lines)?;
+    Ok(true)
+}
+
+/// Makes sure Hyprland runs `command` at startup, appending an `exec-once`
+/// line to the main config unless a loaded file already has one running it.
+/// Returns whether a line was added.
+pub fn ensure_exec_once(command: &str) -> Result<bool> {
+    let main_path = get_config_path()?;
+    let mut files = get_loaded_files().unwrap_or_default();
+    files.push(main_path.clone());
+    let already_runs = files.iter().any(|file| {
+        std::fs::read_to_string(file)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("exec-once"))
+            .filter_map(|rest| rest.trim_start().strip_prefix('='))
+            .any(|value| value.contains(command))
+    });
+    if already_runs {
+        return Ok(false);
+    }
+
+    let content = std::fs::read_to_string(&main_path).unwrap_or_default();
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    lines.push(format!("exec-once = {}", command));
+    write_lines(&main_path, &lines)?;
+    Ok(true)
+}
+
 pub fn create_submap_block(
     path: PathBuf,
     name: &str,
@@ -1238,7 +1673,7 @@ pub fn add_keybind(
         content.lines().map(|s| s.to_string()).collect()
     };
 
-    let is_bindd = flags == "d";
+    let is_bindd = bind_has_description(flags);
     let bind_cmd = if flags.is_empty() {
         "bind".to_string()
     } else {
@@ -1337,6 +1772,12 @@ pub struct BatchUpdate {
     pub description: Option<String>,
 }
 
+/// The `description` a [`BatchUpdate`] needs to keep a bind's own: binds with
+/// a description field get it written back, the others keep their comment.
+pub fn kept_description(flags: &str, description: Option<&str>) -> Option<String> {
+    bind_has_description(flags).then(|| description.unwrap_or("").to_string())
+}
+
 pub fn update_multiple_lines(path: PathBuf, updates: Vec<BatchUpdate>) -> Result<()> {
     let content = std::fs::read_to_string(&path)?;
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
@@ -1356,7 +1797,7 @@ pub fn update_multiple_lines(path: PathBuf, updates: Vec<BatchUpdate>) -> Result
         if let Some(after_bind) = trimmed_start.strip_prefix("bind") {
             if let Some(eq_idx) = after_bind.find('=') {
                 let flags = after_bind[..eq_idx].trim();
-                let is_bindd = flags == "d";
+                let is_bindd = bind_has_description(flags);
                 let mut new_line = if is_bindd {
                     let desc_str = update.description.as_deref().unwrap_or("");
                     if update.new_args.trim().is_empty() {
@@ -0,0 +1,468 @@
+//! Suggests variables for literal values repeated across binds, such as the
+//! same modifiers, terminal or screenshot command, and rewrites the binds to
+//! use them.
+
+use super::variables::variable_names_in_line;
+use super::{
+    bind_line_fields, get_defined_variables, get_source_graph, parse_config, variable_insert_index,
+    write_lines, LoadOrder, Variable,
+};
+use anyhow::{bail, Result};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// How often a value has to appear before it is worth a variable.
+const MIN_REPEATS: usize = 2;
+
+/// Programs that are part of the desktop rather than a choice worth naming.
+const IGNORED_PROGRAMS: &[&str] = &["hyprctl", "sh", "bash"];
+
+/// Variable names for well-known programs, the way Hyprland's example
+/// config names them.
+const PROGRAM_ROLES: &[(&str, &[&str])] = &[
+    (
+        "terminal",
+        &[
+            "kitty",
+            "alacritty",
+            "foot",
+            "wezterm",
+            "ghostty",
+            "konsole",
+            "gnome-terminal",
+        ],
+    ),
+    (
+        "fileManager",
+        &["nautilus", "thunar", "dolphin", "nemo", "pcmanfm"],
+    ),
+    (
+        "browser",
+        &[
+            "firefox",
+            "chromium",
+            "brave",
+            "google-chrome-stable",
+            "zen-browser",
+        ],
+    ),
+    (
+        "menu",
+        &["rofi", "wofi", "fuzzel", "tofi", "walker", "anyrun"],
+    ),
+    (
+        "screenshot",
+        &["grim", "grimblast", "hyprshot", "flameshot", "slurp"],
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LiteralKind {
+    /// The whole modifier field, e.g. `SUPER SHIFT`.
+    Modifiers,
+    /// The program an `exec` bind starts, e.g. `kitty` in `kitty -e htop`.
+    Program,
+    /// A whole `exec` command line.
+    Command,
+}
+
+impl LiteralKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            LiteralKind::Modifiers => "Modifiers",
+            LiteralKind::Program => "Program",
+            LiteralKind::Command => "Command",
+        }
+    }
+}
+
+/// A bind line, 0-based like [`Variable::line_number`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub file_path: PathBuf,
+    pub line_number: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableSuggestion {
+    pub kind: LiteralKind,
+    pub value: String,
+    /// Without the `$`.
+    pub name: String,
+    /// `name` is an existing variable that already holds `value`.
+    pub existing: bool,
+    /// In load order.
+    pub occurrences: Vec<Occurrence>,
+}
+
+/// Where the program name sits in an `exec` command, after any
+/// `[window rules]`.
+fn program_range(command: &str) -> Option<Range<usize>> {
+    let start = if command.starts_with('[') {
+        let end = command.find(']')? + 1;
+        end + (command[end..].len() - command[end..].trim_start().len())
+    } else {
+        0
+    };
+    let len = command[start..]
+        .find(char::is_whitespace)
+        .unwrap_or(command.len() - start);
+    (len > 1).then_some(start..start + len)
+}
+
+/// Where `kind`'s literal is on `line`, if it has one.
+fn literal_range(line: &str, kind: LiteralKind) -> Option<Range<usize>> {
+    let fields = bind_line_fields(line)?;
+    let range = match kind {
+        LiteralKind::Modifiers => fields.mods,
+        LiteralKind::Command | LiteralKind::Program => {
+            if !matches!(&line[fields.dispatcher], "exec" | "execr") {
+                return None;
+            }
+            match kind {
+                LiteralKind::Program => {
+                    let args = fields.args?;
+                    let program = program_range(&line[args.clone()])?;
+                    args.start + program.start..args.start + program.end
+                }
+                _ => fields.args?,
+            }
+        }
+    };
+    let value = &line[range.clone()];
+    (!value.is_empty() && variable_names_in_line(value).is_empty() && !value.contains('#'))
+        .then_some(range)
+}
+
+fn camel_case(words: &[&str]) -> String {
+    let mut name = String::new();
+    for (i, word) in words.iter().enumerate() {
+        let mut chars = word.chars().filter(|c| c.is_alphanumeric());
+        if let Some(first) = chars.next() {
+            if i == 0 {
+                name.extend(first.to_lowercase());
+            } else {
+                name.extend(first.to_uppercase());
+            }
+            name.extend(chars.flat_map(char::to_lowercase));
+        }
+    }
+    name
+}
+
+fn program_name(program: &str) -> String {
+    let base = program.rsplit('/').next().unwrap_or(program);
+    PROGRAM_ROLES
+        .iter()
+        .find(|(_, programs)| programs.contains(&base))
+        .map(|(role, _)| role.to_string())
+        .unwrap_or_else(|| camel_case(&base.split(['-', '_', '.']).collect::<Vec<_>>()))
+}
+
+fn suggested_name(kind: LiteralKind, value: &str) -> String {
+    match kind {
+        LiteralKind::Modifiers => {
+            let mods: Vec<&str> = value
+                .split(|c: char| c.is_whitespace() || c == '_')
+                .filter(|m| !m.is_empty())
+                .collect();
+            if mods.len() == 1 && mods[0].eq_ignore_ascii_case("SUPER") {
+                "mainMod".to_string()
+            } else {
+                format!("{}Mod", camel_case(&mods))
+            }
+        }
+        LiteralKind::Program => program_name(value),
+        LiteralKind::Command => {
+            let program = program_range(value).map_or(value, |r| &value[r]);
+            format!("{}Cmd", program_name(program))
+        }
+    }
+}
+
+/// Whether `var` can stand in for `value` at `first`: its last definition
+/// holds that value and is read before `first`.
+fn reusable(
+    var: &Variable,
+    defined: &[Variable],
+    value: &str,
+    first: &Occurrence,
+    order: &LoadOrder,
+) -> bool {
+    let Some(last) = defined.iter().rev().find(|v| v.name == var.name) else {
+        return false;
+    };
+    let defined_at = order.position(&last.file_path, last.line_number);
+    let used_at = order.position(&first.file_path, first.line_number);
+    last.value.trim() == value && defined_at.is_some() && defined_at < used_at
+}
+
+/// Finds values repeated across `bind_lines` (file, 0-based line, text, in
+/// load order). A value an existing variable already holds is suggested
+/// under that variable's name, if `order` reads its definition before the
+/// value is first used and no later definition changes it.
+pub fn suggest_variables(
+    bind_lines: &[(&Path, usize, &str)],
+    defined: &[Variable],
+    order: &LoadOrder,
+) -> Vec<VariableSuggestion> {
+    let mut found: BTreeMap<(LiteralKind, String), Vec<Occurrence>> = BTreeMap::new();
+    for &(path, line_number, line) in bind_lines {
+        for kind in [
+            LiteralKind::Modifiers,
+            LiteralKind::Program,
+            LiteralKind::Command,
+        ] {
+            let Some(range) = literal_range(line, kind) else {
+                continue;
+            };
+            found
+                .entry((kind, line[range].to_string()))
+                .or_default()
+                .push(Occurrence {
+                    file_path: path.to_path_buf(),
+                    line_number,
+                });
+        }
+    }
+
+    let mut taken: HashSet<String> = defined
+        .iter()
+        .map(|v| v.name.trim_start_matches('$').to_string())
+        .collect();
+    let mut suggestions: Vec<VariableSuggestion> = found
+        .into_iter()
+        .filter(|((kind, value), occurrences)| {
+            occurrences.len() >= MIN_REPEATS
+                && match kind {
+                    LiteralKind::Program => !IGNORED_PROGRAMS.contains(&value.as_str()),
+                    // A bare program is already suggested as a Program
+                    LiteralKind::Command => {
+                        program_range(value).is_some_and(|r| r != (0..value.len()))
+                    }
+                    LiteralKind::Modifiers => true,
+                }
+        })
+        .map(|((kind, value), occurrences)| VariableSuggestion {
+            kind,
+            name: String::new(),
+            value,
+            existing: false,
+            occurrences,
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.occurrences
+            .len()
+            .cmp(&a.occurrences.len())
+            .then(a.kind.cmp(&b.kind))
+            .then(a.value.cmp(&b.value))
+    });
+
+    for suggestion in &mut suggestions {
+        let existing = suggestion.occurrences.first().and_then(|first| {
+            defined
+                .iter()
+                .find(|v| reusable(v, defined, &suggestion.value, first, order))
+        });
+        if let Some(var) = existing {
+            suggestion.name = var.name.trim_start_matches('$').to_string();
+            suggestion.existing = true;
+            continue;
+        }
+        let base = suggested_name(suggestion.kind, &suggestion.value);
+        let mut name = base.clone();
+        let mut n = 2;
+        while taken.contains(&name) {
+            name = format!("{}{}", base, n);
+            n += 1;
+        }
+        taken.insert(name.clone());
+        suggestion.name = name;
+    }
+    suggestions
+}
+
+/// Reads every loaded config file.
+fn read_loaded_files() -> Result<BTreeMap<PathBuf, Vec<String>>> {
+    let mut files = BTreeMap::new();
+    for path in super::get_loaded_files()? {
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            files.insert(path, content.lines().map(|s| s.to_string()).collect());
+        }
+    }
+    Ok(files)
+}
+
+/// Suggestions for the loaded config.
+pub fn variable_suggestions() -> Result<Vec<VariableSuggestion>> {
+    let files = read_loaded_files()?;
+    let keybinds = parse_config()?;
+    let bind_lines: Vec<(&Path, usize, &str)> = keybinds
+        .iter()
+        .filter_map(|kb| {
+            let line = files.get(&kb.file_path)?.get(kb.line_number)?;
+            Some((kb.file_path.as_path(), kb.line_number, line.as_str()))
+        })
+        .collect();
+    Ok(suggest_variables(
+        &bind_lines,
+        &get_defined_variables()?,
+        &get_source_graph()?.load_order(),
+    ))
+}
+
+/// One line a refactor changes or adds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineChange {
+    pub file_path: PathBuf,
+    /// 0-based, in the file before the refactor. Added lines go before it.
+    pub line_number: usize,
+    /// `None` for an added definition.
+    pub before: Option<String>,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RefactorPlan {
+    pub changes: Vec<LineChange>,
+    /// The new contents of every file that changes.
+    pub files: BTreeMap<PathBuf, Vec<String>>,
+}
+
+/// Rewrites the occurrences of each suggestion in `files` to use its
+/// variable, and defines the new variables before their first use in load
+/// order. Lines that no longer hold the value are left alone.
+pub fn plan_refactor(
+    files: &BTreeMap<PathBuf, Vec<String>>,
+    suggestions: &[VariableSuggestion],
+) -> RefactorPlan {
+    let mut plan = RefactorPlan::default();
+    let mut edited: HashMap<(PathBuf, usize), String> = HashMap::new();
+    let mut definitions: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+
+    for suggestion in suggestions {
+        let mut used = false;
+        for occurrence in &suggestion.occurrences {
+            let key = (occurrence.file_path.clone(), occurrence.line_number);
+            let Some(current) = edited.get(&key).cloned().or_else(|| {
+                files
+                    .get(&occurrence.file_path)?
+                    .get(occurrence.line_number)
+                    .cloned()
+            }) else {
+                continue;
+            };
+            let Some(range) = literal_range(&current, suggestion.kind) else {
+                continue;
+            };
+            if current[range.clone()] != suggestion.value {
+                continue;
+            }
+            let mut line = current;
+            line.replace_range(range, &format!("${}", suggestion.name));
+            edited.insert(key, line);
+            used = true;
+        }
+        if used && !suggestion.existing {
+            if let Some(first) = suggestion.occurrences.first() {
+                definitions
+                    .entry(first.file_path.clone())
+                    .or_default()
+                    .push(format!("${} = {}", suggestion.name, suggestion.value));
+            }
+        }
+    }
+
+    let mut edited: Vec<((PathBuf, usize), String)> = edited.into_iter().collect();
+    edited.sort_by(|a, b| a.0.cmp(&b.0));
+    for ((path, line_number), after) in edited {
+        let Some(lines) = files.get(&path) else {
+            continue;
+        };
+        plan.changes.push(LineChange {
+            file_path: path.clone(),
+            line_number,
+            before: Some(lines[line_number].clone()),
+            after: after.clone(),
+        });
+        plan.files.entry(path).or_insert_with(|| lines.clone())[line_number] = after;
+    }
+
+    for (path, new_lines) in definitions {
+        let Some(lines) = plan.files.get_mut(&path) else {
+            continue;
+        };
+        // Before the first changed line, so Hyprland knows them by then
+        let first_use = plan
+            .changes
+            .iter()
+            .filter(|c| c.file_path == path)
+            .map(|c| c.line_number)
+            .min()
+            .unwrap_or(lines.len());
+        let at = variable_insert_index(lines).min(first_use);
+        for (i, line) in new_lines.iter().enumerate() {
+            plan.changes.push(LineChange {
+                file_path: path.clone(),
+                line_number: at,
+                before: None,
+                after: line.clone(),
+            });
+            lines.insert(at + i, line.clone());
+        }
+    }
+    plan.changes.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then(a.line_number.cmp(&b.line_number))
+            .then(a.before.is_some().cmp(&b.before.is_some()))
+    });
+    plan
+}
+
+/// Plans `suggestions` against the current config files.
+pub fn plan_variable_refactor(suggestions: &[VariableSuggestion]) -> Result<RefactorPlan> {
+    Ok(plan_refactor(&read_loaded_files()?, suggestions))
+}
+
+/// Fails if `path` changed on disk since `plan` was made, so writing its
+/// planned contents would undo that change.
+fn check_unchanged(plan: &RefactorPlan, path: &Path, planned: &[String]) -> Result<()> {
+    let content = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let added = plan
+        .changes
+        .iter()
+        .filter(|c| c.file_path == path && c.before.is_none())
+        .count();
+    let matches = lines.len() + added == planned.len()
+        && plan
+            .changes
+            .iter()
+            .filter(|c| c.file_path == path)
+            .all(|c| match &c.before {
+                Some(before) => lines.get(c.line_number) == Some(&before.as_str()),
+                None => true,
+            });
+    if !matches {
+        bail!(
+            "{} changed since the refactor was planned, scan again",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Writes a plan. Returns how many files changed. Nothing is written if any
+/// of the files changed since the plan was made.
+pub fn apply_refactor(plan: &RefactorPlan) -> Result<usize> {
+    for (path, lines) in &plan.files {
+        check_unchanged(plan, path, lines)?;
+    }
+    for (path, lines) in &plan.files {
+        write_lines(path, lines)?;
+    }
+    Ok(plan.files.len())
+}
@@ -0,0 +1,395 @@
+//! Editing submaps as a whole: renaming, deleting, moving binds between them,
+//! and checking that every submap can be entered and left again.
+//!
+//! The `*_lines` functions work on the lines of one file; the others apply
+//! them to every loaded config file.
+
+use super::{bind_line_fields, get_loaded_files, write_lines, Keybind};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// The name declared by a `submap = name` line, `reset` included.
+pub fn submap_declaration(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix("submap")?.trim_start();
+    let value = rest.strip_prefix('=')?;
+    Some(value.split('#').next().unwrap_or("").trim())
+}
+
+/// Byte range of the target in a `bind = ..., submap, target` line.
+fn submap_dispatch_target(line: &str) -> Option<Range<usize>> {
+    let fields = bind_line_fields(line)?;
+    if line[fields.dispatcher] != *"submap" {
+        return None;
+    }
+    fields.args
+}
+
+/// What precedes a submap name in `exec` commands that switch to it.
+const DISPATCH_SUBMAP: &str = "dispatch submap";
+/// What precedes a submap name in a one-shot which-key popup command.
+const WHICH_KEY_ARG: &str = "--which-key";
+
+/// Byte ranges of the names following each `marker` in `command`, up to the
+/// next shell separator or quote. Empty names are left out.
+fn names_after<'a>(command: &'a str, marker: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+    command.match_indices(marker).filter_map(move |(i, _)| {
+        let start = i + marker.len();
+        let name = command[start..]
+            .split([';', '&', '\'', '"'])
+            .next()
+            .unwrap_or("");
+        let start = start + (name.len() - name.trim_start().len());
+        let range = start..start + name.trim().len();
+        (!range.is_empty()).then_some(range)
+    })
+}
+
+/// Byte ranges of the submap names after `marker` in a bind line's `exec`
+/// command.
+fn exec_submap_targets(line: &str, marker: &str) -> Vec<Range<usize>> {
+    let Some(fields) = bind_line_fields(line) else {
+        return Vec::new();
+    };
+    match fields.args {
+        Some(args) if line[fields.dispatcher.clone()] == *"exec" => {
+            names_after(&line[args.clone()], marker)
+                .map(|r| args.start + r.start..args.start + r.end)
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The submap a bind line switches to, if it uses the `submap` dispatcher.
+pub fn submap_dispatch(line: &str) -> Option<&str> {
+    submap_dispatch_target(line).map(|range| &line[range])
+}
+
+/// Renames the `submap = old` blocks, every `submap, old` dispatch, and `old`
+/// in `exec` commands running `hyprctl dispatch submap old` or
+/// `hyprkcs --which-key old`. Returns how many lines changed.
+pub fn rename_submap_lines(lines: &mut [String], old: &str, new: &str) -> usize {
+    let mut changed = 0;
+    for line in lines.iter_mut() {
+        if submap_declaration(line) == Some(old) {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let comment = line.find('#').map(|i| format!(" {}", &line[i..]));
+            *line = format!("{}submap = {}{}", indent, new, comment.unwrap_or_default());
+            changed += 1;
+        } else if let Some(range) = submap_dispatch_target(line) {
+            if line[range.clone()] == *old {
+                line.replace_range(range, new);
+                changed += 1;
+            }
+        } else {
+            let mut targets: Vec<Range<usize>> = [DISPATCH_SUBMAP, WHICH_KEY_ARG]
+                .iter()
+                .flat_map(|marker| exec_submap_targets(line, marker))
+                .filter(|range| line[range.clone()] == *old)
+                .collect();
+            if targets.is_empty() {
+                continue;
+            }
+            // Last first, so the earlier ranges stay valid
+            targets.sort_by_key(|range| std::cmp::Reverse(range.start));
+            for range in targets {
+                line.replace_range(range, new);
+            }
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Removes the `submap = name` blocks with everything in them, and the binds
+/// that switch to the submap, `exec, hyprctl dispatch submap name` ones
+/// included. Returns how many bind lines were removed.
+pub fn delete_submap_lines(lines: &mut Vec<String>, name: &str) -> usize {
+    let is_bind = |line: &str| line.trim_start().starts_with("bind");
+    let mut removed = 0;
+    let mut kept = Vec::with_capacity(lines.len());
+    let mut in_block = false;
+
+    for line in lines.drain(..) {
+        match submap_declaration(&line) {
+            Some(declared) if declared == name => {
+                // Drop the blank line that separated the block
+                if kept.last().is_some_and(|l: &String| l.trim().is_empty()) {
+                    kept.pop();
+                }
+                in_block = true;
+                continue;
+            }
+            Some("reset") if in_block => {
+                in_block = false;
+                continue;
+            }
+            Some(_) => in_block = false,
+            None if in_block => {
+                removed += usize::from(is_bind(&line));
+                continue;
+            }
+            None if submap_dispatch(&line) == Some(name)
+                || exec_submap_targets(&line, DISPATCH_SUBMAP)
+                    .into_iter()
+                    .any(|range| line[range] == *name) =>
+            {
+                removed += 1;
+                continue;
+            }
+            None => {}
+        }
+        kept.push(line);
+    }
+    *lines = kept;
+    removed
+}
+
+/// Moves the bind lines at `line_numbers` (0-based) into the `target`
+/// submap, or out of any submap for `None`. A comment line right above a
+/// bind moves with it, since it is the bind's description. Returns the new
+/// line numbers, in the order given.
+pub fn move_lines_to_submap(
+    lines: &mut Vec<String>,
+    line_numbers: &[usize],
+    target: Option<&str>,
+) -> Vec<usize> {
+    let mut sorted: Vec<usize> = line_numbers
+        .iter()
+        .copied()
+        .filter(|&n| n < lines.len())
+        .collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    // Take the lines out, last first so earlier numbers stay valid
+    let mut moved: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for &n in sorted.iter().rev() {
+        let has_comment = !lines[n].contains('#')
+            && n > 0
+            && lines[n - 1].trim_start().starts_with('#')
+            && !sorted.contains(&(n - 1));
+        let unit: Vec<String> = if has_comment {
+            lines.drain(n - 1..=n).collect()
+        } else {
+            vec![lines.remove(n)]
+        };
+        moved.insert(n, unit);
+    }
+
+    let insert_at = match target {
+        Some(name) => {
+            let declared = lines
+                .iter()
+                .position(|l| submap_declaration(l) == Some(name));
+            match declared {
+                Some(start) => lines
+                    .iter()
+                    .skip(start + 1)
+                    .position(|l| submap_declaration(l).is_some())
+                    .map_or(lines.len(), |i| start + 1 + i),
+                None => {
+                    if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                        lines.push(String::new());
+                    }
+                    lines.push(format!("submap = {}", name));
+                    lines.push("submap = reset".to_string());
+                    lines.len() - 1
+                }
+            }
+        }
+        None => {
+            let inside_block = lines
+                .iter()
+                .rev()
+                .find_map(|l| submap_declaration(l))
+                .is_some_and(|name| name != "reset");
+            if inside_block {
+                lines.push("submap = reset".to_string());
+            }
+            lines.len()
+        }
+    };
+
+    let mut new_numbers = HashMap::new();
+    let mut at = insert_at;
+    for (old, unit) in moved {
+        new_numbers.insert(old, at + unit.len() - 1);
+        let len = unit.len();
+        lines.splice(at..at, unit);
+        at += len;
+    }
+    line_numbers
+        .iter()
+        .map(|n| new_numbers.get(n).copied().unwrap_or(*n))
+        .collect()
+}
+
+/// Applies `edit` to every loaded config file and writes the ones where it
+/// returned a non-zero count. Returns the sum of the counts.
+fn edit_loaded_files(mut edit: impl FnMut(&mut Vec<String>) -> usize) -> Result<usize> {
+    let mut total = 0;
+    for path in get_loaded_files()? {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+        let count = edit(&mut lines);
+        if count > 0 {
+            write_lines(&path, &lines)?;
+            total += count;
+        }
+    }
+    Ok(total)
+}
+
+/// Renames a submap in every config file. Returns how many lines changed.
+pub fn rename_submap(old: &str, new: &str) -> Result<usize> {
+    let new = new.trim();
+    if new.is_empty() || new == "reset" || new.contains([',', '#']) {
+        anyhow::bail!("'{}' can't be used as a submap name", new);
+    }
+    edit_loaded_files(|lines| rename_submap_lines(lines, old, new))
+}
+
+/// Deletes a submap, its binds and the binds entering it from every config
+/// file. Returns how many binds were removed.
+pub fn delete_submap(name: &str) -> Result<usize> {
+    edit_loaded_files(|lines| {
+        let before = lines.len();
+        let removed = delete_submap_lines(lines, name);
+        // Count a block without binds as a change too
+        removed.max(usize::from(lines.len() != before))
+    })
+}
+
+/// Moves binds into `target` (`None` for no submap), each within its own
+/// file. Returns where each bind ended up, in the order given.
+pub fn move_keybinds_to_submap(
+    binds: &[(PathBuf, usize)],
+    target: Option<&str>,
+) -> Result<Vec<(PathBuf, usize)>> {
+    let mut by_file: BTreeMap<&PathBuf, Vec<usize>> = BTreeMap::new();
+    for (path, line) in binds {
+        by_file.entry(path).or_default().push(*line);
+    }
+
+    let mut moved: HashMap<(PathBuf, usize), usize> = HashMap::new();
+    for (path, line_numbers) in by_file {
+        let content = std::fs::read_to_string(path)?;
+        let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+        let new_numbers = move_lines_to_submap(&mut lines, &line_numbers, target);
+        write_lines(path, &lines)?;
+        for (old, new) in line_numbers.into_iter().zip(new_numbers) {
+            moved.insert((path.clone(), old), new);
+        }
+    }
+    Ok(binds
+        .iter()
+        .map(|(path, line)| {
+            let new = moved.get(&(path.clone(), *line)).copied().unwrap_or(*line);
+            (path.clone(), new)
+        })
+        .collect())
+}
+
+/// Whether a submap can be entered and left again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmapHealth {
+    pub name: String,
+    pub bind_count: usize,
+    /// A bind outside the submap switches to it.
+    pub has_entry: bool,
+    /// A bind inside the submap switches to another one or resets.
+    pub has_exit: bool,
+}
+
+impl SubmapHealth {
+    /// Binds switch to it, but it has no binds of its own.
+    pub fn is_undefined(&self) -> bool {
+        self.bind_count == 0
+    }
+
+    /// Once entered there is no way out except restarting Hyprland.
+    pub fn traps(&self) -> bool {
+        self.has_entry && !self.has_exit
+    }
+
+    pub fn problems(&self) -> Vec<&'static str> {
+        let mut problems = Vec::new();
+        if self.is_undefined() {
+            problems.push("No binds are defined in it");
+        }
+        if !self.has_entry {
+            problems.push("No bind enters it");
+        }
+        if !self.is_undefined() && !self.has_exit {
+            problems.push("No bind leaves it, it would trap you");
+        }
+        problems
+    }
+}
+
+/// The submap `kb` switches to, also through `hyprctl dispatch submap`.
+/// `reset` leaves the submap.
+pub fn switches_to(kb: &Keybind) -> Option<&str> {
+    match &*kb.dispatcher {
+        "submap" => Some(kb.args.trim()),
+        "exec" => names_after(&kb.args, DISPATCH_SUBMAP)
+            .next()
+            .map(|range| &kb.args[range]),
+        _ => None,
+    }
+}
+
+/// Checks every submap that has binds or is switched to. The default
+/// submap from the settings is where Hyprland starts, so it needs neither
+/// an entry nor an exit.
+pub fn submap_health(keybinds: &[Keybind], default_submap: Option<&str>) -> Vec<SubmapHealth> {
+    let mut submaps: BTreeMap<String, SubmapHealth> = BTreeMap::new();
+    let mut add = |name: &str| {
+        submaps
+            .entry(name.to_string())
+            .or_insert_with(|| SubmapHealth {
+                name: name.to_string(),
+                bind_count: 0,
+                has_entry: false,
+                has_exit: false,
+            });
+    };
+    for kb in keybinds {
+        if let Some(name) = kb.submap.as_deref() {
+            add(name);
+        }
+        if let Some(target) = switches_to(kb).filter(|t| !t.is_empty() && *t != "reset") {
+            add(target);
+        }
+    }
+
+    for kb in keybinds {
+        let current = kb.submap.as_deref();
+        if let Some(health) = current.and_then(|name| submaps.get_mut(name)) {
+            health.bind_count += 1;
+        }
+        let Some(target) = switches_to(kb).filter(|t| !t.is_empty()) else {
+            continue;
+        };
+        if Some(target) == current {
+            continue;
+        }
+        if let Some(health) = current.and_then(|name| submaps.get_mut(name)) {
+            health.has_exit = true;
+        }
+        if let Some(health) = submaps.get_mut(target) {
+            health.has_entry = true;
+        }
+    }
+
+    if let Some(health) = default_submap.and_then(|name| submaps.get_mut(name)) {
+        health.has_entry = true;
+        health.has_exit = true;
+    }
+    submaps.into_values().collect()
+}
@@ -0,0 +1,204 @@
+//! Where variables are used: every `$name` reference in the loaded config
+//! files, and the variables that are unused, undefined or redefined.
+
+use super::{get_defined_variables, get_loaded_files, split_comment, Variable};
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Set by hyprKCS itself to the config directory, like Hyprland does.
+const BUILTIN_VARIABLES: &[&str] = &["$hypr"];
+
+/// A `$name` on a config line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableReference {
+    /// With the `$`.
+    pub name: String,
+    pub file_path: PathBuf,
+    /// 0-based, like [`Variable::line_number`].
+    pub line_number: usize,
+    /// The line, trimmed.
+    pub line: String,
+}
+
+/// The `$name` references on `line`, without the name a definition line
+/// defines and without comments. Shell-style `$1`, `${x}` and `$(cmd)` are
+/// not variables.
+pub fn variable_names_in_line(line: &str) -> Vec<&str> {
+    let (code, _) = split_comment(line);
+    let definition_end = code
+        .trim_start()
+        .starts_with('$')
+        .then(|| code.find('='))
+        .flatten();
+
+    let mut names = Vec::new();
+    for (idx, _) in code.match_indices('$') {
+        if definition_end.is_some_and(|end| idx < end) {
+            continue;
+        }
+        let rest = &code[idx + 1..];
+        let len = rest
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        let name = &code[idx..idx + 1 + len];
+        if rest[..len].starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Every variable reference in `content`.
+pub fn variable_references_in(content: &str, path: &Path) -> Vec<VariableReference> {
+    content
+        .lines()
+        .enumerate()
+        .flat_map(|(line_number, line)| {
+            variable_names_in_line(line)
+                .into_iter()
+                .map(move |name| VariableReference {
+                    name: name.to_string(),
+                    file_path: path.to_path_buf(),
+                    line_number,
+                    line: line.trim().to_string(),
+                })
+        })
+        .collect()
+}
+
+/// Every variable reference in the loaded config files, by file and line.
+pub fn collect_variable_references() -> Result<Vec<VariableReference>> {
+    let mut references = Vec::new();
+    for path in get_loaded_files()? {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        references.extend(variable_references_in(&content, &path));
+    }
+    references.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then(a.line_number.cmp(&b.line_number))
+    });
+    Ok(references)
+}
+
+/// A definition that a later one of the same name replaces.
+#[derive(Debug, Clone)]
+pub struct ShadowedVariable {
+    pub shadowed: Variable,
+    pub by: Variable,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VariableReport {
+    pub references: Vec<VariableReference>,
+    /// Defined, but no line refers to them.
+    pub unused: Vec<Variable>,
+    /// References no definition matches. Names set in the environment are
+    /// left out, since the shell expands those in `exec` commands.
+    pub undefined: Vec<VariableReference>,
+    pub shadowed: Vec<ShadowedVariable>,
+    /// Every defined name, built-ins included.
+    names: Vec<String>,
+}
+
+impl VariableReport {
+    /// The variable a reference uses. Hyprland substitutes the longest
+    /// defined name it starts with, so `$modShift` uses `$mod` when only
+    /// that exists.
+    pub fn resolves_to(&self, reference: &str) -> Option<&str> {
+        self.names
+            .iter()
+            .filter(|name| reference.starts_with(name.as_str()))
+            .max_by_key(|name| name.len())
+            .map(String::as_str)
+    }
+
+    /// The references using `name` (with the `$`).
+    pub fn references_to<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a VariableReference> + 'a {
+        self.references
+            .iter()
+            .filter(move |r| self.resolves_to(&r.name) == Some(name))
+    }
+
+    pub fn is_unused(&self, var: &Variable) -> bool {
+        self.unused.iter().any(|v| v.name == var.name)
+    }
+
+    /// The definition replacing `var`, if a later one does.
+    pub fn shadowed_by(&self, var: &Variable) -> Option<&Variable> {
+        self.shadowed
+            .iter()
+            .find(|s| {
+                s.shadowed.file_path == var.file_path && s.shadowed.line_number == var.line_number
+            })
+            .map(|s| &s.by)
+    }
+}
+
+/// Checks `defined` (in load order) against `references`. `in_environment`
+/// tells whether a name (without the `$`) is an environment variable.
+pub fn analyze_variables(
+    defined: &[Variable],
+    references: Vec<VariableReference>,
+    in_environment: impl Fn(&str) -> bool,
+) -> VariableReport {
+    let mut names: Vec<String> = BUILTIN_VARIABLES.iter().map(|n| n.to_string()).collect();
+    for var in defined {
+        if !names.iter().any(|n| **n == *var.name) {
+            names.push(var.name.to_string());
+        }
+    }
+    let mut report = VariableReport {
+        names,
+        ..Default::default()
+    };
+
+    let used: HashSet<&str> = references
+        .iter()
+        .filter_map(|r| report.resolves_to(&r.name))
+        .collect();
+    report.unused = report
+        .names
+        .iter()
+        .filter(|name| !used.contains(name.as_str()))
+        .filter_map(|name| defined.iter().find(|v| *v.name == **name))
+        .cloned()
+        .collect();
+
+    report.undefined = references
+        .iter()
+        .filter(|r| report.resolves_to(&r.name).is_none() && !in_environment(&r.name[1..]))
+        .cloned()
+        .collect();
+
+    report.shadowed = defined
+        .iter()
+        .enumerate()
+        .filter_map(|(i, var)| {
+            let by = defined[i + 1..]
+                .iter()
+                .find(|later| later.name == var.name)?;
+            Some(ShadowedVariable {
+                shadowed: var.clone(),
+                by: by.clone(),
+            })
+        })
+        .collect();
+
+    report.references = references;
+    report
+}
+/// Analyzes the variables of the loaded config.
+pub fn variable_report() -> Result<VariableReport> {
+    Ok(analyze_variables(
+        &get_defined_variables()?,
+        collect_variable_references()?,
+        |name| std::env::var_os(name).is_some(),
+    ))
+}
@@ -1,4 +1,10 @@
-use crate::config::hud::{get_hud_pid_path, is_hud_running, load_hud_config, HudPosition};
+use crate::config::favorites::{load_metadata, BindId, MetadataStore};
+use crate::config::hud::{
+    get_hud_instance_pid_path, is_hud_instance_running, load_hud_instance, HudConfig, HudContent,
+    HudKeybind, HudPosition, DEFAULT_HUD,
+};
+use crate::parser::Keybind;
+use crate::ui::utils::search::{filter_keybinds, SearchQuery};
 use gtk::gio;
 use gtk::glib;
 use gtk::prelude::*;
@@ -6,9 +12,127 @@ use gtk4 as gtk;
 use gtk4_layer_shell::{Edge, Layer, LayerShell};
 use libadwaita as adw;
 use libc;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fs;
+use std::io::BufRead;
+use std::path::PathBuf;
 use std::rc::Rc;
 
+/// What the dynamic content modes pick binds from.
+pub struct HudSources<'a> {
+    pub keybinds: &'a [Keybind],
+    pub metadata: &'a MetadataStore,
+    /// Dispatch counts from the execution log, by `(dispatcher, args)`.
+    pub usage: &'a HashMap<(String, String), usize>,
+    /// The submap Hyprland is currently in.
+    pub submap: Option<&'a str>,
+}
+
+fn hud_keybind(kb: &Keybind) -> HudKeybind {
+    HudKeybind::new(&kb.mods, &kb.key, &kb.dispatcher, &kb.args)
+}
+
+/// The binds a HUD shows for its content mode.
+pub fn hud_entries(config: &HudConfig, sources: &HudSources) -> Vec<HudKeybind> {
+    let mut entries: Vec<HudKeybind> = match config.content {
+        HudContent::Manual => return config.keybinds.clone(),
+        HudContent::Favorites => sources
+            .keybinds
+            .iter()
+            .filter(|kb| sources.metadata.is_favorite(&BindId::of(kb)))
+            .map(hud_keybind)
+            .collect(),
+        HudContent::Search => {
+            let query = SearchQuery::parse(&config.query);
+            if query.is_empty() {
+                return Vec::new();
+            }
+            filter_keybinds(sources.keybinds.to_vec(), &query)
+                .iter()
+                .map(hud_keybind)
+                .collect()
+        }
+        HudContent::Submap => {
+            let Some(active) = sources.submap else {
+                return Vec::new();
+            };
+            sources
+                .keybinds
+                .iter()
+                .filter(|kb| kb.submap.as_deref() == Some(active))
+                .map(hud_keybind)
+                .collect()
+        }
+        HudContent::MostUsed => {
+            let mut used: Vec<(usize, &Keybind)> = sources
+                .keybinds
+                .iter()
+                .filter_map(|kb| {
+                    let key = (kb.dispatcher.trim().to_string(), kb.args.trim().to_string());
+                    sources.usage.get(&key).map(|count| (*count, kb))
+                })
+                .collect();
+            // Stable, so equally used binds keep their config order
+            used.sort_by_key(|(count, _)| std::cmp::Reverse(*count));
+            used.into_iter().map(|(_, kb)| hud_keybind(kb)).collect()
+        }
+    };
+    if config.limit > 0 {
+        entries.truncate(config.limit);
+    }
+    entries
+}
+
+/// Reads a line of Hyprland's event socket: `Some(submap)` for a submap
+/// change, with `None` when it went back to the global binds.
+pub fn parse_submap_event(line: &str) -> Option<Option<String>> {
+    let name = line.strip_prefix("submap>>")?.trim();
+    Some((!name.is_empty()).then(|| name.to_string()))
+}
+
+fn hyprland_event_socket() -> Option<PathBuf> {
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+    let runtime = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(|dir| PathBuf::from(dir).join("hypr").join(&signature))
+        .filter(|dir| dir.exists())
+        .unwrap_or_else(|| PathBuf::from("/tmp/hypr").join(&signature));
+    Some(runtime.join(".socket2.sock"))
+}
+
+/// Follows submap changes on Hyprland's event socket in a background thread.
+pub fn watch_submaps(sender: std::sync::mpsc::Sender<Option<String>>) {
+    let Some(path) = hyprland_event_socket() else {
+        return;
+    };
+    std::thread::spawn(move || {
+        let stream = match std::os::unix::net::UnixStream::connect(&path) {
+            Ok(stream) => stream,
+            Err(e) => {
+                crate::log_warn!("hud", "Cannot follow submaps via {:?}: {}", path, e);
+                return;
+            }
+        };
+        for line in std::io::BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Some(submap) = parse_submap_event(&line) {
+                if sender.send(submap).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+fn find_monitor(connector: &str) -> Option<gtk::gdk::Monitor> {
+    let monitors = gtk::gdk::Display::default()?.monitors();
+    (0..monitors.n_items())
+        .filter_map(|i| monitors.item(i).and_downcast::<gtk::gdk::Monitor>())
+        .find(|m| m.connector().is_some_and(|c| c == connector))
+}
+
 fn update_window_position(window: &gtk::ApplicationWindow, position: HudPosition) {
     // Reset anchors first
     window.set_anchor(Edge::Top, false);
@@ -36,7 +160,7 @@ fn update_window_position(window: &gtk::ApplicationWindow, position: HudPosition
     }
 }
 
-fn generate_hud_css(hud_config: &crate::config::hud::HudConfig) -> String {
+pub fn generate_hud_css(hud_config: &HudConfig) -> String {
     let border_radius = format!("{}px", hud_config.border_radius);
     let font_size = format!("{}px", hud_config.font_size);
     let opacity = hud_config.opacity;
@@ -85,7 +209,7 @@ fn generate_hud_css(hud_config: &crate::config::hud::HudConfig) -> String {
     )
 }
 
-fn update_keybind_list(container: &gtk::Box) {
+fn update_keybind_list(container: &gtk::Box, config: &HudConfig, entries: &[HudKeybind]) {
     // Clear current list (skip title and separator)
 
     let mut child = container.first_child(); // Title
@@ -106,17 +230,23 @@ fn update_keybind_list(container: &gtk::Box) {
         }
     }
 
-    let config = load_hud_config();
-
-    if config.keybinds.is_empty() {
+    if entries.is_empty() {
+        let message = match config.content {
+            HudContent::Manual => "No keybinds selected",
+            HudContent::Favorites => "No favorites yet",
+            HudContent::Search if config.query.trim().is_empty() => "No search query set",
+            HudContent::Search => "No keybinds match the query",
+            HudContent::Submap => "No submap active",
+            HudContent::MostUsed => "No keybinds run from hyprKCS yet",
+        };
         container.append(
             &gtk::Label::builder()
-                .label("No keybinds selected")
+                .label(message)
                 .css_classes(["hud-empty"])
                 .build(),
         );
     } else {
-        for kb in &config.keybinds {
+        for kb in entries {
             let row = gtk::Box::builder()
                 .orientation(gtk::Orientation::Horizontal)
                 .spacing(24)
@@ -127,6 +257,11 @@ fn update_keybind_list(container: &gtk::Box) {
             } else {
                 format!("{} + {}", kb.mods, kb.key)
             };
+            let action_text = if kb.args.is_empty() {
+                &kb.dispatcher
+            } else {
+                &kb.args
+            };
 
             row.append(
                 &gtk::Label::builder()
@@ -139,7 +274,7 @@ fn update_keybind_list(container: &gtk::Box) {
 
             row.append(
                 &gtk::Label::builder()
-                    .label(glib::markup_escape_text(&kb.args))
+                    .label(glib::markup_escape_text(action_text))
                     .css_classes(["hud-action"])
                     .halign(gtk::Align::End)
                     .build(),
@@ -150,17 +285,31 @@ fn update_keybind_list(container: &gtk::Box) {
     }
 }
 
-pub fn run_hud() {
-    let config = load_hud_config();
+/// The HUD's title: the submap in submap mode, else the HUD's name.
+fn hud_title(config: &HudConfig, submap: Option<&str>) -> String {
+    match (config.content, submap) {
+        (HudContent::Submap, Some(submap)) => submap.to_string(),
+        _ if config.name == DEFAULT_HUD => "hyprKCS HUD".to_string(),
+        _ => config.name.clone(),
+    }
+}
+
+/// Runs the HUD called `instance` in `hud.conf`, or the default one.
+pub fn run_hud(instance: Option<&str>) {
+    let name = instance.unwrap_or(DEFAULT_HUD).to_string();
+    let Some(config) = load_hud_instance(&name) else {
+        crate::log_error!("hud", "No HUD named '{}' in hud.conf", name);
+        return;
+    };
 
-    // --- Single Instance Locking ---
-    if is_hud_running() {
-        eprintln!("HUD is already running");
+    // --- Single Instance Locking (per HUD) ---
+    if is_hud_instance_running(&name) {
+        crate::log_warn!("hud", "HUD '{}' is already running", name);
         return;
     }
 
     // Write current PID
-    if let Some(pid_path) = get_hud_pid_path() {
+    if let Some(pid_path) = get_hud_instance_pid_path(&name) {
         if let Some(parent) = pid_path.parent() {
             let _ = fs::create_dir_all(parent);
         }
@@ -182,14 +331,23 @@ pub fn run_hud() {
     });
 
     adw::init().unwrap_or_else(|e| {
-        eprintln!("Failed to initialize libadwaita: {}", e);
+        crate::log_error!("hud", "Failed to initialize libadwaita: {}", e);
     });
 
+    // Several HUD processes may run side by side
     let app = adw::Application::builder()
         .application_id("com.github.hyprkcs.hud")
+        .flags(gio::ApplicationFlags::NON_UNIQUE)
         .build();
 
+    let name_c = name.clone();
     app.connect_activate(move |app| {
+        let name = name_c.clone();
+        let load_config = {
+            let name = name.clone();
+            move || load_hud_instance(&name).unwrap_or_else(|| HudConfig::named(&name))
+        };
+
         let app_provider = gtk::CssProvider::new();
 
         let theme_provider = gtk::CssProvider::new();
@@ -247,23 +405,97 @@ pub fn run_hud() {
             .css_classes(["hud-container"])
             .build();
 
-        container.append(
-            &gtk::Label::builder()
-                .label("hyprKCS HUD")
-                .css_classes(["hud-title"])
-                .halign(gtk::Align::Start)
-                .build(),
-        );
+        let title = gtk::Label::builder()
+            .label(hud_title(&config, None))
+            .css_classes(["hud-title"])
+            .halign(gtk::Align::Start)
+            .build();
+        container.append(&title);
 
         container.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
 
-        update_keybind_list(&container);
+        // --- Content ---
+
+        let active_submap: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+
+        let refresh: Rc<dyn Fn()> = {
+            let window = window.clone();
+            let container = container.clone();
+            let title = title.clone();
+            let app_provider = app_provider.clone();
+            let active_submap = active_submap.clone();
+            let load_config = load_config.clone();
+            Rc::new(move || {
+                let cfg = load_config();
+                let submap = active_submap.borrow().clone();
+
+                let entries = if cfg.content == HudContent::Manual {
+                    cfg.keybinds.clone()
+                } else {
+                    let keybinds = crate::parser::parse_config().unwrap_or_default();
+                    let metadata = if cfg.content == HudContent::Favorites {
+                        load_metadata()
+                    } else {
+                        MetadataStore::default()
+                    };
+                    let usage = if cfg.content == HudContent::MostUsed {
+                        crate::ui::utils::execution::execution_counts()
+                    } else {
+                        HashMap::new()
+                    };
+                    hud_entries(
+                        &cfg,
+                        &HudSources {
+                            keybinds: &keybinds,
+                            metadata: &metadata,
+                            usage: &usage,
+                            submap: submap.as_deref(),
+                        },
+                    )
+                };
+
+                title.set_label(&hud_title(&cfg, submap.as_deref()));
+                update_keybind_list(&container, &cfg, &entries);
+                update_window_position(&window, cfg.position);
+                window.set_monitor(cfg.monitor.as_deref().and_then(find_monitor).as_ref());
+                app_provider.load_from_string(&generate_hud_css(&cfg));
+                // A submap HUD only shows up inside a submap
+                window.set_visible(cfg.content != HudContent::Submap || !entries.is_empty());
+            })
+        };
+
+        // --- Submap Tracking ---
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        watch_submaps(sender);
+        let refresh_submap = refresh.clone();
+        let active_submap_c = active_submap.clone();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let mut changed = false;
+            loop {
+                match receiver.try_recv() {
+                    Ok(submap) => {
+                        *active_submap_c.borrow_mut() = submap;
+                        changed = true;
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        return glib::ControlFlow::Break;
+                    }
+                }
+            }
+            if changed {
+                refresh_submap();
+            }
+            glib::ControlFlow::Continue
+        });
 
         // --- Theme and Config Listeners ---
 
         let manager = adw::StyleManager::default();
         let app_prov_c = app_provider.clone();
         let theme_prov_c = theme_provider.clone();
+        let load_config_c = load_config.clone();
 
         let reload_all = move || {
             if let Some(config_dir) = dirs::config_dir() {
@@ -275,7 +507,7 @@ pub fn run_hud() {
                 }
             }
 
-            let hud_cfg = load_hud_config();
+            let hud_cfg = load_config_c();
             app_prov_c.load_from_string(&generate_hud_css(&hud_cfg));
         };
 
@@ -307,7 +539,7 @@ pub fn run_hud() {
                 .join(crate::config::constants::HYPRKCS_DIR)
                 .join(crate::config::constants::HYPRKCS_CONF);
 
-            let hud_json_path = config_dir
+            let hud_conf_path = config_dir
                 .join(crate::config::constants::HYPRKCS_DIR)
                 .join(crate::config::constants::HUD_CONF);
 
@@ -321,6 +553,7 @@ pub fn run_hud() {
                 {
                     let theme_prov_f = theme_provider.clone();
                     let app_prov_f = app_provider.clone();
+                    let load_config_f = load_config.clone();
 
                     monitor.connect_changed(move |_, file, _, event| {
                         if let Some(path) = file.path() {
@@ -333,6 +566,7 @@ pub fn run_hud() {
                                         | gio::FileMonitorEvent::AttributeChanged => {
                                             let tp = theme_prov_f.clone();
                                             let ap = app_prov_f.clone();
+                                            let load_config = load_config_f.clone();
                                             let config_dir = dirs::config_dir().unwrap();
                                             let css_path = config_dir.join("gtk-4.0/gtk.css");
                                             let f = gio::File::for_path(&css_path);
@@ -346,7 +580,7 @@ pub fn run_hud() {
                                                     } else {
                                                         tp.load_from_string("");
                                                     }
-                                                    let hud_cfg = load_hud_config();
+                                                    let hud_cfg = load_config();
                                                     ap.load_from_string(&generate_hud_css(
                                                         &hud_cfg,
                                                     ));
@@ -367,9 +601,6 @@ pub fn run_hud() {
                 }
             }
 
-            let app_prov_f = app_provider.clone();
-            let container_f = container.clone();
-
             // Monitor hyprkcs.conf (style)
 
             let file_conf = gio::File::for_path(&config_path);
@@ -377,10 +608,11 @@ pub fn run_hud() {
             if let Ok(monitor) =
                 file_conf.monitor(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
             {
-                let app_prov_f2 = app_prov_f.clone();
+                let app_prov_f2 = app_provider.clone();
+                let load_config_f = load_config.clone();
 
                 monitor.connect_changed(move |_, _, _, _| {
-                    let hud_cfg = load_hud_config();
+                    let hud_cfg = load_config_f();
 
                     app_prov_f2.load_from_string(&generate_hud_css(&hud_cfg));
                 });
@@ -390,37 +622,50 @@ pub fn run_hud() {
                 }
             }
 
-            // Monitor hud.json (keybind selection and position)
-
-            let file_hud = gio::File::for_path(&hud_json_path);
-
-            if let Ok(monitor) =
-                file_hud.monitor(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
-            {
-                let window_p = window.clone();
-                let app_prov_f3 = app_provider.clone();
-
-                monitor.connect_changed(move |_, _, _, _| {
-                    let cfg = load_hud_config();
-
-                    update_keybind_list(&container_f);
-
-                    update_window_position(&window_p, cfg.position);
-                    app_prov_f3.load_from_string(&generate_hud_css(&cfg));
+            // Monitor what the content is built from: hud.conf, favorites,
+            // the execution log and the Hyprland config files
+
+            let mut watched = vec![hud_conf_path];
+            watched.extend(crate::config::favorites::get_favorites_path());
+            watched.extend(crate::ui::utils::execution::execution_log_path());
+            let config_files = crate::parser::get_loaded_files().unwrap_or_default();
+            let first_config_file = watched.len();
+            watched.extend(config_files);
+
+            for (i, path) in watched.into_iter().enumerate() {
+                let Ok(monitor) = gio::File::for_path(&path)
+                    .monitor(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
+                else {
+                    continue;
+                };
+                let refresh = refresh.clone();
+                let is_config_file = i >= first_config_file;
+                monitor.connect_changed(move |_, _, _, event| {
+                    if !matches!(
+                        event,
+                        gio::FileMonitorEvent::ChangesDoneHint | gio::FileMonitorEvent::Created
+                    ) {
+                        return;
+                    }
+                    if is_config_file {
+                        crate::parser::mark_files_changed(std::slice::from_ref(&path));
+                    }
+                    refresh();
                 });
 
                 unsafe {
-                    window.set_data("hud-monitor", Rc::new(monitor));
+                    window.set_data(&format!("hud-monitor-{}", i), Rc::new(monitor));
                 }
             }
         }
 
         window.set_child(Some(&container));
         window.present();
+        refresh();
     });
 
-    app.connect_shutdown(|_| {
-        if let Some(pid_path) = get_hud_pid_path() {
+    app.connect_shutdown(move |_| {
+        if let Some(pid_path) = get_hud_instance_pid_path(&name) {
             let _ = fs::remove_file(pid_path);
         }
     });
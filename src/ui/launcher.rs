@@ -0,0 +1,301 @@
+//! The `--launcher` palette: a keyboard-driven popup that fuzzy-searches the
+//! binds of the current submap and runs the chosen one.
+//!
+//! Running `hyprkcs --launcher` again while it is open closes it, so the same
+//! bind toggles it like rofi.
+
+use crate::config::hud::load_hud_config;
+use crate::parser::Keybind;
+use crate::ui::hud::generate_hud_css;
+use crate::ui::utils::execution::execute_keybind_sync;
+use crate::ui::utils::search::{KeybindSearch, Pattern, SearchQuery, Term};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use gtk::gdk;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4_layer_shell::{KeyboardMode, Layer, LayerShell};
+use libadwaita as adw;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The submap named by `hyprctl submap`, `None` outside of one.
+pub fn parse_current_submap(output: &str) -> Option<String> {
+    let name = output.trim();
+    (!name.is_empty() && name != "default").then(|| name.to_string())
+}
+
+fn current_submap() -> Option<String> {
+    let output = std::process::Command::new("hyprctl")
+        .arg("submap")
+        .output()
+        .ok()?;
+    parse_current_submap(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The binds that can be run from the launcher while in `submap`. Mouse
+/// drag binds (`bindm`) only work with the mouse and are left out.
+pub fn launcher_candidates(keybinds: Vec<Keybind>, submap: Option<&str>) -> Vec<Keybind> {
+    keybinds
+        .into_iter()
+        .filter(|kb| kb.submap.as_deref() == submap && !kb.flags.contains('m'))
+        .collect()
+}
+
+/// What the launcher shows as a bind's name: its description, or the
+/// dispatcher and arguments.
+pub fn launcher_title(kb: &Keybind) -> String {
+    match kb.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        Some(description) => description.to_string(),
+        None if kb.args.is_empty() => kb.dispatcher.to_string(),
+        None => format!("{} {}", kb.dispatcher, kb.args),
+    }
+}
+
+/// Indices of the binds matching `query`, best first. Binds are ranked by
+/// how well the plain words of the query fuzzy-match their description and
+/// action; field terms like `mod:super` only filter.
+pub fn rank_launcher(keybinds: &[Keybind], query: &str, matcher: &SkimMatcherV2) -> Vec<usize> {
+    let query = SearchQuery::parse(query);
+    let words: Vec<&str> = query
+        .terms()
+        .into_iter()
+        .filter_map(|t| match t {
+            Term::Any {
+                pattern: Pattern::Text(word),
+                ..
+            } => Some(word.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut ranked: Vec<(i64, usize)> = keybinds
+        .iter()
+        .enumerate()
+        .filter(|(_, kb)| {
+            let target = KeybindSearch {
+                keybind: kb,
+                conflicted: false,
+                broken: false,
+                favorite: false,
+            };
+            query.matches(&target, matcher)
+        })
+        .map(|(i, kb)| {
+            let description = kb.description.as_deref().unwrap_or_default();
+            let action = format!("{} {}", kb.dispatcher, kb.args);
+            let score = words
+                .iter()
+                .map(|word| {
+                    [description, action.as_str()]
+                        .iter()
+                        .filter_map(|text| matcher.fuzzy_match(text, word))
+                        .max()
+                        .unwrap_or(0)
+                })
+                .sum::<i64>();
+            (score, i)
+        })
+        .collect();
+    // Stable, so equal scores keep their config order
+    ranked.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    ranked.into_iter().map(|(_, i)| i).collect()
+}
+
+fn create_row(kb: &Keybind) -> gtk::ListBoxRow {
+    let keys = if kb.clean_mods.is_empty() {
+        kb.key.to_string()
+    } else {
+        format!("{} + {}", kb.clean_mods, kb.key)
+    };
+    let row_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(24)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(6)
+        .margin_end(6)
+        .build();
+    row_box.append(
+        &gtk::Label::builder()
+            .label(launcher_title(kb))
+            .css_classes(["hud-action"])
+            .halign(gtk::Align::Start)
+            .hexpand(true)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .build(),
+    );
+    row_box.append(
+        &gtk::Label::builder()
+            .label(keys)
+            .css_classes(["hud-keys"])
+            .halign(gtk::Align::End)
+            .build(),
+    );
+    gtk::ListBoxRow::builder().child(&row_box).build()
+}
+
+pub fn run_launcher() {
+    adw::init().unwrap_or_else(|e| {
+        crate::log_error!("ui", "Failed to initialize libadwaita: {}", e);
+    });
+
+    // Unique, so launching it again reaches the running palette
+    let app = adw::Application::builder()
+        .application_id("com.github.hyprkcs.launcher")
+        .build();
+
+    app.connect_activate(|app| {
+        if let Some(window) = app.active_window() {
+            window.close();
+            return;
+        }
+
+        let provider = gtk::CssProvider::new();
+        provider.load_from_string(&format!(
+            "{}\n.launcher-list {{ background: transparent; }}",
+            generate_hud_css(&load_hud_config())
+        ));
+        if let Some(display) = gdk::Display::default() {
+            gtk::style_context_add_provider_for_display(
+                &display,
+                &provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        let submap = current_submap();
+        let keybinds = Rc::new(launcher_candidates(
+            crate::parser::parse_config().unwrap_or_default(),
+            submap.as_deref(),
+        ));
+
+        let window = gtk::ApplicationWindow::builder()
+            .application(app)
+            .title("hyprKCS Launcher")
+            .resizable(false)
+            .decorated(false)
+            .default_width(560)
+            .build();
+        window.init_layer_shell();
+        window.set_layer(Layer::Overlay);
+        window.set_namespace(Some("hyprkcs-launcher"));
+        window.set_keyboard_mode(KeyboardMode::Exclusive);
+
+        let container = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .css_classes(["hud-container"])
+            .build();
+        let placeholder = match &submap {
+            Some(submap) => format!("Run a bind in {}...", submap),
+            None => "Run a bind...".to_string(),
+        };
+        let entry = gtk::SearchEntry::builder()
+            .placeholder_text(placeholder)
+            .build();
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::Browse)
+            .css_classes(["launcher-list"])
+            .build();
+        list.set_placeholder(Some(
+            &gtk::Label::builder()
+                .label("No matching keybinds")
+                .css_classes(["hud-empty"])
+                .build(),
+        ));
+        let scroll = gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .min_content_height(120)
+            .max_content_height(420)
+            .propagate_natural_height(true)
+            .child(&list)
+            .build();
+        container.append(&entry);
+        container.append(&scroll);
+        window.set_child(Some(&container));
+
+        // Indices into `keybinds` of the rows shown, in order
+        let shown: Rc<RefCell<Vec<usize>>> = Rc::new(RefCell::new(Vec::new()));
+        let matcher = SkimMatcherV2::default();
+
+        let refresh = {
+            let list = list.clone();
+            let keybinds = keybinds.clone();
+            let shown = shown.clone();
+            move |query: &str| {
+                while let Some(child) = list.first_child() {
+                    list.remove(&child);
+                }
+                let ranked = rank_launcher(&keybinds, query, &matcher);
+                for &i in &ranked {
+                    list.append(&create_row(&keybinds[i]));
+                }
+                list.select_row(list.row_at_index(0).as_ref());
+                *shown.borrow_mut() = ranked;
+            }
+        };
+        refresh("");
+        entry.connect_search_changed(move |entry| refresh(&entry.text()));
+
+        let run_selected: Rc<dyn Fn()> = {
+            let window = window.clone();
+            let list = list.clone();
+            Rc::new(move || {
+                let Some(row) = list.selected_row() else {
+                    return;
+                };
+                let Some(&i) = shown.borrow().get(row.index() as usize) else {
+                    return;
+                };
+                let kb = &keybinds[i];
+                // Hide first, so window dispatchers act on the window below
+                window.set_visible(false);
+                execute_keybind_sync(&kb.dispatcher, &kb.args, "launcher");
+                window.close();
+            })
+        };
+
+        let run = run_selected.clone();
+        entry.connect_activate(move |_| run());
+        let run = run_selected.clone();
+        list.connect_row_activated(move |_, _| run());
+
+        let window_c = window.clone();
+        entry.connect_stop_search(move |_| window_c.close());
+
+        // Arrow keys move through the list while the entry keeps the focus
+        let keys = gtk::EventControllerKey::new();
+        keys.set_propagation_phase(gtk::PropagationPhase::Capture);
+        let list_c = list.clone();
+        let scroll_c = scroll.clone();
+        keys.connect_key_pressed(move |_, key, _, state| {
+            let ctrl = state.contains(gdk::ModifierType::CONTROL_MASK);
+            let step = match key {
+                gdk::Key::Down => 1,
+                gdk::Key::Up => -1,
+                gdk::Key::n | gdk::Key::j if ctrl => 1,
+                gdk::Key::p | gdk::Key::k if ctrl => -1,
+                _ => return glib::Propagation::Proceed,
+            };
+            let current = list_c.selected_row().map_or(0, |r| r.index());
+            if let Some(row) = list_c.row_at_index((current + step).max(0)) {
+                list_c.select_row(Some(&row));
+                if let Some(point) = row.compute_point(&list_c, &gtk::graphene::Point::zero()) {
+                    let top = point.y() as f64;
+                    scroll_c
+                        .vadjustment()
+                        .clamp_page(top, top + row.height() as f64);
+                }
+            }
+            glib::Propagation::Stop
+        });
+        window.add_controller(keys);
+
+        window.present();
+        entry.grab_focus();
+    });
+
+    app.run_with_args::<String>(&[]);
+}
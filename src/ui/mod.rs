@@ -1,7 +1,9 @@
 pub mod hud;
+pub mod launcher;
 pub mod settings;
 pub mod style;
 pub mod utils;
 pub mod views;
+pub mod which_key;
 pub mod window;
 pub mod wizards;
@@ -106,7 +106,7 @@ pub fn create_about_page(window: &adw::ApplicationWindow) -> adw::PreferencesPag
             let launcher = gtk::UriLauncher::new(&u);
             launcher.launch(Some(&w), None::<&gtk::gio::Cancellable>, |res| {
                 if let Err(e) = res {
-                    eprintln!("Failed to launch URL: {}", e);
+                    crate::log_error!("ui", "Failed to launch URL: {}", e);
                 }
             });
         });
@@ -131,19 +131,25 @@ pub fn create_appearance_page(
     group_font.add(&b_rad_row);
 
     // Keyboard Layout
-    let layout_opts = ["Auto", "ANSI", "ISO", "JIS", "ABNT2", "Hungarian"];
-    let layout_list = gtk::StringList::new(&layout_opts);
+    let mut layout_opts = vec!["Auto".to_string()];
+    layout_opts.extend(crate::ui::views::keyboard_geometry::list_geometry_names());
+    let layout_list = gtk::StringList::new(
+        &layout_opts
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<&str>>(),
+    );
 
     // Map current string to index
     let current_layout = config.borrow().keyboard_layout.to_uppercase();
-    let layout_idx = match current_layout.as_str() {
-        "ANSI" => 1,
-        "ISO" => 2,
-        "JIS" => 3,
-        "ABNT2" => 4,
-        "HU" | "HUNGARIAN" => 5,
-        _ => 0, // Auto
+    let current_layout = match current_layout.as_str() {
+        "HUNGARIAN" => "HU".to_string(),
+        _ => current_layout,
     };
+    let layout_idx = layout_opts
+        .iter()
+        .position(|l| l.eq_ignore_ascii_case(&current_layout))
+        .unwrap_or(0) as u32; // Auto
 
     let layout_drop = gtk::DropDown::builder()
         .model(&layout_list)
@@ -152,21 +158,20 @@ pub fn create_appearance_page(
         .build();
     let layout_row = adw::ActionRow::builder()
         .title("Keyboard Layout")
-        .subtitle("Visual keyboard map type")
+        .subtitle("Visual keyboard map type (standard, 60%, ortholinear, split or custom)")
         .build();
     layout_row.add_suffix(&layout_drop);
 
     let c = config.clone();
     layout_drop.connect_selected_notify(move |d| {
         let val = match d.selected() {
-            1 => "ANSI",
-            2 => "ISO",
-            3 => "JIS",
-            4 => "ABNT2",
-            5 => "HUNGARIAN",
-            _ => "AUTO",
+            0 => "AUTO".to_string(),
+            i => layout_opts
+                .get(i as usize)
+                .map(|s| s.to_uppercase())
+                .unwrap_or_else(|| "AUTO".to_string()),
         };
-        c.borrow_mut().keyboard_layout = val.to_string();
+        c.borrow_mut().keyboard_layout = val;
         let _ = c.borrow().save();
     });
     group_font.add(&layout_row);
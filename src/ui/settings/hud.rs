@@ -1,7 +1,10 @@
 use crate::config::hud::{
-    get_hud_pid_path, is_hud_running, load_hud_config, save_hud_config, HudKeybind, HudPosition,
+    get_hud_instance_pid_path, is_hud_instance_running, load_hud_configs, load_hud_instance,
+    remove_hud_instance, save_hud_config, HudConfig, HudContent, HudKeybind, HudPosition,
+    DEFAULT_HUD,
 };
 use crate::keybind_object::KeybindObject;
+use crate::ui::utils::components::{create_flat_button, create_pill_button};
 use gtk::{gio, glib};
 use gtk4 as gtk;
 use libadwaita as adw;
@@ -11,12 +14,180 @@ use std::fs;
 use std::process::Command;
 use std::rc::Rc;
 
+/// Stops the HUD process called `name`, if it is running.
+fn stop_hud(name: &str) {
+    let Some(pid_path) = get_hud_instance_pid_path(name) else {
+        return;
+    };
+    if let Ok(pid_str) = fs::read_to_string(&pid_path) {
+        if let Ok(pid) = pid_str.trim().parse::<i32>() {
+            unsafe {
+                if libc::kill(pid, libc::SIGTERM) != 0 {
+                    crate::log_error!("hud", "Failed to kill HUD process (PID: {})", pid);
+                }
+            }
+        }
+    }
+    let _ = fs::remove_file(pid_path);
+}
+
+fn hud_names() -> Vec<String> {
+    load_hud_configs().into_iter().map(|c| c.name).collect()
+}
+
 pub fn create_hud_page(model: &gio::ListStore, on_show_toast: Rc<dyn Fn(String)>) -> gtk::Widget {
     let main_box = gtk::Box::builder()
         .orientation(gtk::Orientation::Vertical)
         .build();
 
-    let config = Rc::new(RefCell::new(load_hud_config()));
+    // --- Instance Bar ---
+    let instance_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .margin_top(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let initial_names = hud_names();
+    let name_refs: Vec<&str> = initial_names.iter().map(String::as_str).collect();
+    let instance_model = gtk::StringList::new(&name_refs);
+    let names = Rc::new(RefCell::new(initial_names));
+    let instance_dropdown = gtk::DropDown::builder()
+        .model(&instance_model)
+        .valign(gtk::Align::Center)
+        .tooltip_text("HUD to configure")
+        .build();
+
+    let new_entry = gtk::Entry::builder()
+        .placeholder_text("New HUD name")
+        .hexpand(true)
+        .valign(gtk::Align::Center)
+        .build();
+    let add_btn = create_pill_button("Add", Some("list-add-symbolic"));
+    let delete_btn = create_flat_button("user-trash-symbolic", "Delete this HUD");
+    delete_btn.set_valign(gtk::Align::Center);
+    delete_btn.set_sensitive(false);
+
+    instance_box.append(&gtk::Label::new(Some("HUD")));
+    instance_box.append(&instance_dropdown);
+    instance_box.append(&new_entry);
+    instance_box.append(&add_btn);
+    instance_box.append(&delete_btn);
+    main_box.append(&instance_box);
+
+    let editor_holder = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .vexpand(true)
+        .build();
+    main_box.append(&editor_holder);
+
+    let model = model.clone();
+    let names_c = names.clone();
+    let holder_weak = editor_holder.downgrade();
+    let delete_weak = delete_btn.downgrade();
+    let toast_c = on_show_toast.clone();
+    let show_selected: Rc<dyn Fn(u32)> = Rc::new(move |index| {
+        let Some(holder) = holder_weak.upgrade() else {
+            return;
+        };
+        let Some(name) = names_c.borrow().get(index as usize).cloned() else {
+            return;
+        };
+        while let Some(child) = holder.first_child() {
+            holder.remove(&child);
+        }
+        if let Some(btn) = delete_weak.upgrade() {
+            btn.set_sensitive(name != DEFAULT_HUD);
+        }
+        holder.append(&create_instance_editor(&name, &model, toast_c.clone()));
+    });
+
+    let show_c = show_selected.clone();
+    instance_dropdown.connect_selected_notify(move |dd| show_c(dd.selected()));
+    show_selected(0);
+
+    // Replaces the dropdown entries and selects `name`
+    let reload_names = {
+        let names = names.clone();
+        let instance_model = instance_model.clone();
+        let dropdown = instance_dropdown.clone();
+        let show_selected = show_selected.clone();
+        move |name: &str| {
+            let fresh = hud_names();
+            let refs: Vec<&str> = fresh.iter().map(String::as_str).collect();
+            let index = fresh.iter().position(|n| n == name).unwrap_or(0) as u32;
+            *names.borrow_mut() = fresh.clone();
+            instance_model.splice(0, instance_model.n_items(), &refs);
+            if dropdown.selected() == index {
+                show_selected(index);
+            } else {
+                dropdown.set_selected(index);
+            }
+        }
+    };
+    let reload_names = Rc::new(reload_names);
+
+    let toast_c = on_show_toast.clone();
+    let names_c = names.clone();
+    let reload_c = reload_names.clone();
+    let new_entry_c = new_entry.clone();
+    let add = move || {
+        let name = new_entry_c.text().trim().to_string();
+        if name.is_empty() || name.contains(['[', ']']) {
+            toast_c("Give the HUD a name without brackets".into());
+            return;
+        }
+        if names_c.borrow().contains(&name) {
+            toast_c(format!("A HUD named '{}' already exists", name));
+            return;
+        }
+        if let Err(e) = save_hud_config(&HudConfig::named(&name)) {
+            toast_c(format!("Failed to save HUD: {}", e));
+            return;
+        }
+        new_entry_c.set_text("");
+        reload_c(&name);
+    };
+    let add = Rc::new(add);
+    let add_c = add.clone();
+    add_btn.connect_clicked(move |_| add_c());
+    new_entry.connect_activate(move |_| add());
+
+    let toast_c = on_show_toast.clone();
+    let dropdown_c = instance_dropdown.clone();
+    delete_btn.connect_clicked(move |_| {
+        let Some(name) = names.borrow().get(dropdown_c.selected() as usize).cloned() else {
+            return;
+        };
+        if name == DEFAULT_HUD {
+            return;
+        }
+        stop_hud(&name);
+        match remove_hud_instance(&name) {
+            Ok(()) => toast_c(format!("Deleted HUD '{}'", name)),
+            Err(e) => toast_c(format!("Failed to delete HUD: {}", e)),
+        }
+        reload_names(DEFAULT_HUD);
+    });
+
+    main_box.upcast()
+}
+
+/// The settings of one HUD from `hud.conf`.
+fn create_instance_editor(
+    name: &str,
+    model: &gio::ListStore,
+    on_show_toast: Rc<dyn Fn(String)>,
+) -> gtk::Box {
+    let main_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .vexpand(true)
+        .build();
+
+    let config = Rc::new(RefCell::new(
+        load_hud_instance(name).unwrap_or_else(|| HudConfig::named(name)),
+    ));
 
     // --- Single Line Header ---
     let header_box = gtk::Box::builder()
@@ -63,7 +234,7 @@ pub fn create_hud_page(model: &gio::ListStore, on_show_toast: Rc<dyn Fn(String)>
     });
 
     // Check if process is actually running to determine switch state
-    let is_running = is_hud_running();
+    let is_running = is_hud_instance_running(name);
     if config.borrow().enabled != is_running {
         // Mismatch detected. Sync config to reality (if dead, mark disabled).
         // If we want to auto-restart, we'd do it here, but safer to default to "Off" if dead.
@@ -78,6 +249,7 @@ pub fn create_hud_page(model: &gio::ListStore, on_show_toast: Rc<dyn Fn(String)>
 
     let config_ref = Rc::clone(&config);
     let toast_cb = Rc::clone(&on_show_toast);
+    let hud_name = name.to_string();
     enable_switch.connect_state_set(move |switch, state| {
         config_ref.borrow_mut().enabled = state;
         let _ = save_hud_config(&config_ref.borrow());
@@ -86,7 +258,11 @@ pub fn create_hud_page(model: &gio::ListStore, on_show_toast: Rc<dyn Fn(String)>
             if let Ok(exe) = std::env::current_exe() {
                 use std::os::unix::process::CommandExt;
                 let mut cmd = Command::new(exe);
-                cmd.arg("--hud");
+                if hud_name == DEFAULT_HUD {
+                    cmd.arg("--hud");
+                } else {
+                    cmd.args(["--hud-instance", &hud_name]);
+                }
                 unsafe {
                     cmd.pre_exec(|| {
                         let _ = libc::setsid();
@@ -96,7 +272,7 @@ pub fn create_hud_page(model: &gio::ListStore, on_show_toast: Rc<dyn Fn(String)>
                 match cmd.spawn() {
                     Ok(_) => toast_cb("HUD Enabled".into()),
                     Err(e) => {
-                        eprintln!("Failed to spawn HUD: {}", e);
+                        crate::log_error!("hud", "Failed to spawn HUD: {}", e);
                         toast_cb(format!("Failed to start HUD: {}", e));
 
                         // Revert config and UI
@@ -109,18 +285,7 @@ pub fn create_hud_page(model: &gio::ListStore, on_show_toast: Rc<dyn Fn(String)>
                 toast_cb("Failed to locate executable".into());
             }
         } else {
-            if let Some(pid_path) = get_hud_pid_path() {
-                if let Ok(pid_str) = fs::read_to_string(&pid_path) {
-                    if let Ok(pid) = pid_str.trim().parse::<i32>() {
-                        unsafe {
-                            if libc::kill(pid, libc::SIGTERM) != 0 {
-                                eprintln!("Failed to kill HUD process (PID: {})", pid);
-                            }
-                        }
-                    }
-                }
-                let _ = fs::remove_file(pid_path);
-            }
+            stop_hud(&hud_name);
             toast_cb("HUD Disabled".into());
         }
         glib::Propagation::Proceed
@@ -231,6 +396,83 @@ pub fn create_hud_page(model: &gio::ListStore, on_show_toast: Rc<dyn Fn(String)>
     expander.add_row(&radius_row);
     expander.add_row(&font_row);
 
+    // --- Content ---
+    let content_expander = adw::ExpanderRow::builder()
+        .title("HUD Content")
+        .subtitle("What the HUD shows and where")
+        .expanded(false)
+        .build();
+
+    let content_labels: Vec<&str> = HudContent::ALL.iter().map(|c| c.label()).collect();
+    let content_row = adw::ComboRow::builder()
+        .title("Show")
+        .model(&gtk::StringList::new(&content_labels))
+        .selected(
+            HudContent::ALL
+                .iter()
+                .position(|c| *c == config.borrow().content)
+                .unwrap_or(0) as u32,
+        )
+        .build();
+
+    let query_entry = gtk::Entry::builder()
+        .text(&config.borrow().query)
+        .placeholder_text("action:workspace -mod:shift")
+        .valign(gtk::Align::Center)
+        .hexpand(true)
+        .build();
+    let query_row = adw::ActionRow::builder().title("Search Query").build();
+    query_row.add_suffix(&query_entry);
+
+    let limit_adj = gtk::Adjustment::new(config.borrow().limit as f64, 0.0, 100.0, 1.0, 5.0, 0.0);
+    let limit_spin = gtk::SpinButton::builder()
+        .adjustment(&limit_adj)
+        .valign(gtk::Align::Center)
+        .build();
+    let limit_row = adw::ActionRow::builder()
+        .title("Maximum Keybinds")
+        .subtitle("0 shows all of them")
+        .build();
+    limit_row.add_suffix(&limit_spin);
+
+    let monitor_entry = gtk::Entry::builder()
+        .text(config.borrow().monitor.as_deref().unwrap_or_default())
+        .placeholder_text("DP-1")
+        .valign(gtk::Align::Center)
+        .build();
+    let monitor_row = adw::ActionRow::builder()
+        .title("Monitor")
+        .subtitle("Connector name, empty for the focused one")
+        .build();
+    monitor_row.add_suffix(&monitor_entry);
+
+    content_expander.add_row(&content_row);
+    content_expander.add_row(&query_row);
+    content_expander.add_row(&limit_row);
+    content_expander.add_row(&monitor_row);
+
+    let config_query_ref = Rc::clone(&config);
+    query_entry.connect_changed(move |e| {
+        let mut cfg = config_query_ref.borrow_mut();
+        cfg.query = e.text().trim().to_string();
+        let _ = save_hud_config(&cfg);
+    });
+
+    let config_limit_ref = Rc::clone(&config);
+    limit_spin.connect_value_changed(move |s| {
+        let mut cfg = config_limit_ref.borrow_mut();
+        cfg.limit = s.value() as usize;
+        let _ = save_hud_config(&cfg);
+    });
+
+    let config_monitor_ref = Rc::clone(&config);
+    monitor_entry.connect_changed(move |e| {
+        let mut cfg = config_monitor_ref.borrow_mut();
+        cfg.monitor = Some(e.text().trim().to_string()).filter(|m| !m.is_empty());
+        let _ = save_hud_config(&cfg);
+    });
+
+    styling_group.add(&content_expander);
     styling_group.add(&expander);
     styling_clamp.set_child(Some(&styling_group));
     main_box.append(&styling_clamp);
@@ -352,5 +594,28 @@ pub fn create_hud_page(model: &gio::ListStore, on_show_toast: Rc<dyn Fn(String)>
     scrolled.set_child(Some(&clamp));
     main_box.append(&scrolled);
 
-    main_box.upcast()
+    // Only the manual mode uses the selection; only the search mode a query
+    let sync_content = {
+        let search_entry = search_entry.clone();
+        move |content: HudContent| {
+            let manual = content == HudContent::Manual;
+            keybinds_label.set_visible(manual);
+            scrolled.set_visible(manual);
+            search_entry.set_visible(manual);
+            query_row.set_visible(content == HudContent::Search);
+            limit_row.set_visible(!manual);
+        }
+    };
+    sync_content(config.borrow().content);
+
+    let config_content_ref = Rc::clone(&config);
+    content_row.connect_selected_notify(move |row| {
+        let content = HudContent::ALL[row.selected() as usize % HudContent::ALL.len()];
+        let mut cfg = config_content_ref.borrow_mut();
+        cfg.content = content;
+        let _ = save_hud_config(&cfg);
+        sync_content(content);
+    });
+
+    main_box
 }
@@ -0,0 +1,178 @@
+use crate::logging::{self, Level, LogRecord, LEVELS};
+use crate::ui::utils::components::create_pill_button;
+use gtk::{gio, prelude::*};
+use gtk4 as gtk;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Targets offered in the filter; anything else shows under "All".
+const TARGETS: &[(&str, &str)] = &[
+    ("", "All Sources"),
+    ("parser", "Parser"),
+    ("write", "Writes"),
+    ("hyprctl", "hyprctl"),
+    ("backup", "Backups"),
+    ("config", "Config"),
+    ("watcher", "Watcher"),
+    ("hud", "HUD"),
+    ("ui", "UI"),
+];
+
+fn load_records() -> Vec<LogRecord> {
+    match logging::log_file() {
+        Some(path) if path.exists() => logging::read_log_file(&path),
+        _ => logging::recent_records(),
+    }
+}
+
+/// Shows the application log with level and source filters, so it can be
+/// copied into bug reports.
+pub fn create_logs_page(on_show_toast: Rc<dyn Fn(String)>) -> gtk::Widget {
+    let main_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .build();
+
+    let header_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let level_labels: Vec<&str> = LEVELS.iter().map(|l| l.as_str()).collect();
+    let level_dropdown = gtk::DropDown::from_strings(&level_labels);
+    level_dropdown.set_tooltip_text(Some("Minimum level"));
+    let current_level = LEVELS
+        .iter()
+        .position(|l| *l == logging::max_level())
+        .unwrap_or(2);
+    level_dropdown.set_selected(current_level as u32);
+
+    let target_labels: Vec<&str> = TARGETS.iter().map(|(_, label)| *label).collect();
+    let target_dropdown = gtk::DropDown::from_strings(&target_labels);
+    target_dropdown.set_tooltip_text(Some("Source"));
+
+    let spacer = gtk::Box::builder().hexpand(true).build();
+    let refresh_btn = create_pill_button("Refresh", Some("view-refresh-symbolic"));
+    let copy_btn = create_pill_button("Copy", Some("edit-copy-symbolic"));
+    let folder_btn = create_pill_button("Open Folder", Some("folder-open-symbolic"));
+    let clear_btn = create_pill_button("Clear", Some("user-trash-symbolic"));
+
+    header_box.append(&level_dropdown);
+    header_box.append(&target_dropdown);
+    header_box.append(&spacer);
+    header_box.append(&refresh_btn);
+    header_box.append(&copy_btn);
+    header_box.append(&folder_btn);
+    header_box.append(&clear_btn);
+    main_box.append(&header_box);
+
+    let path_label = gtk::Label::builder()
+        .label(
+            logging::log_file()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| "Logging to memory only".to_string()),
+        )
+        .halign(gtk::Align::Start)
+        .css_classes(["caption", "dim-label"])
+        .selectable(true)
+        .margin_start(12)
+        .margin_bottom(6)
+        .build();
+    main_box.append(&path_label);
+
+    let text_view = gtk::TextView::builder()
+        .editable(false)
+        .cursor_visible(false)
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .left_margin(12)
+        .right_margin(12)
+        .top_margin(6)
+        .bottom_margin(6)
+        .build();
+    let scroll = gtk::ScrolledWindow::builder()
+        .child(&text_view)
+        .vexpand(true)
+        .build();
+    main_box.append(&scroll);
+
+    let records: Rc<RefCell<Vec<LogRecord>>> = Rc::new(RefCell::new(load_records()));
+
+    let render = {
+        let records = records.clone();
+        let text_view = text_view.clone();
+        let level_dropdown = level_dropdown.clone();
+        let target_dropdown = target_dropdown.clone();
+        Rc::new(move || {
+            let level = LEVELS
+                .get(level_dropdown.selected() as usize)
+                .copied()
+                .unwrap_or(Level::Info);
+            let target = TARGETS
+                .get(target_dropdown.selected() as usize)
+                .map(|(t, _)| *t)
+                .filter(|t| !t.is_empty());
+
+            let records = records.borrow();
+            let visible = logging::filter_records(&records, level, target);
+            let text = if visible.is_empty() {
+                "No log entries.".to_string()
+            } else {
+                visible
+                    .iter()
+                    .map(|r| r.to_line())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+            text_view.buffer().set_text(&text);
+        })
+    };
+    render();
+
+    let r = render.clone();
+    level_dropdown.connect_selected_notify(move |_| r());
+    let r = render.clone();
+    target_dropdown.connect_selected_notify(move |_| r());
+
+    let records_c = records.clone();
+    let r = render.clone();
+    refresh_btn.connect_clicked(move |_| {
+        *records_c.borrow_mut() = load_records();
+        r();
+    });
+
+    let text_view_c = text_view.clone();
+    let on_show_toast_c = on_show_toast.clone();
+    copy_btn.connect_clicked(move |btn| {
+        let buffer = text_view_c.buffer();
+        let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        btn.clipboard().set_text(&text);
+        on_show_toast_c("Log copied to clipboard".to_string());
+    });
+
+    folder_btn.connect_clicked(move |btn| {
+        let Some(dir) = logging::log_dir() else {
+            return;
+        };
+        let launcher = gtk::FileLauncher::new(Some(&gio::File::for_path(&dir)));
+        let window = btn.root().and_downcast::<gtk::Window>();
+        launcher.launch(window.as_ref(), None::<&gio::Cancellable>, |res| {
+            if let Err(e) = res {
+                crate::log_error!("ui", "Failed to open log folder: {}", e);
+            }
+        });
+    });
+
+    let r = render;
+    clear_btn.connect_clicked(move |_| {
+        logging::clear();
+        records.borrow_mut().clear();
+        r();
+        on_show_toast("Log cleared".to_string());
+    });
+
+    main_box.upcast()
+}
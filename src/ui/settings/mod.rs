@@ -4,6 +4,8 @@ pub mod general;
 pub mod gestures;
 pub mod hud;
 pub mod input;
+pub mod logs;
+pub mod routing;
 pub mod submaps;
 pub mod ui_elements;
 pub mod variables;
@@ -28,7 +30,9 @@ struct LazyPageState {
     input: Cell<bool>,
     gestures: Cell<bool>,
     submaps: Cell<bool>,
+    routing: Cell<bool>,
     ui_elements: Cell<bool>,
+    logs: Cell<bool>,
     about: Cell<bool>,
 }
 
@@ -42,7 +46,9 @@ impl Default for LazyPageState {
             input: Cell::new(false),
             gestures: Cell::new(false),
             submaps: Cell::new(false),
+            routing: Cell::new(false),
             ui_elements: Cell::new(false),
+            logs: Cell::new(false),
             about: Cell::new(false),
         }
     }
@@ -62,6 +68,7 @@ pub fn create_settings_view(
     on_sort_change: Rc<dyn Fn(String)>,
     on_show_toast: Rc<dyn Fn(String)>,
     on_focus_submap: Rc<dyn Fn(Option<String>)>,
+    on_edit_bind: Rc<dyn Fn(crate::keybind_object::KeybindObject)>,
     on_restore_clicked: Rc<dyn Fn()>,
 ) -> gtk::Widget {
     let config = Rc::new(RefCell::new(StyleConfig::load()));
@@ -167,9 +174,15 @@ pub fn create_settings_view(
     let placeholder_submaps = gtk::Box::new(gtk::Orientation::Vertical, 0);
     settings_stack.add_titled(&placeholder_submaps, Some("submaps"), "Submaps");
 
+    let placeholder_routing = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    settings_stack.add_titled(&placeholder_routing, Some("routing"), "Bind Routing");
+
     let placeholder_ui = gtk::Box::new(gtk::Orientation::Vertical, 0);
     settings_stack.add_titled(&placeholder_ui, Some("ui"), "UI Elements");
 
+    let placeholder_logs = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    settings_stack.add_titled(&placeholder_logs, Some("logs"), "Logs");
+
     let placeholder_about = gtk::Box::new(gtk::Orientation::Vertical, 0);
     settings_stack.add_titled(&placeholder_about, Some("about"), "About");
 
@@ -208,7 +221,12 @@ pub fn create_settings_view(
         lazy_load!(
             variables,
             "variables",
-            variables::create_variables_page(&window_c, on_show_toast_c.clone())
+            variables::create_variables_page(
+                &window_c,
+                &model_c,
+                on_show_toast_c.clone(),
+                on_edit_bind.clone()
+            )
         );
         lazy_load!(
             window,
@@ -244,6 +262,18 @@ pub fn create_settings_view(
             replace_placeholder(stack_inner, "submaps", &page.upcast());
         }
 
+        lazy_load!(
+            routing,
+            "routing",
+            routing::create_routing_page(config_c.clone(), on_show_toast_c.clone())
+        );
+
+        if name.as_str() == "logs" && !lazy_state.logs.get() {
+            lazy_state.logs.set(true);
+            let page = logs::create_logs_page(on_show_toast_c.clone());
+            replace_placeholder(stack_inner, "logs", &page);
+        }
+
         lazy_load!(about, "about", about::create_about_page(&window_c));
 
         lazy_load!(
@@ -304,7 +334,7 @@ fn ensure_input_config_loaded(
         let (ic, gc) = match load_input_config() {
             Ok((i, g)) => (i, g),
             Err(e) => {
-                eprintln!("Failed to load input/gestures config: {}", e);
+                crate::log_error!("config", "Failed to load input/gestures config: {}", e);
                 (
                     crate::parser::input::InputConfig::default(),
                     crate::parser::input::GesturesConfig::default(),
@@ -0,0 +1,202 @@
+use crate::config::categories::load_categories;
+use crate::config::routing::{RouteMatch, RouteRule};
+use crate::config::StyleConfig;
+use crate::ui::utils::components::{create_flat_button, create_suggested_button};
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type RefreshHandle = Rc<RefCell<Option<Rc<dyn Fn()>>>>;
+
+const KINDS: &[&str] = &["Dispatcher", "Submap", "Category"];
+
+fn describe(rule: &RouteRule) -> String {
+    match &rule.matcher {
+        RouteMatch::Dispatcher(d) => format!("Dispatcher '{}'", d),
+        RouteMatch::Submap(s) if s == "*" => "Any submap".to_string(),
+        RouteMatch::Submap(s) => format!("Submap '{}'", s),
+        RouteMatch::Category(c) => format!("Category '{}'", c),
+    }
+}
+
+fn entry_row(title: &str, placeholder: &str) -> (adw::ActionRow, gtk::Entry) {
+    let entry = gtk::Entry::builder()
+        .placeholder_text(placeholder)
+        .valign(gtk::Align::Center)
+        .build();
+    let row = adw::ActionRow::builder()
+        .title(title)
+        .activatable(true)
+        .build();
+    row.add_suffix(&entry);
+    let e = entry.clone();
+    row.connect_activate(move |_| {
+        e.grab_focus();
+    });
+    (row, entry)
+}
+
+/// Edits the rules deciding which file new binds are written to.
+pub fn create_routing_page(
+    config: Rc<RefCell<StyleConfig>>,
+    on_show_toast: Rc<dyn Fn(String)>,
+) -> adw::PreferencesPage {
+    let page = adw::PreferencesPage::builder()
+        .title("Bind Routing")
+        .icon_name("mail-send-symbolic")
+        .build();
+
+    let rules_group = adw::PreferencesGroup::builder()
+        .title("Routing Rules")
+        .description(
+            "New binds go to the file of the first matching rule, or to the main config \
+             when none matches. Files that aren't sourced yet are created and sourced \
+             from the main config.",
+        )
+        .build();
+    page.add(&rules_group);
+
+    let rows: Rc<RefCell<Vec<adw::ActionRow>>> = Rc::new(RefCell::new(Vec::new()));
+    let refresh: RefreshHandle = Rc::new(RefCell::new(None));
+
+    let save = {
+        let config = config.clone();
+        let on_show_toast = on_show_toast.clone();
+        move || {
+            if let Err(e) = config.borrow().save() {
+                on_show_toast(format!("Failed to save rules: {}", e));
+            }
+        }
+    };
+    let save = Rc::new(save);
+
+    let group_weak = rules_group.downgrade();
+    let refresh_c = refresh.clone();
+    let config_c = config.clone();
+    let save_c = save.clone();
+    let rebuild: Rc<dyn Fn()> = Rc::new(move || {
+        let Some(group) = group_weak.upgrade() else {
+            return;
+        };
+        for row in rows.borrow_mut().drain(..) {
+            group.remove(&row);
+        }
+
+        let rules = config_c.borrow().bind_routes.clone();
+        if rules.is_empty() {
+            let row = adw::ActionRow::builder()
+                .title("No Rules")
+                .subtitle("Every new bind goes to the main config.")
+                .build();
+            group.add(&row);
+            rows.borrow_mut().push(row);
+            return;
+        }
+
+        for (index, rule) in rules.iter().enumerate() {
+            let row = adw::ActionRow::builder()
+                .title(describe(rule))
+                .subtitle(format!("→ {}", rule.file))
+                .build();
+
+            let up_btn = create_flat_button("go-up-symbolic", "Check this rule earlier");
+            up_btn.set_valign(gtk::Align::Center);
+            up_btn.set_sensitive(index > 0);
+            let delete_btn = create_flat_button("user-trash-symbolic", "Delete rule");
+            delete_btn.set_valign(gtk::Align::Center);
+            row.add_suffix(&up_btn);
+            row.add_suffix(&delete_btn);
+
+            let config = config_c.clone();
+            let save = save_c.clone();
+            let refresh = refresh_c.clone();
+            up_btn.connect_clicked(move |_| {
+                config.borrow_mut().bind_routes.swap(index - 1, index);
+                save();
+                if let Some(f) = refresh.borrow().as_ref() {
+                    f();
+                }
+            });
+
+            let config = config_c.clone();
+            let save = save_c.clone();
+            let refresh = refresh_c.clone();
+            delete_btn.connect_clicked(move |_| {
+                config.borrow_mut().bind_routes.remove(index);
+                save();
+                if let Some(f) = refresh.borrow().as_ref() {
+                    f();
+                }
+            });
+
+            group.add(&row);
+            rows.borrow_mut().push(row);
+        }
+    });
+    *refresh.borrow_mut() = Some(rebuild.clone());
+    rebuild();
+
+    // Add Rule
+    let add_group = adw::PreferencesGroup::builder()
+        .title("Add Rule")
+        .description(
+            "Use * as the submap to match every submap, and {submap} in the file to \
+             name it after the bind's submap. Relative files are inside the Hyprland \
+             config directory.",
+        )
+        .build();
+
+    let kind_row = adw::ComboRow::builder()
+        .title("Match")
+        .model(&gtk::StringList::new(KINDS))
+        .build();
+    let (value_row, value_entry) = entry_row("Value", "exec");
+    let (file_row, file_entry) = entry_row("File", "apps.conf");
+    let value_entry_c = value_entry.clone();
+    kind_row.connect_selected_notify(move |row| {
+        let hint = match row.selected() {
+            1 => "*".to_string(),
+            2 => load_categories()
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => "exec".to_string(),
+        };
+        value_entry_c.set_placeholder_text(Some(&hint));
+    });
+
+    let add_btn = create_suggested_button("Add Rule", Some("list-add-symbolic"));
+    add_btn.set_valign(gtk::Align::Center);
+    let add_row = adw::ActionRow::builder().build();
+    add_row.add_suffix(&add_btn);
+
+    add_group.add(&kind_row);
+    add_group.add(&value_row);
+    add_group.add(&file_row);
+    add_group.add(&add_row);
+    page.add(&add_group);
+
+    add_btn.connect_clicked(move |_| {
+        let kind = KINDS[kind_row.selected() as usize % KINDS.len()].to_lowercase();
+        let text = format!(
+            "{}:{} -> {}",
+            kind,
+            value_entry.text().trim(),
+            file_entry.text().trim()
+        );
+        let Some(rule) = RouteRule::parse(&text) else {
+            on_show_toast(format!("Invalid rule '{}'", text));
+            return;
+        };
+        config.borrow_mut().bind_routes.push(rule);
+        save();
+        value_entry.set_text("");
+        file_entry.set_text("");
+        rebuild();
+    });
+
+    page
+}
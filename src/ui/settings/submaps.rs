@@ -1,14 +1,25 @@
+use crate::config::favorites::{follow_edits, BindEdit};
 use crate::config::StyleConfig;
 use crate::keybind_object::KeybindObject;
-use crate::ui::utils::collect_submaps;
+use crate::parser::submaps::{
+    delete_submap, move_keybinds_to_submap, rename_submap, submap_health, SubmapHealth,
+};
+use crate::ui::utils::{
+    collect_submaps, create_destructive_button, create_flat_button, create_pill_button,
+    create_suggested_button, reload_keybinds,
+};
 use crate::ui::wizards::create_add_submap_wizard;
 use gtk::gio;
 use gtk4 as gtk;
 use libadwaita as adw;
 use libadwaita::prelude::*;
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::Rc;
 
+/// The move target that takes binds out of any submap.
+const NO_SUBMAP: &str = "No Submap";
+
 pub fn create_submaps_page(
     model: &gio::ListStore,
     config: Rc<RefCell<StyleConfig>>,
@@ -102,6 +113,7 @@ pub fn create_submaps_page(
     let default_row_weak = default_submap_row.downgrade();
     let config_c_update = config.clone();
     let on_focus_submap_c = on_focus_submap.clone();
+    let toast_c_update = toast_overlay.clone();
     let added_rows = Rc::new(RefCell::new(Vec::<gtk::Widget>::new()));
 
     let update_ui = Rc::new(move |model: &gio::ListStore| {
@@ -125,8 +137,23 @@ pub fn create_submaps_page(
         }
 
         let submaps = collect_submaps(model);
+        let objects: Vec<KeybindObject> = model
+            .snapshot()
+            .into_iter()
+            .filter_map(|o| o.downcast::<KeybindObject>().ok())
+            .collect();
+        let keybinds: Vec<_> = objects.iter().map(|o| o.to_keybind()).collect();
+        let default_submap = config_c_update.borrow().default_submap.clone();
+        let health = submap_health(&keybinds, default_submap.as_deref());
+
+        let with_problems = health.iter().filter(|h| !h.problems().is_empty()).count();
+        list_group.set_description(match with_problems {
+            0 => None,
+            1 => Some("1 submap can't be entered or left properly."),
+            _ => Some("Some submaps can't be entered or left properly."),
+        });
 
-        if submaps.is_empty() {
+        if health.is_empty() {
             let row = adw::ActionRow::builder()
                 .title("No Submaps Detected")
                 .subtitle("Submaps are defined using 'submap = name' in your config.")
@@ -134,33 +161,14 @@ pub fn create_submaps_page(
             list_group.add(&row);
             added_rows.borrow_mut().push(row.upcast());
         } else {
-            for name in &submaps {
-                let mut count = 0;
-                for obj in model.snapshot() {
-                    if let Some(obj) = obj.downcast_ref::<KeybindObject>() {
-                        if obj.with_data(|d| {
-                            d.submap.as_ref().map(|s| s.to_string()) == Some(name.clone())
-                        }) {
-                            count += 1;
-                        }
-                    }
-                }
-
-                let row = adw::ActionRow::builder()
-                    .title(name)
-                    .subtitle(format!("{} keybinds", count))
-                    .activatable(true)
-                    .build();
-
-                let icon = gtk::Image::from_icon_name("go-next-symbolic");
-                row.add_suffix(&icon);
-
-                let on_focus = on_focus_submap_c.clone();
-                let name_clone = name.clone();
-                row.connect_activated(move |_| {
-                    on_focus(Some(name_clone.clone()));
-                });
-
+            let ctx = SubmapRowContext {
+                model: model.clone(),
+                config: config_c_update.clone(),
+                toast_overlay: toast_c_update.clone(),
+                on_focus_submap: on_focus_submap_c.clone(),
+            };
+            for submap in &health {
+                let row = create_submap_row(submap, &objects, &submaps, &ctx);
                 list_group.add(&row);
                 added_rows.borrow_mut().push(row.upcast());
             }
@@ -204,3 +212,319 @@ pub fn create_submaps_page(
     page.add(&list_group);
     page
 }
+
+struct SubmapRowContext {
+    model: gio::ListStore,
+    config: Rc<RefCell<StyleConfig>>,
+    toast_overlay: adw::ToastOverlay,
+    on_focus_submap: Rc<dyn Fn(Option<String>)>,
+}
+
+fn show_toast(overlay: &adw::ToastOverlay, message: String) {
+    overlay.add_toast(
+        adw::Toast::builder()
+            .title(message)
+            .timeout(crate::config::constants::TOAST_TIMEOUT)
+            .build(),
+    );
+}
+
+/// Backs up the config before a submap edit. On failure the edit must not go
+/// ahead, so this shows why and returns false.
+fn backup_before_edit(overlay: &adw::ToastOverlay) -> bool {
+    match crate::ui::utils::backup::perform_backup(true) {
+        Ok(_) => true,
+        Err(e) => {
+            crate::log_error!("backup", "Failed to backup config: {}", e);
+            show_toast(
+                overlay,
+                format!("Backup failed, nothing was changed: {}", e),
+            );
+            false
+        }
+    }
+}
+
+fn bind_location(obj: &KeybindObject) -> (PathBuf, usize) {
+    obj.with_data(|d| (PathBuf::from(d.file_path.as_ref()), d.line_number as usize))
+}
+
+/// Whether the bind is inside `name` or switches to it.
+fn touches_submap(obj: &KeybindObject, name: &str) -> bool {
+    obj.with_data(|d| {
+        d.submap.as_deref() == Some(name)
+            || (&*d.dispatcher == "submap" && d.args.as_deref().map(str::trim) == Some(name))
+    })
+}
+
+/// One submap with its reachability problems, its binds and the controls to
+/// rename, delete or move binds out of it.
+fn create_submap_row(
+    health: &SubmapHealth,
+    objects: &[KeybindObject],
+    all_submaps: &[String],
+    ctx: &SubmapRowContext,
+) -> adw::ExpanderRow {
+    let name = health.name.clone();
+    let subtitle = if health.is_undefined() {
+        "Referenced, but not defined".to_string()
+    } else {
+        format!("{} keybinds", health.bind_count)
+    };
+    let row = adw::ExpanderRow::builder()
+        .title(&name)
+        .subtitle(subtitle)
+        .build();
+
+    let problems = health.problems();
+    if !problems.is_empty() {
+        let icon = gtk::Image::builder()
+            .icon_name("dialog-warning-symbolic")
+            .tooltip_text(problems.join("\n"))
+            .css_classes(["warning"])
+            .build();
+        row.add_prefix(&icon);
+    }
+
+    let focus_btn = create_flat_button("go-next-symbolic", "Show keybinds");
+    focus_btn.set_valign(gtk::Align::Center);
+    let on_focus = ctx.on_focus_submap.clone();
+    let name_c = name.clone();
+    focus_btn.connect_clicked(move |_| on_focus(Some(name_c.clone())));
+    row.add_action(&focus_btn);
+
+    for problem in &problems {
+        row.add_row(
+            &adw::ActionRow::builder()
+                .title(*problem)
+                .css_classes(["warning"])
+                .build(),
+        );
+    }
+
+    // Rename
+    let rename_row = adw::ActionRow::builder()
+        .title("Rename")
+        .subtitle("Also updates every bind that switches to it")
+        .build();
+    let rename_entry = gtk::Entry::builder()
+        .text(&name)
+        .valign(gtk::Align::Center)
+        .build();
+    let rename_btn = create_suggested_button("Rename", None);
+    rename_btn.set_valign(gtk::Align::Center);
+    rename_row.add_suffix(&rename_entry);
+    rename_row.add_suffix(&rename_btn);
+    row.add_row(&rename_row);
+
+    let touching: Vec<KeybindObject> = objects
+        .iter()
+        .filter(|obj| touches_submap(obj, &name))
+        .cloned()
+        .collect();
+    {
+        let name = name.clone();
+        let touching = touching.clone();
+        let model = ctx.model.clone();
+        let config = ctx.config.clone();
+        let toast = ctx.toast_overlay.clone();
+        let entry = rename_entry.clone();
+        let do_rename = move || {
+            let new_name = entry.text().trim().to_string();
+            if new_name == name {
+                return;
+            }
+            let taken = model
+                .snapshot()
+                .iter()
+                .filter_map(|obj| obj.downcast_ref::<KeybindObject>())
+                .any(|obj| touches_submap(obj, &new_name));
+            if taken {
+                show_toast(
+                    &toast,
+                    format!("A submap named '{}' already exists", new_name),
+                );
+                return;
+            }
+            if !backup_before_edit(&toast) {
+                return;
+            }
+            match rename_submap(&name, &new_name) {
+                Ok(_) => {
+                    // Line numbers don't change, only the submap names in them
+                    let edits: Vec<BindEdit> = touching
+                        .iter()
+                        .map(|obj| {
+                            let (file, line) = bind_location(obj);
+                            BindEdit::Moved {
+                                old: obj.bind_id(),
+                                file,
+                                line,
+                            }
+                        })
+                        .collect();
+                    follow_edits(&edits);
+                    if config.borrow().default_submap.as_deref() == Some(name.as_str()) {
+                        config.borrow_mut().default_submap = Some(new_name.clone());
+                        let _ = config.borrow().save();
+                    }
+                    show_toast(&toast, format!("Renamed '{}' to '{}'", name, new_name));
+                    reload_keybinds(&model);
+                }
+                Err(e) => show_toast(&toast, format!("Failed to rename submap: {}", e)),
+            }
+        };
+        let do_rename = Rc::new(do_rename);
+        let f = do_rename.clone();
+        rename_btn.connect_clicked(move |_| f());
+        rename_entry.connect_activate(move |_| do_rename());
+    }
+
+    // Binds, with a way to move the checked ones to another submap
+    let members: Vec<KeybindObject> = objects
+        .iter()
+        .filter(|obj| obj.with_data(|d| d.submap.as_deref() == Some(name.as_str())))
+        .cloned()
+        .collect();
+    let checks: Rc<Vec<(gtk::CheckButton, KeybindObject)>> = Rc::new(
+        members
+            .into_iter()
+            .map(|obj| {
+                let check = gtk::CheckButton::builder()
+                    .valign(gtk::Align::Center)
+                    .build();
+                let (title, keys) = obj.with_data(|d| {
+                    let title = match d.description.as_deref().filter(|s| !s.trim().is_empty()) {
+                        Some(description) => description.to_string(),
+                        None => format!("{} {}", d.dispatcher, d.args.as_deref().unwrap_or("")),
+                    };
+                    let keys = if d.clean_mods.is_empty() {
+                        d.key.to_string()
+                    } else {
+                        format!("{} + {}", d.clean_mods, d.key)
+                    };
+                    (title, keys)
+                });
+                let bind_row = adw::ActionRow::builder()
+                    .title(gtk::glib::markup_escape_text(title.trim()))
+                    .subtitle(gtk::glib::markup_escape_text(&keys))
+                    .activatable_widget(&check)
+                    .build();
+                bind_row.add_prefix(&check);
+                row.add_row(&bind_row);
+                (check, obj)
+            })
+            .collect(),
+    );
+
+    if !checks.is_empty() {
+        let targets: Vec<String> = std::iter::once(NO_SUBMAP.to_string())
+            .chain(all_submaps.iter().filter(|s| **s != name).cloned())
+            .collect();
+        let target_model =
+            gtk::StringList::new(&targets.iter().map(|s| s.as_str()).collect::<Vec<_>>());
+        let move_row = adw::ComboRow::builder()
+            .title("Move Checked Binds")
+            .subtitle("Moves them into another submap, created if needed")
+            .model(&target_model)
+            .build();
+        let move_btn = create_pill_button("Move", None);
+        move_btn.set_valign(gtk::Align::Center);
+        move_row.add_suffix(&move_btn);
+        row.add_row(&move_row);
+
+        let model = ctx.model.clone();
+        let toast = ctx.toast_overlay.clone();
+        let checks = checks.clone();
+        move_btn.connect_clicked(move |_| {
+            let Some(target) = targets.get(move_row.selected() as usize) else {
+                return;
+            };
+            let target = (target != NO_SUBMAP).then_some(target.as_str());
+            let chosen: Vec<&KeybindObject> = checks
+                .iter()
+                .filter(|(check, _)| check.is_active())
+                .map(|(_, obj)| obj)
+                .collect();
+            if chosen.is_empty() {
+                show_toast(&toast, "Check the binds to move first".to_string());
+                return;
+            }
+            let locations: Vec<(PathBuf, usize)> =
+                chosen.iter().map(|obj| bind_location(obj)).collect();
+            if !backup_before_edit(&toast) {
+                return;
+            }
+            match move_keybinds_to_submap(&locations, target) {
+                Ok(moved) => {
+                    let edits: Vec<BindEdit> = chosen
+                        .iter()
+                        .zip(moved)
+                        .map(|(obj, (file, line))| BindEdit::Moved {
+                            old: obj.bind_id(),
+                            file,
+                            line,
+                        })
+                        .collect();
+                    follow_edits(&edits);
+                    show_toast(
+                        &toast,
+                        format!(
+                            "Moved {} keybinds to {}",
+                            edits.len(),
+                            target.unwrap_or("no submap")
+                        ),
+                    );
+                    reload_keybinds(&model);
+                }
+                Err(e) => show_toast(&toast, format!("Failed to move keybinds: {}", e)),
+            }
+        });
+    }
+
+    // Delete, confirmed by a second click
+    let delete_row = adw::ActionRow::builder()
+        .title("Delete Submap")
+        .subtitle(format!(
+            "Removes its block and the {} binds in or entering it",
+            touching.len()
+        ))
+        .build();
+    let delete_btn = create_destructive_button("Delete", None);
+    delete_btn.set_valign(gtk::Align::Center);
+    delete_row.add_suffix(&delete_btn);
+    row.add_row(&delete_row);
+
+    let armed = Rc::new(std::cell::Cell::new(false));
+    let model = ctx.model.clone();
+    let toast = ctx.toast_overlay.clone();
+    delete_btn.connect_clicked(move |btn| {
+        if !armed.replace(true) {
+            btn.set_label("Click Again to Delete");
+            return;
+        }
+        if !backup_before_edit(&toast) {
+            armed.set(false);
+            btn.set_label("Delete");
+            return;
+        }
+        match delete_submap(&name) {
+            Ok(_) => {
+                let edits: Vec<BindEdit> = touching
+                    .iter()
+                    .map(|obj| BindEdit::Deleted(obj.bind_id()))
+                    .collect();
+                follow_edits(&edits);
+                show_toast(&toast, format!("Deleted submap '{}'", name));
+                reload_keybinds(&model);
+            }
+            Err(e) => {
+                armed.set(false);
+                btn.set_label("Delete");
+                show_toast(&toast, format!("Failed to delete submap: {}", e));
+            }
+        }
+    });
+
+    row
+}
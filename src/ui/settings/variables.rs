@@ -1,5 +1,12 @@
+use crate::keybind_object::KeybindObject;
+use crate::parser::refactor::{
+    apply_refactor, plan_variable_refactor, variable_suggestions, RefactorPlan, VariableSuggestion,
+};
+use crate::parser::variables::{variable_report, VariableReference};
 use crate::parser::{self, Variable};
 use crate::ui::utils::components::*;
+use crate::ui::utils::reload_keybinds;
+use gtk::gio;
 use gtk4 as gtk;
 use libadwaita as adw;
 use libadwaita::prelude::*;
@@ -8,10 +15,80 @@ use std::rc::Rc;
 
 type RefreshHandle = Rc<RefCell<Option<Rc<dyn Fn()>>>>;
 type HeaderUpdateFn = Rc<dyn Fn(&str, Option<&str>)>;
+type ShowReferencesFn = Rc<dyn Fn(Option<&Variable>)>;
+
+/// The bind defined on a reference's line, if it is one.
+fn bind_at(model: &gio::ListStore, reference: &VariableReference) -> Option<KeybindObject> {
+    model
+        .snapshot()
+        .into_iter()
+        .filter_map(|o| o.downcast::<KeybindObject>().ok())
+        .find(|obj| {
+            obj.with_data(|d| {
+                d.line_number as usize == reference.line_number
+                    && std::path::Path::new(d.file_path.as_ref()) == reference.file_path
+            })
+        })
+}
+
+fn location(path: &std::path::Path, line_number: usize) -> String {
+    format!("{}:{}", path.display(), line_number + 1)
+}
+
+/// A row for a line using a variable; bind lines open in the edit view.
+fn create_reference_row(
+    reference: &VariableReference,
+    model: &gio::ListStore,
+    on_edit_bind: &Rc<dyn Fn(KeybindObject)>,
+) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(gtk::glib::markup_escape_text(&reference.line))
+        .subtitle(gtk::glib::markup_escape_text(&location(
+            &reference.file_path,
+            reference.line_number,
+        )))
+        .title_lines(2)
+        .build();
+    if let Some(obj) = bind_at(model, reference) {
+        row.set_activatable(true);
+        row.set_tooltip_text(Some("Edit this keybind"));
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        let on_edit = on_edit_bind.clone();
+        row.connect_activated(move |_| on_edit(obj.clone()));
+    }
+    row
+}
+
+/// Lists `$names` used in the config that no variable defines.
+fn refresh_undefined_ui(
+    group: &adw::PreferencesGroup,
+    rows: &RefCell<Vec<adw::ActionRow>>,
+    model: &gio::ListStore,
+    on_edit_bind: &Rc<dyn Fn(KeybindObject)>,
+) {
+    for row in rows.borrow_mut().drain(..) {
+        group.remove(&row);
+    }
+    let undefined = variable_report().map(|r| r.undefined).unwrap_or_default();
+    group.set_visible(!undefined.is_empty());
+    for reference in &undefined {
+        let row = create_reference_row(reference, model, on_edit_bind);
+        row.add_prefix(
+            &gtk::Label::builder()
+                .label(&reference.name)
+                .css_classes(["error", "heading"])
+                .build(),
+        );
+        group.add(&row);
+        rows.borrow_mut().push(row);
+    }
+}
 
 pub fn create_variables_page(
     _window: &adw::ApplicationWindow,
+    model: &gio::ListStore,
     on_show_toast: Rc<dyn Fn(String)>,
+    on_edit_bind: Rc<dyn Fn(KeybindObject)>,
 ) -> gtk::Widget {
     let stack = gtk::Stack::builder()
         .transition_type(gtk::StackTransitionType::SlideLeftRight)
@@ -37,7 +114,13 @@ pub fn create_variables_page(
 
     let add_btn = create_suggested_button("Add New", Some("list-add-symbolic"));
 
+    let suggest_btn = create_pill_button("Suggest", None);
+    suggest_btn.set_tooltip_text(Some(
+        "Suggest variables for values repeated across keybinds",
+    ));
+
     top_bar.append(&search_entry);
+    top_bar.append(&suggest_btn);
     top_bar.append(&add_btn);
     list_box_container.append(&top_bar);
 
@@ -62,6 +145,13 @@ pub fn create_variables_page(
         .build();
 
     list_group_box.append(&list_box);
+
+    let undefined_group = adw::PreferencesGroup::builder()
+        .title("Undefined Variables")
+        .description("Used in your config but never defined, so Hyprland leaves them as they are.")
+        .visible(false)
+        .build();
+    list_group_box.append(&undefined_group);
     clamp.set_child(Some(&list_group_box));
     scrolled.set_child(Some(&clamp));
 
@@ -155,6 +245,9 @@ pub fn create_variables_page(
     pref_group.add(&refactor_row);
 
     form_box.append(&pref_group);
+
+    let refs_group = adw::PreferencesGroup::builder().title("References").build();
+    form_box.append(&refs_group);
     form_clamp.set_child(Some(&form_box));
     form_scroll.set_child(Some(&form_clamp));
 
@@ -297,6 +390,43 @@ pub fn create_variables_page(
     // ================== LOGIC ==================
     let current_var: Rc<RefCell<Option<Variable>>> = Rc::new(RefCell::new(None));
 
+    // -- References of the variable being edited --
+    let refs_rows: Rc<RefCell<Vec<adw::ActionRow>>> = Rc::new(RefCell::new(Vec::new()));
+    let model_refs = model.clone();
+    let on_edit_refs = on_edit_bind.clone();
+    let show_references: ShowReferencesFn = Rc::new(move |var: Option<&Variable>| {
+        for row in refs_rows.borrow_mut().drain(..) {
+            refs_group.remove(&row);
+        }
+        let Some(var) = var else {
+            refs_group.set_visible(false);
+            return;
+        };
+        refs_group.set_visible(true);
+
+        let report = variable_report().unwrap_or_default();
+        let references: Vec<&VariableReference> = report.references_to(&var.name).collect();
+        let mut notes = Vec::new();
+        if let Some(by) = report.shadowed_by(var) {
+            notes.push(format!(
+                "Redefined at {}, which replaces this value for every line after it.",
+                location(&by.file_path, by.line_number)
+            ));
+        }
+        notes.push(match references.len() {
+            0 => "No line uses this variable.".to_string(),
+            1 => "Used on 1 line.".to_string(),
+            n => format!("Used on {} lines.", n),
+        });
+        refs_group.set_description(Some(&notes.join(" ")));
+
+        for reference in references {
+            let row = create_reference_row(reference, &model_refs, &on_edit_refs);
+            refs_group.add(&row);
+            refs_rows.borrow_mut().push(row);
+        }
+    });
+
     // -- Refresh Forward Declaration --
     let refresh_handle: RefreshHandle = Rc::new(RefCell::new(None));
 
@@ -358,7 +488,7 @@ pub fn create_variables_page(
 
         // Backup before modifying
         if let Err(e) = crate::ui::utils::backup::perform_backup(false) {
-            eprintln!("Failed to backup config: {}", e);
+            crate::log_error!("backup", "Failed to backup config: {}", e);
         }
 
         let clean_name = if let Some(stripped) = name.strip_prefix('$') {
@@ -429,7 +559,7 @@ pub fn create_variables_page(
         if let Some(var) = &*current_var_del.borrow() {
             // Backup before modifying
             if let Err(e) = crate::ui::utils::backup::perform_backup(false) {
-                eprintln!("Failed to backup config: {}", e);
+                crate::log_error!("backup", "Failed to backup config: {}", e);
             }
 
             let name_clean = var.name.trim_start_matches('$');
@@ -527,8 +657,17 @@ pub fn create_variables_page(
     let delete_confirm_btn_refresh = delete_confirm_btn.clone();
     // Also reset the refactor switch on add/edit
     let refactor_switch_refresh = refactor_switch.clone();
+    let show_references_refresh = show_references.clone();
+    let undefined_rows: RefCell<Vec<adw::ActionRow>> = RefCell::new(Vec::new());
+    let model_undefined = model.clone();
 
     let refresh_impl = Rc::new(move || {
+        refresh_undefined_ui(
+            &undefined_group,
+            &undefined_rows,
+            &model_undefined,
+            &on_edit_bind,
+        );
         refresh_list_ui(
             &list_box_c,
             &search_entry_c,
@@ -541,11 +680,16 @@ pub fn create_variables_page(
             &warning_body_refresh,
             &delete_confirm_btn_refresh,
             &refactor_switch_refresh,
+            &show_references_refresh,
         );
     });
 
     *refresh_handle.borrow_mut() = Some(refresh_impl.clone());
 
+    let open_suggestions =
+        add_suggestion_pages(&stack, model, on_show_toast.clone(), refresh_handle.clone());
+    suggest_btn.connect_clicked(move |_| open_suggestions());
+
     // Initial
     refresh_impl();
 
@@ -562,12 +706,14 @@ pub fn create_variables_page(
     let value_entry_c = value_entry.clone();
     let update_title_c = update_header_title.clone();
     let refactor_switch_add = refactor_switch.clone();
+    let show_references_add = show_references.clone();
 
     add_btn.connect_clicked(move |_| {
         *current_var_c.borrow_mut() = None;
         name_entry_c.set_text("");
         value_entry_c.set_text("");
         refactor_switch_add.set_active(false); // Reset switch
+        show_references_add(None);
         update_title_c("Add New Variable", None);
         stack_c.set_visible_child_name("edit");
         name_entry_c.grab_focus();
@@ -578,6 +724,317 @@ pub fn create_variables_page(
     stack.upcast()
 }
 
+/// A suggestion on the suggestions page.
+struct SuggestionRow {
+    suggestion: VariableSuggestion,
+    check: gtk::CheckButton,
+    name_entry: gtk::Entry,
+}
+
+/// The checked suggestions under the names entered for them.
+fn checked_suggestions(rows: &[SuggestionRow]) -> anyhow::Result<Vec<VariableSuggestion>> {
+    let defined = parser::get_defined_variables()?;
+    let mut checked: Vec<VariableSuggestion> = Vec::new();
+    for row in rows.iter().filter(|r| r.check.is_active()) {
+        let mut suggestion = row.suggestion.clone();
+        if !suggestion.existing {
+            let name = row
+                .name_entry
+                .text()
+                .trim()
+                .trim_start_matches('$')
+                .to_string();
+            if name.is_empty()
+                || name.starts_with(|c: char| c.is_ascii_digit())
+                || !name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            {
+                anyhow::bail!("'{}' is not a valid variable name", name);
+            }
+            if defined
+                .iter()
+                .any(|v| v.name.trim_start_matches('$') == name)
+            {
+                anyhow::bail!("A variable named '${}' already exists", name);
+            }
+            if checked.iter().any(|s| s.name == name) {
+                anyhow::bail!("'${}' is used for more than one value", name);
+            }
+            suggestion.name = name;
+        }
+        checked.push(suggestion);
+    }
+    Ok(checked)
+}
+
+/// Adds the pages suggesting variables for repeated values and previewing
+/// the edits. Returns the function opening them.
+fn add_suggestion_pages(
+    stack: &gtk::Stack,
+    model: &gio::ListStore,
+    on_show_toast: Rc<dyn Fn(String)>,
+    refresh_handle: RefreshHandle,
+) -> Rc<dyn Fn()> {
+    // ================== SUGGESTIONS VIEW ==================
+    let suggest_container = gtk::Box::new(gtk::Orientation::Vertical, 0);
+
+    let stack_c = stack.clone();
+    let suggest_header = create_page_header(
+        "Suggested Variables",
+        Some("Values repeated across your keybinds"),
+        "Back",
+        move || {
+            stack_c.set_visible_child_name("list");
+        },
+    );
+    suggest_header.set_margin_top(12);
+    suggest_header.set_margin_bottom(12);
+    suggest_header.set_margin_start(12);
+    suggest_header.set_margin_end(12);
+
+    let preview_btn = create_suggested_button("Preview", None);
+    preview_btn.set_tooltip_text(Some("Preview the edits for the checked values"));
+    suggest_header.append(&preview_btn);
+
+    suggest_container.append(&suggest_header);
+    suggest_container.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+    let suggest_group = adw::PreferencesGroup::builder()
+        .description(
+            "Check the values to replace with a variable. Values an existing variable \
+             already holds use that variable.",
+        )
+        .margin_top(24)
+        .margin_bottom(24)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+    suggest_container.append(
+        &gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .vexpand(true)
+            .child(
+                &adw::Clamp::builder()
+                    .maximum_size(800)
+                    .child(&suggest_group)
+                    .build(),
+            )
+            .build(),
+    );
+    stack.add_named(&suggest_container, Some("suggest"));
+
+    // ================== PREVIEW VIEW ==================
+    let preview_container = gtk::Box::new(gtk::Orientation::Vertical, 0);
+
+    let stack_c = stack.clone();
+    let preview_header = create_page_header(
+        "Preview Changes",
+        Some("A backup is made before anything is written"),
+        "Back",
+        move || {
+            stack_c.set_visible_child_name("suggest");
+        },
+    );
+    preview_header.set_margin_top(12);
+    preview_header.set_margin_bottom(12);
+    preview_header.set_margin_start(12);
+    preview_header.set_margin_end(12);
+
+    let apply_btn = create_suggested_button("Apply", None);
+    apply_btn.set_tooltip_text(Some("Back up the config and apply every edit"));
+    preview_header.append(&apply_btn);
+
+    preview_container.append(&preview_header);
+    preview_container.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+    let preview_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(24)
+        .margin_top(24)
+        .margin_bottom(24)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+    preview_container.append(
+        &gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .vexpand(true)
+            .child(
+                &adw::Clamp::builder()
+                    .maximum_size(800)
+                    .child(&preview_box)
+                    .build(),
+            )
+            .build(),
+    );
+    stack.add_named(&preview_container, Some("preview"));
+
+    // ================== LOGIC ==================
+    let rows: Rc<RefCell<Vec<SuggestionRow>>> = Rc::new(RefCell::new(Vec::new()));
+    let suggest_widgets: Rc<RefCell<Vec<adw::ActionRow>>> = Rc::new(RefCell::new(Vec::new()));
+    let plan: Rc<RefCell<Option<RefactorPlan>>> = Rc::new(RefCell::new(None));
+
+    // Preview
+    let rows_p = rows.clone();
+    let plan_p = plan.clone();
+    let stack_p = stack.clone();
+    let toast_p = on_show_toast.clone();
+    preview_btn.connect_clicked(move |_| {
+        let checked = match checked_suggestions(&rows_p.borrow()) {
+            Ok(checked) => checked,
+            Err(e) => {
+                toast_p(e.to_string());
+                return;
+            }
+        };
+        if checked.is_empty() {
+            toast_p("Check at least one value".to_string());
+            return;
+        }
+        let new_plan = match plan_variable_refactor(&checked) {
+            Ok(new_plan) => new_plan,
+            Err(e) => {
+                toast_p(format!("Error: {}", e));
+                return;
+            }
+        };
+        if new_plan.changes.is_empty() {
+            toast_p("None of the checked values are left to replace".to_string());
+            return;
+        }
+
+        while let Some(child) = preview_box.first_child() {
+            preview_box.remove(&child);
+        }
+        let mut group: Option<(std::path::PathBuf, adw::PreferencesGroup)> = None;
+        for change in &new_plan.changes {
+            if group.as_ref().map(|(path, _)| path) != Some(&change.file_path) {
+                let new_group = adw::PreferencesGroup::builder()
+                    .title(gtk::glib::markup_escape_text(
+                        &change.file_path.display().to_string(),
+                    ))
+                    .build();
+                preview_box.append(&new_group);
+                group = Some((change.file_path.clone(), new_group));
+            }
+            let subtitle = match &change.before {
+                Some(before) => format!("Line {}, was: {}", change.line_number + 1, before.trim()),
+                None => format!("New definition before line {}", change.line_number + 1),
+            };
+            let row = adw::ActionRow::builder()
+                .title(gtk::glib::markup_escape_text(change.after.trim()))
+                .subtitle(gtk::glib::markup_escape_text(&subtitle))
+                .subtitle_lines(2)
+                .build();
+            row.add_prefix(&gtk::Image::from_icon_name(if change.before.is_some() {
+                "document-edit-symbolic"
+            } else {
+                "list-add-symbolic"
+            }));
+            if let Some((_, group)) = &group {
+                group.add(&row);
+            }
+        }
+        *plan_p.borrow_mut() = Some(new_plan);
+        stack_p.set_visible_child_name("preview");
+    });
+
+    // Apply
+    let plan_a = plan.clone();
+    let stack_a = stack.clone();
+    let toast_a = on_show_toast.clone();
+    let model_a = model.clone();
+    apply_btn.connect_clicked(move |_| {
+        let Some(plan) = plan_a.borrow_mut().take() else {
+            return;
+        };
+        // The batch touches many lines, so never apply it without a backup
+        if let Err(e) = crate::ui::utils::backup::perform_backup(true) {
+            crate::log_error!("backup", "Failed to backup config: {}", e);
+            toast_a(format!("Backup failed, nothing was changed: {}", e));
+            *plan_a.borrow_mut() = Some(plan);
+            return;
+        }
+        match apply_refactor(&plan) {
+            Ok(files) => {
+                toast_a(format!(
+                    "Applied {} edits in {} file{}",
+                    plan.changes.len(),
+                    files,
+                    if files == 1 { "" } else { "s" }
+                ));
+                reload_keybinds(&model_a);
+                stack_a.set_visible_child_name("list");
+                if let Some(refresh) = &*refresh_handle.borrow() {
+                    refresh();
+                }
+            }
+            Err(e) => {
+                toast_a(format!("Error: {}", e));
+            }
+        }
+    });
+
+    // Open
+    let stack_o = stack.clone();
+    Rc::new(move || {
+        let suggestions = match variable_suggestions() {
+            Ok(suggestions) => suggestions,
+            Err(e) => {
+                on_show_toast(format!("Error: {}", e));
+                return;
+            }
+        };
+        if suggestions.is_empty() {
+            on_show_toast("No repeated values found in your keybinds".to_string());
+            return;
+        }
+
+        for row in suggest_widgets.borrow_mut().drain(..) {
+            suggest_group.remove(&row);
+        }
+        rows.borrow_mut().clear();
+        for suggestion in suggestions {
+            let mut subtitle = format!(
+                "{} · used on {} lines",
+                suggestion.kind.label(),
+                suggestion.occurrences.len()
+            );
+            if suggestion.existing {
+                subtitle.push_str(" · already a variable");
+            }
+            let row = adw::ActionRow::builder()
+                .title(gtk::glib::markup_escape_text(&suggestion.value))
+                .subtitle(subtitle)
+                .build();
+
+            let check = gtk::CheckButton::builder()
+                .valign(gtk::Align::Center)
+                .build();
+            row.add_prefix(&check);
+            row.set_activatable_widget(Some(&check));
+
+            let name_entry = gtk::Entry::builder()
+                .text(&suggestion.name)
+                .width_chars(14)
+                .valign(gtk::Align::Center)
+                .sensitive(!suggestion.existing)
+                .tooltip_text("Variable name (without $)")
+                .build();
+            row.add_suffix(&gtk::Label::new(Some("$")));
+            row.add_suffix(&name_entry);
+
+            suggest_group.add(&row);
+            suggest_widgets.borrow_mut().push(row);
+            rows.borrow_mut().push(SuggestionRow {
+                suggestion,
+                check,
+                name_entry,
+            });
+        }
+        stack_o.set_visible_child_name("suggest");
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 fn refresh_list_ui(
     list_box: &gtk::ListBox,
@@ -591,6 +1048,7 @@ fn refresh_list_ui(
     warning_body: &gtk::Label,
     delete_confirm_btn: &gtk::Button,
     refactor_switch: &gtk::Switch,
+    show_references: &ShowReferencesFn,
 ) {
     while let Some(child) = list_box.first_child() {
         list_box.remove(&child);
@@ -605,6 +1063,8 @@ fn refresh_list_ui(
 
     let filter = search_entry.text().to_string().to_lowercase();
 
+    let report = variable_report().unwrap_or_default();
+
     match parser::get_defined_variables() {
         Ok(mut vars) => {
             if !filter.is_empty() {
@@ -641,6 +1101,29 @@ fn refresh_list_ui(
                 let box_actions = gtk::Box::new(gtk::Orientation::Horizontal, 6);
                 box_actions.set_valign(gtk::Align::Center);
 
+                let badge = if let Some(by) = report.shadowed_by(&var) {
+                    Some((
+                        "Redefined",
+                        format!(
+                            "Replaced by the definition at {}",
+                            location(&by.file_path, by.line_number)
+                        ),
+                    ))
+                } else if report.is_unused(&var) {
+                    Some(("Unused", "No line uses this variable".to_string()))
+                } else {
+                    None
+                };
+                if let Some((label, tooltip)) = badge {
+                    box_actions.append(
+                        &gtk::Label::builder()
+                            .label(label)
+                            .tooltip_text(tooltip)
+                            .css_classes(["caption", "warning"])
+                            .build(),
+                    );
+                }
+
                 let delete_btn = create_destructive_button("", Some("user-trash-symbolic"));
                 delete_btn.set_tooltip_text(Some("Delete"));
                 delete_btn.add_css_class("flat"); // Make it flat initially
@@ -661,8 +1144,10 @@ fn refresh_list_ui(
                 let value_entry_c = value_entry.clone();
                 let var_clone = var.clone();
                 let update_title_c = update_header_title.clone();
+                let show_references_c = show_references.clone();
 
                 row.connect_activated(move |_| {
+                    show_references_c(Some(&var_clone));
                     *current_var_c.borrow_mut() = Some(var_clone.clone());
                     // Strip $ for entry
                     let clean_name = var_clone.name.trim_start_matches('$');
@@ -694,6 +1179,7 @@ fn refresh_list_ui(
                 let warning_body_r = warning_body.clone();
                 let delete_confirm_btn_r = delete_confirm_btn.clone();
                 let refactor_switch_r = refactor_switch.clone();
+                let show_references_r = show_references.clone();
 
                 // Disconnect previous signal handlers on the shared confirm button?
                 // Actually, the button is shared globally in the UI function, so we need to be careful.
@@ -744,7 +1230,7 @@ fn refresh_list_ui(
                         if *state {
                             // Backup before modifying
                             if let Err(e) = crate::ui::utils::backup::perform_backup(false) {
-                                eprintln!("Failed to backup config: {}", e);
+                                crate::log_error!("backup", "Failed to backup config: {}", e);
                             }
 
                             match parser::delete_variable(var_del.file_path.clone(), var_del.line_number) {
@@ -762,6 +1248,7 @@ fn refresh_list_ui(
                                         &warning_body_r,
                                         &delete_confirm_btn_r,
                                         &refactor_switch_r,
+                                        &show_references_r,
                                     );
                                 }
                                 Err(e) => {
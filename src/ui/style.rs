@@ -219,6 +219,53 @@ fn generate_css(config: &StyleConfig) -> String {
             border-color: alpha(@accent_color, 0.78);
         }}
 
+        /* Layer Chart (exported as-is, so it carries its own background) */
+        .layer-chart {{
+            background-color: @window_bg_color;
+            padding: 12px;
+        }}
+
+        .keyboard-key.layer-key {{
+            padding: 2px;
+        }}
+
+        .layer-action {{
+            font-family: sans-serif;
+            font-weight: normal;
+            font-size: 0.7em;
+        }}
+
+        /* Submap Flow Graph */
+        .graph-node {{
+            background-color: @card_bg_color;
+            border: 2px solid alpha(@window_fg_color, 0.15);
+            border-radius: 10px;
+            padding: 4px 8px;
+        }}
+
+        .graph-node.start {{
+            border-color: @accent_color;
+        }}
+
+        .graph-node.dead-end {{
+            border-color: @warning_color;
+        }}
+
+        .graph-node.unreachable {{
+            border-color: @error_color;
+            border-style: dashed;
+        }}
+
+        .graph-edge-label {{
+            font-size: 0.75em;
+            min-height: 0;
+            min-width: 0;
+            padding: 1px 6px;
+            border-radius: 999px;
+            background-color: @window_bg_color;
+            border: 1px solid alpha(@window_fg_color, 0.2);
+        }}
+
         .dim-label {{
              opacity: 0.6;
         }}
@@ -574,6 +621,11 @@ fn monitor_dir(
                 m.borrow_mut().push(monitor);
             });
         }
-        Err(e) => eprintln!("Failed to monitor theme directory {:?}: {}", path, e),
+        Err(e) => crate::log_warn!(
+            "theme",
+            "Failed to monitor theme directory {:?}: {}",
+            path,
+            e
+        ),
     }
 }
@@ -1,27 +1,125 @@
+use crate::ui::utils::execution::command_exists;
+use crate::ui::utils::macro_builder::shell_quote;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// An entry from a `[Desktop Action ...]` group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesktopAction {
+    pub id: String,
+    pub name: String,
+    pub command: Vec<String>,
+}
 
 #[derive(Debug, Clone)]
 pub struct AppInfo {
+    /// Desktop file ID without the `.desktop` suffix.
+    pub id: String,
+    /// `Name`, localized for the current locale when available.
     pub name: String,
+    /// Binary name of the program, without path or arguments.
     pub exec: String,
+    /// The `Exec` line split into arguments with field codes expanded.
+    pub command: Vec<String>,
     pub icon: Option<String>,
+    pub try_exec: Option<String>,
+    pub actions: Vec<DesktopAction>,
+    pub no_display: bool,
+    pub path: PathBuf,
 }
 
+impl AppInfo {
+    /// Whether the program this entry launches is present (`TryExec`, or the
+    /// `Exec` binary when there is none).
+    pub fn is_installed(&self) -> bool {
+        match &self.try_exec {
+            Some(try_exec) => command_exists(try_exec),
+            None => self.command.first().is_some_and(|cmd| command_exists(cmd)),
+        }
+    }
+
+    pub fn action(&self, id: &str) -> Option<&DesktopAction> {
+        self.actions.iter().find(|a| a.id == id)
+    }
+}
+
+/// Applications shown to the user: visible entries whose `TryExec`, if any,
+/// is present.
 pub fn get_installed_apps() -> Vec<AppInfo> {
+    get_desktop_entries()
+        .into_iter()
+        .filter(|app| !app.no_display)
+        .filter(|app| app.try_exec.as_deref().is_none_or(command_exists))
+        .collect()
+}
+
+/// Modification times of the application dirs and their subdirectories.
+type DirStamps = Vec<(PathBuf, Option<SystemTime>)>;
+
+static DESKTOP_ENTRY_CACHE: Mutex<Option<(DirStamps, Vec<AppInfo>)>> = Mutex::new(None);
+
+/// Installing or removing an app adds or removes a file, which changes the
+/// modification time of its directory.
+fn application_dir_stamps() -> DirStamps {
+    let mut stamps = Vec::new();
+    let mut pending: Vec<PathBuf> = get_xdg_data_dirs()
+        .into_iter()
+        .map(|dir| dir.join("applications"))
+        .collect();
+    while let Some(dir) = pending.pop() {
+        let modified = fs::metadata(&dir).and_then(|m| m.modified()).ok();
+        if modified.is_some() {
+            if let Ok(entries) = fs::read_dir(&dir) {
+                pending.extend(
+                    entries
+                        .flatten()
+                        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                        .map(|e| e.path()),
+                );
+            }
+        }
+        stamps.push((dir, modified));
+    }
+    stamps.sort();
+    stamps
+}
+
+/// Every application entry in the XDG data dirs, including hidden ones and
+/// ones whose program is missing, sorted by name. The entries are rescanned
+/// only when an application dir changed since the last call.
+pub fn get_desktop_entries() -> Vec<AppInfo> {
+    let stamps = application_dir_stamps();
+    if let Ok(cache) = DESKTOP_ENTRY_CACHE.lock() {
+        if let Some((cached_stamps, entries)) = &*cache {
+            if *cached_stamps == stamps {
+                return entries.clone();
+            }
+        }
+    }
+
+    let entries = scan_desktop_entries();
+    if let Ok(mut cache) = DESKTOP_ENTRY_CACHE.lock() {
+        *cache = Some((stamps, entries.clone()));
+    }
+    entries
+}
+
+fn scan_desktop_entries() -> Vec<AppInfo> {
     let mut apps = HashMap::new();
-    let data_dirs = get_xdg_data_dirs();
+    let locales = current_locales();
 
-    for dir in data_dirs {
+    for dir in get_xdg_data_dirs() {
         let applications_dir = dir.join("applications");
         if applications_dir.exists() {
-            scan_dir(&applications_dir, &mut apps);
+            scan_dir(&applications_dir, "", &locales, &mut apps);
         }
     }
 
-    let mut result: Vec<AppInfo> = apps.into_values().collect();
-    result.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    let mut result: Vec<AppInfo> = apps.into_values().flatten().collect();
+    result.sort_by_key(|a| a.name.to_lowercase());
     result
 }
 
@@ -46,95 +144,490 @@ fn get_xdg_data_dirs() -> Vec<PathBuf> {
     dirs
 }
 
-fn scan_dir(dir: &Path, apps: &mut HashMap<String, AppInfo>) {
+/// Desktop file IDs of entries in subdirectories are prefixed with the
+/// directory names joined by `-` (e.g. `kde4/konsole.desktop` -> `kde4-konsole`).
+fn scan_dir(
+    dir: &Path,
+    prefix: &str,
+    locales: &[String],
+    apps: &mut HashMap<String, Option<AppInfo>>,
+) {
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
             if path.is_dir() {
-                scan_dir(&path, apps);
-            } else if let Some(ext) = path.extension() {
-                if ext == "desktop" {
-                    if let Some(app) = parse_desktop_file(&path) {
-                        if let Some(file_stem) = path.file_stem() {
-                            let key = file_stem.to_string_lossy().to_string();
-                            apps.entry(key).or_insert(app);
+                scan_dir(&path, &format!("{}{}-", prefix, file_name), locales, apps);
+            } else if let Some(stem) = file_name.strip_suffix(".desktop") {
+                let id = format!("{}{}", prefix, stem);
+                // The first directory wins, even if its entry is hidden
+                if let std::collections::hash_map::Entry::Vacant(slot) = apps.entry(id) {
+                    let app = fs::read_to_string(&path).ok().and_then(|content| {
+                        parse_desktop_entry(&content, slot.key(), &path, locales)
+                    });
+                    slot.insert(app);
+                }
+            }
+        }
+    }
+}
+
+/// Locale names to try for `Key[locale]`, most specific first.
+pub fn locale_variants(locale: &str) -> Vec<String> {
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, modifier)) => (rest, Some(modifier)),
+        None => (locale, None),
+    };
+    let rest = rest.split('.').next().unwrap_or_default();
+    let (lang, country) = match rest.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (rest, None),
+    };
+    if lang.is_empty() || lang == "C" || lang == "POSIX" {
+        return Vec::new();
+    }
+
+    let mut variants = Vec::new();
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        variants.push(format!("{}_{}@{}", lang, country, modifier));
+    }
+    if let Some(country) = country {
+        variants.push(format!("{}_{}", lang, country));
+    }
+    if let Some(modifier) = modifier {
+        variants.push(format!("{}@{}", lang, modifier));
+    }
+    variants.push(lang.to_string());
+    variants
+}
+
+fn current_locales() -> Vec<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map(|locale| locale_variants(&locale))
+        .unwrap_or_default()
+}
+
+/// Resolves the escape sequences allowed in desktop entry string values.
+fn unescape_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => out.push(' '),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Splits a command line into arguments. Handles double quotes with
+/// backslash escapes (as in `Exec`) and single quotes (as in shell commands).
+pub fn split_command(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            }
+            '"' => {
+                let arg = current.get_or_insert_with(String::new);
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => {
+                            if let Some(next) = chars.next() {
+                                arg.push(next);
+                            }
                         }
+                        c => arg.push(c),
+                    }
+                }
+            }
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    arg.push(c);
+                }
+            }
+            '\\' => {
+                let arg = current.get_or_insert_with(String::new);
+                if let Some(next) = chars.next() {
+                    arg.push(next);
+                }
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(arg) = current {
+        args.push(arg);
+    }
+    args
+}
+
+/// Expands the field codes of an `Exec` value. File and URL codes are
+/// dropped since a keybind launches the app without any.
+pub fn parse_exec(exec: &str, name: &str, icon: Option<&str>, path: &Path) -> Vec<String> {
+    let mut args = Vec::new();
+    for arg in split_command(exec) {
+        match arg.as_str() {
+            "%f" | "%F" | "%u" | "%U" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => {}
+            "%i" => {
+                if let Some(icon) = icon {
+                    args.push("--icon".to_string());
+                    args.push(icon.to_string());
+                }
+            }
+            _ => {
+                let mut expanded = String::with_capacity(arg.len());
+                let mut chars = arg.chars();
+                while let Some(c) = chars.next() {
+                    if c != '%' {
+                        expanded.push(c);
+                        continue;
+                    }
+                    match chars.next() {
+                        Some('%') => expanded.push('%'),
+                        Some('c') => expanded.push_str(name),
+                        Some('k') => expanded.push_str(&path.to_string_lossy()),
+                        _ => {}
                     }
                 }
+                if !expanded.is_empty() {
+                    args.push(expanded);
+                }
             }
         }
     }
+    args
 }
 
-fn parse_desktop_file(path: &Path) -> Option<AppInfo> {
-    let content = fs::read_to_string(path).ok()?;
-    let mut name = None;
-    let mut exec = None;
-    let mut icon = None;
-    let mut is_desktop_entry = false;
-    let mut no_display = false;
-    let mut is_application = true;
+/// The program a command runs, skipping an `env VAR=value` prefix.
+fn program(command: &[String]) -> Option<&str> {
+    let mut args = command.iter().map(String::as_str).peekable();
+    if args.peek() == Some(&"env") {
+        args.next();
+        while args
+            .peek()
+            .is_some_and(|a| a.contains('=') || a.starts_with('-'))
+        {
+            args.next();
+        }
+    }
+    args.next()
+}
+
+fn binary_name(program: &str) -> &str {
+    program.rsplit('/').next().unwrap_or(program)
+}
 
+/// Picks `Key[locale]` over `Key` following the locale priority.
+fn localized(values: &HashMap<String, String>, key: &str, locales: &[String]) -> Option<String> {
+    locales
+        .iter()
+        .find_map(|locale| values.get(&format!("{}[{}]", key, locale)))
+        .or_else(|| values.get(key))
+        .cloned()
+}
+
+/// Parses a desktop entry. Returns `None` for non-applications, hidden
+/// (deleted) entries and entries without `Name` or `Exec`.
+pub fn parse_desktop_entry(
+    content: &str,
+    id: &str,
+    path: &Path,
+    locales: &[String],
+) -> Option<AppInfo> {
+    let mut groups: Vec<(String, HashMap<String, String>)> = Vec::new();
     for line in content.lines() {
         let line = line.trim();
-        if line == "[Desktop Entry]" {
-            is_desktop_entry = true;
+        if line.is_empty() || line.starts_with('#') {
             continue;
         }
-
-        if !is_desktop_entry {
-            continue;
+        if let Some(group) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            groups.push((group.to_string(), HashMap::new()));
+        } else if let (Some((_, values)), Some((key, value))) =
+            (groups.last_mut(), line.split_once('='))
+        {
+            // The first occurrence of a key within a group wins
+            values
+                .entry(key.trim().to_string())
+                .or_insert_with(|| unescape_value(value.trim()));
         }
+    }
+
+    let entry = &groups.iter().find(|(g, _)| g == "Desktop Entry")?.1;
+    if entry.get("Type").is_some_and(|t| t != "Application")
+        || entry.get("Hidden").is_some_and(|h| h == "true")
+    {
+        return None;
+    }
+
+    let name = localized(entry, "Name", locales)?;
+    let icon = entry.get("Icon").cloned().filter(|i| !i.is_empty());
+    let command = parse_exec(entry.get("Exec")?, &name, icon.as_deref(), path);
+    let exec = binary_name(program(&command)?).to_string();
+
+    let actions = entry
+        .get("Actions")
+        .map(|list| {
+            list.split(';')
+                .filter(|a| !a.is_empty())
+                .filter_map(|action_id| {
+                    let group = format!("Desktop Action {}", action_id);
+                    let values = &groups.iter().find(|(g, _)| *g == group)?.1;
+                    let action_name = localized(values, "Name", locales)?;
+                    let action_icon = values.get("Icon").or(icon.as_ref());
+                    let command = parse_exec(
+                        values.get("Exec")?,
+                        &name,
+                        action_icon.map(String::as_str),
+                        path,
+                    );
+                    Some(DesktopAction {
+                        id: action_id.to_string(),
+                        name: action_name,
+                        command,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(AppInfo {
+        id: id.to_string(),
+        name,
+        exec,
+        command,
+        icon,
+        try_exec: entry.get("TryExec").cloned().filter(|t| !t.is_empty()),
+        actions,
+        no_display: entry.get("NoDisplay").is_some_and(|n| n == "true"),
+        path: path.to_path_buf(),
+    })
+}
+
+/// How an exec bind starts an application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchStyle {
+    /// Runs the command directly.
+    Command,
+    /// `gtk-launch <id>`
+    GtkLaunch,
+    /// `uwsm app -- <id>.desktop[:action]`, or `uwsm app -- <command>`
+    Uwsm,
+}
+
+pub const LAUNCH_STYLES: &[(LaunchStyle, &str)] = &[
+    (LaunchStyle::Command, "Command"),
+    (LaunchStyle::GtkLaunch, "gtk-launch"),
+    (LaunchStyle::Uwsm, "uwsm app"),
+];
+
+/// What an exec bind launches once launcher wrappers are removed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecTarget {
+    /// A desktop file ID, with an optional action.
+    DesktopId {
+        id: String,
+        action: Option<String>,
+        style: LaunchStyle,
+    },
+    /// A plain command line.
+    Command {
+        args: Vec<String>,
+        style: LaunchStyle,
+    },
+}
 
-        if line.starts_with('[') && line != "[Desktop Entry]" {
-            break;
+/// Splits Hyprland exec rules like `[float; size 50%]` off a command.
+pub fn split_exec_rules(args: &str) -> (&str, &str) {
+    let args = args.trim();
+    if args.starts_with('[') {
+        if let Some(end) = args.find(']') {
+            return (&args[..=end], args[end + 1..].trim());
         }
+    }
+    ("", args)
+}
 
-        if line.starts_with("Type=") {
-            let type_val = line.trim_start_matches("Type=");
-            if type_val != "Application" {
-                is_application = false;
-            }
+pub fn parse_exec_target(args: &str) -> Option<ExecTarget> {
+    let (_, command) = split_exec_rules(args);
+    let words = split_command(command);
+
+    let rest = match words.first().map(String::as_str) {
+        Some("gtk-launch") => {
+            let id = words.get(1)?;
+            return Some(ExecTarget::DesktopId {
+                id: id.trim_end_matches(".desktop").to_string(),
+                action: None,
+                style: LaunchStyle::GtkLaunch,
+            });
         }
+        Some("uwsm") if words.get(1).is_some_and(|w| w == "app") => &words[2..],
+        Some("uwsm-app") => &words[1..],
+        Some(_) => {
+            return Some(ExecTarget::Command {
+                args: words,
+                style: LaunchStyle::Command,
+            })
+        }
+        None => return None,
+    };
+
+    // uwsm options come before the app, optionally ended by `--`
+    let start = match rest.iter().position(|w| w == "--") {
+        Some(sep) => sep + 1,
+        None => rest.iter().position(|w| !w.starts_with('-'))?,
+    };
+    let rest = rest.get(start..).filter(|r| !r.is_empty())?;
 
-        if line.starts_with("Name=") && name.is_none() {
-            name = Some(line.trim_start_matches("Name=").to_string());
-        } else if line.starts_with("Exec=") && exec.is_none() {
-            exec = Some(line.trim_start_matches("Exec=").to_string());
-        } else if line.starts_with("Icon=") && icon.is_none() {
-            icon = Some(line.trim_start_matches("Icon=").to_string());
-        } else if line == "NoDisplay=true" {
-            no_display = true;
+    if let Some((id, action)) = rest[0]
+        .split_once(".desktop:")
+        .map(|(id, action)| (id, Some(action)))
+        .or_else(|| rest[0].strip_suffix(".desktop").map(|id| (id, None)))
+    {
+        return Some(ExecTarget::DesktopId {
+            id: binary_name(id).to_string(),
+            action: action.filter(|a| !a.is_empty()).map(str::to_string),
+            style: LaunchStyle::Uwsm,
+        });
+    }
+    Some(ExecTarget::Command {
+        args: rest.to_vec(),
+        style: LaunchStyle::Uwsm,
+    })
+}
+
+/// The application an exec bind launches.
+#[derive(Debug, Clone)]
+pub struct AppLink<'a> {
+    pub app: &'a AppInfo,
+    pub action: Option<&'a DesktopAction>,
+    pub style: LaunchStyle,
+}
+
+impl AppLink<'_> {
+    pub fn display_name(&self) -> String {
+        match self.action {
+            Some(action) => format!("{}: {}", self.app.name, action.name),
+            None => self.app.name.clone(),
         }
     }
+}
 
-    if no_display || !is_application {
-        return None;
+/// Finds the desktop entry an exec bind launches. Commands match an entry's
+/// `Exec` (or one of its actions) exactly first, then by program name.
+pub fn link_exec_bind<'a>(args: &str, entries: &'a [AppInfo]) -> Option<AppLink<'a>> {
+    match parse_exec_target(args)? {
+        ExecTarget::DesktopId { id, action, style } => {
+            let app = entries.iter().find(|app| app.id == id)?;
+            let action = match action {
+                Some(action) => Some(app.action(&action)?),
+                None => None,
+            };
+            Some(AppLink { app, action, style })
+        }
+        ExecTarget::Command { args, style } => {
+            if let Some(app) = entries.iter().find(|app| app.command == args) {
+                return Some(AppLink {
+                    app,
+                    action: None,
+                    style,
+                });
+            }
+            for app in entries {
+                if let Some(action) = app.actions.iter().find(|a| a.command == args) {
+                    return Some(AppLink {
+                        app,
+                        action: Some(action),
+                        style,
+                    });
+                }
+            }
+
+            let binary = binary_name(program(&args)?);
+            entries
+                .iter()
+                .filter(|app| app.exec == binary)
+                .min_by_key(|app| app.no_display)
+                .map(|app| AppLink {
+                    app,
+                    action: None,
+                    style,
+                })
+        }
     }
+}
 
-    if let (Some(name), Some(exec)) = (name, exec) {
-        // Clean up Exec command (remove field codes like %u, %F, etc.)
-        let clean_exec = clean_exec_cmd(&exec);
-        Some(AppInfo {
-            name,
-            exec: clean_exec,
-            icon,
-        })
-    } else {
-        None
+/// Why an exec bind's application can't be launched, if it can't. Plain
+/// commands are left to the PATH check; this covers desktop IDs and the
+/// programs behind launcher wrappers.
+pub fn app_problem(args: &str, entries: &[AppInfo]) -> Option<String> {
+    match parse_exec_target(args)? {
+        ExecTarget::DesktopId { id, action, .. } => {
+            let Some(app) = entries.iter().find(|app| app.id == id) else {
+                return Some(format!("Application not installed: {}", id));
+            };
+            if let Some(action) = action {
+                if app.action(&action).is_none() {
+                    return Some(format!("{} has no action '{}'", app.name, action));
+                }
+            }
+            if !app.is_installed() {
+                return Some(format!("Application not installed: {}", app.name));
+            }
+            None
+        }
+        ExecTarget::Command {
+            args,
+            style: LaunchStyle::Uwsm,
+        } => {
+            let program = program(&args)?;
+            (!command_exists(program)).then(|| format!("Executable not found: {}", program))
+        }
+        ExecTarget::Command { .. } => None,
     }
 }
 
-fn clean_exec_cmd(cmd: &str) -> String {
-    let parts: Vec<&str> = cmd.split_whitespace().collect();
-    if let Some(first) = parts.first() {
-        let bin_name = if let Some(idx) = first.rfind('/') {
-            &first[idx + 1..]
-        } else {
-            first
-        };
-        bin_name.to_string()
-    } else {
-        cmd.to_string()
+/// Builds exec arguments that launch `app` (or one of its actions) in the
+/// given style. `gtk-launch` can't start actions, so those run directly.
+pub fn launch_command(app: &AppInfo, action: Option<&DesktopAction>, style: LaunchStyle) -> String {
+    let direct = || {
+        action
+            .map_or(&app.command, |a| &a.command)
+            .iter()
+            .map(|arg| shell_quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    match (style, action) {
+        (LaunchStyle::GtkLaunch, None) => format!("gtk-launch {}", app.id),
+        (LaunchStyle::Uwsm, None) => format!("uwsm app -- {}.desktop", app.id),
+        (LaunchStyle::Uwsm, Some(action)) => {
+            format!("uwsm app -- {}.desktop:{}", app.id, action.id)
+        }
+        _ => direct(),
     }
 }
@@ -0,0 +1,576 @@
+use crate::parser::dispatchers::{lookup, validate_dispatcher, ArgKind};
+use crate::ui::utils::apps::{
+    get_desktop_entries, launch_command, link_exec_bind, split_exec_rules, LAUNCH_STYLES,
+};
+use gtk::prelude::*;
+use gtk4 as gtk;
+use std::rc::Rc;
+
+const DIRECTIONS: &[(&str, &str)] = &[("Left", "l"), ("Right", "r"), ("Up", "u"), ("Down", "d")];
+
+const WORKSPACE_MODES: &[&str] = &[
+    "Number",
+    "Relative",
+    "Relative on Monitor",
+    "Relative on Monitor, with Empty",
+    "Relative Open",
+    "Name",
+    "Special",
+    "Previous",
+    "Empty",
+];
+
+const WINDOW_PREFIXES: &[&str] = &[
+    "class",
+    "title",
+    "initialclass",
+    "initialtitle",
+    "tag",
+    "pid",
+    "address",
+];
+
+fn signed(value: &str) -> String {
+    if value.starts_with(['+', '-']) {
+        value.to_string()
+    } else {
+        format!("+{}", value)
+    }
+}
+
+/// Modes from [`WORKSPACE_MODES`] at or past this one take no value.
+const WORKSPACE_MODES_WITHOUT_VALUE: u32 = 7;
+
+fn compose_workspace(mode: u32, value: &str) -> String {
+    let value = value.trim();
+    match mode {
+        1 => signed(value),
+        2 => format!("m{}", signed(value)),
+        3 => format!("r{}", signed(value)),
+        4 => format!("e{}", signed(value)),
+        5 => format!("name:{}", value),
+        6 if value.is_empty() => "special".to_string(),
+        6 => format!("special:{}", value),
+        7 => "previous".to_string(),
+        8 => "empty".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// The [`WORKSPACE_MODES`] index and value the workspace editor shows for
+/// `workspace`, or `None` when the editor can't represent it.
+pub fn parse_workspace(workspace: &str) -> Option<(u32, String)> {
+    let workspace = workspace.trim();
+    let is_offset = |s: &str| {
+        s.strip_prefix(['+', '-'])
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    };
+
+    if workspace.is_empty() || workspace.chars().all(|c| c.is_ascii_digit()) {
+        return Some((0, workspace.to_string()));
+    }
+    if is_offset(workspace) {
+        return Some((1, workspace.to_string()));
+    }
+    for (mode, prefix) in [(2, "m"), (3, "r"), (4, "e")] {
+        if let Some(offset) = workspace.strip_prefix(prefix).filter(|o| is_offset(o)) {
+            return Some((mode, offset.to_string()));
+        }
+    }
+    if let Some(name) = workspace.strip_prefix("name:") {
+        return Some((5, name.to_string()));
+    }
+    if workspace == "special" {
+        return Some((6, String::new()));
+    }
+    if let Some(name) = workspace.strip_prefix("special:") {
+        return Some((6, name.to_string()));
+    }
+    match workspace {
+        "previous" => Some((7, String::new())),
+        "empty" => Some((8, String::new())),
+        _ => None,
+    }
+}
+
+/// Keeps the `,window` part of the current arguments when a typed editor
+/// rewrites the part before it.
+fn with_window_tail(args_entry: &gtk::Entry, head: &str) -> String {
+    let current = args_entry.text();
+    match current.split_once(',') {
+        Some((_, tail)) => format!("{},{}", head, tail),
+        None => head.to_string(),
+    }
+}
+
+fn create_dropdown(items: &[&str], current: &str) -> gtk::DropDown {
+    let dropdown = gtk::DropDown::from_strings(items);
+    if let Some(idx) = items
+        .iter()
+        .position(|i| i.eq_ignore_ascii_case(current.trim()))
+    {
+        dropdown.set_selected(idx as u32);
+    }
+    dropdown
+}
+
+fn create_spin(min: f64, max: f64, value: f64) -> gtk::SpinButton {
+    let spin = gtk::SpinButton::with_range(min, max, 1.0);
+    spin.set_value(value);
+    spin
+}
+
+/// Shows the application an exec command launches, with pickers for its
+/// desktop actions and for how it is launched.
+fn build_app_controls(controls: &gtk::Box, args_entry: &gtk::Entry) {
+    let entries = Rc::new(get_desktop_entries());
+
+    let app_icon = gtk::Image::builder().pixel_size(24).build();
+    let app_label = gtk::Label::builder()
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .ellipsize(gtk::pango::EllipsizeMode::End)
+        .build();
+    let action_dropdown = gtk::DropDown::from_strings(&[]);
+    action_dropdown.set_tooltip_text(Some("Desktop action"));
+    let style_labels: Vec<&str> = LAUNCH_STYLES.iter().map(|(_, label)| *label).collect();
+    let style_dropdown = gtk::DropDown::from_strings(&style_labels);
+    style_dropdown.set_tooltip_text(Some("Launch with"));
+
+    controls.append(&app_icon);
+    controls.append(&app_label);
+    controls.append(&action_dropdown);
+    controls.append(&style_dropdown);
+
+    // Set while the pickers are synced from the entry, so they don't write back
+    let syncing = Rc::new(std::cell::Cell::new(false));
+
+    let sync = {
+        let entries = entries.clone();
+        let controls = controls.downgrade();
+        let app_icon = app_icon.downgrade();
+        let app_label = app_label.downgrade();
+        let action_dropdown = action_dropdown.downgrade();
+        let style_dropdown = style_dropdown.downgrade();
+        let syncing = syncing.clone();
+        move |args: &str| {
+            let (Some(controls), Some(app_icon), Some(app_label), Some(actions), Some(styles)) = (
+                controls.upgrade(),
+                app_icon.upgrade(),
+                app_label.upgrade(),
+                action_dropdown.upgrade(),
+                style_dropdown.upgrade(),
+            ) else {
+                return;
+            };
+
+            let Some(link) = link_exec_bind(args, &entries) else {
+                controls.set_visible(false);
+                return;
+            };
+            controls.set_visible(true);
+            syncing.set(true);
+
+            match link.app.icon.as_deref() {
+                Some(icon) if icon.starts_with('/') => app_icon.set_from_file(Some(icon)),
+                Some(icon) => app_icon.set_icon_name(Some(icon)),
+                None => app_icon.set_icon_name(Some("application-x-executable-symbolic")),
+            }
+            app_label.set_label(&link.app.name);
+
+            let mut action_names = vec!["Default"];
+            action_names.extend(link.app.actions.iter().map(|a| a.name.as_str()));
+            actions.set_model(Some(&gtk::StringList::new(&action_names)));
+            let action_idx = link
+                .action
+                .and_then(|a| link.app.actions.iter().position(|x| x.id == a.id))
+                .map_or(0, |i| i + 1);
+            actions.set_selected(action_idx as u32);
+            actions.set_visible(!link.app.actions.is_empty());
+
+            let style_idx = LAUNCH_STYLES
+                .iter()
+                .position(|(style, _)| *style == link.style)
+                .unwrap_or(0);
+            styles.set_selected(style_idx as u32);
+
+            syncing.set(false);
+        }
+    };
+    let sync = Rc::new(sync);
+
+    let rewrite = {
+        let args_entry = args_entry.downgrade();
+        let action_dropdown = action_dropdown.clone();
+        let style_dropdown = style_dropdown.clone();
+        move || {
+            let Some(args_entry) = args_entry.upgrade() else {
+                return;
+            };
+            if syncing.get() {
+                return;
+            }
+            let current = args_entry.text();
+            let Some(link) = link_exec_bind(&current, &entries) else {
+                return;
+            };
+            let action = (action_dropdown.selected() as usize)
+                .checked_sub(1)
+                .and_then(|i| link.app.actions.get(i));
+            let style = LAUNCH_STYLES[style_dropdown.selected() as usize % LAUNCH_STYLES.len()].0;
+
+            let (rules, _) = split_exec_rules(&current);
+            let command = launch_command(link.app, action, style);
+            let text = if rules.is_empty() {
+                command
+            } else {
+                format!("{} {}", rules, command)
+            };
+            if args_entry.text() != text {
+                args_entry.set_text(&text);
+            }
+        }
+    };
+    let rewrite = Rc::new(rewrite);
+    let r = rewrite.clone();
+    action_dropdown.connect_selected_notify(move |_| r());
+    style_dropdown.connect_selected_notify(move |_| rewrite());
+
+    sync(&args_entry.text());
+
+    // The controls are rebuilt when the dispatcher changes; stop following
+    // the entry once they are gone
+    let sync_c = sync.clone();
+    let handler = args_entry.connect_changed(move |entry| sync_c(&entry.text()));
+    let handler = std::cell::RefCell::new(Some(handler));
+    let args_entry_weak = args_entry.downgrade();
+    app_icon.connect_notify_local(Some("parent"), move |icon, _| {
+        if icon.parent().is_none() {
+            if let (Some(entry), Some(id)) = (args_entry_weak.upgrade(), handler.take()) {
+                entry.disconnect(id);
+            }
+        }
+    });
+}
+
+/// Builds controls for the dispatcher's argument grammar that write into
+/// `args_entry`. Kinds without a structured form get no controls.
+fn build_controls(controls: &gtk::Box, kind: ArgKind, args_entry: &gtk::Entry) {
+    let current = args_entry.text().to_string();
+    let entry_weak = args_entry.downgrade();
+    let write = Rc::new(move |text: String| {
+        if let Some(entry) = entry_weak.upgrade() {
+            if entry.text() != text {
+                entry.set_text(&text);
+            }
+        }
+    });
+
+    match kind {
+        ArgKind::Workspace | ArgKind::WorkspaceWithWindow => {
+            let head = match kind {
+                ArgKind::WorkspaceWithWindow => current.split(',').next().unwrap_or_default(),
+                _ => current.as_str(),
+            };
+            // Left to the text entry, so the controls never overwrite it
+            let Some((selected, text)) = parse_workspace(head) else {
+                return;
+            };
+            let mode = gtk::DropDown::from_strings(WORKSPACE_MODES);
+            mode.set_selected(selected);
+            let value = gtk::Entry::builder()
+                .text(text)
+                .placeholder_text("e.g. 1")
+                .hexpand(true)
+                .sensitive(selected < WORKSPACE_MODES_WITHOUT_VALUE)
+                .build();
+            controls.append(&mode);
+            controls.append(&value);
+
+            let update = {
+                let mode = mode.clone();
+                let value = value.clone();
+                let args_entry = args_entry.clone();
+                move || {
+                    let selected = mode.selected();
+                    value.set_sensitive(selected < WORKSPACE_MODES_WITHOUT_VALUE);
+                    let head = compose_workspace(selected, &value.text());
+                    if kind == ArgKind::WorkspaceWithWindow {
+                        write(with_window_tail(&args_entry, &head));
+                    } else {
+                        write(head);
+                    }
+                }
+            };
+            let update = Rc::new(update);
+            let u = update.clone();
+            mode.connect_selected_notify(move |_| u());
+            value.connect_changed(move |_| update());
+        }
+        ArgKind::Direction | ArgKind::DirectionOrMonitor => {
+            let labels: Vec<&str> = DIRECTIONS.iter().map(|(label, _)| *label).collect();
+            let dropdown = gtk::DropDown::from_strings(&labels);
+            if let Some(idx) = DIRECTIONS
+                .iter()
+                .position(|(_, code)| current.split_whitespace().next() == Some(*code))
+            {
+                dropdown.set_selected(idx as u32);
+            }
+            controls.append(&dropdown);
+
+            let silent = gtk::CheckButton::builder()
+                .label("Silent")
+                .tooltip_text("Don't follow the window")
+                .active(current.ends_with("silent"))
+                .visible(kind == ArgKind::DirectionOrMonitor)
+                .build();
+            controls.append(&silent);
+
+            let update = {
+                let dropdown = dropdown.clone();
+                let silent = silent.clone();
+                move || {
+                    let code = DIRECTIONS[dropdown.selected() as usize % DIRECTIONS.len()].1;
+                    if silent.is_active() {
+                        write(format!("{} silent", code));
+                    } else {
+                        write(code.to_string());
+                    }
+                }
+            };
+            let update = Rc::new(update);
+            let u = update.clone();
+            dropdown.connect_selected_notify(move |_| u());
+            silent.connect_toggled(move |_| update());
+        }
+        ArgKind::Choice(choices)
+        | ArgKind::ChoiceWithWindow(choices)
+        | ArgKind::ChoiceWithMonitor(choices) => {
+            let head = current
+                .split([',', ' '])
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            let dropdown = create_dropdown(choices, &head);
+            controls.append(&dropdown);
+
+            let args_entry = args_entry.clone();
+            dropdown.connect_selected_notify(move |d| {
+                let choice = choices[d.selected() as usize % choices.len()];
+                match kind {
+                    ArgKind::ChoiceWithWindow(_) => write(with_window_tail(&args_entry, choice)),
+                    ArgKind::ChoiceWithMonitor(_) => {
+                        let text = args_entry.text();
+                        match text.trim().split_once(char::is_whitespace) {
+                            Some((_, monitor)) => write(format!("{} {}", choice, monitor.trim())),
+                            None => write(choice.to_string()),
+                        }
+                    }
+                    _ => write(choice.to_string()),
+                }
+            });
+        }
+        ArgKind::Integer(min, max) => {
+            let value = current.trim().parse::<f64>().unwrap_or(min as f64);
+            let spin = create_spin(min as f64, max as f64, value);
+            controls.append(&spin);
+            spin.connect_value_changed(move |s| write(s.value_as_int().to_string()));
+        }
+        ArgKind::Window => {
+            let (prefix, value) = current
+                .split_once(':')
+                .unwrap_or(("class", current.as_str()));
+            let dropdown = create_dropdown(WINDOW_PREFIXES, prefix);
+            let entry = gtk::Entry::builder()
+                .text(value)
+                .placeholder_text("e.g. ^(kitty)$")
+                .hexpand(true)
+                .build();
+            controls.append(&dropdown);
+            controls.append(&entry);
+
+            let update = {
+                let dropdown = dropdown.clone();
+                let entry = entry.clone();
+                move || {
+                    let value = entry.text();
+                    if value.trim().is_empty() {
+                        write(String::new());
+                    } else {
+                        let prefix = WINDOW_PREFIXES[dropdown.selected() as usize];
+                        write(format!("{}:{}", prefix, value.trim()));
+                    }
+                }
+            };
+            let update = Rc::new(update);
+            let u = update.clone();
+            dropdown.connect_selected_notify(move |_| u());
+            entry.connect_changed(move |_| update());
+        }
+        ArgKind::Deltas => {
+            let exact = current.starts_with("exact");
+            let numbers: Vec<f64> = current
+                .split_whitespace()
+                .filter_map(|p| p.trim_end_matches('%').parse().ok())
+                .collect();
+            let x = create_spin(-10000.0, 10000.0, numbers.first().copied().unwrap_or(0.0));
+            let y = create_spin(-10000.0, 10000.0, numbers.get(1).copied().unwrap_or(0.0));
+            let exact_check = gtk::CheckButton::builder()
+                .label("Exact")
+                .tooltip_text("Set the size or position instead of changing it")
+                .active(exact)
+                .build();
+            let percent_check = gtk::CheckButton::builder()
+                .label("%")
+                .tooltip_text("Values are percentages of the monitor")
+                .active(current.contains('%'))
+                .build();
+            controls.append(&gtk::Label::new(Some("X")));
+            controls.append(&x);
+            controls.append(&gtk::Label::new(Some("Y")));
+            controls.append(&y);
+            controls.append(&exact_check);
+            controls.append(&percent_check);
+
+            let update = {
+                let x = x.clone();
+                let y = y.clone();
+                let exact_check = exact_check.clone();
+                let percent_check = percent_check.clone();
+                move || {
+                    let unit = if percent_check.is_active() { "%" } else { "" };
+                    let deltas =
+                        format!("{}{} {}{}", x.value_as_int(), unit, y.value_as_int(), unit);
+                    if exact_check.is_active() {
+                        write(format!("exact {}", deltas));
+                    } else {
+                        write(deltas);
+                    }
+                }
+            };
+            let update = Rc::new(update);
+            let (u1, u2, u3) = (update.clone(), update.clone(), update.clone());
+            x.connect_value_changed(move |_| u1());
+            y.connect_value_changed(move |_| u2());
+            exact_check.connect_toggled(move |_| u3());
+            percent_check.connect_toggled(move |_| update());
+        }
+        ArgKind::Flags(flags) => {
+            let toggles: Vec<gtk::ToggleButton> = flags
+                .iter()
+                .map(|flag| {
+                    gtk::ToggleButton::builder()
+                        .label(*flag)
+                        .css_classes(["small", "mod-toggle"])
+                        .active(current.split_whitespace().any(|w| w == *flag))
+                        .build()
+                })
+                .collect();
+            for toggle in &toggles {
+                controls.append(toggle);
+            }
+
+            let update = {
+                let toggles = toggles.clone();
+                move || {
+                    let words: Vec<&str> = flags
+                        .iter()
+                        .zip(&toggles)
+                        .filter(|(_, t)| t.is_active())
+                        .map(|(f, _)| *f)
+                        .collect();
+                    write(words.join(" "));
+                }
+            };
+            let update = Rc::new(update);
+            for toggle in &toggles {
+                let u = update.clone();
+                toggle.connect_toggled(move |_| u());
+            }
+        }
+        ArgKind::Command => build_app_controls(controls, args_entry),
+        _ => {}
+    }
+}
+
+/// Typed controls and a live syntax check for the arguments of the dispatcher
+/// typed into `dispatcher_entry`. Editing the controls rewrites `args_entry`,
+/// which stays editable for anything the controls don't cover.
+pub fn create_args_editor(dispatcher_entry: &gtk::Entry, args_entry: &gtk::Entry) -> gtk::Box {
+    let container = gtk::Box::new(gtk::Orientation::Vertical, 6);
+
+    let controls = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+    container.append(&controls);
+
+    let hint = gtk::Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .xalign(0.0)
+        .css_classes(["caption", "dim-label"])
+        .build();
+    container.append(&hint);
+
+    let update_hint = {
+        let dispatcher_entry = dispatcher_entry.clone();
+        let args_entry = args_entry.clone();
+        let hint = hint.clone();
+        move || {
+            let dispatcher = dispatcher_entry.text();
+            let args = args_entry.text();
+
+            match validate_dispatcher(&dispatcher, &args) {
+                Some(reason) if !args.trim().is_empty() || lookup(&dispatcher).is_none() => {
+                    hint.set_label(&reason);
+                    hint.remove_css_class("dim-label");
+                    hint.add_css_class("error");
+                }
+                _ => {
+                    hint.remove_css_class("error");
+                    hint.add_css_class("dim-label");
+                    match lookup(&dispatcher) {
+                        Some(spec) => hint.set_label(&format!("Arguments: {}", spec.usage)),
+                        None => hint.set_label(""),
+                    }
+                }
+            }
+            hint.set_visible(!hint.label().is_empty());
+        }
+    };
+    let update_hint = Rc::new(update_hint);
+
+    let rebuild = {
+        let dispatcher_entry = dispatcher_entry.clone();
+        let args_entry = args_entry.clone();
+        let controls = controls.clone();
+        let last_kind = std::cell::Cell::new(None);
+        move || {
+            let kind = lookup(&dispatcher_entry.text()).map(|spec| spec.arg);
+            if last_kind.get() == Some(kind) {
+                return;
+            }
+            last_kind.set(Some(kind));
+
+            while let Some(child) = controls.first_child() {
+                controls.remove(&child);
+            }
+            if let Some(kind) = kind {
+                build_controls(&controls, kind, &args_entry);
+            }
+            controls.set_visible(controls.first_child().is_some());
+        }
+    };
+    let rebuild = Rc::new(rebuild);
+
+    let (rb, uh) = (rebuild.clone(), update_hint.clone());
+    dispatcher_entry.connect_changed(move |_| {
+        rb();
+        uh();
+    });
+    let uh = update_hint.clone();
+    args_entry.connect_changed(move |_| uh());
+
+    rebuild();
+    update_hint();
+
+    container
+}
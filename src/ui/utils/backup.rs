@@ -136,21 +136,22 @@ pub fn perform_backup(force: bool) -> Result<String> {
         &mut count,
         &mut errors,
     ) {
-        eprintln!("Backup process encountered error: {}", e);
+        crate::log_error!("backup", "Backup process encountered error: {}", e);
     }
 
     if !errors.is_empty() {
         for err in &errors {
-            eprintln!("{}", err);
+            crate::log_error!("backup", "{}", err);
         }
     }
 
     if config.max_backups_enabled {
         if let Err(e) = prune_backups(&backup_root, config.max_backups_count as usize) {
-            eprintln!("Failed to prune backups: {}", e);
+            crate::log_error!("backup", "Failed to prune backups: {}", e);
         }
     }
 
+    crate::log_info!("backup", "Backed up {} files to {}", count, timestamp);
     Ok(format!("Backed up {} files to {}", count, timestamp))
 }
 
@@ -218,7 +219,7 @@ pub fn restore_backup(backup_path: &Path) -> Result<String> {
 
     if !errors.is_empty() {
         for err in &errors {
-            eprintln!("{}", err);
+            crate::log_error!("backup", "{}", err);
         }
         return Ok(format!(
             "Restored {} files with {} errors",
@@ -340,7 +341,7 @@ fn prune_backups(backup_root: &Path, max_count: usize) -> Result<()> {
         let to_remove = entries.len() - max_count;
         for path in entries.iter().take(to_remove) {
             if let Err(e) = fs::remove_dir_all(path) {
-                eprintln!("Failed to remove old backup {:?}: {}", path, e);
+                crate::log_error!("backup", "Failed to remove old backup {:?}: {}", path, e);
             }
         }
     }
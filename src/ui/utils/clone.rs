@@ -1,3 +1,4 @@
+use crate::config::favorites::{follow_edits, BindEdit, BindId};
 use crate::parser;
 use crate::ui::utils::components::{get_flag_from_index, get_mouse_code_from_index};
 use crate::ui::utils::macro_builder::compile_macro;
@@ -10,6 +11,8 @@ use std::path::PathBuf;
 #[allow(deprecated)]
 pub struct CloneContext {
     pub file_path: PathBuf,
+    /// Identity of the bind being cloned, whose metadata the copy inherits.
+    pub bind_id: BindId,
     pub model: gio::ListStore,
     pub toast_overlay: adw::ToastOverlay,
     pub stack: gtk::Stack,
@@ -40,6 +43,7 @@ pub fn create_clone_button(ctx: CloneContext) -> gtk::Button {
     // we'll clone what we need. Since GTK widgets are ref-counted (internally), cloning them is cheap.
 
     let file_path = ctx.file_path.clone();
+    let bind_id = ctx.bind_id.clone();
     let model = ctx.model.clone();
     let toast_overlay = ctx.toast_overlay.clone();
     let stack = ctx.stack.clone();
@@ -98,10 +102,10 @@ pub fn create_clone_button(ctx: CloneContext) -> gtk::Button {
 
         let (new_dispatcher, new_args) = if macro_switch.is_active() {
             match compile_macro(&macro_list) {
-                Some(res) => res,
-                None => {
+                Ok(res) => res,
+                Err(e) => {
                     let toast = adw::Toast::builder()
-                        .title("Macro is empty or invalid")
+                        .title(e.to_string())
                         .timeout(crate::config::constants::TOAST_TIMEOUT)
                         .build();
                     toast_overlay.add_toast(toast);
@@ -120,11 +124,27 @@ pub fn create_clone_button(ctx: CloneContext) -> gtk::Button {
         };
 
         if let Err(e) = perform_backup(false) {
-            eprintln!("Auto-backup failed: {}", e);
+            crate::log_error!("backup", "Auto-backup failed: {}", e);
         }
 
-        match parser::add_keybind(
+        let destination = crate::config::routing::destination_for(
+            &new_dispatcher,
+            &new_args,
+            &new_key,
+            new_submap.as_deref(),
             file_path.clone(),
+        );
+        if let Err(e) = parser::ensure_sourced(&destination) {
+            let toast = adw::Toast::builder()
+                .title(format!("Error cloning: {}", e))
+                .timeout(crate::config::constants::TOAST_TIMEOUT)
+                .build();
+            toast_overlay.add_toast(toast);
+            return;
+        }
+
+        match parser::add_keybind(
+            destination.clone(),
             &new_mods,
             &new_key,
             &new_dispatcher,
@@ -133,7 +153,12 @@ pub fn create_clone_button(ctx: CloneContext) -> gtk::Button {
             if desc.is_empty() { None } else { Some(desc) },
             new_flag,
         ) {
-            Ok(_) => {
+            Ok(line) => {
+                follow_edits(&[BindEdit::Copied {
+                    source: bind_id.clone(),
+                    file: destination,
+                    line,
+                }]);
                 reload_keybinds(&model);
                 let toast = adw::Toast::builder()
                     .title("Keybind cloned successfully")
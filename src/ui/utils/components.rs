@@ -3,6 +3,7 @@ use gtk::gio;
 use gtk::prelude::*;
 use gtk4 as gtk;
 use std::collections::HashSet;
+use std::path::Path;
 
 pub fn collect_submaps(model: &gio::ListStore) -> Vec<String> {
     let mut submaps = HashSet::new();
@@ -171,6 +172,17 @@ pub fn create_form_group(label_text: &str, widget: &impl IsA<gtk::Widget>) -> gt
     group
 }
 
+/// Opens `path` in the default application for its type.
+pub fn open_file(widget: &impl IsA<gtk::Widget>, path: &Path) {
+    let launcher = gtk::FileLauncher::new(Some(&gio::File::for_path(path)));
+    let window = widget.root().and_downcast::<gtk::Window>();
+    launcher.launch(window.as_ref(), None::<&gio::Cancellable>, |res| {
+        if let Err(e) = res {
+            crate::log_error!("ui", "Failed to open file: {}", e);
+        }
+    });
+}
+
 pub fn create_card_row(
     title: &str,
     subtitle: Option<&str>,
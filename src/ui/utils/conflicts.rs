@@ -1,10 +1,13 @@
 use crate::keybind_object::KeybindObject;
+use crate::parser::{bind_line_fields, write_lines, Keybind};
 use crate::ui::utils::components::{create_destructive_button, create_pill_button};
 use crate::ui::utils::keybinds::normalize;
+use anyhow::Result;
 use gtk::gio;
 use gtk::prelude::*;
 use gtk4 as gtk;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 pub struct ConflictInfo {
     pub dispatcher: String,
@@ -224,3 +227,257 @@ pub fn generate_suggestions(
     suggestions.truncate(3);
     suggestions
 }
+
+/// Modifiers tried, in order, when moving a bind out of a conflict.
+const REBIND_MODS: &[&str] = &["SHIFT", "CTRL", "ALT", "SUPER"];
+
+/// Why a bind is part of an auto-resolve plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebindReason {
+    /// The bind is shadowed by a later definition of the same combination.
+    Conflict,
+    /// The bind is not conflicting itself but moves along with related binds
+    /// (e.g. the rest of `workspace 1-9`) to keep their modifiers consistent.
+    KeepGroupConsistent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedRebind {
+    /// Index into the keybinds the plan was built from.
+    pub index: usize,
+    pub new_mods: String,
+    /// The modifiers added to the ones the bind line has.
+    pub extra_mods: String,
+    pub reason: RebindReason,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResolutionPlan {
+    pub rebinds: Vec<PlannedRebind>,
+    /// Shadowed binds for which no free combination was found.
+    pub unresolved: Vec<usize>,
+}
+
+impl ResolutionPlan {
+    pub fn is_empty(&self) -> bool {
+        self.rebinds.is_empty() && self.unresolved.is_empty()
+    }
+}
+
+type Slot = (String, String, String);
+
+fn slot_of(kb: &Keybind, extra_mods: &[&str]) -> Slot {
+    let mods = if extra_mods.is_empty() {
+        kb.clean_mods.to_string()
+    } else {
+        format!("{} {}", kb.clean_mods, extra_mods.join(" "))
+    };
+    let (mods, key) = normalize(&mods, &kb.key);
+    let submap = kb.submap.as_deref().unwrap_or("").to_string();
+    (mods, key, submap)
+}
+
+/// Binds belong to the same family when they share a submap, modifiers and
+/// dispatcher and their arguments differ only by a number or a direction,
+/// like `workspace 1` .. `workspace 9` or `movefocus l` .. `movefocus d`.
+fn family_of(kb: &Keybind) -> Option<Slot> {
+    let args = kb.args.trim().to_lowercase();
+    let mut varies = false;
+    let pattern: Vec<String> = args
+        .split_whitespace()
+        .map(|token| match token {
+            "l" | "r" | "u" | "d" | "left" | "right" | "up" | "down" => {
+                varies = true;
+                "<dir>".to_string()
+            }
+            t if t.chars().any(|c| c.is_ascii_digit()) => {
+                varies = true;
+                t.chars()
+                    .map(|c| if c.is_ascii_digit() { '#' } else { c })
+                    .collect()
+            }
+            t => t.to_string(),
+        })
+        .collect();
+
+    if !varies {
+        return None;
+    }
+    let (mods, _, submap) = slot_of(kb, &[]);
+    Some((
+        format!("{} {}", submap, mods),
+        kb.dispatcher.to_lowercase(),
+        pattern.join(" "),
+    ))
+}
+
+fn candidate_mods(kb: &Keybind) -> Vec<Vec<&'static str>> {
+    let (current, _) = normalize(&kb.clean_mods, "");
+    let available: Vec<&'static str> = REBIND_MODS
+        .iter()
+        .copied()
+        .filter(|m| !current.split(' ').any(|c| c == *m))
+        .collect();
+
+    let mut candidates: Vec<Vec<&'static str>> = available.iter().map(|m| vec![*m]).collect();
+    for (i, first) in available.iter().enumerate() {
+        for second in &available[i + 1..] {
+            candidates.push(vec![*first, *second]);
+        }
+    }
+    candidates
+}
+
+fn rebind_mods(kb: &Keybind, extra_mods: &[&str]) -> String {
+    let mods = kb.mods.trim();
+    if mods.is_empty() {
+        extra_mods.join(" ")
+    } else {
+        format!("{} {}", mods, extra_mods.join(" "))
+    }
+}
+
+/// Proposes new modifiers for every shadowed keybind so that no conflicts
+/// remain.
+///
+/// In each conflict group the bind defined last (the one Hyprland honors)
+/// keeps its combination and the others get extra modifiers. With
+/// `keep_groups`, related binds (like `workspace 1-9`) move together onto the
+/// same modifiers where possible. Planned combinations are never already
+/// taken or used twice.
+pub fn plan_conflict_resolution(keybinds: &[Keybind], keep_groups: bool) -> ResolutionPlan {
+    let mut occupied: std::collections::HashSet<Slot> =
+        keybinds.iter().map(|kb| slot_of(kb, &[])).collect();
+
+    let mut groups: HashMap<Slot, Vec<usize>> = HashMap::new();
+    for (i, kb) in keybinds.iter().enumerate() {
+        groups.entry(slot_of(kb, &[])).or_default().push(i);
+    }
+    let mut shadowed: Vec<usize> = groups
+        .values()
+        .filter(|g| g.len() > 1)
+        .flat_map(|g| g[..g.len() - 1].iter().copied())
+        .collect();
+    shadowed.sort_unstable();
+
+    let mut families: HashMap<Slot, Vec<usize>> = HashMap::new();
+    for (i, kb) in keybinds.iter().enumerate() {
+        if let Some(family) = family_of(kb) {
+            families.entry(family).or_default().push(i);
+        }
+    }
+
+    let mut plan = ResolutionPlan::default();
+    let mut planned = std::collections::HashSet::new();
+
+    let fits =
+        |members: &[usize], extra: &[&str], occupied: &std::collections::HashSet<Slot>| -> bool {
+            let mut local = std::collections::HashSet::new();
+            members.iter().all(|&i| {
+                let slot = slot_of(&keybinds[i], extra);
+                !occupied.contains(&slot) && local.insert(slot)
+            })
+        };
+
+    for &idx in &shadowed {
+        if planned.contains(&idx) {
+            continue;
+        }
+        let kb = &keybinds[idx];
+
+        let family: Vec<usize> = family_of(kb)
+            .filter(|_| keep_groups)
+            .and_then(|f| families.get(&f))
+            .filter(|members| members.len() > 1)
+            .map(|members| {
+                members
+                    .iter()
+                    .copied()
+                    .filter(|i| !planned.contains(i))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let candidates = candidate_mods(kb);
+        let family_extra = if family.len() > 1 {
+            candidates
+                .iter()
+                .find(|extra| fits(&family, extra, &occupied))
+        } else {
+            None
+        };
+
+        // Fall back to moving the shadowed bind on its own
+        let (members, extra) = match family_extra {
+            Some(extra) => (family, extra),
+            None => match candidates
+                .iter()
+                .find(|extra| fits(&[idx], extra, &occupied))
+            {
+                Some(extra) => (vec![idx], extra),
+                None => {
+                    plan.unresolved.push(idx);
+                    continue;
+                }
+            },
+        };
+
+        for i in members {
+            occupied.insert(slot_of(&keybinds[i], extra));
+            planned.insert(i);
+            plan.rebinds.push(PlannedRebind {
+                index: i,
+                new_mods: rebind_mods(&keybinds[i], extra),
+                extra_mods: extra.join(" "),
+                reason: if shadowed.binary_search(&i).is_ok() {
+                    RebindReason::Conflict
+                } else {
+                    RebindReason::KeepGroupConsistent
+                },
+            });
+        }
+    }
+
+    plan.rebinds.sort_by_key(|r| r.index);
+    plan
+}
+
+/// Writes a plan to disk, adding the extra modifiers to each rebound line's
+/// modifier field as written. Variables, descriptions, arguments and comments
+/// are left alone. Returns the number of rewritten lines.
+pub fn apply_resolution_plan(keybinds: &[Keybind], plan: &ResolutionPlan) -> Result<usize> {
+    let mut by_file: HashMap<PathBuf, Vec<(usize, &str)>> = HashMap::new();
+    for rebind in &plan.rebinds {
+        let Some(kb) = keybinds.get(rebind.index) else {
+            continue;
+        };
+        by_file
+            .entry(kb.file_path.clone())
+            .or_default()
+            .push((kb.line_number, &rebind.extra_mods));
+    }
+
+    let mut count = 0;
+    for (path, edits) in by_file {
+        let content = std::fs::read_to_string(&path)?;
+        let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+        for (line_number, extra) in edits {
+            let Some(line) = lines.get_mut(line_number) else {
+                continue;
+            };
+            let Some(fields) = bind_line_fields(line) else {
+                continue;
+            };
+            let mods = &line[fields.mods.clone()];
+            let new_mods = if mods.is_empty() {
+                extra.to_string()
+            } else {
+                format!("{} {}", mods, extra)
+            };
+            line.replace_range(fields.mods, &new_mods);
+            count += 1;
+        }
+        write_lines(&path, &lines)?;
+    }
+    Ok(count)
+}
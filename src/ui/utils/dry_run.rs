@@ -0,0 +1,173 @@
+use crate::ui::utils::components::{create_card_row, create_pill_button, create_suggested_button};
+use crate::ui::utils::execution::{
+    dry_run, find_terminal, query_active_window, run_dry_run, run_in_terminal, DryRun, DryRunTarget,
+};
+use gtk::prelude::*;
+use gtk4 as gtk;
+use libadwaita as adw;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct DryRunPanel {
+    pub container: gtk::Box,
+    pub back_btn: gtk::Button,
+    command_label: gtk::Label,
+    program_label: gtk::Label,
+    target_label: gtk::Label,
+    current: Rc<RefCell<Option<DryRun>>>,
+}
+
+fn value_label() -> gtk::Label {
+    gtk::Label::builder()
+        .halign(gtk::Align::End)
+        .wrap(true)
+        .wrap_mode(gtk::pango::WrapMode::WordChar)
+        .max_width_chars(40)
+        .selectable(true)
+        .justify(gtk::Justification::Right)
+        .build()
+}
+
+fn show_toast(toast_overlay: &adw::ToastOverlay, title: &str) {
+    let toast = adw::Toast::builder()
+        .title(title)
+        .timeout(crate::config::constants::TOAST_TIMEOUT)
+        .build();
+    toast_overlay.add_toast(toast);
+}
+
+/// A preview of what a bind would do, with options to run it, run it in a
+/// floating terminal or copy the command.
+pub fn create_dry_run_panel(toast_overlay: &adw::ToastOverlay) -> DryRunPanel {
+    let container = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .valign(gtk::Align::Center)
+        .halign(gtk::Align::Center)
+        .width_request(480)
+        .build();
+
+    let title = gtk::Label::builder()
+        .label("Dry Run")
+        .css_classes(["title-2"])
+        .build();
+    container.append(&title);
+
+    let subtitle = gtk::Label::builder()
+        .label("Nothing has been executed yet.")
+        .css_classes(["dim-label"])
+        .margin_bottom(12)
+        .build();
+    container.append(&subtitle);
+
+    let command_label = value_label();
+    command_label.add_css_class("monospace");
+    container.append(&create_card_row("Command", None, &command_label));
+
+    let program_label = value_label();
+    container.append(&create_card_row("Program", None, &program_label));
+
+    let target_label = value_label();
+    container.append(&create_card_row("Target", None, &target_label));
+
+    let button_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(12)
+        .halign(gtk::Align::Center)
+        .margin_top(12)
+        .build();
+
+    let back_btn = create_pill_button("Back", None);
+    let copy_btn = create_pill_button("Copy Command", Some("edit-copy-symbolic"));
+    let terminal_btn = create_pill_button("Run in Terminal", Some("utilities-terminal-symbolic"));
+    terminal_btn.set_tooltip_text(Some("Run in a floating terminal to see its output"));
+    let run_btn = create_suggested_button("Run", Some("media-playback-start-symbolic"));
+
+    button_box.append(&back_btn);
+    button_box.append(&copy_btn);
+    button_box.append(&terminal_btn);
+    button_box.append(&run_btn);
+    container.append(&button_box);
+
+    let current: Rc<RefCell<Option<DryRun>>> = Rc::new(RefCell::new(None));
+
+    let current_c = current.clone();
+    let toast_overlay_c = toast_overlay.clone();
+    copy_btn.connect_clicked(move |btn| {
+        if let Some(run) = current_c.borrow().as_ref() {
+            btn.clipboard().set_text(&run.command_line());
+            show_toast(&toast_overlay_c, "Command copied");
+        }
+    });
+
+    let current_c = current.clone();
+    let toast_overlay_c = toast_overlay.clone();
+    terminal_btn.connect_clicked(move |_| {
+        if let Some(run) = current_c.borrow().as_ref() {
+            let variables = crate::parser::get_variables().unwrap_or_default();
+            match find_terminal(&variables) {
+                Some(terminal) => {
+                    run_in_terminal(run, &terminal);
+                    show_toast(&toast_overlay_c, "Started in a floating terminal");
+                }
+                None => show_toast(&toast_overlay_c, "No terminal found"),
+            }
+        }
+    });
+
+    let current_c = current.clone();
+    let toast_overlay_c = toast_overlay.clone();
+    run_btn.connect_clicked(move |_| {
+        if let Some(run) = current_c.borrow().as_ref() {
+            run_dry_run(run, "dry-run");
+            show_toast(&toast_overlay_c, "Dispatched");
+        }
+    });
+
+    DryRunPanel {
+        container,
+        back_btn,
+        command_label,
+        program_label,
+        target_label,
+        current,
+    }
+}
+
+impl DryRunPanel {
+    /// Resolves the bind and fills in the preview.
+    pub fn show(&self, dispatcher: &str, args: &str) {
+        let run = dry_run(dispatcher, args);
+
+        self.command_label.set_label(&run.command_line());
+
+        self.program_label.remove_css_class("error");
+        match &run.program {
+            Some(program) if run.program_exists => {
+                self.program_label
+                    .set_label(&format!("{} (found)", program));
+            }
+            Some(program) => {
+                self.program_label
+                    .set_label(&format!("{} (not found)", program));
+                self.program_label.add_css_class("error");
+            }
+            None => self.program_label.set_label("None"),
+        }
+
+        let target = match &run.target {
+            DryRunTarget::NewProcess => "Starts a new process".to_string(),
+            DryRunTarget::Selector(selector) => format!("Windows matching {}", selector),
+            DryRunTarget::FocusedWindow => match query_active_window() {
+                Some(window) if window.title.is_empty() => {
+                    format!("Focused window: {}", window.class)
+                }
+                Some(window) => format!("Focused window: {} — {}", window.class, window.title),
+                None => "Focused window (none is focused)".to_string(),
+            },
+        };
+        self.target_label.set_label(&target);
+
+        *self.current.borrow_mut() = Some(run);
+    }
+}
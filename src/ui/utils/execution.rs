@@ -1,77 +1,443 @@
-pub fn execute_keybind(dispatcher: &str, args: &str) {
-    let variables = crate::parser::get_variables().unwrap_or_default();
+use crate::config::constants::HYPRKCS_DIR;
+use crate::json::{self, JsonValue};
+use crate::parser::dispatchers::{lookup, ArgKind};
+use crate::parser::substitute_variables;
+use crate::ui::utils::apps::{split_command, split_exec_rules};
+use crate::ui::utils::macro_builder::shell_quote;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
-    let mut resolved_dispatcher = dispatcher.to_string();
-    let mut resolved_args = args.to_string();
+/// What a dispatcher call would act on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DryRunTarget {
+    /// `exec` and friends start a new process instead.
+    NewProcess,
+    /// The window focused when the bind fires.
+    FocusedWindow,
+    /// Windows matching an explicit selector like `class:kitty`.
+    Selector(String),
+}
 
-    let mut sorted_vars: Vec<_> = variables.keys().collect();
-    sorted_vars.sort_by_key(|b: &&String| std::cmp::Reverse(b.len()));
+/// A dispatcher call with variables substituted, for previewing a bind
+/// before running it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRun {
+    pub dispatcher: String,
+    pub args: String,
+    /// The command an exec bind runs, without exec rules.
+    pub command: Option<String>,
+    /// The program that command starts.
+    pub program: Option<String>,
+    pub program_exists: bool,
+    pub target: DryRunTarget,
+}
 
-    for key in sorted_vars {
-        if resolved_dispatcher.contains(key) {
-            resolved_dispatcher = resolved_dispatcher.replace(key, &variables[key]);
+impl DryRun {
+    /// Arguments for `hyprctl`.
+    pub fn hyprctl_args(&self) -> Vec<String> {
+        let mut args = vec!["dispatch".to_string(), self.dispatcher.clone()];
+        if !self.args.trim().is_empty() {
+            args.push(self.args.clone());
         }
-        if resolved_args.contains(key) {
-            resolved_args = resolved_args.replace(key, &variables[key]);
+        args
+    }
+
+    /// The `hyprctl dispatch` call as it would be typed into a shell.
+    pub fn command_line(&self) -> String {
+        let mut line = "hyprctl".to_string();
+        for arg in self.hyprctl_args() {
+            line.push(' ');
+            line.push_str(&shell_quote(&arg));
         }
+        line
     }
 
-    let mut command = std::process::Command::new("hyprctl");
-    command.arg("dispatch").arg(&resolved_dispatcher);
+    /// What to run in a terminal to see the bind's output: the command
+    /// itself for exec binds, the dispatch call otherwise.
+    pub fn terminal_command(&self) -> String {
+        self.command.clone().unwrap_or_else(|| self.command_line())
+    }
+}
 
-    if !resolved_args.trim().is_empty() {
-        command.arg(&resolved_args);
+fn dry_run_target(dispatcher: &str, args: &str) -> DryRunTarget {
+    let args = args.trim();
+    let selector = |s: &str| {
+        let s = s.trim();
+        if s.is_empty() {
+            DryRunTarget::FocusedWindow
+        } else {
+            DryRunTarget::Selector(s.to_string())
+        }
+    };
+    match lookup(dispatcher).map(|spec| spec.arg) {
+        Some(ArgKind::Command) => DryRunTarget::NewProcess,
+        Some(ArgKind::Window) => selector(args),
+        Some(
+            ArgKind::WorkspaceWithWindow
+            | ArgKind::DeltasWithWindow
+            | ArgKind::ChoiceWithWindow(_)
+            | ArgKind::Tag,
+        ) => args
+            .split_once(',')
+            .map_or(DryRunTarget::FocusedWindow, |(_, window)| selector(window)),
+        _ => DryRunTarget::FocusedWindow,
     }
+}
 
-    let _ = command.spawn();
+/// Resolves a bind against `vars` without running anything.
+pub fn prepare_dry_run(dispatcher: &str, args: &str, vars: &HashMap<String, String>) -> DryRun {
+    let dispatcher = substitute_variables(dispatcher.trim(), vars);
+    let args = substitute_variables(args.trim(), vars);
+    let target = dry_run_target(&dispatcher, &args);
+
+    let command = (target == DryRunTarget::NewProcess)
+        .then(|| split_exec_rules(&args).1.to_string())
+        .filter(|c| !c.is_empty());
+    let program = command
+        .as_deref()
+        .and_then(|c| split_command(c).into_iter().next());
+    let program_exists = program.as_deref().is_some_and(command_exists);
+
+    DryRun {
+        dispatcher,
+        args,
+        command,
+        program,
+        program_exists,
+        target,
+    }
 }
 
-pub fn execute_hyprctl(args: &[&str]) {
-    let args_owned: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+/// Resolves a bind against the current config's variables.
+pub fn dry_run(dispatcher: &str, args: &str) -> DryRun {
+    let variables = crate::parser::get_variables().unwrap_or_default();
+    prepare_dry_run(dispatcher, args, &variables)
+}
 
-    std::thread::spawn(move || {
-        run_hyprctl_inner(&args_owned);
-    });
+/// The fields of `hyprctl -j activewindow` shown in the dry-run panel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowInfo {
+    pub address: String,
+    pub class: String,
+    pub title: String,
+    pub workspace: String,
+    pub floating: bool,
 }
 
-/// Synchronous variant — blocks until hyprctl exits.
-/// Use this when subsequent logic depends on the command having taken effect
-/// (e.g. activating a submap before listening for key input).
-pub fn execute_hyprctl_sync(args: &[&str]) {
-    let args_owned: Vec<String> = args.iter().map(|s| s.to_string()).collect();
-    run_hyprctl_inner(&args_owned);
+/// Parses `hyprctl -j activewindow`. Hyprland prints `{}` when nothing is
+/// focused.
+pub fn parse_active_window(content: &str) -> Option<WindowInfo> {
+    let root = json::parse(content)?;
+    root.get("address")?;
+    Some(WindowInfo {
+        address: root.str_field("address"),
+        class: root.str_field("class"),
+        title: root.str_field("title"),
+        workspace: root
+            .get("workspace")
+            .map(|ws| ws.str_field("name"))
+            .unwrap_or_default(),
+        floating: root
+            .get("floating")
+            .and_then(JsonValue::as_bool)
+            .unwrap_or(false),
+    })
+}
+
+pub fn query_active_window() -> Option<WindowInfo> {
+    let output = std::process::Command::new("hyprctl")
+        .args(["-j", "activewindow"])
+        .output()
+        .ok()?;
+    parse_active_window(&String::from_utf8_lossy(&output.stdout))
 }
 
-fn run_hyprctl_inner(args: &[String]) {
-    use std::io::Write;
+/// A terminal to run commands in: `$terminal` from the config, then
+/// `$TERMINAL`, then the first common terminal on PATH.
+pub fn find_terminal(vars: &HashMap<String, String>) -> Option<String> {
+    vars.get("$terminal")
+        .cloned()
+        .or_else(|| std::env::var("TERMINAL").ok())
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| {
+            ["kitty", "foot", "alacritty", "wezterm", "ghostty"]
+                .into_iter()
+                .find(|t| command_exists(t))
+                .map(str::to_string)
+        })
+}
 
-    let output = std::process::Command::new("hyprctl").args(args).output();
+/// Exec arguments that run `command` in a floating terminal which stays open
+/// until Enter is pressed.
+pub fn terminal_exec_args(terminal: &str, command: &str) -> String {
+    let script = format!(
+        "sh -c {}; printf '\\n[exit status %s] Press Enter to close' \"$?\"; read -r _",
+        shell_quote(command)
+    );
+    format!("[float] {} -e sh -c {}", terminal, shell_quote(&script))
+}
+
+/// One entry of the execution log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionRecord {
+    pub timestamp: String,
+    /// Where the call came from, e.g. `dry-run` or `terminal`.
+    pub origin: String,
+    pub command: Vec<String>,
+    pub status: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub error: Option<String>,
+}
+
+impl ExecutionRecord {
+    pub fn to_json(&self) -> JsonValue {
+        let mut map = BTreeMap::new();
+        let string = |s: &str| JsonValue::String(s.to_string());
+        map.insert("timestamp".to_string(), string(&self.timestamp));
+        map.insert("origin".to_string(), string(&self.origin));
+        map.insert(
+            "command".to_string(),
+            JsonValue::Array(self.command.iter().map(|a| string(a)).collect()),
+        );
+        map.insert(
+            "status".to_string(),
+            self.status
+                .map_or(JsonValue::Null, |s| JsonValue::Number(s as f64)),
+        );
+        map.insert("stdout".to_string(), string(&self.stdout));
+        map.insert("stderr".to_string(), string(&self.stderr));
+        map.insert(
+            "error".to_string(),
+            self.error.as_deref().map_or(JsonValue::Null, string),
+        );
+        JsonValue::Object(map)
+    }
+}
 
+/// `$XDG_STATE_HOME/hyprkcs/executions.jsonl`, one JSON record per line.
+/// Rotated like the main log.
+pub fn execution_log_path() -> Option<PathBuf> {
+    dirs::state_dir().map(|dir| dir.join(HYPRKCS_DIR).join("executions.jsonl"))
+}
+
+fn log_execution(record: &ExecutionRecord) {
+    let Some(path) = execution_log_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    crate::logging::rotate_if_large(&path);
     if let Ok(mut file) = std::fs::OpenOptions::new()
         .create(true)
         .append(true)
-        .open("/tmp/hyprkcs-debug.log")
+        .open(&path)
     {
-        let _ = writeln!(file, "Executing: hyprctl {:?}", args);
-        match &output {
-            Ok(out) => {
-                let _ = writeln!(file, "Status: {}", out.status);
-                let _ = writeln!(file, "Stdout: {}", String::from_utf8_lossy(&out.stdout));
-                let _ = writeln!(file, "Stderr: {}", String::from_utf8_lossy(&out.stderr));
+        let _ = writeln!(file, "{}", record.to_json().to_compact_string());
+    }
+}
+
+type ExecutionCounts = HashMap<(String, String), usize>;
+
+fn add_execution_counts(content: &str, counts: &mut ExecutionCounts) {
+    for line in content.lines() {
+        let Some(record) = json::parse(line) else {
+            continue;
+        };
+        if record.get("error").and_then(JsonValue::as_str).is_some()
+            || record.get("status").and_then(JsonValue::as_f64) != Some(0.0)
+        {
+            continue;
+        }
+        let command: Vec<&str> = record
+            .get("command")
+            .and_then(JsonValue::as_array)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(JsonValue::as_str)
+            .collect();
+        if let ["hyprctl", "dispatch", dispatcher, rest @ ..] = command.as_slice() {
+            let key = (
+                dispatcher.trim().to_string(),
+                rest.join(" ").trim().to_string(),
+            );
+            *counts.entry(key).or_insert(0) += 1;
+        }
+    }
+}
+
+/// How often each `(dispatcher, args)` pair was dispatched, from the lines
+/// of the execution log. Calls that failed or exited with an error are not
+/// counted.
+pub fn parse_execution_counts(content: &str) -> ExecutionCounts {
+    let mut counts = HashMap::new();
+    add_execution_counts(content, &mut counts);
+    counts
+}
+
+/// What [`execution_counts_in`] has read of an execution log so far.
+struct CountsCache {
+    path: PathBuf,
+    /// Modification time of the newest rotated copy, which changes when the
+    /// log is rotated.
+    rotated: Option<SystemTime>,
+    /// Bytes of the current log already counted.
+    offset: u64,
+    counts: ExecutionCounts,
+}
+
+static COUNTS_CACHE: Mutex<Option<CountsCache>> = Mutex::new(None);
+
+/// [`parse_execution_counts`] for the execution log at `path` and its
+/// rotated copies. Only records appended since the last call are read,
+/// unless the log was rotated in between.
+pub fn execution_counts_in(path: &Path) -> ExecutionCounts {
+    let rotated = std::fs::metadata(crate::logging::rotated_path(path, 1))
+        .and_then(|m| m.modified())
+        .ok();
+    let len = std::fs::metadata(path).map_or(0, |m| m.len());
+    let Ok(mut guard) = COUNTS_CACHE.lock() else {
+        return HashMap::new();
+    };
+
+    let mut cache = match guard.take() {
+        Some(cache) if cache.path == path && cache.rotated == rotated && cache.offset <= len => {
+            cache
+        }
+        _ => {
+            let mut counts = HashMap::new();
+            for n in (1..=crate::logging::KEEP_ROTATED).rev() {
+                if let Ok(content) = std::fs::read_to_string(crate::logging::rotated_path(path, n))
+                {
+                    add_execution_counts(&content, &mut counts);
+                }
+            }
+            CountsCache {
+                path: path.to_path_buf(),
+                rotated,
+                offset: 0,
+                counts,
             }
-            Err(e) => {
-                let _ = writeln!(file, "Failed to execute: {}", e);
+        }
+    };
+
+    if len > cache.offset {
+        if let Ok(mut file) = std::fs::File::open(path) {
+            let mut appended = Vec::new();
+            if file.seek(SeekFrom::Start(cache.offset)).is_ok()
+                && file.read_to_end(&mut appended).is_ok()
+            {
+                // A line still being written is counted on the next call
+                let complete = appended
+                    .iter()
+                    .rposition(|&b| b == b'\n')
+                    .map_or(0, |i| i + 1);
+                add_execution_counts(
+                    &String::from_utf8_lossy(&appended[..complete]),
+                    &mut cache.counts,
+                );
+                cache.offset += complete as u64;
             }
         }
     }
 
-    if let Err(e) = output {
-        eprintln!("Failed to execute hyprctl: {}", e);
+    let counts = cache.counts.clone();
+    *guard = Some(cache);
+    counts
+}
+
+/// [`execution_counts_in`] for the execution log on disk.
+pub fn execution_counts() -> ExecutionCounts {
+    execution_log_path()
+        .map(|path| execution_counts_in(&path))
+        .unwrap_or_default()
+}
+
+/// Runs `hyprctl` with `args` and records the result in the execution log.
+pub fn run_logged(args: &[String], origin: &str) {
+    let output = std::process::Command::new("hyprctl").args(args).output();
+
+    let mut command = vec!["hyprctl".to_string()];
+    command.extend(args.iter().cloned());
+    let mut record = ExecutionRecord {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        origin: origin.to_string(),
+        command,
+        status: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        error: None,
+    };
+    match &output {
+        Ok(out) => {
+            record.status = out.status.code();
+            record.stdout = String::from_utf8_lossy(&out.stdout).trim_end().to_string();
+            record.stderr = String::from_utf8_lossy(&out.stderr).trim_end().to_string();
+        }
+        Err(e) => record.error = Some(e.to_string()),
+    }
+    log_execution(&record);
+
+    let command_line = record.command.join(" ");
+    match (&record.error, record.status) {
+        (Some(e), _) => crate::log_error!("hyprctl", "Failed to run {}: {}", command_line, e),
+        (None, Some(0)) => crate::log_info!("hyprctl", "{} ({})", command_line, origin),
+        (None, status) => crate::log_warn!(
+            "hyprctl",
+            "{} exited with {:?}: {}",
+            command_line,
+            status,
+            record.stderr
+        ),
     }
 }
 
-use std::collections::HashMap;
-use std::sync::Mutex;
+/// Dispatches a resolved bind in the background.
+pub fn run_dry_run(run: &DryRun, origin: &str) {
+    let args = run.hyprctl_args();
+    let origin = origin.to_string();
+    std::thread::spawn(move || run_logged(&args, &origin));
+}
+
+/// Runs a resolved bind in a floating terminal so its output stays visible.
+pub fn run_in_terminal(run: &DryRun, terminal: &str) {
+    let args = vec![
+        "dispatch".to_string(),
+        "exec".to_string(),
+        terminal_exec_args(terminal, &run.terminal_command()),
+    ];
+    std::thread::spawn(move || run_logged(&args, "terminal"));
+}
+
+pub fn execute_keybind(dispatcher: &str, args: &str) {
+    run_dry_run(&dry_run(dispatcher, args), "execute");
+}
+
+/// Synchronous variant of [`execute_keybind`], for processes that exit
+/// right after running the bind.
+pub fn execute_keybind_sync(dispatcher: &str, args: &str, origin: &str) {
+    run_logged(&dry_run(dispatcher, args).hyprctl_args(), origin);
+}
+
+pub fn execute_hyprctl(args: &[&str]) {
+    let args_owned: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+
+    std::thread::spawn(move || {
+        run_logged(&args_owned, "hyprctl");
+    });
+}
+
+/// Synchronous variant — blocks until hyprctl exits.
+/// Use this when subsequent logic depends on the command having taken effect
+/// (e.g. activating a submap before listening for key input).
+pub fn execute_hyprctl_sync(args: &[&str]) {
+    let args_owned: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    run_logged(&args_owned, "hyprctl");
+}
 
 static COMMAND_CACHE: Mutex<Option<HashMap<String, bool>>> = Mutex::new(None);
 
@@ -1,4 +1,6 @@
+use crate::config::categories::load_categories;
 use crate::keybind_object::KeybindObject;
+use crate::ui::utils::search::SearchQuery;
 use anyhow::Result;
 use gtk::gio;
 use gtk::prelude::*;
@@ -7,19 +9,50 @@ use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
+/// Writes the keybinds as Markdown tables, one section per category from
+/// `categories.json`. Each bind is listed under the first category it is in,
+/// and binds without one end up under "Other".
 pub fn export_keybinds_to_markdown(model: &gio::ListStore, path: &Path) -> Result<()> {
+    let categories: Vec<(String, SearchQuery)> = load_categories()
+        .into_iter()
+        .map(|c| (c.name, SearchQuery::parse(&c.query)))
+        .collect();
+    let mut groups: Vec<Vec<KeybindObject>> = vec![Vec::new(); categories.len() + 1];
+    for i in 0..model.n_items() {
+        if let Some(obj) = model.item(i).and_downcast::<KeybindObject>() {
+            let index = obj.with_data(|d| {
+                categories
+                    .iter()
+                    .position(|(_, query)| query.matches_exact(d))
+                    .unwrap_or(categories.len())
+            });
+            groups[index].push(obj);
+        }
+    }
+
     let mut file = File::create(path)?;
 
     writeln!(file, "# Hyprland Keybinds\n")?;
     writeln!(file, "(Exported with HyprKCS)\n")?;
-    writeln!(
-        file,
-        "| Modifiers | Key | Action | Arguments | Submap | Description |"
-    )?;
-    writeln!(file, "|---|---|---|---|---|---|")?;
 
-    for i in 0..model.n_items() {
-        if let Some(obj) = model.item(i).and_downcast::<KeybindObject>() {
+    let names = categories
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .chain(std::iter::once("Other"));
+    for (name, binds) in names.zip(&groups) {
+        if binds.is_empty() {
+            continue;
+        }
+        if !categories.is_empty() {
+            writeln!(file, "## {}\n", name)?;
+        }
+        writeln!(
+            file,
+            "| Modifiers | Key | Action | Arguments | Submap | Description |"
+        )?;
+        writeln!(file, "|---|---|---|---|---|---|")?;
+
+        for obj in binds {
             obj.with_data(|d| -> std::io::Result<()> {
                 writeln!(
                     file,
@@ -33,6 +66,48 @@ pub fn export_keybinds_to_markdown(model: &gio::ListStore, path: &Path) -> Resul
                 )
             })?;
         }
+        writeln!(file)?;
+    }
+
+    Ok(())
+}
+
+/// Renders a widget to an image file through its snapshot.
+///
+/// The format follows the file extension: `.svg` is drawn as vectors through
+/// cairo, anything else is written as PNG. The widget must be realized and
+/// allocated (i.e. currently shown).
+pub fn export_widget_image(widget: &impl IsA<gtk::Widget>, path: &Path) -> Result<()> {
+    let widget = widget.as_ref();
+    let (width, height) = (widget.width(), widget.height());
+    if width <= 0 || height <= 0 {
+        anyhow::bail!("Nothing to export: the chart is not visible");
+    }
+
+    let paintable = gtk::WidgetPaintable::new(Some(widget));
+    let snapshot = gtk::Snapshot::new();
+    paintable.snapshot(&snapshot, width as f64, height as f64);
+    let node = snapshot
+        .to_node()
+        .ok_or_else(|| anyhow::anyhow!("Nothing to export: the chart is empty"))?;
+
+    let is_svg = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+
+    if is_svg {
+        let surface = cairo::SvgSurface::new(width as f64, height as f64, Some(path))?;
+        let cr = cairo::Context::new(&surface)?;
+        node.draw(&cr);
+        drop(cr);
+        surface.finish();
+    } else {
+        let renderer = widget
+            .native()
+            .and_then(|n| n.renderer())
+            .ok_or_else(|| anyhow::anyhow!("No renderer available for export"))?;
+        let texture = renderer.render_texture(&node, None);
+        texture.save_to_png(path)?;
     }
 
     Ok(())
@@ -0,0 +1,210 @@
+use crate::parser::Keybind;
+use crate::ui::utils::keybinds::normalize;
+use crate::ui::views::keyboard_geometry::{GeometryKey, KeyboardGeometry};
+use std::collections::HashSet;
+
+/// Evdev codes of the home-row resting keys (A S D F and J K L ;).
+const HOME_KEYCODES: &[u32] = &[30, 31, 32, 33, 36, 37, 38, 39];
+
+/// Modifier keys are never offered as a free key.
+const MODIFIER_KEYS: &[&str] = &[
+    "shift_l",
+    "shift_r",
+    "control_l",
+    "control_r",
+    "alt_l",
+    "alt_r",
+    "super_l",
+    "super_r",
+    "caps_lock",
+    "iso_level3_shift",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyClass {
+    Letter,
+    Digit,
+    Function,
+    Other,
+}
+
+impl KeyClass {
+    pub fn of(key: &str) -> Self {
+        let mut chars = key.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => Self::Letter,
+            (Some(c), None) if c.is_ascii_digit() => Self::Digit,
+            (Some('f' | 'F'), Some(_)) if key[1..].parse::<u8>().is_ok() => Self::Function,
+            _ => Self::Other,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Letter => "Letter",
+            Self::Digit => "Digit",
+            Self::Function => "F-Key",
+            Self::Other => "Other",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FreeKeyOptions {
+    /// Modifiers to combine with the free key (any notation `normalize` accepts).
+    pub mods: String,
+    /// `None` searches the global map.
+    pub submap: Option<String>,
+    pub letters: bool,
+    pub digits: bool,
+    pub function_keys: bool,
+    pub other: bool,
+    /// Only keep keys at most one row away from the home row.
+    pub near_home_row: bool,
+}
+
+impl Default for FreeKeyOptions {
+    fn default() -> Self {
+        Self {
+            mods: String::new(),
+            submap: None,
+            letters: true,
+            digits: true,
+            function_keys: true,
+            other: true,
+            near_home_row: false,
+        }
+    }
+}
+
+impl FreeKeyOptions {
+    fn allows(&self, class: KeyClass) -> bool {
+        match class {
+            KeyClass::Letter => self.letters,
+            KeyClass::Digit => self.digits,
+            KeyClass::Function => self.function_keys,
+            KeyClass::Other => self.other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeKey {
+    /// Label as drawn on the keyboard.
+    pub label: String,
+    /// Normalized key name as returned by `normalize`.
+    pub key: String,
+    pub class: KeyClass,
+    /// Distance to the nearest home-row key in key units (lower is easier).
+    pub distance: f64,
+}
+
+impl FreeKey {
+    /// The key as it would conventionally be written in a bind (`Q`, `F5`).
+    pub fn bind_key(&self) -> String {
+        match self.class {
+            KeyClass::Letter | KeyClass::Function => self.key.to_uppercase(),
+            _ => self.key.clone(),
+        }
+    }
+}
+
+fn key_center(k: &GeometryKey) -> (f64, f64) {
+    (k.x + k.width / 2.0, k.y + k.height / 2.0)
+}
+
+fn is_bound_in(kb: &Keybind, submap: Option<&str>) -> bool {
+    let kb_submap = kb
+        .submap
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("reset"));
+    kb_submap == submap
+}
+
+/// Lists every key on the given geometry that is unbound for the chosen
+/// modifiers and submap, easiest to reach first.
+///
+/// `resolve` maps a physical key to its label and normalized key name (the
+/// keyboard view resolves these through XKB so non-QWERTY layouts are right).
+pub fn find_free_keys(
+    keybinds: &[Keybind],
+    geometry: &KeyboardGeometry,
+    opts: &FreeKeyOptions,
+    resolve: impl Fn(&GeometryKey) -> (String, String),
+) -> Vec<FreeKey> {
+    let (target_mods, _) = normalize(&opts.mods, "");
+    let submap = opts
+        .submap
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("reset"));
+
+    let occupied: HashSet<String> = keybinds
+        .iter()
+        .filter(|kb| is_bound_in(kb, submap))
+        .filter_map(|kb| {
+            let (mods, key) = normalize(&kb.clean_mods, &kb.key);
+            (mods == target_mods).then_some(key)
+        })
+        .collect();
+
+    let home: Vec<&GeometryKey> = geometry
+        .keys
+        .iter()
+        .filter(|k| HOME_KEYCODES.contains(&k.keycode))
+        .collect();
+    let home_row_y = home.first().map(|k| k.y);
+
+    let mut seen = HashSet::new();
+    let mut free: Vec<FreeKey> = Vec::new();
+
+    for k in &geometry.keys {
+        if k.keycode == 0 {
+            continue;
+        }
+        let (label, key) = resolve(k);
+        if key.is_empty() || MODIFIER_KEYS.contains(&key.as_str()) || occupied.contains(&key) {
+            continue;
+        }
+
+        let class = KeyClass::of(&key);
+        if !opts.allows(class) {
+            continue;
+        }
+
+        if opts.near_home_row {
+            match home_row_y {
+                Some(y) if (k.y - y).abs() <= 1.0 + f64::EPSILON => {}
+                _ => continue,
+            }
+        }
+
+        if !seen.insert(key.clone()) {
+            continue;
+        }
+
+        let (cx, cy) = key_center(k);
+        let distance = home
+            .iter()
+            .map(|h| {
+                let (hx, hy) = key_center(h);
+                ((cx - hx).powi(2) + (cy - hy).powi(2)).sqrt()
+            })
+            .fold(f64::INFINITY, f64::min);
+
+        free.push(FreeKey {
+            label,
+            key,
+            class,
+            distance: if distance.is_finite() { distance } else { 0.0 },
+        });
+    }
+
+    free.sort_by(|a, b| {
+        a.distance
+            .total_cmp(&b.distance)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    free
+}
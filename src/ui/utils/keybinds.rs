@@ -1,5 +1,6 @@
-use crate::config::favorites::{is_favorite, load_favorites};
+use crate::config::favorites::{load_metadata, BindId};
 use crate::keybind_object::KeybindObject;
+use crate::ui::utils::apps::{app_problem, get_desktop_entries, link_exec_bind, AppInfo};
 use crate::ui::utils::execution::command_exists;
 use gtk::gio;
 use gtk::glib;
@@ -117,13 +118,28 @@ pub fn detect_conflicts(keybinds: &[crate::parser::Keybind]) -> Vec<Option<Strin
     results
 }
 
-fn detect_broken(keybinds: &[crate::parser::Keybind]) -> Vec<Option<String>> {
+/// Why each exec bind is broken, if it is.
+pub fn detect_broken(
+    keybinds: &[crate::parser::Keybind],
+    desktop_entries: &[AppInfo],
+) -> Vec<Option<String>> {
+    let scripts_dir = crate::ui::utils::scripts::scripts_dir().ok();
+
     keybinds
         .iter()
         .map(|kb| {
             let disp = kb.dispatcher.to_lowercase();
             if disp == "exec" || disp == "execr" {
                 let cmd = kb.args.trim();
+                if let Some(reason) = scripts_dir
+                    .as_deref()
+                    .and_then(|dir| crate::ui::utils::scripts::script_problem(dir, cmd))
+                {
+                    return Some(reason);
+                }
+                if let Some(reason) = app_problem(cmd, desktop_entries) {
+                    return Some(reason);
+                }
                 if !cmd.is_empty() && !command_exists(cmd) {
                     return Some(format!(
                         "Executable not found: {}",
@@ -136,15 +152,42 @@ fn detect_broken(keybinds: &[crate::parser::Keybind]) -> Vec<Option<String>> {
         .collect()
 }
 
+/// Name and icon of the application each exec bind launches.
+fn detect_apps(
+    keybinds: &[crate::parser::Keybind],
+    desktop_entries: &[AppInfo],
+) -> Vec<Option<(String, Option<String>)>> {
+    keybinds
+        .iter()
+        .map(|kb| {
+            let disp = kb.dispatcher.to_lowercase();
+            if disp != "exec" && disp != "execr" {
+                return None;
+            }
+            let link = link_exec_bind(&kb.args, desktop_entries)?;
+            Some((link.display_name(), link.app.icon.clone()))
+        })
+        .collect()
+}
+
 static RELOAD_GENERATION: AtomicU64 = AtomicU64::new(0);
 
 type ReloadData = (
     Vec<crate::parser::Keybind>,
     Vec<Option<String>>,
     Vec<Option<String>>,
+    Vec<Option<(String, Option<String>)>>,
     u64, // Generation ID
 );
 
+/// Snapshot of the keybinds currently in the model, in model order.
+pub fn model_keybinds(model: &gio::ListStore) -> Vec<crate::parser::Keybind> {
+    (0..model.n_items())
+        .filter_map(|i| model.item(i).and_downcast::<KeybindObject>())
+        .map(|obj| obj.to_keybind())
+        .collect()
+}
+
 pub fn reload_keybinds(model: &gio::ListStore) {
     // Revert to polling loop because MainContext::channel is not available in current re-export
     let (tx, rx) = std::sync::mpsc::channel::<ReloadData>();
@@ -155,20 +198,22 @@ pub fn reload_keybinds(model: &gio::ListStore) {
         crate::ui::utils::execution::invalidate_command_cache();
 
         let keybinds = crate::parser::parse_config().unwrap_or_else(|err| {
-            eprintln!("Error parsing config: {}", err);
+            crate::log_error!("parser", "Error parsing config: {}", err);
             vec![]
         });
 
+        let desktop_entries = get_desktop_entries();
         let conflicts = detect_conflicts(&keybinds);
-        let broken = detect_broken(&keybinds);
+        let broken = detect_broken(&keybinds, &desktop_entries);
+        let apps = detect_apps(&keybinds, &desktop_entries);
 
-        let _ = tx.send((keybinds, conflicts, broken, gen));
+        let _ = tx.send((keybinds, conflicts, broken, apps, gen));
     });
 
     let model = model.clone();
     glib::timeout_add_local(std::time::Duration::from_millis(10), move || {
         match rx.try_recv() {
-            Ok((keybinds, conflicts, broken, result_gen)) => {
+            Ok((keybinds, conflicts, broken, apps, result_gen)) => {
                 // Check if this is the latest requested generation
                 if result_gen < RELOAD_GENERATION.load(Ordering::SeqCst) {
                     return glib::ControlFlow::Break;
@@ -206,21 +251,12 @@ pub fn reload_keybinds(model: &gio::ListStore) {
 
                 let mut pool = StringPool::new();
                 let mut new_objects = Vec::with_capacity(keybinds.len());
-                let favs = load_favorites();
+                let metadata = load_metadata();
 
-                for ((kb, conflict), is_broken) in keybinds
-                    .into_iter()
-                    .zip(conflicts.into_iter())
-                    .zip(broken.into_iter())
+                for (((kb, conflict), is_broken), app) in
+                    keybinds.into_iter().zip(conflicts).zip(broken).zip(apps)
                 {
-                    let is_fav = is_favorite(
-                        &favs,
-                        &kb.clean_mods,
-                        &kb.key,
-                        kb.submap.as_deref().unwrap_or(""),
-                        &kb.dispatcher,
-                        &kb.args,
-                    );
+                    let is_fav = metadata.is_favorite(&BindId::of(&kb));
 
                     let kb_flags = kb.flags.clone();
 
@@ -276,7 +312,7 @@ pub fn reload_keybinds(model: &gio::ListStore) {
                         }
                     });
 
-                    new_objects.push(KeybindObject::new(
+                    let obj = KeybindObject::new(
                         crate::parser::Keybind {
                             mods: mods.clone(),
                             clean_mods: clean_mods.clone(),
@@ -299,7 +335,14 @@ pub fn reload_keybinds(model: &gio::ListStore) {
                         args_lower,
                         description_lower,
                         kb_flags,
-                    ));
+                    );
+                    if let Some((name, icon)) = app {
+                        obj.set_app(
+                            Some(pool.intern(name.into())),
+                            icon.map(|i| pool.intern(i.into())),
+                        );
+                    }
+                    new_objects.push(obj);
                 }
 
                 model.splice(0, n_items, &new_objects);
@@ -0,0 +1,134 @@
+use crate::parser::Keybind;
+use crate::ui::utils::keybinds::normalize;
+use std::collections::BTreeMap;
+
+/// Display order of modifiers in layer titles.
+const MOD_ORDER: &[&str] = &["SUPER", "SHIFT", "CTRL", "ALT"];
+
+/// What a single key does on a layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerAction {
+    pub dispatcher: String,
+    pub args: String,
+    pub description: Option<String>,
+}
+
+impl LayerAction {
+    /// The dispatcher with its arguments, e.g. `workspace 1`.
+    pub fn action_text(&self) -> String {
+        if self.args.is_empty() {
+            self.dispatcher.clone()
+        } else {
+            format!("{} {}", self.dispatcher, self.args)
+        }
+    }
+
+    /// The description when one is set, otherwise the action.
+    pub fn label(&self, prefer_description: bool) -> String {
+        match &self.description {
+            Some(desc) if prefer_description => desc.clone(),
+            _ => self.action_text(),
+        }
+    }
+}
+
+/// All binds sharing one modifier combination inside one submap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyLayer {
+    /// `None` for the global (root) map.
+    pub submap: Option<String>,
+    /// Normalized modifiers as returned by `normalize` (e.g. `SHIFT SUPER`).
+    pub mods: String,
+    /// Normalized key name -> actions bound to it (more than one on conflicts).
+    pub keys: BTreeMap<String, Vec<LayerAction>>,
+}
+
+impl KeyLayer {
+    /// Human-readable modifier combo, e.g. `SUPER + SHIFT`.
+    pub fn mods_title(&self) -> String {
+        if self.mods.is_empty() {
+            return "No Modifier".to_string();
+        }
+        let mut mods: Vec<&str> = self.mods.split(' ').collect();
+        mods.sort_by_key(|m| mod_rank(m));
+        mods.join(" + ")
+    }
+
+    pub fn title(&self) -> String {
+        match &self.submap {
+            Some(submap) => format!("{} — submap {}", self.mods_title(), submap),
+            None => self.mods_title(),
+        }
+    }
+}
+
+fn mod_rank(m: &str) -> usize {
+    MOD_ORDER
+        .iter()
+        .position(|known| *known == m)
+        .unwrap_or(MOD_ORDER.len())
+}
+
+/// Groups keybinds into one layer per (submap, modifier combination).
+///
+/// The global map comes first, then submaps alphabetically. Within a submap,
+/// layers are ordered by number of modifiers and then SUPER, SHIFT, CTRL, ALT,
+/// so `SUPER` precedes `SUPER + SHIFT`. Binds are kept in definition order.
+pub fn build_layers(keybinds: &[Keybind]) -> Vec<KeyLayer> {
+    let mut grouped: BTreeMap<(Option<String>, String), BTreeMap<String, Vec<LayerAction>>> =
+        BTreeMap::new();
+
+    for kb in keybinds {
+        let (mods, key) = normalize(&kb.clean_mods, &kb.key);
+        if key.is_empty() {
+            continue;
+        }
+
+        let submap = kb
+            .submap
+            .as_deref()
+            .filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("reset"))
+            .map(str::to_string);
+
+        grouped
+            .entry((submap, mods))
+            .or_default()
+            .entry(key)
+            .or_default()
+            .push(LayerAction {
+                dispatcher: kb.dispatcher.to_string(),
+                args: kb.args.to_string(),
+                description: kb
+                    .description
+                    .as_deref()
+                    .filter(|d| !d.is_empty())
+                    .map(str::to_string),
+            });
+    }
+
+    let mut layers: Vec<KeyLayer> = grouped
+        .into_iter()
+        .map(|((submap, mods), keys)| KeyLayer { submap, mods, keys })
+        .collect();
+
+    layers.sort_by(|a, b| {
+        let rank = |l: &KeyLayer| {
+            let mods: Vec<usize> = if l.mods.is_empty() {
+                Vec::new()
+            } else {
+                let mut ranks: Vec<usize> = l.mods.split(' ').map(mod_rank).collect();
+                ranks.sort_unstable();
+                ranks
+            };
+            (mods.len(), mods)
+        };
+        a.submap
+            .is_some()
+            .cmp(&b.submap.is_some())
+            .then_with(|| a.submap.cmp(&b.submap))
+            .then_with(|| rank(a).cmp(&rank(b)))
+            .then_with(|| a.mods.cmp(&b.mods))
+    });
+
+    layers
+}
@@ -1,122 +1,620 @@
 use crate::ui::utils::setup_dispatcher_completion;
+use anyhow::{bail, Result};
 use gtk::prelude::*;
 use gtk4 as gtk;
 
-pub fn create_macro_row(
-    dispatcher_val: Option<&str>,
-    args_val: Option<&str>,
-) -> (gtk::Box, gtk::Entry, gtk::Entry, gtk::Button) {
-    let row = gtk::Box::builder()
-        .orientation(gtk::Orientation::Horizontal)
-        .spacing(8)
-        .build();
+/// One action of a macro keybind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacroStep {
+    /// `hyprctl dispatch <dispatcher> <args>`
+    Dispatch { dispatcher: String, args: String },
+    /// `hyprctl keyword <name> <value>`
+    Keyword { name: String, value: String },
+    /// `sleep`, in milliseconds.
+    Delay(u32),
+    /// Any other shell command.
+    Shell(String),
+    /// Runs `step` only when the shell `condition` succeeds.
+    Conditional {
+        condition: String,
+        step: Box<MacroStep>,
+    },
+}
 
-    let disp_entry = gtk::Entry::builder()
-        .placeholder_text("Dispatcher")
-        .hexpand(true)
-        .text(dispatcher_val.unwrap_or(""))
-        .build();
-    setup_dispatcher_completion(&disp_entry);
+impl MacroStep {
+    /// The dispatcher and arguments of a `Dispatch` step.
+    pub fn as_dispatch(&self) -> Option<(&str, &str)> {
+        match self {
+            Self::Dispatch { dispatcher, args } => Some((dispatcher, args)),
+            _ => None,
+        }
+    }
 
-    let args_entry = gtk::Entry::builder()
-        .placeholder_text("Arguments")
-        .hexpand(true)
-        .text(args_val.unwrap_or(""))
-        .build();
+    /// Whether the step has a `#`, which the config parser would take as the
+    /// start of a comment and cut the compiled bind short.
+    pub fn has_comment(&self) -> bool {
+        match self {
+            Self::Dispatch {
+                dispatcher: first,
+                args: second,
+            }
+            | Self::Keyword {
+                name: first,
+                value: second,
+            } => first.contains('#') || second.contains('#'),
+            Self::Delay(_) => false,
+            Self::Shell(cmd) => cmd.contains('#'),
+            Self::Conditional { condition, step } => condition.contains('#') || step.has_comment(),
+        }
+    }
 
-    let delete_btn = gtk::Button::builder()
-        .icon_name("user-trash-symbolic")
-        .css_classes(["flat", "destructive-action"])
-        .tooltip_text("Remove Action")
-        .build();
+    fn uses_hyprctl(&self) -> bool {
+        match self {
+            Self::Dispatch { .. } | Self::Keyword { .. } => true,
+            Self::Conditional { step, .. } => step.uses_hyprctl(),
+            _ => false,
+        }
+    }
 
-    row.append(&disp_entry);
-    row.append(&args_entry);
-    row.append(&delete_btn);
+    /// Steps that can go into `hyprctl --batch`, which splits on `;`.
+    fn batch_command(&self) -> Option<String> {
+        let (verb, first, rest) = match self {
+            Self::Dispatch { dispatcher, args } => ("dispatch", dispatcher, args),
+            Self::Keyword { name, value } => ("keyword", name, value),
+            _ => return None,
+        };
+        if first.is_empty() || first.contains(char::is_whitespace) || rest.contains([';', '\n']) {
+            return None;
+        }
+        Some(if rest.is_empty() {
+            format!("{} {}", verb, first)
+        } else {
+            format!("{} {} {}", verb, first, rest)
+        })
+    }
+}
+
+/// Quotes a word for the shell, leaving simple words untouched.
+pub fn shell_quote(s: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-./:,+=@%^".contains(c);
+    if !s.is_empty() && s.chars().all(is_safe) {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A word with quotes removed, and its byte span in the source.
+    Word(String, usize, usize),
+    /// `;` or a newline.
+    Separator(usize),
+}
+
+/// Splits a shell command line into words and separators, honoring quotes and
+/// backslash escapes. Returns `None` for unterminated quotes.
+fn tokenize(script: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = script.char_indices().peekable();
+    let mut word: Option<(String, usize)> = None;
+
+    let finish = |word: &mut Option<(String, usize)>, tokens: &mut Vec<Token>, end: usize| {
+        if let Some((text, start)) = word.take() {
+            tokens.push(Token::Word(text, start, end));
+        }
+    };
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            ';' | '\n' => {
+                finish(&mut word, &mut tokens, i);
+                tokens.push(Token::Separator(i));
+            }
+            c if c.is_whitespace() => finish(&mut word, &mut tokens, i),
+            '\'' => {
+                let text = &mut word.get_or_insert_with(|| (String::new(), i)).0;
+                loop {
+                    match chars.next()? {
+                        (_, '\'') => break,
+                        (_, ch) => text.push(ch),
+                    }
+                }
+            }
+            '"' => {
+                let text = &mut word.get_or_insert_with(|| (String::new(), i)).0;
+                loop {
+                    match chars.next()? {
+                        (_, '"') => break,
+                        (_, '\\') => match chars.peek() {
+                            Some(&(_, next @ ('"' | '\\' | '$' | '`'))) => {
+                                text.push(next);
+                                chars.next();
+                            }
+                            _ => text.push('\\'),
+                        },
+                        (_, ch) => text.push(ch),
+                    }
+                }
+            }
+            '\\' => {
+                let text = &mut word.get_or_insert_with(|| (String::new(), i)).0;
+                if let Some((_, next)) = chars.next() {
+                    text.push(next);
+                }
+            }
+            c => word.get_or_insert_with(|| (String::new(), i)).0.push(c),
+        }
+    }
+    finish(&mut word, &mut tokens, script.len());
+    Some(tokens)
+}
 
-    (row, disp_entry, args_entry, delete_btn)
+/// The words of a shell command line with quotes removed, `None` for
+/// unterminated quotes.
+pub fn shell_words(script: &str) -> Option<Vec<String>> {
+    Some(
+        tokenize(script)?
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Word(word, _, _) => Some(word),
+                Token::Separator(_) => None,
+            })
+            .collect(),
+    )
 }
 
-pub fn compile_macro(container: &gtk::Box) -> Option<(String, String)> {
-    let mut commands = Vec::new();
+fn is_word(token: Option<&Token>, expected: &str) -> bool {
+    matches!(token, Some(Token::Word(w, _, _)) if w == expected)
+}
 
-    let mut child = container.first_child();
-    while let Some(widget) = child {
-        if let Some(box_widget) = widget.downcast_ref::<gtk::Box>() {
-            let mut iter = box_widget.first_child();
-            let disp_widget = iter.and_then(|w| w.downcast::<gtk::Entry>().ok());
-            iter = box_widget.first_child().and_then(|w| w.next_sibling());
-            let args_widget = iter.and_then(|w| w.downcast::<gtk::Entry>().ok());
+/// Parses the `;`-separated commands of a `hyprctl --batch` argument.
+fn parse_batch(batch: &str) -> Option<Vec<MacroStep>> {
+    batch
+        .split(';')
+        .map(str::trim)
+        .filter(|cmd| !cmd.is_empty())
+        .map(|cmd| {
+            let (verb, rest) = cmd.split_once(char::is_whitespace)?;
+            let rest = rest.trim_start();
+            let (first, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            let (first, value) = (first.to_string(), value.trim_start().to_string());
+            match verb {
+                "dispatch" => Some(MacroStep::Dispatch {
+                    dispatcher: first,
+                    args: value,
+                }),
+                "keyword" => Some(MacroStep::Keyword { name: first, value }),
+                _ => None,
+            }
+        })
+        .collect()
+}
 
-            if let (Some(d), Some(a)) = (disp_widget, args_widget) {
-                let d_text = d.text().to_string();
-                let a_text = a.text().to_string();
+/// Parses one statement starting at `tokens[pos]`. Returns the steps and the
+/// position after the statement's trailing separator.
+fn parse_statement(
+    script: &str,
+    tokens: &[Token],
+    mut pos: usize,
+) -> Option<(Vec<MacroStep>, usize)> {
+    let Some(Token::Word(first, start, _)) = tokens.get(pos) else {
+        return None;
+    };
 
-                if !d_text.trim().is_empty() {
-                    let cmd = if a_text.trim().is_empty() {
-                        format!("hyprctl dispatch {}", d_text.trim())
-                    } else {
-                        let escaped_args = a_text.replace('"', "\\\"");
-                        format!("hyprctl dispatch {} \"{}\"", d_text.trim(), escaped_args)
-                    };
-                    commands.push(cmd);
+    match first.as_str() {
+        "if" => {
+            // if COND; then STEP; fi
+            let cond_start = match tokens.get(pos + 1)? {
+                Token::Word(_, s, _) => *s,
+                Token::Separator(_) => return None,
+            };
+            pos += 1;
+            while let Some(Token::Word(..)) = tokens.get(pos) {
+                pos += 1;
+            }
+            let Token::Separator(cond_end) = tokens.get(pos)? else {
+                return None;
+            };
+            let condition = script[cond_start..*cond_end].trim().to_string();
+            if !is_word(tokens.get(pos + 1), "then") {
+                return None;
+            }
+            let (mut steps, next) = parse_statement(script, tokens, pos + 2)?;
+            if steps.len() != 1 || !is_word(tokens.get(next), "fi") {
+                return None;
+            }
+            let step = Box::new(steps.remove(0));
+            Some((
+                vec![MacroStep::Conditional { condition, step }],
+                skip_separator(tokens, next + 1)?,
+            ))
+        }
+        "{" => {
+            // { COMMANDS; } groups a shell step that contains separators
+            let inner_start = match tokens.get(pos) {
+                Some(Token::Word(_, _, end)) => *end,
+                _ => return None,
+            };
+            // Braces are only reserved words at the start of a command
+            let mut depth = 0;
+            let mut at_start = true;
+            let mut last_separator = None;
+            loop {
+                match tokens.get(pos)? {
+                    Token::Separator(s) => {
+                        last_separator = Some(*s);
+                        at_start = true;
+                    }
+                    Token::Word(w, _, _) if at_start && w == "{" => depth += 1,
+                    Token::Word(w, _, _) if at_start && w == "}" => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                        at_start = false;
+                    }
+                    Token::Word(..) => at_start = false,
+                }
+                pos += 1;
+            }
+            let inner_end = last_separator.filter(|s| *s > inner_start)?;
+            let command = script[inner_start..inner_end].trim().to_string();
+            Some((
+                vec![MacroStep::Shell(command)],
+                skip_separator(tokens, pos + 1)?,
+            ))
+        }
+        _ => {
+            let mut words = Vec::new();
+            let mut end = *start;
+            while let Some(Token::Word(w, _, e)) = tokens.get(pos) {
+                words.push(w.as_str());
+                end = *e;
+                pos += 1;
+            }
+            let raw = script[*start..end].trim().to_string();
+
+            let steps = match words.as_slice() {
+                ["hyprctl", "--batch", batch] => {
+                    parse_batch(batch).unwrap_or_else(|| vec![MacroStep::Shell(raw)])
+                }
+                ["hyprctl", "dispatch", dispatcher, args @ ..] => vec![MacroStep::Dispatch {
+                    dispatcher: dispatcher.to_string(),
+                    args: args.join(" "),
+                }],
+                ["hyprctl", "keyword", name, value @ ..] => vec![MacroStep::Keyword {
+                    name: name.to_string(),
+                    value: value.join(" "),
+                }],
+                ["sleep", secs] if secs.parse::<f64>().is_ok_and(|s| s >= 0.0) => {
+                    let ms = secs.parse::<f64>().unwrap_or(0.0) * 1000.0;
+                    vec![MacroStep::Delay(ms.round() as u32)]
                 }
+                _ => vec![MacroStep::Shell(raw)],
+            };
+            Some((steps, skip_separator(tokens, pos)?))
+        }
+    }
+}
+
+/// Moves past a separator at `pos`, if any. Anything else but the end of the
+/// script or a keyword closing an `if` is a parse error.
+fn skip_separator(tokens: &[Token], pos: usize) -> Option<usize> {
+    match tokens.get(pos) {
+        Some(Token::Separator(_)) => Some(pos + 1),
+        None => Some(pos),
+        Some(Token::Word(w, _, _)) if w == "fi" => Some(pos),
+        Some(Token::Word(..)) => None,
+    }
+}
+
+/// Parses a shell script into macro steps.
+fn parse_script(script: &str) -> Option<Vec<MacroStep>> {
+    let tokens = tokenize(script)?;
+    let mut steps = Vec::new();
+    let mut pos = 0;
+    while pos < tokens.len() {
+        if let Token::Separator(_) = tokens[pos] {
+            pos += 1;
+            continue;
+        }
+        let (parsed, next) = parse_statement(script, &tokens, pos)?;
+        if is_word(tokens.get(next), "fi") {
+            return None;
+        }
+        steps.extend(parsed);
+        pos = next;
+    }
+    Some(steps)
+}
+
+fn format_delay(ms: u32) -> String {
+    let secs = format!("{}.{:03}", ms / 1000, ms % 1000);
+    secs.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Compiles a single step as one shell statement (never batched).
+fn compile_statement(step: &MacroStep) -> String {
+    match step {
+        MacroStep::Dispatch { dispatcher, args } => {
+            let mut cmd = format!("hyprctl dispatch {}", shell_quote(dispatcher));
+            if !args.is_empty() {
+                cmd.push(' ');
+                cmd.push_str(&shell_quote(args));
             }
+            cmd
+        }
+        MacroStep::Keyword { name, value } => {
+            format!(
+                "hyprctl keyword {} {}",
+                shell_quote(name),
+                shell_quote(value)
+            )
+        }
+        MacroStep::Delay(ms) => format!("sleep {}", format_delay(*ms)),
+        MacroStep::Shell(cmd) => {
+            let cmd = cmd.trim();
+            // Group commands that would otherwise not parse back as one step
+            if parse_script(cmd).is_some_and(|s| s == [MacroStep::Shell(cmd.to_string())]) {
+                cmd.to_string()
+            } else {
+                format!("{{ {}; }}", cmd)
+            }
+        }
+        MacroStep::Conditional { condition, step } => {
+            format!(
+                "if {}; then {}; fi",
+                condition.trim(),
+                compile_statement(step)
+            )
         }
-        child = widget.next_sibling();
     }
+}
+
+/// Compiles macro steps into the dispatcher and arguments of an `exec` bind.
+///
+/// Consecutive dispatch and keyword steps are merged into one
+/// `hyprctl --batch` call. When that is all there is, the batch is the whole
+/// command; otherwise the steps are wrapped in `bash -c`.
+pub fn compile_steps(steps: &[MacroStep]) -> (String, String) {
+    let mut statements = Vec::new();
+    let mut batch: Vec<String> = Vec::new();
+
+    let flush = |batch: &mut Vec<String>, statements: &mut Vec<String>| {
+        if !batch.is_empty() {
+            statements.push(format!(
+                "hyprctl --batch {}",
+                shell_quote(&batch.join(" ; "))
+            ));
+            batch.clear();
+        }
+    };
 
-    if commands.is_empty() {
+    for step in steps {
+        match step.batch_command() {
+            Some(cmd) => batch.push(cmd),
+            None => {
+                flush(&mut batch, &mut statements);
+                statements.push(compile_statement(step));
+            }
+        }
+    }
+    flush(&mut batch, &mut statements);
+
+    let command = match statements.as_slice() {
+        [single] if single.starts_with("hyprctl --batch ") => single.clone(),
+        _ => format!("bash -c {}", shell_quote(&statements.join("; "))),
+    };
+    ("exec".to_string(), command)
+}
+
+/// The script inside a `bash -c '...'` or `sh -c '...'` command.
+pub fn unwrap_shell_script(args: &str) -> Option<String> {
+    let args = args.trim();
+    if !args.starts_with("bash -c ") && !args.starts_with("sh -c ") {
         return None;
     }
+    match tokenize(args)?.as_slice() {
+        [Token::Word(..), Token::Word(flag, _, _), Token::Word(script, _, _)] if flag == "-c" => {
+            Some(script.clone())
+        }
+        _ => None,
+    }
+}
 
-    let script = commands.join("; ");
-    Some(("exec".to_string(), format!("bash -c \"{}\"", script)))
+/// Writes macro steps as a script with one command per line.
+pub fn steps_to_script(steps: &[MacroStep]) -> String {
+    steps
+        .iter()
+        .map(compile_statement)
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-/// Tries to parse a `bash -c "hyprctl ..."` string back into a list of actions.
-pub fn parse_macro(dispatcher: &str, args: &str) -> Option<Vec<(String, String)>> {
+/// Tries to parse an `exec` bind back into macro steps. Accepts both
+/// `hyprctl --batch` commands and `bash -c` scripts that call `hyprctl`.
+pub fn parse_macro(dispatcher: &str, args: &str) -> Option<Vec<MacroStep>> {
     if dispatcher != "exec" && dispatcher != "execr" {
         return None;
     }
 
-    let trimmed_args = args.trim();
-    if !trimmed_args.starts_with("bash -c \"") || !trimmed_args.ends_with('"') {
+    let args = args.trim();
+    let steps = if args.starts_with("bash -c ") || args.starts_with("sh -c ") {
+        parse_script(&unwrap_shell_script(args)?)?
+    } else if args.starts_with("hyprctl ") {
+        parse_script(args)?
+    } else {
         return None;
+    };
+
+    // A script without any hyprctl call is just a command, not a macro
+    if steps.iter().any(MacroStep::uses_hyprctl) {
+        Some(steps)
+    } else {
+        None
     }
+}
 
-    // Extract inner content
-    let inner = &trimmed_args[9..trimmed_args.len() - 1]; // strip `bash -c "` and `"`
+const STEP_KINDS: &[&str] = &["Dispatch", "Keyword", "Delay (ms)", "Shell"];
 
-    let mut actions = Vec::new();
-    for part in inner.split(';') {
-        let part = part.trim();
-        if part.is_empty() {
-            continue;
-        }
+/// A macro row: step kind, one or two inputs, an optional condition and a
+/// delete button. `read_macro_row` depends on this child order.
+pub fn create_macro_row(step: Option<&MacroStep>) -> (gtk::Box, gtk::Button) {
+    let row = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(8)
+        .build();
 
-        if let Some(rest) = part.strip_prefix("hyprctl dispatch ") {
-            let rest = rest.trim();
-            let (disp, arg) = rest.split_once(' ').unwrap_or((rest, ""));
-            let arg = arg.trim();
+    let (condition, step) = match step {
+        Some(MacroStep::Conditional { condition, step }) => (condition.as_str(), Some(&**step)),
+        other => ("", other),
+    };
+    let (kind, first, second) = match step {
+        Some(MacroStep::Dispatch { dispatcher, args }) => (0, dispatcher.clone(), args.clone()),
+        Some(MacroStep::Keyword { name, value }) => (1, name.clone(), value.clone()),
+        Some(MacroStep::Delay(ms)) => (2, ms.to_string(), String::new()),
+        Some(MacroStep::Shell(cmd)) => (3, cmd.clone(), String::new()),
+        _ => (0, String::new(), String::new()),
+    };
 
-            // Unescape quotes if we added them: "arg" -> arg
-            let clean_arg = if arg.starts_with('"') && arg.ends_with('"') {
-                &arg[1..arg.len() - 1]
-            } else {
-                arg
-            };
+    let kind_dropdown = gtk::DropDown::from_strings(STEP_KINDS);
+    kind_dropdown.set_selected(kind);
 
-            actions.push((disp.to_string(), clean_arg.replace("\\\"", "\"")));
-        } else {
-            // Found something that isn't hyprctl dispatch -> abort parsing
-            return None;
+    let first_entry = gtk::Entry::builder().hexpand(true).text(&first).build();
+    setup_dispatcher_completion(&first_entry);
+
+    let second_entry = gtk::Entry::builder().hexpand(true).text(&second).build();
+
+    let condition_toggle = gtk::ToggleButton::builder()
+        .icon_name("dialog-question-symbolic")
+        .css_classes(["flat"])
+        .tooltip_text("Only run this action when a shell command succeeds")
+        .active(!condition.is_empty())
+        .build();
+    let condition_entry = gtk::Entry::builder()
+        .placeholder_text("Condition, e.g. pgrep waybar")
+        .text(condition)
+        .visible(!condition.is_empty())
+        .build();
+
+    let delete_btn = gtk::Button::builder()
+        .icon_name("user-trash-symbolic")
+        .css_classes(["flat", "destructive-action"])
+        .tooltip_text("Remove Action")
+        .build();
+
+    let update_kind = {
+        let first_entry = first_entry.clone();
+        let second_entry = second_entry.clone();
+        move |kind: u32| {
+            let (first, second) = match kind {
+                1 => ("Keyword, e.g. general:gaps_in", Some("Value")),
+                2 => ("Milliseconds", None),
+                3 => ("Command", None),
+                _ => ("Dispatcher", Some("Arguments")),
+            };
+            first_entry.set_placeholder_text(Some(first));
+            second_entry.set_placeholder_text(second);
+            second_entry.set_visible(second.is_some());
         }
+    };
+    update_kind(kind);
+    kind_dropdown.connect_selected_notify(move |d| update_kind(d.selected()));
+
+    let condition_entry_c = condition_entry.clone();
+    condition_toggle.connect_toggled(move |t| condition_entry_c.set_visible(t.is_active()));
+
+    row.append(&kind_dropdown);
+    row.append(&first_entry);
+    row.append(&second_entry);
+    row.append(&condition_toggle);
+    row.append(&condition_entry);
+    row.append(&delete_btn);
+
+    (row, delete_btn)
+}
+
+/// The step described by a macro row's fields: the step kind's index in the
+/// dropdown, its two entries and, for conditional steps, the condition.
+/// `None` for a row left empty, an error for a delay that isn't a number.
+pub fn step_from_fields(
+    kind: u32,
+    first: &str,
+    second: &str,
+    condition: Option<&str>,
+) -> Result<Option<MacroStep>> {
+    let first = first.trim();
+    if first.is_empty() {
+        return Ok(None);
     }
+    let step = match kind {
+        1 => MacroStep::Keyword {
+            name: first.to_string(),
+            value: second.trim().to_string(),
+        },
+        2 => match first.parse() {
+            Ok(ms) => MacroStep::Delay(ms),
+            Err(_) => bail!("Delay '{}' is not a number of milliseconds", first),
+        },
+        3 => MacroStep::Shell(first.to_string()),
+        _ => MacroStep::Dispatch {
+            dispatcher: first.to_string(),
+            args: second.trim().to_string(),
+        },
+    };
 
-    if actions.is_empty() {
-        None
-    } else {
-        Some(actions)
+    match condition.map(str::trim) {
+        Some(condition) if !condition.is_empty() => Ok(Some(MacroStep::Conditional {
+            condition: condition.to_string(),
+            step: Box::new(step),
+        })),
+        _ => Ok(Some(step)),
+    }
+}
+
+/// Reads the step a row created by `create_macro_row` describes.
+fn read_macro_row(row: &gtk::Box) -> Result<Option<MacroStep>> {
+    let mut children = std::iter::successors(row.first_child(), |w| w.next_sibling());
+    let mut next = || children.next();
+    let (Some(kind), Some(first), Some(second), Some(conditional), Some(condition)) =
+        (next(), next(), next(), next(), next())
+    else {
+        return Ok(None);
+    };
+    let (Ok(kind), Ok(first), Ok(second), Ok(conditional), Ok(condition)) = (
+        kind.downcast::<gtk::DropDown>(),
+        first.downcast::<gtk::Entry>(),
+        second.downcast::<gtk::Entry>(),
+        conditional.downcast::<gtk::ToggleButton>(),
+        condition.downcast::<gtk::Entry>(),
+    ) else {
+        return Ok(None);
+    };
+    let condition = condition.text();
+    step_from_fields(
+        kind.selected(),
+        &first.text(),
+        &second.text(),
+        conditional.is_active().then_some(condition.as_str()),
+    )
+}
+
+/// Compiles the macro rows in `container`. Fails when there are no steps, a
+/// delay isn't a number, or a step has a `#`, which can't be stored in a bind.
+pub fn compile_macro(container: &gtk::Box) -> Result<(String, String)> {
+    let mut steps = Vec::new();
+    for row in std::iter::successors(container.first_child(), |w| w.next_sibling())
+        .filter_map(|w| w.downcast::<gtk::Box>().ok())
+    {
+        steps.extend(read_macro_row(&row)?);
+    }
+
+    if steps.is_empty() {
+        bail!("Macro needs at least one action");
+    }
+    if steps.iter().any(MacroStep::has_comment) {
+        bail!("Macro actions can't contain #");
     }
+    Ok(compile_steps(&steps))
 }
@@ -1,13 +1,19 @@
 pub mod apps;
+pub mod arg_editor;
 pub mod backup;
 pub mod clone;
 pub mod components;
 pub mod conflicts;
+pub mod dry_run;
 pub mod execution;
 pub mod export;
+pub mod free_keys;
 pub mod keybinds;
+pub mod layers;
 pub mod macro_builder;
+pub mod scripts;
 pub mod search;
+pub mod submap_graph;
 pub mod widgets;
 
 pub use apps::*;
@@ -0,0 +1,243 @@
+use crate::config::constants::{HYPRKCS_DIR, SCRIPTS_DIR};
+use crate::parser::{get_config_path, Keybind};
+use crate::ui::utils::apps::split_exec_rules;
+use crate::ui::utils::macro_builder::{
+    parse_macro, shell_quote, shell_words, steps_to_script, unwrap_shell_script,
+};
+use anyhow::{Context, Result};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+const SCRIPT_HEADER: &str = "#!/usr/bin/env bash\n# Managed by hyprKCS\n";
+
+/// `scripts/hyprkcs/` next to the active Hyprland config.
+pub fn scripts_dir() -> Result<PathBuf> {
+    let config = get_config_path()?;
+    let hypr_dir = config
+        .parent()
+        .context("Config file has no parent directory")?;
+    Ok(hypr_dir.join(SCRIPTS_DIR).join(HYPRKCS_DIR))
+}
+
+/// Files in the script library, sorted by name.
+pub fn list_scripts(dir: &Path) -> Vec<PathBuf> {
+    let mut scripts: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    scripts.sort();
+    scripts
+}
+
+/// The script contents for an `exec` bind: macros get one step per line,
+/// `bash -c` wrappers are unwrapped and anything else is kept as is.
+pub fn script_body(dispatcher: &str, args: &str) -> String {
+    let (_, command) = split_exec_rules(args);
+    if let Some(steps) = parse_macro(dispatcher, command) {
+        steps_to_script(&steps)
+    } else if let Some(script) = unwrap_shell_script(command) {
+        script
+    } else {
+        command.to_string()
+    }
+}
+
+fn script_file_stem(name: &str) -> String {
+    let stem: String = name
+        .trim()
+        .trim_end_matches(".sh")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let stem = stem.trim_matches('-').to_string();
+    if stem.is_empty() {
+        "script".to_string()
+    } else {
+        stem
+    }
+}
+
+/// Writes an executable script into `path`, failing if it already exists.
+pub fn create_script(path: &Path, body: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut content = SCRIPT_HEADER.to_string();
+    content.push_str(body.trim_end());
+    content.push('\n');
+
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, content.as_bytes()))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path.display()))?;
+    Ok(())
+}
+
+/// Moves an `exec` command or macro into a new script in `dir`. Returns the
+/// script path and the arguments that make the bind call it instead. Exec
+/// rules stay on the bind.
+pub fn extract_to_script(
+    dir: &Path,
+    name: &str,
+    dispatcher: &str,
+    args: &str,
+) -> Result<(PathBuf, String)> {
+    let stem = script_file_stem(name);
+    let mut path = dir.join(format!("{}.sh", stem));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.sh", stem, n));
+        n += 1;
+    }
+
+    create_script(&path, &script_body(dispatcher, args))?;
+
+    let (rules, _) = split_exec_rules(args);
+    let call = script_call(&path);
+    let new_args = if rules.is_empty() {
+        call
+    } else {
+        format!("{} {}", rules, call)
+    };
+    Ok((path, new_args))
+}
+
+/// `path` as a bind's `exec` runs it: quoted for the shell, with the home
+/// directory as `"$HOME"`, which the shell still expands.
+pub fn script_call(path: &Path) -> String {
+    if let Some(home) = dirs::home_dir() {
+        if let Ok(rest) = path.strip_prefix(&home) {
+            return format!("\"$HOME\"/{}", shell_quote(&rest.to_string_lossy()));
+        }
+    }
+    shell_quote(&path.to_string_lossy())
+}
+
+/// Shortens paths under the home directory to `~/...`.
+pub fn display_path(path: &Path) -> String {
+    if let Some(home) = dirs::home_dir() {
+        if let Ok(rest) = path.strip_prefix(&home) {
+            return format!("~/{}", rest.display());
+        }
+    }
+    path.display().to_string()
+}
+
+fn expand_word(word: &str) -> Option<PathBuf> {
+    let home_rest = word
+        .strip_prefix("~/")
+        .or_else(|| word.strip_prefix("$HOME/"))
+        .or_else(|| word.strip_prefix("${HOME}/"));
+    match home_rest {
+        Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+        None if word.starts_with('/') => Some(PathBuf::from(word)),
+        None => None,
+    }
+}
+
+/// Paths inside `dir` that a bind's arguments refer to, quoted or not.
+pub fn referenced_scripts(dir: &Path, args: &str) -> Vec<PathBuf> {
+    // Quoted words for paths with spaces, and the raw pieces for paths
+    // inside `bash -c '...'` strings
+    let quoted = shell_words(args).unwrap_or_default();
+    let mut paths: Vec<PathBuf> = args
+        .split(|c: char| c.is_whitespace() || matches!(c, ';' | '\'' | '"' | '&' | '|'))
+        .chain(quoted.iter().map(String::as_str))
+        .filter_map(expand_word)
+        .filter(|path| path.starts_with(dir) && path != dir)
+        .collect();
+    paths.sort();
+    paths.dedup();
+    // A quoted `bash -c` string is one word; keep the script it starts with
+    let all = paths.clone();
+    paths.retain(|path| {
+        let path = path.to_string_lossy();
+        !all.iter().any(|other| {
+            path.strip_prefix(&*other.to_string_lossy())
+                .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+        })
+    });
+    paths
+}
+
+/// Why a bind calling a library script would fail, if it would.
+pub fn script_problem(dir: &Path, args: &str) -> Option<String> {
+    referenced_scripts(dir, args).into_iter().find_map(|path| {
+        let name = path.file_name()?.to_string_lossy().to_string();
+        match std::fs::metadata(&path) {
+            Err(_) => Some(format!("Script not found: {}", name)),
+            Ok(meta) if meta.permissions().mode() & 0o111 == 0 => {
+                Some(format!("Script is not executable: {}", name))
+            }
+            Ok(_) => None,
+        }
+    })
+}
+
+fn is_exec(kb: &Keybind) -> bool {
+    kb.dispatcher.eq_ignore_ascii_case("exec") || kb.dispatcher.eq_ignore_ascii_case("execr")
+}
+
+/// A library script and the indices of the binds that call it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptUsage {
+    pub path: PathBuf,
+    pub used_by: Vec<usize>,
+}
+
+impl ScriptUsage {
+    pub fn is_orphaned(&self) -> bool {
+        self.used_by.is_empty()
+    }
+}
+
+/// Every script in `dir` with the binds that use it.
+pub fn script_usage(dir: &Path, keybinds: &[Keybind]) -> Vec<ScriptUsage> {
+    let references: Vec<(usize, Vec<PathBuf>)> = keybinds
+        .iter()
+        .enumerate()
+        .filter(|(_, kb)| is_exec(kb))
+        .map(|(i, kb)| (i, referenced_scripts(dir, &kb.args)))
+        .collect();
+
+    list_scripts(dir)
+        .into_iter()
+        .map(|path| {
+            let used_by = references
+                .iter()
+                .filter(|(_, paths)| paths.contains(&path))
+                .map(|(i, _)| *i)
+                .collect();
+            ScriptUsage { path, used_by }
+        })
+        .collect()
+}
+
+/// Binds that call a library script which doesn't exist.
+pub fn missing_scripts(dir: &Path, keybinds: &[Keybind]) -> Vec<(usize, PathBuf)> {
+    keybinds
+        .iter()
+        .enumerate()
+        .filter(|(_, kb)| is_exec(kb))
+        .flat_map(|(i, kb)| {
+            referenced_scripts(dir, &kb.args)
+                .into_iter()
+                .filter(|path| !path.exists())
+                .map(move |path| (i, path))
+        })
+        .collect()
+}
@@ -1,48 +1,592 @@
+//! The search query language shared by the keybind list and `--search`.
+//!
+//! A query is a list of terms that all have to match. Terms can be joined
+//! with `OR`, grouped with parentheses and negated with a leading `-`:
+//!
+//! ```text
+//! mod:super -action:exec (key:q OR key:w) desc:"move window" args:/^\d+$/
+//! ```
+//!
+//! Values are matched case-insensitively as substrings, `"quoted phrases"`
+//! can contain spaces, and `/regex/` values are regular expressions. Bare
+//! words are looked up in the modifiers, key, dispatcher, arguments and
+//! description, falling back to a fuzzy match unless they are negated.
+
+use crate::parser::Keybind;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use gtk::glib;
+use gtk4 as gtk;
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Mods,
+    /// The modifiers with variables resolved; searched together with `Mods`.
+    CleanMods,
+    Key,
+    Action,
+    Args,
+    Description,
+    Flag,
+    Submap,
+    File,
+    Conflict,
+    Broken,
+    Favorite,
+}
+
+impl Field {
+    /// The field a `tag:` prefix refers to.
+    pub fn from_tag(tag: &str) -> Option<Field> {
+        Some(match tag.to_lowercase().as_str() {
+            "mod" | "mods" => Field::Mods,
+            "key" => Field::Key,
+            "act" | "action" | "disp" | "dispatcher" => Field::Action,
+            "arg" | "args" => Field::Args,
+            "desc" | "description" => Field::Description,
+            "flag" | "flags" => Field::Flag,
+            "submap" => Field::Submap,
+            "file" => Field::File,
+            "conflict" | "conflicts" => Field::Conflict,
+            "broken" => Field::Broken,
+            "fav" | "favorite" => Field::Favorite,
+            _ => return None,
+        })
+    }
+
+    /// Whether the field is a yes/no state rather than text.
+    pub fn is_state(self) -> bool {
+        matches!(self, Field::Conflict | Field::Broken | Field::Favorite)
+    }
+}
+
+/// Fields searched by bare words.
+const ANY_FIELDS: &[Field] = &[
+    Field::Mods,
+    Field::CleanMods,
+    Field::Key,
+    Field::Action,
+    Field::Args,
+    Field::Description,
+];
+
+/// Something a query can be matched against.
+pub trait SearchTarget {
+    /// The lowercased text of a field, empty when the bind doesn't have it.
+    fn text(&self, field: Field) -> Cow<'_, str>;
+    /// Conflict, broken and favorite state.
+    fn state(&self, field: Field) -> bool;
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// Lowercased substring.
+    Text(String),
+    Regex(glib::Regex),
+}
+
+impl Pattern {
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            Pattern::Text(needle) => text.contains(needle.as_str()),
+            Pattern::Regex(regex) => {
+                let text = glib::GString::from(text);
+                regex
+                    .match_(text.as_gstr(), glib::RegexMatchFlags::empty())
+                    .is_ok_and(|m| m.matches())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Term {
+    /// A bare word or phrase, searched in every text field.
+    Any {
+        pattern: Pattern,
+        fuzzy: bool,
+    },
+    Field {
+        field: Field,
+        pattern: Pattern,
+    },
+    State {
+        field: Field,
+        value: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum QueryNode {
+    Term { term: Term, negated: bool },
+    And(Vec<QueryNode>),
+    Or(Vec<QueryNode>),
+    Not(Box<QueryNode>),
+}
+
+impl QueryNode {
+    fn matches(&self, target: &impl SearchTarget, matcher: &impl FuzzyMatcher) -> bool {
+        match self {
+            QueryNode::Term { term, negated } => term.matches(target, matcher) != *negated,
+            QueryNode::And(nodes) => nodes.iter().all(|n| n.matches(target, matcher)),
+            QueryNode::Or(nodes) => nodes.iter().any(|n| n.matches(target, matcher)),
+            QueryNode::Not(node) => !node.matches(target, matcher),
+        }
+    }
+
+    fn visit_terms<'a>(&'a self, f: &mut impl FnMut(&'a Term)) {
+        match self {
+            QueryNode::Term { term, .. } => f(term),
+            QueryNode::And(nodes) | QueryNode::Or(nodes) => {
+                nodes.iter().for_each(|n| n.visit_terms(f))
+            }
+            QueryNode::Not(node) => node.visit_terms(f),
+        }
+    }
+}
+
+impl Term {
+    fn matches(&self, target: &impl SearchTarget, matcher: &impl FuzzyMatcher) -> bool {
+        match self {
+            Term::State { field, value } => target.state(*field) == *value,
+            Term::Field {
+                field: Field::Mods,
+                pattern,
+            } => {
+                pattern.is_match(&target.text(Field::Mods))
+                    || pattern.is_match(&target.text(Field::CleanMods))
+            }
+            Term::Field { field, pattern } => pattern.is_match(&target.text(*field)),
+            Term::Any { pattern, fuzzy } => {
+                if ANY_FIELDS
+                    .iter()
+                    .any(|f| pattern.is_match(&target.text(*f)))
+                {
+                    return true;
+                }
+                let Pattern::Text(needle) = pattern else {
+                    return false;
+                };
+                *fuzzy
+                    && ANY_FIELDS.iter().any(|f| {
+                        let text = target.text(*f);
+                        !text.is_empty() && matcher.fuzzy_match(&text, needle).is_some()
+                    })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct SearchQuery {
-    pub mods: Option<String>,
-    pub key: Option<String>,
-    pub action: Option<String>,
-    pub args: Option<String>,
-    pub description: Option<String>,
-    pub general_query: String,
+    /// `None` for an empty query, which matches everything.
+    pub root: Option<QueryNode>,
+    /// Problems found while parsing, e.g. invalid regular expressions.
+    pub errors: Vec<String>,
 }
 
 impl SearchQuery {
     pub fn parse(text: &str) -> Self {
-        let mut mods = None;
-        let mut key = None;
-        let mut action = None;
-        let mut args = None;
-        let mut description = None;
-        let mut general_terms = Vec::new();
-
-        for token in text.split_whitespace() {
-            let token_lower = token.to_lowercase();
-            if let Some((tag, value)) = token_lower.split_once(':') {
-                if value.is_empty() {
-                    general_terms.push(token_lower);
-                    continue;
-                }
-                match tag {
-                    "mod" | "mods" => mods = Some(value.to_string()),
-                    "key" => key = Some(value.to_string()),
-                    "act" | "action" | "disp" | "dispatcher" => action = Some(value.to_string()),
-                    "arg" | "args" => args = Some(value.to_string()),
-                    "desc" | "description" => description = Some(value.to_string()),
-                    _ => general_terms.push(token_lower),
+        let tokens = tokenize(text);
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            errors: Vec::new(),
+            negations: 0,
+        };
+        let root = parser.parse_or();
+        SearchQuery {
+            root,
+            errors: parser.errors,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn matches(&self, target: &impl SearchTarget, matcher: &impl FuzzyMatcher) -> bool {
+        self.root
+            .as_ref()
+            .is_none_or(|root| root.matches(target, matcher))
+    }
+
+    /// Like [`SearchQuery::matches`] without the fuzzy fallback for bare
+    /// words, for queries that define a category.
+    pub fn matches_exact(&self, target: &impl SearchTarget) -> bool {
+        self.matches(target, &NoFuzzy)
+    }
+
+    /// Every term of the query, in order.
+    pub fn terms(&self) -> Vec<&Term> {
+        let mut terms = Vec::new();
+        if let Some(root) = &self.root {
+            root.visit_terms(&mut |t| terms.push(t));
+        }
+        terms
+    }
+
+    /// Whether any term looks at `field`.
+    pub fn uses(&self, field: Field) -> bool {
+        self.terms().iter().any(|t| match t {
+            Term::Field { field: f, .. } | Term::State { field: f, .. } => *f == field,
+            Term::Any { .. } => false,
+        })
+    }
+}
+
+struct NoFuzzy;
+
+impl FuzzyMatcher for NoFuzzy {
+    fn fuzzy_indices(&self, _choice: &str, _pattern: &str) -> Option<(i64, Vec<usize>)> {
+        None
+    }
+}
+
+/// A parsed keybind together with the state shown next to it in the list.
+pub struct KeybindSearch<'a> {
+    pub keybind: &'a Keybind,
+    pub conflicted: bool,
+    pub broken: bool,
+    pub favorite: bool,
+}
+
+impl SearchTarget for KeybindSearch<'_> {
+    fn text(&self, field: Field) -> Cow<'_, str> {
+        let kb = self.keybind;
+        let text = match field {
+            Field::Mods => &kb.mods,
+            Field::CleanMods => &kb.clean_mods,
+            Field::Key => &kb.key,
+            Field::Action => &kb.dispatcher,
+            Field::Args => &kb.args,
+            Field::Description => {
+                return Cow::Owned(kb.description.as_deref().unwrap_or("").to_lowercase())
+            }
+            Field::Flag => &kb.flags,
+            Field::Submap => return Cow::Owned(kb.submap.as_deref().unwrap_or("").to_lowercase()),
+            Field::File => return Cow::Owned(kb.file_path.to_string_lossy().to_lowercase()),
+            Field::Conflict | Field::Broken | Field::Favorite => return Cow::Borrowed(""),
+        };
+        Cow::Owned(text.to_lowercase())
+    }
+
+    fn state(&self, field: Field) -> bool {
+        match field {
+            Field::Conflict => self.conflicted,
+            Field::Broken => self.broken,
+            Field::Favorite => self.favorite,
+            _ => false,
+        }
+    }
+}
+
+/// The keybinds matching `query`, for callers without a list model. Conflict,
+/// broken and favorite state is only worked out when the query asks for it.
+pub fn filter_keybinds(keybinds: Vec<Keybind>, query: &SearchQuery) -> Vec<Keybind> {
+    if query.is_empty() {
+        return keybinds;
+    }
+    let matcher = SkimMatcherV2::default();
+    let conflicts = query
+        .uses(Field::Conflict)
+        .then(|| super::keybinds::detect_conflicts(&keybinds));
+    let broken = query.uses(Field::Broken).then(|| {
+        let entries = super::apps::get_desktop_entries();
+        super::keybinds::detect_broken(&keybinds, &entries)
+    });
+    let metadata = query
+        .uses(Field::Favorite)
+        .then(crate::config::favorites::load_metadata);
+
+    keybinds
+        .iter()
+        .enumerate()
+        .filter(|(i, kb)| {
+            let target = KeybindSearch {
+                keybind: kb,
+                conflicted: conflicts.as_ref().is_some_and(|c| c[*i].is_some()),
+                broken: broken.as_ref().is_some_and(|b| b[*i].is_some()),
+                favorite: metadata
+                    .as_ref()
+                    .is_some_and(|m| m.is_favorite(&crate::config::favorites::BindId::of(kb))),
+            };
+            query.matches(&target, &matcher)
+        })
+        .map(|(_, kb)| kb.clone())
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Plain(String),
+    Quoted(String),
+    Regex(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open {
+        negated: bool,
+    },
+    Close,
+    Or,
+    Word {
+        negated: bool,
+        field: Option<Field>,
+        /// The token as typed, for error messages.
+        raw: String,
+        value: Value,
+    },
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+
+        // `-` negates when it's followed by something it can apply to, so
+        // arguments like `--private` stay plain words
+        let negated = chars[i] == '-'
+            && chars
+                .get(i + 1)
+                .is_some_and(|c| c.is_alphabetic() || matches!(c, '"' | '/' | '('));
+        if negated {
+            i += 1;
+        }
+
+        if chars[i] == '(' {
+            tokens.push(Token::Open { negated });
+            depth += 1;
+            i += 1;
+            continue;
+        }
+        if chars[i] == ')' && depth > 0 {
+            tokens.push(Token::Close);
+            depth -= 1;
+            i += 1;
+            continue;
+        }
+
+        // `tag:` prefix
+        let mut field = None;
+        let tag_end = (i..chars.len())
+            .find(|&j| !chars[j].is_ascii_alphabetic())
+            .unwrap_or(chars.len());
+        if tag_end > i && chars.get(tag_end) == Some(&':') {
+            let tag: String = chars[i..tag_end].iter().collect();
+            if let Some(f) = Field::from_tag(&tag) {
+                let has_value = chars
+                    .get(tag_end + 1)
+                    .is_some_and(|c| !c.is_whitespace() && *c != ')');
+                if has_value {
+                    field = Some(f);
+                    i = tag_end + 1;
                 }
+            }
+        }
+
+        let value = match chars[i] {
+            '"' => {
+                let end = (i + 1..chars.len())
+                    .find(|&j| chars[j] == '"')
+                    .unwrap_or(chars.len());
+                let phrase: String = chars[i + 1..end].iter().collect();
+                i = (end + 1).min(chars.len());
+                Value::Quoted(phrase)
+            }
+            '/' => {
+                let mut j = i + 1;
+                while j < chars.len() && chars[j] != '/' {
+                    if chars[j] == '\\' {
+                        j += 1;
+                    }
+                    j += 1;
+                }
+                let closed = j < chars.len()
+                    && chars
+                        .get(j + 1)
+                        .is_none_or(|c| c.is_whitespace() || (*c == ')' && depth > 0));
+                if closed && j > i + 1 {
+                    let pattern: String = chars[i + 1..j].iter().collect();
+                    i = j + 1;
+                    Value::Regex(pattern)
+                } else {
+                    let word = read_word(&chars, &mut i, depth);
+                    Value::Plain(word)
+                }
+            }
+            _ => Value::Plain(read_word(&chars, &mut i, depth)),
+        };
+
+        let raw: String = chars[start..i].iter().collect();
+        if field.is_none() && !negated && value == Value::Plain("OR".to_string()) {
+            tokens.push(Token::Or);
+        } else {
+            tokens.push(Token::Word {
+                negated,
+                field,
+                raw,
+                value,
+            });
+        }
+    }
+    tokens
+}
+
+/// Reads up to the next whitespace, or a `)` closing an open group.
+fn read_word(chars: &[char], i: &mut usize, depth: usize) -> String {
+    let start = *i;
+    while *i < chars.len() && !chars[*i].is_whitespace() && !(chars[*i] == ')' && depth > 0) {
+        *i += 1;
+    }
+    chars[start..*i].iter().collect()
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    errors: Vec<String>,
+    /// Negated groups around the current position.
+    negations: usize,
+}
+
+impl Parser {
+    fn parse_or(&mut self) -> Option<QueryNode> {
+        let mut alternatives = Vec::new();
+        loop {
+            if let Some(node) = self.parse_and() {
+                alternatives.push(node);
+            }
+            if self.tokens.get(self.pos) == Some(&Token::Or) {
+                self.pos += 1;
             } else {
-                general_terms.push(token_lower);
+                break;
             }
         }
+        match alternatives.len() {
+            0 => None,
+            1 => alternatives.pop(),
+            _ => Some(QueryNode::Or(alternatives)),
+        }
+    }
 
-        SearchQuery {
-            mods,
-            key,
-            action,
-            args,
-            description,
-            general_query: general_terms.join(" "),
+    fn parse_and(&mut self) -> Option<QueryNode> {
+        let mut nodes = Vec::new();
+        while let Some(token) = self.tokens.get(self.pos).cloned() {
+            match token {
+                Token::Or | Token::Close => break,
+                Token::Open { negated } => {
+                    self.pos += 1;
+                    self.negations += usize::from(negated);
+                    let inner = self.parse_or();
+                    self.negations -= usize::from(negated);
+                    if self.tokens.get(self.pos) == Some(&Token::Close) {
+                        self.pos += 1;
+                    }
+                    if let Some(inner) = inner {
+                        nodes.push(if negated {
+                            QueryNode::Not(Box::new(inner))
+                        } else {
+                            inner
+                        });
+                    }
+                }
+                Token::Word {
+                    negated,
+                    field,
+                    raw,
+                    value,
+                } => {
+                    self.pos += 1;
+                    let excluding = (self.negations + usize::from(negated)) % 2 == 1;
+                    if let Some(term) = self.make_term(field, &raw, value, excluding) {
+                        nodes.push(QueryNode::Term { term, negated });
+                    }
+                }
+            }
+        }
+        match nodes.len() {
+            0 => None,
+            1 => nodes.pop(),
+            _ => Some(QueryNode::And(nodes)),
+        }
+    }
+
+    /// `excluding` terms drop binds they match, so they get no fuzzy fallback:
+    /// a fuzzy `-exec` would drop nearly every bind.
+    fn make_term(
+        &mut self,
+        field: Option<Field>,
+        raw: &str,
+        value: Value,
+        excluding: bool,
+    ) -> Option<Term> {
+        if let Some(field) = field.filter(|f| f.is_state()) {
+            let text = match &value {
+                Value::Plain(s) | Value::Quoted(s) | Value::Regex(s) => s.to_lowercase(),
+            };
+            let state = match text.as_str() {
+                "yes" | "y" | "true" | "1" => true,
+                "no" | "n" | "false" | "0" => false,
+                _ => {
+                    self.errors.push(format!(
+                        "'{}' expects yes or no",
+                        raw.trim_start_matches('-')
+                    ));
+                    return None;
+                }
+            };
+            return Some(Term::State {
+                field,
+                value: state,
+            });
         }
+
+        let (pattern, fuzzy) = match value {
+            Value::Plain(s) => {
+                let s = s.to_lowercase();
+                // `flag:binde` means the same as `flag:e`
+                let s = match field {
+                    Some(Field::Flag) => s.strip_prefix("bind").unwrap_or(&s).to_string(),
+                    _ => s,
+                };
+                (Pattern::Text(s), !excluding)
+            }
+            Value::Quoted(s) => {
+                if s.is_empty() {
+                    return None;
+                }
+                (Pattern::Text(s.to_lowercase()), false)
+            }
+            Value::Regex(s) => {
+                match glib::Regex::new(
+                    &s,
+                    glib::RegexCompileFlags::CASELESS,
+                    glib::RegexMatchFlags::empty(),
+                ) {
+                    Ok(Some(regex)) => (Pattern::Regex(regex), false),
+                    Ok(None) | Err(_) => {
+                        self.errors
+                            .push(format!("Invalid regular expression /{}/", s));
+                        return None;
+                    }
+                }
+            }
+        };
+
+        Some(match field {
+            Some(field) => Term::Field { field, pattern },
+            None => Term::Any { pattern, fuzzy },
+        })
     }
 }
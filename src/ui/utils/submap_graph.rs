@@ -0,0 +1,142 @@
+use crate::parser::submaps::switches_to;
+use crate::parser::Keybind;
+use std::collections::{BTreeSet, VecDeque};
+
+/// A map binds can live in: the default (root) map or a submap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphNode {
+    /// `None` for the default map.
+    pub name: Option<String>,
+    /// Number of switches needed to get here from the default map (or the
+    /// default submap from the settings), `None` when no bind leads here.
+    pub depth: Option<usize>,
+    /// Has binds switching to it but none switching away again.
+    pub dead_end: bool,
+}
+
+impl GraphNode {
+    pub fn title(&self) -> &str {
+        self.name.as_deref().unwrap_or("Default")
+    }
+
+    pub fn is_reachable(&self) -> bool {
+        self.depth.is_some()
+    }
+}
+
+/// A bind switching from one map to another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphEdge {
+    /// Index of the source node in [`SubmapGraph::nodes`].
+    pub from: usize,
+    pub to: usize,
+    /// Index of the bind in the keybinds the graph was built from.
+    pub bind: usize,
+    /// Goes back to the default map through `submap, reset`.
+    pub is_reset: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SubmapGraph {
+    /// The default map first, then reachable submaps by depth and name,
+    /// then the unreachable ones.
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl SubmapGraph {
+    pub fn node(&self, name: Option<&str>) -> Option<&GraphNode> {
+        self.nodes.iter().find(|n| n.name.as_deref() == name)
+    }
+}
+
+/// Builds the flow between the default map and `submaps` (as collected from
+/// the model), adding any submap a bind switches to. `home` is the default
+/// submap from the settings, which counts as a starting point like the
+/// default map.
+pub fn build_submap_graph(
+    keybinds: &[Keybind],
+    submaps: &[String],
+    home: Option<&str>,
+) -> SubmapGraph {
+    let mut names: BTreeSet<&str> = submaps.iter().map(String::as_str).collect();
+    names.extend(
+        keybinds
+            .iter()
+            .filter_map(switches_to)
+            .filter(|t| !t.is_empty() && *t != "reset"),
+    );
+
+    let mut nodes: Vec<Option<String>> = vec![None];
+    nodes.extend(names.into_iter().map(|n| Some(n.to_string())));
+    let index_of = |name: Option<&str>| nodes.iter().position(|n| n.as_deref() == name);
+
+    let mut edges = Vec::new();
+    for (bind, kb) in keybinds.iter().enumerate() {
+        let Some(target) = switches_to(kb).filter(|t| !t.is_empty()) else {
+            continue;
+        };
+        let is_reset = target == "reset";
+        let (Some(from), Some(to)) = (
+            index_of(kb.submap.as_deref()),
+            index_of((!is_reset).then_some(target)),
+        ) else {
+            continue;
+        };
+        if from != to {
+            edges.push(GraphEdge {
+                from,
+                to,
+                bind,
+                is_reset,
+            });
+        }
+    }
+
+    // Breadth-first from the starting points
+    let mut depth: Vec<Option<usize>> = vec![None; nodes.len()];
+    let mut queue = VecDeque::new();
+    for start in [Some(0), home.and_then(|h| index_of(Some(h)))]
+        .into_iter()
+        .flatten()
+    {
+        depth[start] = Some(0);
+        queue.push_back(start);
+    }
+    while let Some(node) = queue.pop_front() {
+        for edge in edges.iter().filter(|e| e.from == node) {
+            if depth[edge.to].is_none() {
+                depth[edge.to] = Some(depth[node].unwrap_or(0) + 1);
+                queue.push_back(edge.to);
+            }
+        }
+    }
+
+    let graph_nodes: Vec<GraphNode> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, name)| GraphNode {
+            name: name.clone(),
+            depth: depth[i],
+            dead_end: depth[i] != Some(0)
+                && edges.iter().any(|e| e.to == i)
+                && !edges.iter().any(|e| e.from == i),
+        })
+        .collect();
+
+    // Sort the nodes and point the edges at their new positions
+    let mut order: Vec<usize> = (0..graph_nodes.len()).collect();
+    order.sort_by_key(|&i| (i != 0, depth[i].unwrap_or(usize::MAX), nodes[i].clone()));
+    let mut position = vec![0; order.len()];
+    for (new, &old) in order.iter().enumerate() {
+        position[old] = new;
+    }
+    for edge in &mut edges {
+        edge.from = position[edge.from];
+        edge.to = position[edge.to];
+    }
+    SubmapGraph {
+        nodes: order.iter().map(|&i| graph_nodes[i].clone()).collect(),
+        edges,
+    }
+}
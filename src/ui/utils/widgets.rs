@@ -4,61 +4,9 @@ use gtk4 as gtk;
 
 #[allow(deprecated)]
 pub fn setup_dispatcher_completion(entry: &gtk::Entry) {
-    let dispatchers = [
-        "exec",
-        "execr",
-        "pass",
-        "killactive",
-        "closewindow",
-        "workspace",
-        "movetoworkspace",
-        "movetoworkspacesilent",
-        "togglefloating",
-        "fullscreen",
-        "fakefullscreen",
-        "dpms",
-        "pin",
-        "movefocus",
-        "movewindow",
-        "centerwindow",
-        "resizeactive",
-        "moveactive",
-        "cyclenext",
-        "swapnext",
-        "focuswindow",
-        "focusmonitor",
-        "splitratio",
-        "toggleopaque",
-        "movecursortocorner",
-        "workspaceopt",
-        "exit",
-        "forcerendererreload",
-        "movecurrentworkspacetomonitor",
-        "focusworkspaceoncurrentmonitor",
-        "togglespecialworkspace",
-        "focusurgentorlast",
-        "togglegroup",
-        "changegroupactive",
-        "swapprev",
-        "focuscurrentorlast",
-        "lockgroups",
-        "lockactivegroup",
-        "moveintogroup",
-        "moveoutofgroup",
-        "movewindoworgroup",
-        "movegroupwindow",
-        "denywindowfromgroup",
-        "setignoregrouplock",
-        "alterzorder",
-        "tag",
-        "layoutmsg",
-        "sendshortcut",
-        "sendkeystate",
-    ];
-
     let list_store = gtk::ListStore::new(&[glib::Type::STRING]);
-    for dispatcher in dispatchers {
-        list_store.set(&list_store.append(), &[(0, &dispatcher)]);
+    for dispatcher in crate::parser::dispatchers::DISPATCHERS {
+        list_store.set(&list_store.append(), &[(0, &dispatcher.name)]);
     }
 
     let completion = gtk::EntryCompletion::builder()
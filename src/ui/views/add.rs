@@ -1,13 +1,16 @@
 use crate::parser;
+use crate::parser::dispatchers::validate_dispatcher;
+use crate::ui::utils::arg_editor::create_args_editor;
 use crate::ui::utils::components::{
     create_flags_dropdown, create_mouse_button_dropdown, create_recorder_row, get_flag_from_index,
     get_mouse_code_from_index,
 };
 use crate::ui::utils::conflicts::{check_conflict, generate_suggestions};
+use crate::ui::utils::dry_run::create_dry_run_panel;
 use crate::ui::utils::macro_builder::{compile_macro, create_macro_row};
 use crate::ui::utils::{
     command_exists, create_destructive_button, create_form_group, create_page_header,
-    create_pill_button, create_suggested_button, execute_keybind, perform_backup, reload_keybinds,
+    create_pill_button, create_suggested_button, perform_backup, reload_keybinds,
     setup_dispatcher_completion,
 };
 use gtk::glib;
@@ -21,6 +24,7 @@ pub fn create_add_view(
     model: &gio::ListStore,
     toast_overlay: &adw::ToastOverlay,
     default_submap: Option<&str>,
+    initial_combo: Option<(&str, &str)>,
 ) -> gtk::Widget {
     let local_stack = gtk::Stack::builder()
         .transition_type(gtk::StackTransitionType::SlideLeftRight)
@@ -74,6 +78,11 @@ pub fn create_add_view(
         .build();
     crate::ui::utils::setup_key_completion(&entry_key);
 
+    if let Some((mods, key)) = initial_combo {
+        entry_mods.set_text(mods);
+        entry_key.set_text(key);
+    }
+
     let mouse_dropdown = create_mouse_button_dropdown();
     mouse_dropdown.set_visible(false);
 
@@ -138,6 +147,7 @@ pub fn create_add_view(
         .build();
     crate::ui::utils::setup_app_completion(&entry_dispatcher, &entry_args);
     simple_container.append(&create_form_group("Arguments:", &entry_args));
+    simple_container.append(&create_args_editor(&entry_dispatcher, &entry_args));
 
     form_box.append(&simple_container);
 
@@ -162,7 +172,7 @@ pub fn create_add_view(
     // Logic to add rows
     let macro_list_c = macro_list.clone();
     add_action_btn.connect_clicked(move |_| {
-        let (row, _, _, del_btn) = create_macro_row(None, None);
+        let (row, del_btn) = create_macro_row(None);
         let list_c = macro_list_c.clone();
         let list_c_del = list_c.clone(); // Clone for closure
         let row_c = row.clone();
@@ -189,6 +199,24 @@ pub fn create_add_view(
     let entry_submap = crate::ui::utils::components::create_submap_combo(model, default_submap);
     form_box.append(&create_form_group("Submap (Optional):", &entry_submap));
 
+    // Destination: "Automatic" follows the routing rules, falling back to the
+    // main config; the other entries are the currently loaded files.
+    let mut dest_files = parser::get_loaded_files().unwrap_or_default();
+    let main_config = parser::get_config_path().ok();
+    dest_files.sort_by_key(|p| (Some(p) != main_config.as_ref(), p.clone()));
+    let mut dest_labels = vec!["Automatic (routing rules)".to_string()];
+    dest_labels.extend(
+        dest_files
+            .iter()
+            .map(|p| crate::ui::utils::scripts::display_path(p)),
+    );
+    let dest_label_refs: Vec<&str> = dest_labels.iter().map(|s| s.as_str()).collect();
+    let dest_dropdown = gtk::DropDown::from_strings(&dest_label_refs);
+    dest_dropdown.set_tooltip_text(Some(
+        "File the bind is written to. Automatic uses the rules under Settings > Bind Routing.",
+    ));
+    form_box.append(&create_form_group("Destination File:", &dest_dropdown));
+
     let entry_desc = gtk::Entry::builder()
         .placeholder_text("Comment appended to the config line")
         .activates_default(true)
@@ -204,7 +232,9 @@ pub fn create_add_view(
 
     let cancel_btn = create_pill_button("Cancel", None);
     let exec_btn = create_pill_button("Execute", None);
-    exec_btn.set_tooltip_text(Some("Test this keybind immediately using hyprctl dispatch"));
+    exec_btn.set_tooltip_text(Some(
+        "Preview this keybind and test it with hyprctl dispatch",
+    ));
     let add_btn = create_suggested_button("Add Keybind", None);
 
     button_box.append(&cancel_btn);
@@ -261,6 +291,16 @@ pub fn create_add_view(
     let conflict_panel = create_conflict_panel("Add Anyway");
     local_stack.add_named(&conflict_panel.container, Some("conflict"));
 
+    // --- Dry Run View Construction ---
+    let dry_run_panel = Rc::new(create_dry_run_panel(toast_overlay));
+    local_stack.add_named(&dry_run_panel.container, Some("dryrun"));
+    let local_stack_weak = local_stack.downgrade();
+    dry_run_panel.back_btn.connect_clicked(move |_| {
+        if let Some(ls) = local_stack_weak.upgrade() {
+            ls.set_visible_child_name("form");
+        }
+    });
+
     // --- Logic ---
 
     let entry_dispatcher_exec = entry_dispatcher.clone();
@@ -268,9 +308,11 @@ pub fn create_add_view(
     let macro_switch_exec = macro_switch.clone();
     let macro_list_exec = macro_list.clone();
 
+    let dry_run_panel_c = dry_run_panel.clone();
+    let local_stack_exec = local_stack.clone();
     exec_btn.connect_clicked(move |_| {
         let (dispatcher, args) = if macro_switch_exec.is_active() {
-            if let Some((d, a)) = compile_macro(&macro_list_exec) {
+            if let Ok((d, a)) = compile_macro(&macro_list_exec) {
                 (d, a)
             } else {
                 return;
@@ -283,7 +325,8 @@ pub fn create_add_view(
         };
 
         if !dispatcher.trim().is_empty() {
-            execute_keybind(&dispatcher, &args);
+            dry_run_panel_c.show(&dispatcher, &args);
+            local_stack_exec.set_visible_child_name("dryrun");
         }
     });
 
@@ -321,6 +364,7 @@ pub fn create_add_view(
     let flags_dropdown_c = flags_dropdown.clone();
     let mouse_switch_c = mouse_switch.clone();
     let mouse_dropdown_c = mouse_dropdown.clone();
+    let dest_dropdown_c = dest_dropdown.clone();
     let stack_weak = stack.downgrade();
 
     // Core Add Logic
@@ -338,10 +382,10 @@ pub fn create_add_view(
         // Determine Dispatcher/Args based on mode
         let (dispatcher, args) = if macro_switch_c.is_active() {
             match compile_macro(&macro_list_c) {
-                Some(res) => res,
-                None => {
+                Ok(res) => res,
+                Err(e) => {
                     let toast = adw::Toast::builder()
-                        .title("Macro is empty or invalid")
+                        .title(e.to_string())
                         .timeout(crate::config::constants::TOAST_TIMEOUT)
                         .build();
                     toast_overlay_clone.add_toast(toast);
@@ -381,8 +425,27 @@ pub fn create_add_view(
         };
 
         let config_path = parser::get_config_path().unwrap();
+        let destination = match dest_dropdown_c.selected() {
+            0 | gtk::INVALID_LIST_POSITION => crate::config::routing::destination_for(
+                &dispatcher,
+                &args,
+                &key,
+                submap.as_deref(),
+                config_path,
+            ),
+            i => dest_files[i as usize - 1].clone(),
+        };
+        if let Err(e) = parser::ensure_sourced(&destination) {
+            let toast = adw::Toast::builder()
+                .title(format!("Error: {}", e))
+                .timeout(crate::config::constants::TOAST_TIMEOUT)
+                .build();
+            toast_overlay_clone.add_toast(toast);
+            return;
+        }
+
         match parser::add_keybind(
-            config_path.clone(),
+            destination,
             &mods,
             &key,
             &dispatcher,
@@ -395,7 +458,7 @@ pub fn create_add_view(
                 reload_keybinds(&model_clone);
 
                 if let Err(e) = perform_backup(false) {
-                    eprintln!("Auto-backup failed: {}", e);
+                    crate::log_error!("backup", "Auto-backup failed: {}", e);
                 }
 
                 let toast = adw::Toast::builder()
@@ -480,9 +543,9 @@ pub fn create_add_view(
             // In macro mode, we skip simple validation for now
             // We could parse the 'bash -c' string but it's complex.
             // Just ensure it's not empty
-             if compile_macro(&macro_list_c).is_none() {
+             if let Err(e) = compile_macro(&macro_list_c) {
                  let toast = adw::Toast::builder()
-                    .title("Error: Macro must have at least one valid action")
+                    .title(format!("Error: {}", e))
                     .timeout(crate::config::constants::TOAST_TIMEOUT)
                     .build();
                 toast_overlay_clone.add_toast(toast);
@@ -510,11 +573,18 @@ pub fn create_add_view(
                     local_stack_c.set_visible_child_name("confirm");
                     return;
                 }
+            } else if let Some(reason) = validate_dispatcher(&dispatcher, &args) {
+                confirm_label_c.set_label(&format!(
+                    "{}.\nAre you sure you want to add this keybind?",
+                    reason
+                ));
+                local_stack_c.set_visible_child_name("confirm");
+                return;
             }
         }
 
         let variables = parser::get_variables().unwrap_or_else(|e| {
-            eprintln!("Failed to load variables for conflict checking: {}", e);
+            crate::log_warn!("parser", "Failed to load variables for conflict checking: {}", e);
             std::collections::HashMap::new()
         });
 
@@ -562,3 +632,30 @@ pub fn create_add_view(
 
     local_stack.upcast::<gtk::Widget>()
 }
+
+/// Replaces the contents of the "add" page and shows it, optionally with the
+/// modifiers and key already filled in.
+pub fn open_add_view(
+    stack: &gtk::Stack,
+    model: &gio::ListStore,
+    default_submap: Option<&str>,
+    initial_combo: Option<(&str, &str)>,
+) {
+    let Some(container) = stack.child_by_name("add").and_downcast::<gtk::Box>() else {
+        return;
+    };
+    let Some(toast_overlay) = stack
+        .ancestor(adw::ToastOverlay::static_type())
+        .and_downcast::<adw::ToastOverlay>()
+    else {
+        return;
+    };
+
+    while let Some(child) = container.first_child() {
+        container.remove(&child);
+    }
+
+    let add_view = create_add_view(stack, model, &toast_overlay, default_submap, initial_combo);
+    container.append(&add_view);
+    stack.set_visible_child_name("add");
+}
@@ -1,15 +1,20 @@
+use crate::config::favorites::{follow_edits, BindEdit};
 use crate::keybind_object::KeybindObject;
 use crate::parser;
+use crate::parser::dispatchers::validate_dispatcher;
+use crate::ui::utils::arg_editor::create_args_editor;
 use crate::ui::utils::clone::{create_clone_button, CloneContext};
 use crate::ui::utils::components::{
     create_flags_dropdown, create_mouse_button_dropdown, create_recorder_row, get_flag_from_index,
     get_index_from_flag, get_index_from_mouse_code, get_mouse_code_from_index,
 };
 use crate::ui::utils::conflicts::{check_conflict, generate_suggestions};
-use crate::ui::utils::macro_builder::{compile_macro, create_macro_row, parse_macro};
+use crate::ui::utils::dry_run::create_dry_run_panel;
+use crate::ui::utils::macro_builder::{compile_macro, create_macro_row, parse_macro, MacroStep};
+use crate::ui::utils::scripts::{extract_to_script, script_body, scripts_dir};
 use crate::ui::utils::{
     command_exists, create_destructive_button, create_form_group, create_page_header,
-    create_pill_button, create_suggested_button, execute_keybind, perform_backup, reload_keybinds,
+    create_pill_button, create_suggested_button, perform_backup, reload_keybinds,
     setup_dispatcher_completion,
 };
 use gtk::glib;
@@ -191,6 +196,7 @@ pub fn create_edit_view(
     }
     crate::ui::utils::setup_app_completion(&entry_dispatcher, &entry_args);
     simple_container.append(&create_form_group("Arguments:", &entry_args));
+    simple_container.append(&create_args_editor(&entry_dispatcher, &entry_args));
 
     form_box.append(&simple_container);
 
@@ -213,8 +219,8 @@ pub fn create_edit_view(
         .build();
 
     let macro_list_c = macro_list.clone();
-    let add_row = move |disp: Option<&str>, arg: Option<&str>| {
-        let (row, _, _, del_btn) = create_macro_row(disp, arg);
+    let add_row = move |step: Option<&MacroStep>| {
+        let (row, del_btn) = create_macro_row(step);
         let list_c = macro_list_c.clone();
         let list_c_del = list_c.clone(); // Clone for closure
         let row_c = row.clone();
@@ -228,21 +234,21 @@ pub fn create_edit_view(
     let add_row_cb = add_row_c.clone();
 
     add_action_btn.connect_clicked(move |_| {
-        add_row_cb(None, None);
+        add_row_cb(None);
     });
 
     // Populate existing macro rows if any
     if let Some(actions) = parsed_macro {
-        for (d, a) in actions {
-            add_row_c(Some(&d), Some(&a));
+        for step in &actions {
+            add_row_c(Some(step));
         }
     } else if is_macro {
         // Fallback should not happen due to `is_macro` check but just in case
-        add_row_c(None, None);
+        add_row_c(None);
     } else {
         // If switching TO macro mode from simple, maybe pre-fill with current?
         // For now start empty or with one row
-        add_row_c(None, None);
+        add_row_c(None);
     }
 
     macro_container_wrapper.append(&add_action_btn);
@@ -285,6 +291,7 @@ pub fn create_edit_view(
     let delete_btn = create_destructive_button("Delete", None);
     let clone_ctx = CloneContext {
         file_path: PathBuf::from(&file_path_display),
+        bind_id: obj.bind_id(),
         model: model.clone(),
         toast_overlay: toast_overlay.clone(),
         stack: stack.clone(),
@@ -304,7 +311,13 @@ pub fn create_edit_view(
     };
     let clone_btn = create_clone_button(clone_ctx);
     let exec_btn = create_pill_button("Execute", None);
-    exec_btn.set_tooltip_text(Some("Test this keybind immediately using hyprctl dispatch"));
+    exec_btn.set_tooltip_text(Some(
+        "Preview this keybind and test it with hyprctl dispatch",
+    ));
+    let extract_btn = create_pill_button("Extract to Script", None);
+    extract_btn.set_tooltip_text(Some(
+        "Move this command into an executable script and bind the script instead",
+    ));
     let cancel_btn = create_pill_button("Cancel", None);
     let save_btn = create_suggested_button("Save Changes", None);
 
@@ -313,6 +326,7 @@ pub fn create_edit_view(
     button_box.append(&spacer);
     button_box.append(&clone_btn);
     button_box.append(&exec_btn);
+    button_box.append(&extract_btn);
     button_box.append(&cancel_btn);
     button_box.append(&save_btn);
     container.append(&button_box);
@@ -361,11 +375,82 @@ pub fn create_edit_view(
 
     local_stack.add_named(&confirm_container, Some("confirm"));
 
+    // --- Extract to Script View Construction ---
+    let extract_container = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let local_stack_weak = local_stack.downgrade();
+    let extract_header = create_page_header(
+        "Extract to Script",
+        scripts_dir()
+            .ok()
+            .map(|dir| crate::ui::utils::scripts::display_path(&dir))
+            .as_deref(),
+        "Back",
+        move || {
+            if let Some(ls) = local_stack_weak.upgrade() {
+                ls.set_visible_child_name("form");
+            }
+        },
+    );
+    extract_container.append(&extract_header);
+
+    let script_name_entry = gtk::Entry::builder()
+        .placeholder_text("e.g. screenshot")
+        .build();
+    extract_container.append(&create_form_group("Script Name:", &script_name_entry));
+
+    let script_preview = gtk::TextView::builder()
+        .editable(false)
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .top_margin(8)
+        .bottom_margin(8)
+        .left_margin(8)
+        .right_margin(8)
+        .build();
+    let preview_scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .css_classes(["card"])
+        .child(&script_preview)
+        .build();
+    extract_container.append(&create_form_group("Script:", &preview_scroll));
+
+    let extract_buttons = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(12)
+        .halign(gtk::Align::End)
+        .build();
+    let extract_back_btn = create_pill_button("Back", None);
+    let extract_create_btn = create_suggested_button("Create Script", None);
+    extract_buttons.append(&extract_back_btn);
+    extract_buttons.append(&extract_create_btn);
+    extract_container.append(&extract_buttons);
+
+    local_stack.add_named(&extract_container, Some("extract"));
+
     // --- Conflict View Construction ---
     use crate::ui::utils::conflicts::create_conflict_panel;
     let conflict_panel = create_conflict_panel("Save Anyway");
     local_stack.add_named(&conflict_panel.container, Some("conflict"));
 
+    // --- Dry Run View Construction ---
+    let dry_run_panel = Rc::new(create_dry_run_panel(toast_overlay));
+    local_stack.add_named(&dry_run_panel.container, Some("dryrun"));
+    let local_stack_weak = local_stack.downgrade();
+    dry_run_panel.back_btn.connect_clicked(move |_| {
+        if let Some(ls) = local_stack_weak.upgrade() {
+            ls.set_visible_child_name("form");
+        }
+    });
+
     // --- Logic ---
 
     let entry_dispatcher_exec = entry_dispatcher.clone();
@@ -373,9 +458,11 @@ pub fn create_edit_view(
     let macro_switch_exec = macro_switch.clone();
     let macro_list_exec = macro_list.clone();
 
+    let dry_run_panel_c = dry_run_panel.clone();
+    let local_stack_exec = local_stack.clone();
     exec_btn.connect_clicked(move |_| {
         let (dispatcher, args) = if macro_switch_exec.is_active() {
-            if let Some((d, a)) = compile_macro(&macro_list_exec) {
+            if let Ok((d, a)) = compile_macro(&macro_list_exec) {
                 (d, a)
             } else {
                 return;
@@ -388,7 +475,31 @@ pub fn create_edit_view(
         };
 
         if !dispatcher.trim().is_empty() {
-            execute_keybind(&dispatcher, &args);
+            dry_run_panel_c.show(&dispatcher, &args);
+            local_stack_exec.set_visible_child_name("dryrun");
+        }
+    });
+
+    // Only exec commands and macros can become scripts
+    let update_extract = {
+        let extract_btn = extract_btn.clone();
+        let entry_dispatcher = entry_dispatcher.clone();
+        let macro_switch = macro_switch.clone();
+        Rc::new(move || {
+            let dispatcher = entry_dispatcher.text();
+            let is_exec = dispatcher == "exec" || dispatcher == "execr";
+            extract_btn.set_visible(!args_had_prefix && (is_exec || macro_switch.is_active()));
+        })
+    };
+    update_extract();
+    let update_extract_c = update_extract.clone();
+    entry_dispatcher.connect_changed(move |_| update_extract_c());
+    macro_switch.connect_active_notify(move |_| update_extract());
+
+    let local_stack_weak = local_stack.downgrade();
+    extract_back_btn.connect_clicked(move |_| {
+        if let Some(ls) = local_stack_weak.upgrade() {
+            ls.set_visible_child_name("form");
         }
     });
 
@@ -423,9 +534,11 @@ pub fn create_edit_view(
     let selection_model_c = selection_model.clone();
     let column_view_c = column_view.clone();
     let original_submap = current_submap.clone();
+    let bind_id = obj.bind_id();
 
     let do_save = {
         let file_path = file_path.clone();
+        let bind_id = bind_id.clone();
         let model_clone = model_clone.clone();
         let toast_overlay_clone = toast_overlay_clone.clone();
         let stack_weak = stack_weak.clone();
@@ -487,10 +600,10 @@ pub fn create_edit_view(
             // Resolve Dispatcher/Args
             let (new_dispatcher, new_args) = if macro_switch_c.is_active() {
                 match compile_macro(&macro_list_c) {
-                    Some(res) => res,
-                    None => {
+                    Ok(res) => res,
+                    Err(e) => {
                         let toast = adw::Toast::builder()
-                            .title("Macro is empty or invalid")
+                            .title(e.to_string())
                             .timeout(crate::config::constants::TOAST_TIMEOUT)
                             .build();
                         toast_overlay_clone.add_toast(toast);
@@ -522,8 +635,7 @@ pub fn create_edit_view(
                         new_submap.clone(),
                         if desc.is_empty() { None } else { Some(desc) },
                         new_flag,
-                    )
-                    .map(|_| ()), // map usize to ()
+                    ),
                     Err(e) => Err(e),
                 }
             } else {
@@ -538,14 +650,20 @@ pub fn create_edit_view(
                     if desc.is_empty() { None } else { Some(desc) },
                     Some(new_flag),
                 )
+                .map(|_| line_number)
             };
 
             match result {
-                Ok(_) => {
+                Ok(new_line) => {
+                    follow_edits(&[BindEdit::Moved {
+                        old: bind_id.clone(),
+                        file: file_path.clone(),
+                        line: new_line,
+                    }]);
                     reload_keybinds(&model_clone);
 
                     if let Err(e) = perform_backup(false) {
-                        eprintln!("Auto-backup failed: {}", e);
+                        crate::log_error!("backup", "Auto-backup failed: {}", e);
                     }
 
                     if !submap_changed {
@@ -609,6 +727,95 @@ pub fn create_edit_view(
         do_save_c();
     });
 
+    // The bind's current command, from whichever editor mode is active
+    let current_command = {
+        let entry_dispatcher = entry_dispatcher.clone();
+        let entry_args = entry_args.clone();
+        let macro_switch = macro_switch.clone();
+        let macro_list = macro_list.clone();
+        Rc::new(move || {
+            if macro_switch.is_active() {
+                compile_macro(&macro_list).ok()
+            } else {
+                Some((
+                    entry_dispatcher.text().to_string(),
+                    entry_args.text().to_string(),
+                ))
+            }
+        })
+    };
+
+    let current_command_c = current_command.clone();
+    let local_stack_c = local_stack.clone();
+    let script_name_entry_c = script_name_entry.clone();
+    let script_preview_c = script_preview.clone();
+    let entry_desc_c = entry_desc.clone();
+    let toast_overlay_c = toast_overlay.clone();
+    extract_btn.connect_clicked(move |_| {
+        let Some((dispatcher, args)) = current_command_c() else {
+            let toast = adw::Toast::builder()
+                .title("Macro is empty or an action contains #")
+                .timeout(crate::config::constants::TOAST_TIMEOUT)
+                .build();
+            toast_overlay_c.add_toast(toast);
+            return;
+        };
+
+        if script_name_entry_c.text().is_empty() {
+            let desc = entry_desc_c.text();
+            let default_name = if desc.trim().is_empty() {
+                current_key.clone()
+            } else {
+                desc.to_string()
+            };
+            script_name_entry_c.set_text(&default_name);
+        }
+        script_preview_c
+            .buffer()
+            .set_text(&script_body(&dispatcher, &args));
+        local_stack_c.set_visible_child_name("extract");
+    });
+
+    let entry_dispatcher_c = entry_dispatcher.clone();
+    let entry_args_c = entry_args.clone();
+    let macro_switch_c = macro_switch.clone();
+    let toast_overlay_c = toast_overlay.clone();
+    let do_save_c = do_save.clone();
+    extract_create_btn.connect_clicked(move |_| {
+        let Some((dispatcher, args)) = current_command() else {
+            return;
+        };
+
+        let result = scripts_dir()
+            .and_then(|dir| extract_to_script(&dir, &script_name_entry.text(), &dispatcher, &args));
+        match result {
+            Ok((path, new_args)) => {
+                let toast = adw::Toast::builder()
+                    .title(format!(
+                        "Created {}",
+                        path.file_name().unwrap_or_default().to_string_lossy()
+                    ))
+                    .timeout(crate::config::constants::TOAST_TIMEOUT)
+                    .build();
+                toast_overlay_c.add_toast(toast);
+
+                macro_switch_c.set_active(false);
+                if dispatcher != "execr" {
+                    entry_dispatcher_c.set_text("exec");
+                }
+                entry_args_c.set_text(&new_args);
+                do_save_c();
+            }
+            Err(e) => {
+                let toast = adw::Toast::builder()
+                    .title(format!("Error: {}", e))
+                    .timeout(crate::config::constants::TOAST_TIMEOUT)
+                    .build();
+                toast_overlay_c.add_toast(toast);
+            }
+        }
+    });
+
     let entry_dispatcher_save = entry_dispatcher.clone();
     let local_stack_c = local_stack.clone();
     let confirm_label_c = confirm_label.clone();
@@ -667,9 +874,9 @@ pub fn create_edit_view(
         }
 
         if macro_switch_c.is_active() {
-             if compile_macro(&macro_list_c).is_none() {
+             if let Err(e) = compile_macro(&macro_list_c) {
                  let toast = adw::Toast::builder()
-                    .title("Error: Macro must have at least one valid action")
+                    .title(format!("Error: {}", e))
                     .timeout(crate::config::constants::TOAST_TIMEOUT)
                     .build();
                 toast_overlay_clone.add_toast(toast);
@@ -700,11 +907,20 @@ pub fn create_edit_view(
                     local_stack_c.set_visible_child_name("confirm");
                     return;
                 }
+            } else if !args_had_prefix {
+                if let Some(reason) = validate_dispatcher(&new_dispatcher, &new_args) {
+                    confirm_label_c.set_label(&format!(
+                        "{}.\nAre you sure you want to save this keybind?",
+                        reason
+                    ));
+                    local_stack_c.set_visible_child_name("confirm");
+                    return;
+                }
             }
         }
 
         let variables = parser::get_variables().unwrap_or_else(|e| {
-            eprintln!("Failed to load variables for conflict checking: {}", e);
+            crate::log_warn!("parser", "Failed to load variables for conflict checking: {}", e);
             std::collections::HashMap::new()
         });
 
@@ -760,10 +976,11 @@ pub fn create_edit_view(
     delete_btn.connect_clicked(move |_| {
         match parser::delete_keybind(file_path.clone(), line_number) {
             Ok(_) => {
+                follow_edits(&[BindEdit::Deleted(bind_id.clone())]);
                 reload_keybinds(&model_clone);
 
                 if let Err(e) = perform_backup(false) {
-                    eprintln!("Auto-backup failed: {}", e);
+                    crate::log_error!("backup", "Auto-backup failed: {}", e);
                 }
 
                 let toast = adw::Toast::builder()
@@ -0,0 +1,218 @@
+use crate::ui::utils::components::{collect_submaps, create_card_row, create_page_header};
+use crate::ui::utils::create_suggested_button;
+use crate::ui::utils::free_keys::{find_free_keys, FreeKeyOptions};
+use crate::ui::utils::keybinds::model_keybinds;
+use crate::ui::views::keyboard::resolve_key;
+use crate::ui::views::keyboard_geometry::KeyboardGeometry;
+use crate::ui::views::open_add_view;
+use crate::xkb_handler::XkbHandler;
+use gtk::{gio, prelude::*};
+use gtk4 as gtk;
+use std::rc::Rc;
+
+fn create_toggle(label: &str, active: bool) -> gtk::ToggleButton {
+    gtk::ToggleButton::builder()
+        .label(label)
+        .active(active)
+        .css_classes(["small", "mod-toggle"])
+        .build()
+}
+
+/// Lists every unbound key for a modifier combination, ranked by how close it
+/// sits to the home row on the selected physical layout.
+pub fn create_free_keys_view(
+    stack: &gtk::Stack,
+    model: &gio::ListStore,
+    geometry: KeyboardGeometry,
+    xkb: Option<XkbHandler>,
+    initial_mods: &[String],
+    initial_submap: Option<String>,
+) -> gtk::Box {
+    let container = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let stack_weak = stack.downgrade();
+    let header = create_page_header(
+        "Find a Free Key",
+        Some("Unbound combinations, easiest to reach first"),
+        "Back to Keyboard",
+        move || {
+            if let Some(s) = stack_weak.upgrade() {
+                s.set_visible_child_name("keyboard");
+            }
+        },
+    );
+    container.append(&header);
+
+    // Modifiers & Submap
+    let mod_box = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+    mod_box.set_halign(gtk::Align::Center);
+    mod_box.append(&gtk::Label::new(Some("Modifiers:")));
+
+    let mod_toggles: Vec<(String, gtk::ToggleButton)> = ["SUPER", "SHIFT", "CTRL", "ALT"]
+        .iter()
+        .map(|m| {
+            let btn = create_toggle(m, initial_mods.iter().any(|im| im == m));
+            mod_box.append(&btn);
+            (m.to_string(), btn)
+        })
+        .collect();
+
+    mod_box.append(&gtk::Separator::new(gtk::Orientation::Vertical));
+    mod_box.append(&gtk::Label::new(Some("Submap:")));
+
+    let mut submap_items = vec!["Global (Root)".to_string()];
+    submap_items.extend(collect_submaps(model));
+    let submap_dropdown = gtk::DropDown::from_strings(
+        &submap_items
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<&str>>(),
+    );
+    if let Some(sub) = &initial_submap {
+        if let Some(idx) = submap_items.iter().position(|s| s == sub) {
+            submap_dropdown.set_selected(idx as u32);
+        }
+    }
+    mod_box.append(&submap_dropdown);
+    container.append(&mod_box);
+
+    // Key Classes
+    let class_box = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+    class_box.set_halign(gtk::Align::Center);
+    class_box.append(&gtk::Label::new(Some("Keys:")));
+
+    let letters_btn = create_toggle("Letters", true);
+    let digits_btn = create_toggle("Digits", true);
+    let fkeys_btn = create_toggle("F-Keys", true);
+    let other_btn = create_toggle("Other", false);
+    other_btn.set_tooltip_text(Some("Punctuation, navigation and editing keys"));
+    let home_btn = create_toggle("Near Home Row", false);
+    home_btn.set_tooltip_text(Some("Only keys at most one row from the home row"));
+
+    for btn in [&letters_btn, &digits_btn, &fkeys_btn, &other_btn] {
+        class_box.append(btn);
+    }
+    class_box.append(&gtk::Separator::new(gtk::Orientation::Vertical));
+    class_box.append(&home_btn);
+    container.append(&class_box);
+
+    let count_label = gtk::Label::builder()
+        .css_classes(["dim-label"])
+        .halign(gtk::Align::Start)
+        .build();
+    container.append(&count_label);
+
+    let results_box = gtk::Box::new(gtk::Orientation::Vertical, 8);
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&results_box)
+        .build();
+    container.append(&scroll);
+
+    let keybinds = model_keybinds(model);
+    let stack_weak = stack.downgrade();
+    let model = model.clone();
+
+    let refresh = {
+        let submap_dropdown = submap_dropdown.clone();
+        let letters_btn = letters_btn.clone();
+        let digits_btn = digits_btn.clone();
+        let fkeys_btn = fkeys_btn.clone();
+        let other_btn = other_btn.clone();
+        let home_btn = home_btn.clone();
+        let mod_toggles = mod_toggles.clone();
+
+        move || {
+            while let Some(child) = results_box.first_child() {
+                results_box.remove(&child);
+            }
+
+            let mods: Vec<&str> = mod_toggles
+                .iter()
+                .filter(|(_, btn)| btn.is_active())
+                .map(|(name, _)| name.as_str())
+                .collect();
+            let mods = mods.join(" ");
+
+            let submap = match submap_dropdown.selected() {
+                0 => None,
+                i => submap_items.get(i as usize).cloned(),
+            };
+
+            let opts = FreeKeyOptions {
+                mods: mods.clone(),
+                submap: submap.clone(),
+                letters: letters_btn.is_active(),
+                digits: digits_btn.is_active(),
+                function_keys: fkeys_btn.is_active(),
+                other: other_btn.is_active(),
+                near_home_row: home_btn.is_active(),
+            };
+
+            let free = find_free_keys(&keybinds, &geometry, &opts, |k| resolve_key(k, &xkb));
+
+            count_label.set_label(&match free.len() {
+                0 => "No free keys for this selection".to_string(),
+                1 => "1 free key".to_string(),
+                n => format!("{} free keys", n),
+            });
+
+            for fk in free {
+                let combo = if mods.is_empty() {
+                    fk.bind_key()
+                } else {
+                    format!("{} + {}", mods.replace(' ', " + "), fk.bind_key())
+                };
+                let subtitle = format!(
+                    "{} · {:.1} keys from the home row",
+                    fk.class.name(),
+                    fk.distance
+                );
+
+                let use_btn = create_suggested_button("Use", Some("list-add-symbolic"));
+                use_btn.set_tooltip_text(Some("Create a keybind with this combination"));
+
+                let stack_weak = stack_weak.clone();
+                let model = model.clone();
+                let mods = mods.clone();
+                let submap = submap.clone();
+                use_btn.connect_clicked(move |_| {
+                    if let Some(stack) = stack_weak.upgrade() {
+                        open_add_view(
+                            &stack,
+                            &model,
+                            submap.as_deref(),
+                            Some((&mods, &fk.bind_key())),
+                        );
+                    }
+                });
+
+                results_box.append(&create_card_row(&combo, Some(&subtitle), &use_btn));
+            }
+        }
+    };
+    let refresh = Rc::new(refresh);
+
+    for (_, btn) in &mod_toggles {
+        let r = refresh.clone();
+        btn.connect_toggled(move |_| r());
+    }
+    for btn in [&letters_btn, &digits_btn, &fkeys_btn, &other_btn, &home_btn] {
+        let r = refresh.clone();
+        btn.connect_toggled(move |_| r());
+    }
+    let r = refresh.clone();
+    submap_dropdown.connect_selected_notify(move |_| r());
+
+    refresh();
+
+    container
+}
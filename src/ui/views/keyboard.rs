@@ -1,15 +1,133 @@
 use crate::config::StyleConfig;
 use crate::keybind_object::KeybindObject;
-use crate::parser::input::load_input_config;
+use crate::parser::devices::{list_keyboards, KeyboardDevice};
+use crate::parser::input::{load_input_config, InputConfig};
 use crate::ui::utils::components::{collect_submaps, create_close_button};
 use crate::ui::utils::normalize;
-use crate::ui::views::keyboard_layouts::{
-    detect_layout, get_layout_rows, KeyDef, ROW_ARROWS, ROW_FUNC,
+use crate::ui::views::free_keys::create_free_keys_view;
+use crate::ui::views::keyboard_geometry::{
+    get_geometry, import_qmk_info, list_geometry_names, save_user_layout, standard_geometry,
+    GeometryKey, KeyboardGeometry,
 };
+use crate::ui::views::keyboard_layouts::detect_layout;
+use crate::ui::views::layers::create_layers_view;
 use crate::xkb_handler::XkbHandler;
 use gtk::{gio, prelude::*};
 use gtk4 as gtk;
 use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/// Builds the XKB state for the selected keyboard, or for the global `input`
+/// block (and `customXkbFile`) when no specific device is selected.
+fn create_xkb_handler(
+    device: Option<&KeyboardDevice>,
+    config: &StyleConfig,
+    input_cfg: &InputConfig,
+) -> Option<XkbHandler> {
+    match device {
+        Some(dev) => {
+            if let Some(file) = &dev.kb_file {
+                if let Some(handler) = XkbHandler::from_file(file) {
+                    return Some(handler);
+                }
+            }
+            XkbHandler::new(&dev.layout, &dev.variant, &dev.model, &dev.options)
+        }
+        None => {
+            if let Some(custom_file) = &config.custom_xkb_file {
+                XkbHandler::from_file(custom_file)
+            } else {
+                XkbHandler::new(
+                    &input_cfg.kb_layout,
+                    &input_cfg.kb_variant,
+                    &input_cfg.kb_model,
+                    &input_cfg.kb_options,
+                )
+            }
+        }
+    }
+}
+
+/// Returns the label to draw and the normalized key name used to match binds.
+pub(super) fn resolve_key(k: &GeometryKey, xkb: &Option<XkbHandler>) -> (String, String) {
+    // Keycode 0 marks keys without an evdev code (e.g. layer keys on split boards)
+    let (label, hypr_name) = match xkb {
+        Some(handler) if k.keycode != 0 => handler.get_key_info(k.keycode),
+        _ => (k.label.clone(), k.hypr_name.clone()),
+    };
+    let (_, norm_key) = normalize("", &hypr_name);
+    (label, norm_key)
+}
+
+fn create_key_button(k: &GeometryKey, xkb: &Option<XkbHandler>) -> gtk::Button {
+    let (label_text, norm_key) = resolve_key(k, xkb);
+
+    let btn_label = gtk::Label::builder()
+        .label(&label_text)
+        .ellipsize(gtk::pango::EllipsizeMode::End)
+        .halign(gtk::Align::Center)
+        .valign(gtk::Align::Center)
+        .build();
+
+    let btn = gtk::Button::builder()
+        .child(&btn_label)
+        .css_classes(["keyboard-key"])
+        .hexpand(true)
+        .vexpand(true)
+        .tooltip_text(&label_text) // Show full label on hover
+        .build();
+
+    // Store normalized key name
+    btn.set_widget_name(&norm_key);
+    btn
+}
+
+/// Resolves the shape picked in the dropdown; "Auto" follows the XKB layout.
+fn resolve_geometry(shape: &str, kb_layout: &str) -> KeyboardGeometry {
+    if shape.eq_ignore_ascii_case("Auto") {
+        standard_geometry(detect_layout(kb_layout))
+    } else {
+        get_geometry(shape)
+    }
+}
+
+/// Clears the grid and lays out the keys of the given geometry.
+///
+/// Positions are in key units; each unit spans four grid cells in both
+/// directions so staggered and split layouts can be placed accurately.
+fn populate_grid(grid: &gtk::Grid, geometry: &KeyboardGeometry, xkb: &Option<XkbHandler>) {
+    while let Some(child) = grid.first_child() {
+        grid.remove(&child);
+    }
+
+    let to_cells = |units: f64| (units * 4.0).round() as i32;
+
+    for k in &geometry.keys {
+        grid.attach(
+            &create_key_button(k, xkb),
+            to_cells(k.x),
+            to_cells(k.y),
+            to_cells(k.width).max(1),
+            to_cells(k.height).max(1),
+        );
+    }
+}
+
+fn set_xkb_status(title: &gtk::Label, ok: bool) {
+    if ok {
+        title.set_tooltip_text(None);
+        title.remove_css_class("dim-label");
+    } else {
+        title.set_tooltip_text(Some(
+            "Notice: XKB layout resolution failed. Displaying fallback labels.",
+        ));
+        title.add_css_class("dim-label");
+        crate::log_warn!(
+            "keyboard",
+            "XKB initialization failed. Falling back to static labels."
+        );
+    }
+}
 
 pub fn create_keyboard_view(stack: &gtk::Stack, model: &gio::ListStore) -> gtk::Box {
     const DETAILS_DEFAULT: &str = "Hover over a highlighted key to see the action";
@@ -18,29 +136,11 @@ pub fn create_keyboard_view(stack: &gtk::Stack, model: &gio::ListStore) -> gtk::
 
     let config = StyleConfig::load();
     let (input_cfg, _) = load_input_config().unwrap_or_default();
+    let keyboards = Rc::new(list_keyboards(&input_cfg));
 
-    let xkb = if let Some(custom_file) = &config.custom_xkb_file {
-        XkbHandler::from_file(custom_file)
-    } else {
-        XkbHandler::new(
-            &input_cfg.kb_layout,
-            &input_cfg.kb_variant,
-            &input_cfg.kb_model,
-            &input_cfg.kb_options,
-        )
-    };
-
+    let xkb = create_xkb_handler(None, &config, &input_cfg);
     let layout_pref = config.keyboard_layout.to_uppercase();
 
-    let layout = if layout_pref == "AUTO" {
-        detect_layout(&input_cfg.kb_layout).to_string()
-    } else {
-        layout_pref
-    };
-
-    // Select Rows based on layout
-    let (row1, row2, row3, row4, row5) = get_layout_rows(&layout);
-
     let container = gtk::Box::new(gtk::Orientation::Vertical, 8);
     container.set_margin_top(8);
     container.set_margin_bottom(8);
@@ -75,21 +175,90 @@ pub fn create_keyboard_view(stack: &gtk::Stack, model: &gio::ListStore) -> gtk::
         .halign(gtk::Align::Center)
         .build();
 
-    if xkb.is_none() {
-        title.set_tooltip_text(Some(
-            "Notice: XKB layout resolution failed. Displaying fallback labels.",
-        ));
-        title.add_css_class("dim-label");
-        eprintln!("[Keyboard View] XKB initialization failed. Falling back to static labels.");
-    }
+    set_xkb_status(&title, xkb.is_some());
+
+    let layers_btn = gtk::Button::builder()
+        .icon_name("view-grid-symbolic")
+        .css_classes(["flat", "circular"])
+        .tooltip_text("Layer Chart (every modifier combination, printable)")
+        .build();
+
+    let free_keys_btn = gtk::Button::builder()
+        .icon_name("edit-find-symbolic")
+        .css_classes(["flat", "circular"])
+        .tooltip_text("Find a free key for the selected modifiers")
+        .build();
 
     let close_btn = create_close_button();
 
     header_box.append(&back_btn);
     header_box.append(&title);
+    header_box.append(&free_keys_btn);
+    header_box.append(&layers_btn);
     header_box.append(&close_btn);
     container.append(&header_box);
 
+    // Keyboard Device & Physical Layout Selection
+    let layout_box = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+    layout_box.set_halign(gtk::Align::Center);
+
+    layout_box.append(&gtk::Label::new(Some("Keyboard:")));
+    let mut device_items = vec!["Global (input)".to_string()];
+    device_items.extend(keyboards.iter().map(|kb| {
+        if kb.main {
+            format!("{} (main)", kb.name)
+        } else {
+            kb.name.clone()
+        }
+    }));
+    let device_dropdown = gtk::DropDown::from_strings(
+        &device_items
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<&str>>(),
+    );
+    device_dropdown.set_tooltip_text(Some(
+        "Render the keymap of a specific keyboard (per-device layouts)",
+    ));
+    device_dropdown.set_sensitive(!keyboards.is_empty());
+    layout_box.append(&device_dropdown);
+
+    layout_box.append(&gtk::Label::new(Some("Shape:")));
+    let mut shape_items = vec!["Auto".to_string()];
+    shape_items.extend(list_geometry_names());
+    let shape_list = gtk::StringList::new(
+        &shape_items
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<&str>>(),
+    );
+    let shape_dropdown = gtk::DropDown::builder()
+        .model(&shape_list)
+        .selected(0)
+        .tooltip_text("Physical key arrangement, independent of the keymap")
+        .build();
+    let pref = if layout_pref == "HUNGARIAN" {
+        "HU"
+    } else {
+        layout_pref.as_str()
+    };
+    if let Some(idx) = shape_items
+        .iter()
+        .position(|l| l.eq_ignore_ascii_case(pref))
+    {
+        shape_dropdown.set_selected(idx as u32);
+    }
+    layout_box.append(&shape_dropdown);
+
+    let import_btn = gtk::Button::builder()
+        .icon_name("document-open-symbolic")
+        .css_classes(["flat"])
+        .tooltip_text("Import a QMK info.json or VIA definition as a layout")
+        .build();
+    layout_box.append(&import_btn);
+
+    container.append(&layout_box);
+
     // Modifier Toggles & Submap Dropdown
     let mod_box = gtk::Box::new(gtk::Orientation::Horizontal, 8);
     mod_box.set_halign(gtk::Align::Center);
@@ -176,93 +345,12 @@ pub fn create_keyboard_view(stack: &gtk::Stack, model: &gio::ListStore) -> gtk::
 
     grid.add_css_class("keyboard-container");
 
-    let mut row_idx = 0;
-
-    let add_row = |keys: &[KeyDef], r_idx: i32, g: &gtk::Grid, xkb: &Option<XkbHandler>| {
-        let mut col_idx = 0;
-        for k in keys {
-            let (label_text, hypr_name) = if let Some(handler) = xkb {
-                let (l, n) = handler.get_key_info(k.keycode);
-                (l, n)
-            } else {
-                (k.label.to_string(), k.hypr_name.to_string())
-            };
-
-            let width_cells = (k.width * 4.0).round() as i32;
-
-            let btn_label = gtk::Label::builder()
-                .label(&label_text)
-                .ellipsize(gtk::pango::EllipsizeMode::End)
-                .halign(gtk::Align::Center)
-                .valign(gtk::Align::Center)
-                .build();
-
-            let btn = gtk::Button::builder()
-                .child(&btn_label)
-                .css_classes(["keyboard-key"])
-                .hexpand(true)
-                .vexpand(true)
-                .tooltip_text(&label_text) // Show full label on hover
-                .build();
-
-            // Store normalized key name
-            let (_, norm_key) = normalize("", &hypr_name);
-            btn.set_widget_name(&norm_key);
-
-            g.attach(&btn, col_idx, r_idx, width_cells, 1);
-            col_idx += width_cells;
-        }
-    };
-
-    add_row(ROW_FUNC, row_idx, &grid, &xkb);
-    row_idx += 1;
-    add_row(row1, row_idx, &grid, &xkb);
-    row_idx += 1;
-    add_row(row2, row_idx, &grid, &xkb);
-    row_idx += 1;
-    add_row(row3, row_idx, &grid, &xkb);
-    row_idx += 1;
-    add_row(row4, row_idx, &grid, &xkb);
-    row_idx += 1;
-    add_row(row5, row_idx, &grid, &xkb);
-    row_idx += 1;
-
-    // Arrow keys
-    // Total columns approx 60.
-    // Arrows are 4 keys = 4 width each = 16 cols.
-    // Centered start = (60 - 16) / 2 = 22.
-    let arrow_start_col = 22;
-    let mut arrow_col = arrow_start_col;
-    for k in ROW_ARROWS {
-        let (label_text, hypr_name) = if let Some(handler) = &xkb {
-            let (l, n) = handler.get_key_info(k.keycode);
-            (l, n)
-        } else {
-            (k.label.to_string(), k.hypr_name.to_string())
-        };
-
-        let width_cells = 4; // 1.0 * 4
-
-        let btn_label = gtk::Label::builder()
-            .label(&label_text)
-            .ellipsize(gtk::pango::EllipsizeMode::End)
-            .halign(gtk::Align::Center)
-            .valign(gtk::Align::Center)
-            .build();
-
-        let btn = gtk::Button::builder()
-            .child(&btn_label)
-            .css_classes(["keyboard-key"])
-            .hexpand(true)
-            .vexpand(true)
-            .tooltip_text(&label_text) // Show full label on hover
-            .build();
-        let (_, norm_key) = normalize("", &hypr_name);
-        btn.set_widget_name(&norm_key);
-
-        grid.attach(&btn, arrow_col, row_idx, width_cells, 1);
-        arrow_col += width_cells;
-    }
+    let initial_shape = shape_items[shape_dropdown.selected() as usize].clone();
+    populate_grid(
+        &grid,
+        &resolve_geometry(&initial_shape, &input_cfg.kb_layout),
+        &xkb,
+    );
 
     container.append(&grid);
 
@@ -452,6 +540,157 @@ pub fn create_keyboard_view(stack: &gtk::Stack, model: &gio::ListStore) -> gtk::
     let ot6 = on_toggle.clone();
     density_toggle.connect_toggled(move |_| ot6());
 
+    // Rebuild the grid when the device or physical shape changes
+    // Geometry and keymap for the current device/shape selection
+    let current_selection = {
+        let device_dropdown = device_dropdown.clone();
+        let shape_dropdown = shape_dropdown.clone();
+        let keyboards = keyboards.clone();
+        move || {
+            let device = match device_dropdown.selected() {
+                0 => None,
+                i => keyboards.get(i as usize - 1),
+            };
+            let xkb = create_xkb_handler(device, &config, &input_cfg);
+
+            let shape = shape_dropdown
+                .selected_item()
+                .and_downcast::<gtk::StringObject>()
+                .map(|o| o.string().to_string())
+                .unwrap_or_else(|| "Auto".to_string());
+            let kb_layout = device.map_or(input_cfg.kb_layout.as_str(), |d| d.layout.as_str());
+
+            (resolve_geometry(&shape, kb_layout), xkb)
+        }
+    };
+    let current_selection = Rc::new(current_selection);
+
+    let rebuild = {
+        let grid = grid.clone();
+        let title = title.clone();
+        let current_selection = current_selection.clone();
+        let on_toggle = on_toggle.clone();
+        move || {
+            let (geometry, xkb) = current_selection();
+            set_xkb_status(&title, xkb.is_some());
+            populate_grid(&grid, &geometry, &xkb);
+            on_toggle();
+        }
+    };
+    let rebuild = Rc::new(rebuild);
+
+    let stack_weak = stack.downgrade();
+    let model_free = model.clone();
+    let current_selection_free = current_selection.clone();
+    let submap_dropdown_free = submap_dropdown.clone();
+    let mod_buttons = [
+        btn_super.clone(),
+        btn_shift.clone(),
+        btn_ctrl.clone(),
+        btn_alt.clone(),
+    ];
+    free_keys_btn.connect_clicked(move |_| {
+        let Some(stack) = stack_weak.upgrade() else {
+            return;
+        };
+        let (geometry, xkb) = current_selection_free();
+
+        let mods: Vec<String> = mod_buttons
+            .iter()
+            .filter(|b| b.is_active())
+            .filter_map(|b| b.label().map(|l| l.to_string()))
+            .collect();
+        let submap = match submap_dropdown_free.selected() {
+            0 => None,
+            _ => submap_dropdown_free
+                .selected_item()
+                .and_downcast::<gtk::StringObject>()
+                .map(|o| o.string().to_string()),
+        };
+
+        if let Some(old) = stack.child_by_name("freekeys") {
+            stack.remove(&old);
+        }
+        let view = create_free_keys_view(&stack, &model_free, geometry, xkb, &mods, submap);
+        stack.add_named(&view, Some("freekeys"));
+        stack.set_visible_child_name("freekeys");
+    });
+
+    let stack_weak = stack.downgrade();
+    let model_layers = model.clone();
+    layers_btn.connect_clicked(move |_| {
+        let Some(stack) = stack_weak.upgrade() else {
+            return;
+        };
+        let (geometry, xkb) = current_selection();
+
+        if let Some(old) = stack.child_by_name("layers") {
+            stack.remove(&old);
+        }
+        let view = create_layers_view(&stack, &model_layers, geometry, xkb);
+        stack.add_named(&view, Some("layers"));
+        stack.set_visible_child_name("layers");
+    });
+
+    let rb1 = rebuild.clone();
+    device_dropdown.connect_selected_notify(move |_| rb1());
+
+    let rb2 = rebuild.clone();
+    shape_dropdown.connect_selected_notify(move |_| rb2());
+
+    let shape_dropdown_c = shape_dropdown.clone();
+    let details_label_c = details_label.clone();
+    let rb3 = rebuild.clone();
+    import_btn.connect_clicked(move |btn| {
+        let dialog = gtk::FileDialog::builder()
+            .title("Import QMK/VIA Layout")
+            .modal(true)
+            .build();
+
+        let window = btn.root().and_downcast::<gtk::Window>();
+        let shape_list = shape_list.clone();
+        let shape_dropdown = shape_dropdown_c.clone();
+        let details_label = details_label_c.clone();
+        let rebuild = rb3.clone();
+
+        dialog.open(window.as_ref(), None::<&gio::Cancellable>, move |res| {
+            let Some(path) = res.ok().and_then(|f| f.path()) else {
+                return;
+            };
+
+            let result = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| import_qmk_info(&content, None))
+                .and_then(|geometry| {
+                    save_user_layout(&geometry).map(|saved| (geometry.name, saved))
+                });
+
+            match result {
+                Ok((name, saved)) => {
+                    let existing = (0..shape_list.n_items())
+                        .find(|&i| shape_list.string(i).is_some_and(|s| s == name.as_str()));
+                    let idx = existing.unwrap_or_else(|| {
+                        shape_list.append(&name);
+                        shape_list.n_items() - 1
+                    });
+                    if shape_dropdown.selected() == idx {
+                        rebuild();
+                    } else {
+                        shape_dropdown.set_selected(idx);
+                    }
+                    details_label.set_label(&format!(
+                        "Imported layout '{}' to {}",
+                        name,
+                        saved.display()
+                    ));
+                }
+                Err(e) => {
+                    details_label.set_label(&format!("Import failed: {}", e));
+                }
+            }
+        });
+    });
+
     // Initial Trigger
     on_toggle();
 
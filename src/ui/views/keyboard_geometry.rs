@@ -0,0 +1,736 @@
+//! Physical keyboard geometries for the visual keyboard map.
+//!
+//! The standard row-staggered layouts come from `keyboard_layouts`. Anything
+//! else (ortholinear, split, custom boards) is described in a small layout file
+//! format, either bundled with hyprKCS or placed in `~/.config/hyprkcs/layouts/`:
+//!
+//! ```text
+//! name = Corne
+//!
+//! row {
+//!     key = Tab, Tab, 15
+//!     key = Q, Q, 16
+//!     gap = 1.5
+//!     key = ",", comma, 51, w=1.5
+//!     key = Space, space, 57, x=7.5, y=3.25
+//! }
+//! ```
+//!
+//! Each `key` is `label, key name, evdev keycode` followed by optional `w`, `h`,
+//! `x` and `y` (all in key units). Keys are placed left to right unless `x` is
+//! given, and each `row` block sits one unit below the previous one unless `y`
+//! is given.
+
+use crate::config::constants;
+use crate::json::{self, JsonValue};
+use crate::ui::views::keyboard_layouts::{
+    get_layout_rows, KeyDef, ANSI_ROW_1, ANSI_ROW_2, ANSI_ROW_3, ANSI_ROW_4, ANSI_ROW_5,
+    ROW_ARROWS, ROW_FUNC,
+};
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeometryKey {
+    pub label: String,
+    pub hypr_name: String,
+    pub keycode: u32,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyboardGeometry {
+    pub name: String,
+    pub keys: Vec<GeometryKey>,
+}
+
+/// Standard layouts handled by `get_layout_rows`.
+pub const STANDARD_LAYOUTS: &[&str] = &["ANSI", "ISO", "JIS", "ABNT2", "HU"];
+
+/// Name of the built-in compact layout (ANSI without function row and arrows).
+pub const SIXTY_PERCENT: &str = "60%";
+
+const BUNDLED_LAYOUTS: &[&str] = &[
+    include_str!("layouts/planck.conf"),
+    include_str!("layouts/corne.conf"),
+];
+
+fn push_row(keys: &mut Vec<GeometryKey>, row: &[KeyDef], start_x: f64, y: f64) {
+    let mut x = start_x;
+    for k in row {
+        keys.push(GeometryKey {
+            label: k.label.to_string(),
+            hypr_name: k.hypr_name.to_string(),
+            keycode: k.keycode,
+            x,
+            y,
+            width: k.width,
+            height: 1.0,
+        });
+        x += k.width;
+    }
+}
+
+/// Builds the geometry of a standard row-staggered layout, including the
+/// function row and arrow cluster.
+pub fn standard_geometry(layout: &str) -> KeyboardGeometry {
+    let (row1, row2, row3, row4, row5) = get_layout_rows(layout);
+    let mut keys = Vec::new();
+
+    push_row(&mut keys, ROW_FUNC, 0.0, 0.0);
+    for (i, row) in [row1, row2, row3, row4, row5].iter().enumerate() {
+        push_row(&mut keys, row, 0.0, i as f64 + 1.0);
+    }
+    // Centered below the main block
+    push_row(&mut keys, ROW_ARROWS, 5.5, 6.0);
+
+    KeyboardGeometry {
+        name: layout.to_string(),
+        keys,
+    }
+}
+
+fn sixty_percent_geometry() -> KeyboardGeometry {
+    let mut keys = Vec::new();
+    for (i, row) in [ANSI_ROW_1, ANSI_ROW_2, ANSI_ROW_3, ANSI_ROW_4, ANSI_ROW_5]
+        .iter()
+        .enumerate()
+    {
+        push_row(&mut keys, row, 0.0, i as f64);
+    }
+    KeyboardGeometry {
+        name: SIXTY_PERCENT.to_string(),
+        keys,
+    }
+}
+
+/// Layout definitions shipped with hyprKCS.
+pub fn bundled_layouts() -> Vec<KeyboardGeometry> {
+    BUNDLED_LAYOUTS
+        .iter()
+        .filter_map(|content| parse_layout(content).ok())
+        .collect()
+}
+
+pub fn get_layouts_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| {
+        dir.join(constants::HYPRKCS_DIR)
+            .join(constants::LAYOUTS_DIR)
+    })
+}
+
+/// Loads every `*.conf` layout in `dir`, skipping files that fail to parse.
+pub fn load_layouts_from(dir: &Path) -> Vec<KeyboardGeometry> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "conf"))
+        .collect();
+    paths.sort();
+
+    let mut layouts = Vec::new();
+    for path in paths {
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| parse_layout(&content));
+        match parsed {
+            Ok(layout) => layouts.push(layout),
+            Err(e) => crate::log_warn!("keyboard", "Skipping layout {}: {}", path.display(), e),
+        }
+    }
+    layouts
+}
+
+pub fn load_user_layouts() -> Vec<KeyboardGeometry> {
+    get_layouts_dir()
+        .map(|dir| load_layouts_from(&dir))
+        .unwrap_or_default()
+}
+
+/// Names offered in layout pickers: standard layouts, the 60% variant,
+/// bundled layouts, then user layouts.
+pub fn list_geometry_names() -> Vec<String> {
+    let mut names: Vec<String> = STANDARD_LAYOUTS.iter().map(|s| s.to_string()).collect();
+    names.push(SIXTY_PERCENT.to_string());
+    for layout in bundled_layouts().into_iter().chain(load_user_layouts()) {
+        if !names.iter().any(|n| n.eq_ignore_ascii_case(&layout.name)) {
+            names.push(layout.name);
+        }
+    }
+    names
+}
+
+/// Resolves a layout name (case-insensitive). User layouts take precedence over
+/// bundled ones with the same name; unknown names fall back to ANSI.
+pub fn get_geometry(name: &str) -> KeyboardGeometry {
+    let upper = name.trim().to_uppercase();
+    if upper == "HUNGARIAN" || STANDARD_LAYOUTS.contains(&upper.as_str()) {
+        return standard_geometry(&upper);
+    }
+    if upper == SIXTY_PERCENT {
+        return sixty_percent_geometry();
+    }
+
+    load_user_layouts()
+        .into_iter()
+        .chain(bundled_layouts())
+        .find(|l| l.name.eq_ignore_ascii_case(name.trim()))
+        .unwrap_or_else(|| standard_geometry("ANSI"))
+}
+
+// --- Layout file format ---
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\\' if in_quotes && !escaped => {
+                escaped = true;
+                continue;
+            }
+            '"' if !escaped => in_quotes = !in_quotes,
+            '#' if !in_quotes => return &line[..i],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+/// Splits on commas outside of double quotes, unquoting quoted fields.
+fn split_fields(value: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if in_quotes => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if !quoted => {
+                // Drop whitespace before the opening quote
+                current.clear();
+                in_quotes = true;
+                quoted = true;
+            }
+            ',' if !in_quotes => {
+                fields.push(if quoted {
+                    std::mem::take(&mut current)
+                } else {
+                    current.trim().to_string()
+                });
+                current.clear();
+                quoted = false;
+            }
+            c if quoted && !in_quotes && c.is_whitespace() => {}
+            _ => current.push(c),
+        }
+    }
+    fields.push(if quoted {
+        current
+    } else {
+        current.trim().to_string()
+    });
+    fields
+}
+
+fn parse_number(value: &str, line_no: usize, what: &str) -> Result<f64> {
+    let n: f64 = value
+        .trim()
+        .parse()
+        .with_context(|| format!("line {}: invalid {} '{}'", line_no, what, value.trim()))?;
+    if !n.is_finite() || n < 0.0 {
+        bail!("line {}: {} must be a positive number", line_no, what);
+    }
+    Ok(n)
+}
+
+/// Parses a layout definition file.
+pub fn parse_layout(content: &str) -> Result<KeyboardGeometry> {
+    let mut geometry = KeyboardGeometry::default();
+    let mut in_row = false;
+    let mut row_index = 0usize;
+    let mut cursor_x = 0.0;
+
+    for (i, raw) in content.lines().enumerate() {
+        let line_no = i + 1;
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.strip_suffix('{').map(str::trim) == Some("row") {
+            if in_row {
+                bail!("line {}: nested row block", line_no);
+            }
+            in_row = true;
+            cursor_x = 0.0;
+            continue;
+        }
+
+        if line == "}" {
+            if !in_row {
+                bail!("line {}: unexpected '}}'", line_no);
+            }
+            in_row = false;
+            row_index += 1;
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            bail!("line {}: expected 'key = value'", line_no);
+        };
+        let value = value.trim();
+
+        match (key.trim(), in_row) {
+            ("name", false) => geometry.name = value.to_string(),
+            ("gap", true) => cursor_x += parse_number(value, line_no, "gap")?,
+            ("key", true) => {
+                let fields = split_fields(value);
+                if fields.len() < 3 {
+                    bail!("line {}: key needs 'label, key name, keycode'", line_no);
+                }
+
+                let keycode: u32 = fields[2].parse().with_context(|| {
+                    format!("line {}: invalid keycode '{}'", line_no, fields[2])
+                })?;
+
+                let mut k = GeometryKey {
+                    label: fields[0].clone(),
+                    hypr_name: fields[1].clone(),
+                    keycode,
+                    x: cursor_x,
+                    y: row_index as f64,
+                    width: 1.0,
+                    height: 1.0,
+                };
+
+                for opt in &fields[3..] {
+                    let Some((name, val)) = opt.split_once('=') else {
+                        bail!("line {}: invalid key option '{}'", line_no, opt);
+                    };
+                    match name.trim() {
+                        "w" => k.width = parse_number(val, line_no, "width")?,
+                        "h" => k.height = parse_number(val, line_no, "height")?,
+                        "x" => k.x = parse_number(val, line_no, "x")?,
+                        "y" => k.y = parse_number(val, line_no, "y")?,
+                        other => bail!("line {}: unknown key option '{}'", line_no, other),
+                    }
+                }
+
+                if k.width <= 0.0 || k.height <= 0.0 {
+                    bail!("line {}: key size must be greater than zero", line_no);
+                }
+
+                cursor_x = k.x + k.width;
+                geometry.keys.push(k);
+            }
+            (other, true) => bail!("line {}: unknown row setting '{}'", line_no, other),
+            (other, false) => bail!("line {}: unknown setting '{}'", line_no, other),
+        }
+    }
+
+    if in_row {
+        bail!("unterminated row block");
+    }
+    if geometry.name.is_empty() {
+        bail!("missing 'name'");
+    }
+    if geometry.keys.is_empty() {
+        bail!("layout '{}' has no keys", geometry.name);
+    }
+
+    Ok(geometry)
+}
+
+fn format_number(n: f64) -> String {
+    let rounded = (n * 1000.0).round() / 1000.0;
+    format!("{}", rounded)
+}
+
+fn quote_field(s: &str) -> String {
+    if s.is_empty() || s.contains([',', '"', '#', '\\']) || s.trim() != s {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Writes a geometry in the layout file format. Keys are grouped into rows by
+/// their vertical position; `x`/`y` are only written where they differ from the
+/// implied position.
+pub fn serialize_layout(geometry: &KeyboardGeometry) -> String {
+    let mut keys: Vec<&GeometryKey> = geometry.keys.iter().collect();
+    keys.sort_by(|a, b| {
+        a.y.floor()
+            .total_cmp(&b.y.floor())
+            .then(a.x.total_cmp(&b.x))
+    });
+
+    let mut out = String::from(
+        "# hyprKCS keyboard layout\n\
+         # key = label, key name, evdev keycode[, w=width][, h=height][, x=column][, y=row]\n\n",
+    );
+    out.push_str(&format!("name = {}\n", geometry.name));
+
+    let mut row_index = 0usize;
+    let mut idx = 0;
+    while idx < keys.len() {
+        let row_floor = keys[idx].y.floor();
+        out.push_str("\nrow {\n");
+
+        let mut cursor_x = 0.0;
+        while idx < keys.len() && keys[idx].y.floor() == row_floor {
+            let k = keys[idx];
+            let mut line = format!(
+                "    key = {}, {}, {}",
+                quote_field(&k.label),
+                quote_field(&k.hypr_name),
+                k.keycode
+            );
+            if (k.width - 1.0).abs() > f64::EPSILON {
+                line.push_str(&format!(", w={}", format_number(k.width)));
+            }
+            if (k.height - 1.0).abs() > f64::EPSILON {
+                line.push_str(&format!(", h={}", format_number(k.height)));
+            }
+            if (k.x - cursor_x).abs() > 1e-6 {
+                line.push_str(&format!(", x={}", format_number(k.x)));
+            }
+            if (k.y - row_index as f64).abs() > 1e-6 {
+                line.push_str(&format!(", y={}", format_number(k.y)));
+            }
+            out.push_str(&line);
+            out.push('\n');
+
+            cursor_x = k.x + k.width;
+            idx += 1;
+        }
+
+        out.push_str("}\n");
+        row_index += 1;
+    }
+
+    out
+}
+
+fn layout_file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let stem = stem.trim_matches('-').to_string();
+    if stem.is_empty() {
+        "layout".to_string()
+    } else {
+        stem
+    }
+}
+
+/// Saves a layout into `dir` without overwriting existing files.
+pub fn save_layout_to(dir: &Path, geometry: &KeyboardGeometry) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let stem = layout_file_stem(&geometry.name);
+    let mut path = dir.join(format!("{}.conf", stem));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.conf", stem, n));
+        n += 1;
+    }
+
+    std::fs::write(&path, serialize_layout(geometry))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+pub fn save_user_layout(geometry: &KeyboardGeometry) -> Result<PathBuf> {
+    let dir = get_layouts_dir().context("Could not determine config directory")?;
+    save_layout_to(&dir, geometry)
+}
+
+// --- QMK / VIA import ---
+
+/// Maps a legend to a key name and evdev keycode.
+fn lookup_label(label: &str) -> Option<(String, u32)> {
+    let lower = label.trim().to_lowercase();
+    if lower.is_empty() {
+        return None;
+    }
+
+    let alias = match lower.as_str() {
+        "esc" | "escape" => Some(("Escape", 1)),
+        "bksp" | "bspc" | "backspace" | "back space" => Some(("BackSpace", 14)),
+        "enter" | "ent" | "return" => Some(("Return", 28)),
+        "space" | "spc" | "spacebar" => Some(("space", 57)),
+        "ctrl" | "control" | "lctrl" | "lctl" => Some(("Control_L", 29)),
+        "rctrl" | "rctl" => Some(("Control_R", 97)),
+        "shift" | "lshift" | "lsft" => Some(("Shift_L", 42)),
+        "rshift" | "rsft" => Some(("Shift_R", 54)),
+        "alt" | "lalt" | "opt" | "option" => Some(("Alt_L", 56)),
+        "ralt" | "altgr" | "alt gr" => Some(("Alt_R", 100)),
+        "gui" | "lgui" | "super" | "sup" | "win" | "cmd" | "meta" => Some(("Super_L", 125)),
+        "rgui" => Some(("Super_R", 126)),
+        "caps" | "caps lock" | "capslock" => Some(("Caps_Lock", 58)),
+        "del" | "delete" => Some(("Delete", 111)),
+        "tab" => Some(("Tab", 15)),
+        "menu" | "app" | "apps" => Some(("Menu", 139)),
+        "left" | "←" => Some(("Left", 105)),
+        "right" | "→" => Some(("Right", 106)),
+        "up" | "↑" => Some(("Up", 103)),
+        "down" | "↓" => Some(("Down", 108)),
+        _ => None,
+    };
+    if let Some((name, code)) = alias {
+        return Some((name.to_string(), code));
+    }
+
+    [
+        ROW_FUNC, ANSI_ROW_1, ANSI_ROW_2, ANSI_ROW_3, ANSI_ROW_4, ANSI_ROW_5,
+    ]
+    .iter()
+    .flat_map(|row| row.iter())
+    .find(|k| k.label.to_lowercase() == lower || k.hypr_name.to_lowercase() == lower)
+    .map(|k| (k.hypr_name.to_string(), k.keycode))
+}
+
+const ORTHO_NUMBER_ROW: &[(&str, &str, u32)] = &[
+    ("`", "grave", 41),
+    ("1", "1", 2),
+    ("2", "2", 3),
+    ("3", "3", 4),
+    ("4", "4", 5),
+    ("5", "5", 6),
+    ("6", "6", 7),
+    ("7", "7", 8),
+    ("8", "8", 9),
+    ("9", "9", 10),
+    ("0", "0", 11),
+    ("-", "minus", 12),
+];
+
+const ORTHO_TOP_ROW: &[(&str, &str, u32)] = &[
+    ("Tab", "Tab", 15),
+    ("Q", "Q", 16),
+    ("W", "W", 17),
+    ("E", "E", 18),
+    ("R", "R", 19),
+    ("T", "T", 20),
+    ("Y", "Y", 21),
+    ("U", "U", 22),
+    ("I", "I", 23),
+    ("O", "O", 24),
+    ("P", "P", 25),
+    ("Bksp", "BackSpace", 14),
+];
+
+const ORTHO_HOME_ROW: &[(&str, &str, u32)] = &[
+    ("Ctrl", "Control_L", 29),
+    ("A", "A", 30),
+    ("S", "S", 31),
+    ("D", "D", 32),
+    ("F", "F", 33),
+    ("G", "G", 34),
+    ("H", "H", 35),
+    ("J", "J", 36),
+    ("K", "K", 37),
+    ("L", "L", 38),
+    (";", "semicolon", 39),
+    ("'", "apostrophe", 40),
+];
+
+const ORTHO_BOTTOM_ROW: &[(&str, &str, u32)] = &[
+    ("Shift", "Shift_L", 42),
+    ("Z", "Z", 44),
+    ("X", "X", 45),
+    ("C", "C", 46),
+    ("V", "V", 47),
+    ("B", "B", 48),
+    ("N", "N", 49),
+    ("M", "M", 50),
+    (",", "comma", 51),
+    (".", "period", 52),
+    ("/", "slash", 53),
+    ("Shift", "Shift_R", 54),
+];
+
+/// Fills in unlabeled keys of ortholinear/split boards by position: rows of 10
+/// or 12 keys get the usual alpha block (plus a number row on 5-row boards).
+/// Anything else is left unassigned for the user to edit.
+fn assign_by_position(keys: &mut [GeometryKey]) {
+    let mut rows: Vec<f64> = keys.iter().map(|k| k.y.round()).collect();
+    rows.sort_by(f64::total_cmp);
+    rows.dedup();
+
+    let templates: Vec<&[(&str, &str, u32)]> = if rows.len() >= 5 {
+        vec![
+            ORTHO_NUMBER_ROW,
+            ORTHO_TOP_ROW,
+            ORTHO_HOME_ROW,
+            ORTHO_BOTTOM_ROW,
+        ]
+    } else {
+        vec![ORTHO_TOP_ROW, ORTHO_HOME_ROW, ORTHO_BOTTOM_ROW]
+    };
+
+    for (row_y, template) in rows.iter().zip(templates) {
+        let mut row_keys: Vec<&mut GeometryKey> =
+            keys.iter_mut().filter(|k| k.y.round() == *row_y).collect();
+        row_keys.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+        let template = match row_keys.len() {
+            12 => template,
+            10 => &template[1..11],
+            _ => continue,
+        };
+
+        for (k, (label, name, code)) in row_keys.into_iter().zip(template) {
+            if k.keycode == 0 && k.hypr_name.is_empty() {
+                k.label = label.to_string();
+                k.hypr_name = name.to_string();
+                k.keycode = *code;
+            }
+        }
+    }
+}
+
+fn import_key(label: &str, x: f64, y: f64, width: f64, height: f64) -> GeometryKey {
+    let (hypr_name, keycode) = lookup_label(label).unwrap_or_default();
+    GeometryKey {
+        label: label.to_string(),
+        hypr_name,
+        keycode,
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+fn number_field(value: &JsonValue, key: &str, default: f64) -> f64 {
+    value
+        .get(key)
+        .and_then(JsonValue::as_f64)
+        .unwrap_or(default)
+}
+
+/// Reads keys from VIA's KLE-style `layouts.keymap` array.
+fn import_kle_rows(rows: &[JsonValue]) -> Vec<GeometryKey> {
+    let mut keys = Vec::new();
+    let mut y = 0.0;
+
+    for row in rows {
+        let Some(items) = row.as_array() else {
+            continue;
+        };
+        let mut x = 0.0;
+        let (mut w, mut h) = (1.0, 1.0);
+
+        for item in items {
+            match item {
+                JsonValue::Object(_) => {
+                    x += number_field(item, "x", 0.0);
+                    y += number_field(item, "y", 0.0);
+                    w = number_field(item, "w", 1.0);
+                    h = number_field(item, "h", 1.0);
+                }
+                JsonValue::String(legend) => {
+                    // VIA legends hold matrix positions ("0,1"), not key names
+                    let label = legend.split('\n').next().unwrap_or("");
+                    let label = if label.contains(',') { "" } else { label };
+                    keys.push(import_key(label, x, y, w, h));
+                    x += w;
+                    w = 1.0;
+                    h = 1.0;
+                }
+                _ => {}
+            }
+        }
+        y += 1.0;
+    }
+
+    keys
+}
+
+/// Converts a QMK `info.json` (or a VIA definition) into a layout.
+///
+/// For QMK files `layout_name` selects the `LAYOUT_*` macro to use; by default
+/// `LAYOUT` is preferred, then the first one found. Legends are mapped to key
+/// names where possible, and unlabeled ortholinear rows are filled in by
+/// position.
+pub fn import_qmk_info(content: &str, layout_name: Option<&str>) -> Result<KeyboardGeometry> {
+    let root = json::parse(content).context("Not a valid JSON file")?;
+
+    let name = ["keyboard_name", "name"]
+        .iter()
+        .map(|k| root.str_field(k))
+        .find(|n| !n.is_empty())
+        .unwrap_or_else(|| "Imported".to_string());
+
+    let layouts = root
+        .get("layouts")
+        .context("No 'layouts' section found (expected QMK info.json or VIA definition)")?;
+
+    let mut keys = if let Some(kle) = layouts.get("keymap").and_then(JsonValue::as_array) {
+        import_kle_rows(kle)
+    } else {
+        let map = layouts
+            .as_object()
+            .context("'layouts' has an unexpected format")?;
+        let (_, chosen) = match layout_name {
+            Some(wanted) => map
+                .iter()
+                .find(|(k, _)| k.as_str() == wanted)
+                .with_context(|| format!("Layout '{}' not found", wanted))?,
+            None => map
+                .iter()
+                .find(|(k, _)| k.as_str() == "LAYOUT")
+                .or_else(|| map.iter().next())
+                .context("No layouts defined")?,
+        };
+
+        chosen
+            .get("layout")
+            .and_then(JsonValue::as_array)
+            .context("Layout has no 'layout' key list")?
+            .iter()
+            .map(|k| {
+                import_key(
+                    &k.str_field("label"),
+                    number_field(k, "x", 0.0),
+                    number_field(k, "y", 0.0),
+                    number_field(k, "w", 1.0),
+                    number_field(k, "h", 1.0),
+                )
+            })
+            .collect()
+    };
+
+    if keys.is_empty() {
+        bail!("Layout '{}' has no keys", name);
+    }
+
+    assign_by_position(&mut keys);
+
+    Ok(KeyboardGeometry { name, keys })
+}
@@ -0,0 +1,280 @@
+use crate::ui::utils::components::{collect_submaps, create_close_button};
+use crate::ui::utils::export::export_widget_image;
+use crate::ui::utils::keybinds::model_keybinds;
+use crate::ui::utils::layers::{build_layers, KeyLayer};
+use crate::ui::views::keyboard::resolve_key;
+use crate::ui::views::keyboard_geometry::KeyboardGeometry;
+use crate::xkb_handler::XkbHandler;
+use gtk::{gio, prelude::*};
+use gtk4 as gtk;
+use std::rc::Rc;
+
+/// Pixel size of one key unit in the chart, chosen to leave room for two
+/// lines of action text per key.
+const UNIT_WIDTH: f64 = 64.0;
+const UNIT_HEIGHT: f64 = 56.0;
+
+fn create_layer_card(
+    layer: &KeyLayer,
+    geometry: &KeyboardGeometry,
+    xkb: &Option<XkbHandler>,
+    prefer_description: bool,
+) -> gtk::Box {
+    let card = gtk::Box::new(gtk::Orientation::Vertical, 6);
+
+    let title = gtk::Label::builder()
+        .label(layer.title())
+        .css_classes(["title-4"])
+        .halign(gtk::Align::Start)
+        .build();
+    card.append(&title);
+
+    let grid = gtk::Grid::builder()
+        .column_homogeneous(true)
+        .row_homogeneous(true)
+        .column_spacing(2)
+        .row_spacing(2)
+        .css_classes(["keyboard-container"])
+        .build();
+
+    let to_cells = |units: f64| (units * 4.0).round() as i32;
+
+    for k in &geometry.keys {
+        let (label, norm_key) = resolve_key(k, xkb);
+        let actions = layer.keys.get(&norm_key);
+
+        let cell = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .css_classes(["keyboard-key", "layer-key"])
+            .width_request((k.width * UNIT_WIDTH) as i32)
+            .height_request((k.height * UNIT_HEIGHT) as i32)
+            .build();
+
+        cell.append(
+            &gtk::Label::builder()
+                .label(&label)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .build(),
+        );
+
+        if let Some(actions) = actions {
+            cell.add_css_class("accent");
+
+            let text = actions
+                .iter()
+                .map(|a| a.label(prefer_description))
+                .collect::<Vec<_>>()
+                .join(" / ");
+            let action_label = gtk::Label::builder()
+                .label(&text)
+                .css_classes(["layer-action"])
+                .wrap(true)
+                .wrap_mode(gtk::pango::WrapMode::WordChar)
+                .lines(2)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .justify(gtk::Justification::Center)
+                .max_width_chars(1)
+                .hexpand(true)
+                .vexpand(true)
+                .build();
+            cell.append(&action_label);
+
+            let tooltip = actions
+                .iter()
+                .map(|a| match &a.description {
+                    Some(desc) => format!("{} ({})", a.action_text(), desc),
+                    None => a.action_text(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            cell.set_tooltip_text(Some(&tooltip));
+        }
+
+        grid.attach(
+            &cell,
+            to_cells(k.x),
+            to_cells(k.y),
+            to_cells(k.width).max(1),
+            to_cells(k.height).max(1),
+        );
+    }
+
+    card.append(&grid);
+    card
+}
+
+/// A printable chart with the full keyboard drawn once per modifier
+/// combination and submap.
+pub fn create_layers_view(
+    stack: &gtk::Stack,
+    model: &gio::ListStore,
+    geometry: KeyboardGeometry,
+    xkb: Option<XkbHandler>,
+) -> gtk::Box {
+    let container = gtk::Box::new(gtk::Orientation::Vertical, 8);
+    container.set_margin_top(8);
+    container.set_margin_bottom(8);
+    container.set_margin_start(12);
+    container.set_margin_end(12);
+    container.set_vexpand(true);
+    container.set_hexpand(true);
+
+    // Header
+    let header_box = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+
+    let back_btn = gtk::Button::builder()
+        .icon_name("go-previous-symbolic")
+        .css_classes(["flat", "circular"])
+        .tooltip_text("Back to Keyboard")
+        .build();
+
+    let stack_weak = stack.downgrade();
+    back_btn.connect_clicked(move |_| {
+        if let Some(s) = stack_weak.upgrade() {
+            s.set_visible_child_name("keyboard");
+        }
+    });
+
+    let title = gtk::Label::builder()
+        .label(format!("Layer Chart ({})", geometry.name))
+        .css_classes(["title-2"])
+        .hexpand(true)
+        .halign(gtk::Align::Center)
+        .build();
+
+    let export_btn = gtk::Button::builder()
+        .icon_name("document-save-as-symbolic")
+        .css_classes(["flat", "circular"])
+        .tooltip_text("Export chart as PNG or SVG")
+        .build();
+
+    header_box.append(&back_btn);
+    header_box.append(&title);
+    header_box.append(&export_btn);
+    header_box.append(&create_close_button());
+    container.append(&header_box);
+
+    // Filters
+    let filter_box = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+    filter_box.set_halign(gtk::Align::Center);
+
+    filter_box.append(&gtk::Label::new(Some("Submap:")));
+    let mut submap_items = vec!["All".to_string(), "Global (Root)".to_string()];
+    submap_items.extend(collect_submaps(model));
+    let submap_dropdown = gtk::DropDown::from_strings(
+        &submap_items
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<&str>>(),
+    );
+    filter_box.append(&submap_dropdown);
+
+    filter_box.append(&gtk::Label::new(Some("Labels:")));
+    let label_dropdown = gtk::DropDown::from_strings(&["Description", "Action"]);
+    label_dropdown.set_tooltip_text(Some(
+        "Show bind descriptions where available, or always the dispatcher and arguments",
+    ));
+    filter_box.append(&label_dropdown);
+    container.append(&filter_box);
+
+    let status_label = gtk::Label::builder()
+        .css_classes(["dim-label"])
+        .ellipsize(gtk::pango::EllipsizeMode::End)
+        .build();
+    container.append(&status_label);
+
+    // Chart
+    let chart_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(24)
+        .css_classes(["layer-chart"])
+        .build();
+
+    let scrolled = gtk::ScrolledWindow::builder()
+        .child(&chart_box)
+        .hexpand(true)
+        .vexpand(true)
+        .build();
+    container.append(&scrolled);
+
+    let layers = build_layers(&model_keybinds(model));
+
+    let render = {
+        let chart_box = chart_box.clone();
+        let status_label = status_label.clone();
+        let submap_dropdown = submap_dropdown.clone();
+        let label_dropdown = label_dropdown.clone();
+        move || {
+            while let Some(child) = chart_box.first_child() {
+                chart_box.remove(&child);
+            }
+
+            let prefer_description = label_dropdown.selected() == 0;
+            let filter = match submap_dropdown.selected() {
+                0 => None,
+                1 => Some(None),
+                i => submap_items.get(i as usize).map(|s| Some(s.as_str())),
+            };
+
+            let mut shown = 0;
+            for layer in &layers {
+                if let Some(wanted) = filter {
+                    if layer.submap.as_deref() != wanted {
+                        continue;
+                    }
+                }
+                chart_box.append(&create_layer_card(
+                    layer,
+                    &geometry,
+                    &xkb,
+                    prefer_description,
+                ));
+                shown += 1;
+            }
+
+            status_label.set_label(&match shown {
+                0 => "No keybinds in this selection".to_string(),
+                1 => "1 layer".to_string(),
+                n => format!("{} layers", n),
+            });
+        }
+    };
+    let render = Rc::new(render);
+
+    let r1 = render.clone();
+    submap_dropdown.connect_selected_notify(move |_| r1());
+    let r2 = render.clone();
+    label_dropdown.connect_selected_notify(move |_| r2());
+
+    let chart_weak = chart_box.downgrade();
+    let status_weak = status_label.downgrade();
+    export_btn.connect_clicked(move |btn| {
+        let dialog = gtk::FileDialog::builder()
+            .title("Export Layer Chart")
+            .initial_name("hyprkcs-layers.png")
+            .modal(true)
+            .build();
+
+        let window = btn.root().and_downcast::<gtk::Window>();
+        let chart_weak = chart_weak.clone();
+        let status_weak = status_weak.clone();
+
+        dialog.save(window.as_ref(), None::<&gio::Cancellable>, move |res| {
+            let (Some(chart), Some(status)) = (chart_weak.upgrade(), status_weak.upgrade()) else {
+                return;
+            };
+            let Some(path) = res.ok().and_then(|f| f.path()) else {
+                return;
+            };
+
+            match export_widget_image(&chart, &path) {
+                Ok(_) => status.set_label(&format!("Exported to {}", path.display())),
+                Err(e) => status.set_label(&format!("Export failed: {}", e)),
+            }
+        });
+    });
+
+    render();
+
+    container
+}
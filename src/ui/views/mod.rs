@@ -1,10 +1,23 @@
 mod add;
 mod edit;
+mod free_keys;
 mod keyboard;
+pub mod keyboard_geometry;
 pub mod keyboard_layouts;
+mod layers;
+mod problems;
 mod restore;
+mod scripts;
+mod searches;
+mod sources;
+mod submap_graph;
 
-pub use add::create_add_view;
+pub use add::{create_add_view, open_add_view};
 pub use edit::create_edit_view;
 pub use keyboard::create_keyboard_view;
+pub use problems::{create_problems_view, current_problems};
 pub use restore::create_restore_view;
+pub use scripts::create_scripts_view;
+pub use searches::create_searches_view;
+pub use sources::create_sources_view;
+pub use submap_graph::create_submap_graph_view;
@@ -0,0 +1,102 @@
+use crate::lint::collect_problems;
+use crate::parser::{get_diagnostics, Diagnostic, Severity};
+use crate::ui::utils::components::{create_card_row, create_page_header, open_file};
+use crate::ui::utils::create_pill_button;
+use crate::ui::utils::keybinds::model_keybinds;
+use gtk::{gio, prelude::*};
+use gtk4 as gtk;
+use libadwaita as adw;
+
+/// Parser diagnostics and lint issues for the binds currently in `model`.
+pub fn current_problems(model: &gio::ListStore) -> Vec<Diagnostic> {
+    let diagnostics = get_diagnostics().unwrap_or_default();
+    collect_problems(&model_keybinds(model), &diagnostics)
+}
+
+fn location(diagnostic: &Diagnostic) -> String {
+    if diagnostic.line > 0 {
+        format!("{}:{}", diagnostic.file.display(), diagnostic.line)
+    } else {
+        diagnostic.file.display().to_string()
+    }
+}
+
+/// Lists config lines the parser skipped or only partly understood, and binds
+/// the linter flags, each with a button to open the file.
+pub fn create_problems_view(stack: &gtk::Stack, model: &gio::ListStore) -> gtk::Box {
+    let container = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let mut problems = current_problems(model);
+    problems.sort_by(|a, b| {
+        a.severity
+            .cmp(&b.severity)
+            .then_with(|| a.file.cmp(&b.file))
+            .then(a.line.cmp(&b.line))
+    });
+
+    let count = |severity| problems.iter().filter(|p| p.severity == severity).count();
+    let mut subtitle = format!(
+        "{} error(s), {} warning(s)",
+        count(Severity::Error),
+        count(Severity::Warning)
+    );
+    let notes = count(Severity::Note);
+    if notes > 0 {
+        subtitle.push_str(&format!(", {} note(s)", notes));
+    }
+
+    let stack_weak = stack.downgrade();
+    let header = create_page_header("Problems", Some(&subtitle), "Back", move || {
+        if let Some(s) = stack_weak.upgrade() {
+            s.set_visible_child_name("home");
+        }
+    });
+    container.append(&header);
+
+    if problems.is_empty() {
+        let empty = adw::StatusPage::builder()
+            .title("No Problems")
+            .description("Every line in your config was parsed.")
+            .icon_name("object-select-symbolic")
+            .vexpand(true)
+            .build();
+        container.append(&empty);
+        return container;
+    }
+
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .build();
+    let list_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(8)
+        .build();
+    scroll.set_child(Some(&list_box));
+    container.append(&scroll);
+
+    for problem in problems {
+        let open_btn = create_pill_button("Open", Some("document-open-symbolic"));
+        open_btn.set_tooltip_text(Some("Open the file in your editor"));
+
+        let row = create_card_row(&problem.message, Some(&location(&problem)), &open_btn);
+        row.add_css_class(match problem.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "dim-label",
+        });
+
+        let path = problem.file;
+        open_btn.connect_clicked(move |btn| open_file(btn, &path));
+        list_box.append(&row);
+    }
+
+    container
+}
@@ -0,0 +1,202 @@
+use crate::parser::Keybind;
+use crate::ui::utils::components::{create_card_row, create_page_header, open_file};
+use crate::ui::utils::keybinds::model_keybinds;
+use crate::ui::utils::scripts::{
+    create_script, display_path, missing_scripts, script_usage, scripts_dir,
+};
+use crate::ui::utils::{create_destructive_button, create_pill_button, reload_keybinds};
+use gtk::{gio, prelude::*};
+use gtk4 as gtk;
+use libadwaita as adw;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+fn describe_bind(kb: &Keybind) -> String {
+    let combo = if kb.mods.trim().is_empty() {
+        kb.key.to_string()
+    } else {
+        format!("{} + {}", kb.mods, kb.key)
+    };
+    match kb.submap.as_deref() {
+        Some(submap) => format!("{} [{}]", combo, submap),
+        None => combo,
+    }
+}
+
+fn section_label(text: &str) -> gtk::Label {
+    gtk::Label::builder()
+        .label(text)
+        .halign(gtk::Align::Start)
+        .css_classes(["heading"])
+        .margin_top(6)
+        .build()
+}
+
+fn show_toast(toast_overlay: &adw::ToastOverlay, title: &str) {
+    let toast = adw::Toast::builder()
+        .title(title)
+        .timeout(crate::config::constants::TOAST_TIMEOUT)
+        .build();
+    toast_overlay.add_toast(toast);
+}
+
+fn populate(
+    list_box: &gtk::Box,
+    dir: &Path,
+    model: &gio::ListStore,
+    toast_overlay: &adw::ToastOverlay,
+) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+
+    let keybinds = model_keybinds(model);
+    let usage = script_usage(dir, &keybinds);
+    let missing = missing_scripts(dir, &keybinds);
+
+    let refresh = {
+        let list_box = list_box.downgrade();
+        let dir = dir.to_path_buf();
+        let model = model.clone();
+        let toast_overlay = toast_overlay.clone();
+        Rc::new(move || {
+            if let Some(list_box) = list_box.upgrade() {
+                populate(&list_box, &dir, &model, &toast_overlay);
+            }
+        })
+    };
+
+    if !missing.is_empty() {
+        list_box.append(&section_label("Missing Scripts"));
+        for (idx, path) in missing {
+            let kb = &keybinds[idx];
+            let title = format!("{} → {}", describe_bind(kb), display_path(&path));
+            let subtitle = format!("{}:{}", kb.file_path.display(), kb.line_number + 1);
+
+            let create_btn = create_pill_button("Create Stub", None);
+            create_btn.set_tooltip_text(Some("Create an empty script at this path"));
+            let row = create_card_row(&title, Some(&subtitle), &create_btn);
+            row.add_css_class("error");
+
+            let toast_overlay = toast_overlay.clone();
+            let model = model.clone();
+            let refresh = refresh.clone();
+            create_btn.connect_clicked(move |_| match create_script(&path, "") {
+                Ok(()) => {
+                    show_toast(&toast_overlay, "Script created");
+                    reload_keybinds(&model);
+                    refresh();
+                }
+                Err(e) => show_toast(&toast_overlay, &format!("Error: {}", e)),
+            });
+            list_box.append(&row);
+        }
+    }
+
+    list_box.append(&section_label("Scripts"));
+    if usage.is_empty() {
+        let empty = adw::StatusPage::builder()
+            .title("No Scripts Yet")
+            .description("Use \"Extract to Script\" when editing an exec keybind to move it here.")
+            .icon_name("text-x-script-symbolic")
+            .vexpand(true)
+            .build();
+        list_box.append(&empty);
+        return;
+    }
+
+    for script in usage {
+        let name = script
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let subtitle = if script.is_orphaned() {
+            "Orphaned: no keybind calls this script".to_string()
+        } else {
+            let binds: Vec<String> = script
+                .used_by
+                .iter()
+                .map(|&i| describe_bind(&keybinds[i]))
+                .collect();
+            format!("Used by {}", binds.join(", "))
+        };
+
+        let actions = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let open_btn = create_pill_button("Open", None);
+        actions.append(&open_btn);
+
+        let path: PathBuf = script.path.clone();
+        open_btn.connect_clicked(move |btn| open_file(btn, &path));
+
+        if script.is_orphaned() {
+            let delete_btn = create_destructive_button("Delete", None);
+            actions.append(&delete_btn);
+
+            let path = script.path.clone();
+            let toast_overlay = toast_overlay.clone();
+            let refresh = refresh.clone();
+            delete_btn.connect_clicked(move |_| match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    show_toast(&toast_overlay, "Script deleted");
+                    refresh();
+                }
+                Err(e) => show_toast(&toast_overlay, &format!("Error: {}", e)),
+            });
+        }
+
+        let row = create_card_row(&name, Some(&subtitle), &actions);
+        if script.is_orphaned() {
+            row.add_css_class("dim-label");
+        }
+        list_box.append(&row);
+    }
+}
+
+/// Browses the managed script library, flagging scripts no bind uses and binds
+/// calling scripts that are gone.
+pub fn create_scripts_view(
+    stack: &gtk::Stack,
+    model: &gio::ListStore,
+    toast_overlay: &adw::ToastOverlay,
+) -> gtk::Box {
+    let container = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let dir = scripts_dir().ok();
+    let subtitle = dir
+        .as_deref()
+        .map(display_path)
+        .unwrap_or_else(|| "Could not determine config directory".to_string());
+
+    let stack_weak = stack.downgrade();
+    let header = create_page_header("Script Library", Some(&subtitle), "Back", move || {
+        if let Some(s) = stack_weak.upgrade() {
+            s.set_visible_child_name("home");
+        }
+    });
+    container.append(&header);
+
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .build();
+    let list_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(8)
+        .build();
+    scroll.set_child(Some(&list_box));
+    container.append(&scroll);
+
+    if let Some(dir) = dir {
+        populate(&list_box, &dir, model, toast_overlay);
+    }
+
+    container
+}
@@ -0,0 +1,229 @@
+use crate::config::categories::{find_category, load_categories, RESERVED_NAMES};
+use crate::config::searches::{
+    load_saved_searches, save_saved_searches, upsert_saved_search, SavedSearch,
+};
+use crate::ui::utils::components::{
+    create_card_row, create_flat_button, create_page_header, create_pill_button,
+    create_suggested_button,
+};
+use crate::ui::utils::SearchQuery;
+use gtk::prelude::*;
+use gtk4 as gtk;
+use libadwaita as adw;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type RefreshHandle = Rc<RefCell<Option<Rc<dyn Fn()>>>>;
+
+/// Lists the saved searches and saves the current one. Pinned searches show
+/// up in the category dropdown; `on_changed` is called after every change so
+/// it can be rebuilt.
+pub fn create_searches_view(
+    stack: &gtk::Stack,
+    current_query: &str,
+    toast_overlay: &adw::ToastOverlay,
+    on_apply: Rc<dyn Fn(&str)>,
+    on_changed: Rc<dyn Fn()>,
+) -> gtk::Box {
+    let container = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let stack_weak = stack.downgrade();
+    let header = create_page_header(
+        "Saved Searches",
+        Some("Pinned searches appear in the category list"),
+        "Back",
+        move || {
+            if let Some(s) = stack_weak.upgrade() {
+                s.set_visible_child_name("home");
+            }
+        },
+    );
+    container.append(&header);
+
+    // Save the current search
+    let save_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    let name_entry = gtk::Entry::builder()
+        .placeholder_text("Name")
+        .activates_default(true)
+        .build();
+    let pin_check = gtk::CheckButton::builder()
+        .label("Pin")
+        .active(true)
+        .tooltip_text("Show in the category list")
+        .build();
+    let save_btn = create_suggested_button("Save", Some("document-save-symbolic"));
+    save_box.append(&name_entry);
+    save_box.append(&pin_check);
+    save_box.append(&save_btn);
+
+    let query_label = if current_query.trim().is_empty() {
+        "Type a search in the main list first".to_string()
+    } else {
+        current_query.to_string()
+    };
+    let save_row = create_card_row("Save Current Search", Some(&query_label), &save_box);
+    save_btn.set_sensitive(!current_query.trim().is_empty());
+    name_entry.set_sensitive(!current_query.trim().is_empty());
+    container.append(&save_row);
+
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .build();
+    let list_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(8)
+        .build();
+    scroll.set_child(Some(&list_box));
+    container.append(&scroll);
+
+    let refresh: RefreshHandle = Rc::new(RefCell::new(None));
+
+    let list_weak = list_box.downgrade();
+    let refresh_c = refresh.clone();
+    let on_changed_c = on_changed.clone();
+    let rebuild: Rc<dyn Fn()> = Rc::new(move || {
+        let Some(list_box) = list_weak.upgrade() else {
+            return;
+        };
+        while let Some(child) = list_box.first_child() {
+            list_box.remove(&child);
+        }
+
+        let searches = load_saved_searches();
+        if searches.is_empty() {
+            let empty = adw::StatusPage::builder()
+                .title("No Saved Searches")
+                .description("Save a search to reuse it or pin it to the category list.")
+                .icon_name("edit-find-symbolic")
+                .vexpand(true)
+                .build();
+            list_box.append(&empty);
+            return;
+        }
+
+        for search in searches {
+            let actions = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            let apply_btn = create_pill_button("Apply", Some("edit-find-symbolic"));
+            let pin_btn = gtk::ToggleButton::builder()
+                .icon_name("view-pin-symbolic")
+                .tooltip_text("Pin to the category list")
+                .active(search.pinned)
+                .css_classes(["flat", "small"])
+                .valign(gtk::Align::Center)
+                .build();
+            let delete_btn = create_flat_button("user-trash-symbolic", "Delete");
+            delete_btn.set_valign(gtk::Align::Center);
+            actions.append(&apply_btn);
+            actions.append(&pin_btn);
+            actions.append(&delete_btn);
+
+            let errors = SearchQuery::parse(&search.query).errors;
+            let subtitle = if errors.is_empty() {
+                search.query.clone()
+            } else {
+                format!("{} · {}", search.query, errors.join(", "))
+            };
+            let row = create_card_row(&search.name, Some(&subtitle), &actions);
+            if !errors.is_empty() {
+                row.add_css_class("warning");
+            }
+
+            let query = search.query.clone();
+            let on_apply = on_apply.clone();
+            apply_btn.connect_clicked(move |_| on_apply(&query));
+
+            let name = search.name.clone();
+            let on_changed = on_changed_c.clone();
+            pin_btn.connect_toggled(move |btn| {
+                let mut searches = load_saved_searches();
+                if let Some(s) = searches.iter_mut().find(|s| s.name == name) {
+                    s.pinned = btn.is_active();
+                }
+                if let Err(e) = save_saved_searches(&searches) {
+                    crate::log_error!("ui", "Failed to save searches: {}", e);
+                }
+                on_changed();
+            });
+
+            let name = search.name.clone();
+            let on_changed = on_changed_c.clone();
+            let refresh = refresh_c.clone();
+            delete_btn.connect_clicked(move |_| {
+                let mut searches = load_saved_searches();
+                searches.retain(|s| s.name != name);
+                if let Err(e) = save_saved_searches(&searches) {
+                    crate::log_error!("ui", "Failed to save searches: {}", e);
+                }
+                on_changed();
+                if let Some(f) = refresh.borrow().as_ref() {
+                    f();
+                }
+            });
+
+            list_box.append(&row);
+        }
+    });
+    *refresh.borrow_mut() = Some(rebuild.clone());
+    rebuild();
+
+    let query = current_query.trim().to_string();
+    let toast_overlay = toast_overlay.clone();
+    let name_entry_c = name_entry.clone();
+    let save = move || {
+        let name = name_entry_c.text().trim().to_string();
+        let message = if name.is_empty() {
+            Some("Give the search a name".to_string())
+        } else if RESERVED_NAMES.contains(&name.as_str())
+            || find_category(&load_categories(), &name).is_some()
+        {
+            Some(format!("'{}' is already a category", name))
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            let toast = adw::Toast::builder()
+                .title(message)
+                .timeout(crate::config::constants::TOAST_TIMEOUT)
+                .build();
+            toast_overlay.add_toast(toast);
+            return;
+        }
+
+        let mut searches = load_saved_searches();
+        upsert_saved_search(
+            &mut searches,
+            SavedSearch {
+                name: name.clone(),
+                query: query.clone(),
+                pinned: pin_check.is_active(),
+            },
+        );
+        let title = match save_saved_searches(&searches) {
+            Ok(()) => format!("Saved search '{}'", name),
+            Err(e) => format!("Failed to save search: {}", e),
+        };
+        let toast = adw::Toast::builder()
+            .title(title)
+            .timeout(crate::config::constants::TOAST_TIMEOUT)
+            .build();
+        toast_overlay.add_toast(toast);
+        name_entry_c.set_text("");
+        on_changed();
+        rebuild();
+    };
+    let save = Rc::new(save);
+
+    let save_c = save.clone();
+    save_btn.connect_clicked(move |_| save_c());
+    name_entry.connect_activate(move |_| save());
+
+    container
+}
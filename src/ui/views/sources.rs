@@ -0,0 +1,156 @@
+use crate::parser::{get_defined_variables, get_source_graph, IncludeKind, IncludeNode};
+use crate::ui::utils::components::{create_card_row, create_page_header, open_file};
+use crate::ui::utils::create_pill_button;
+use crate::ui::utils::keybinds::model_keybinds;
+use crate::ui::utils::scripts::display_path;
+use gtk::{gio, prelude::*};
+use gtk4 as gtk;
+use libadwaita as adw;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("1 {}", word)
+    } else {
+        format!("{} {}s", count, word)
+    }
+}
+
+fn describe(
+    node: &IncludeNode,
+    binds: &HashMap<PathBuf, usize>,
+    variables: &HashMap<PathBuf, usize>,
+) -> String {
+    let mut parts = Vec::new();
+    match node.kind {
+        IncludeKind::File if node.path.is_dir() => parts.push("Directory".to_string()),
+        IncludeKind::File => {
+            parts.push(plural(binds.get(&node.path).copied().unwrap_or(0), "bind"));
+            parts.push(plural(
+                variables.get(&node.path).copied().unwrap_or(0),
+                "variable",
+            ));
+        }
+        IncludeKind::Duplicate => parts.push("Already sourced above, skipped".to_string()),
+        IncludeKind::Cycle => parts.push("Sources one of its includers, skipped".to_string()),
+        IncludeKind::Glob => parts.push(format!("Glob matched {}", plural(node.matched, "file"))),
+        IncludeKind::Missing => parts.push("Not found".to_string()),
+    }
+    if node.line > 0 {
+        parts.push(format!("line {}", node.line));
+    }
+    if let Some(original) = &node.remapped_from {
+        parts.push(format!("remapped from {}", original));
+    }
+    parts.join(" · ")
+}
+
+/// Shows which config file sources which, with the globs and directories in
+/// between, and lets you open a file or filter the keybind list to it.
+pub fn create_sources_view(
+    stack: &gtk::Stack,
+    model: &gio::ListStore,
+    on_filter_file: Rc<dyn Fn(&Path)>,
+) -> gtk::Box {
+    let container = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let graph = get_source_graph().unwrap_or_default();
+    let tree = graph.tree();
+    let file_count = tree
+        .iter()
+        .filter(|n| n.kind == IncludeKind::File && !n.path.is_dir())
+        .count();
+
+    let stack_weak = stack.downgrade();
+    let header = create_page_header(
+        "Config Files",
+        Some(&format!(
+            "{} sourced from {}",
+            plural(file_count, "file"),
+            display_path(&graph.root)
+        )),
+        "Back",
+        move || {
+            if let Some(s) = stack_weak.upgrade() {
+                s.set_visible_child_name("home");
+            }
+        },
+    );
+    container.append(&header);
+
+    let mut binds: HashMap<PathBuf, usize> = HashMap::new();
+    for kb in model_keybinds(model) {
+        *binds.entry(kb.file_path).or_default() += 1;
+    }
+    let mut variables: HashMap<PathBuf, usize> = HashMap::new();
+    for var in get_defined_variables().unwrap_or_default() {
+        *variables.entry(var.file_path).or_default() += 1;
+    }
+
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .build();
+    let list_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(8)
+        .build();
+    scroll.set_child(Some(&list_box));
+    container.append(&scroll);
+
+    if tree.is_empty() {
+        let empty = adw::StatusPage::builder()
+            .title("No Config Found")
+            .icon_name("dialog-error-symbolic")
+            .vexpand(true)
+            .build();
+        list_box.append(&empty);
+        return container;
+    }
+
+    for node in tree {
+        let title = match node.kind {
+            IncludeKind::Glob | IncludeKind::Missing => node.path.display().to_string(),
+            _ => display_path(&node.path),
+        };
+        let subtitle = describe(&node, &binds, &variables);
+
+        let actions = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        if node.kind == IncludeKind::File && !node.path.is_dir() {
+            let filter_btn = create_pill_button("Show Binds", Some("edit-find-symbolic"));
+            filter_btn.set_tooltip_text(Some("Filter the keybind list to this file"));
+            let open_btn = create_pill_button("Open", Some("document-open-symbolic"));
+            actions.append(&filter_btn);
+            actions.append(&open_btn);
+
+            let path = node.path.clone();
+            let on_filter_file = on_filter_file.clone();
+            filter_btn.connect_clicked(move |_| on_filter_file(&path));
+
+            let path = node.path.clone();
+            open_btn.connect_clicked(move |btn| open_file(btn, &path));
+        }
+
+        let row = create_card_row(&title, Some(&subtitle), &actions);
+        row.set_margin_start(4 + 24 * node.depth as i32);
+        match node.kind {
+            IncludeKind::Missing => row.add_css_class("error"),
+            IncludeKind::Cycle => row.add_css_class("warning"),
+            IncludeKind::Glob if node.matched == 0 => row.add_css_class("warning"),
+            IncludeKind::Duplicate => row.add_css_class("dim-label"),
+            _ => {}
+        }
+        list_box.append(&row);
+    }
+
+    container
+}
@@ -0,0 +1,375 @@
+use crate::keybind_object::KeybindObject;
+use crate::parser::Keybind;
+use crate::ui::utils::components::{collect_submaps, create_page_header};
+use crate::ui::utils::submap_graph::{build_submap_graph, GraphNode, SubmapGraph};
+use gtk::{gio, prelude::*};
+use gtk4 as gtk;
+use libadwaita as adw;
+use std::rc::Rc;
+
+const NODE_WIDTH: f64 = 150.0;
+const NODE_HEIGHT: f64 = 52.0;
+const COLUMN_GAP: f64 = 150.0;
+const ROW_GAP: f64 = 64.0;
+const MARGIN: f64 = 40.0;
+/// Distance between the curves of binds connecting the same two maps.
+const PARALLEL_SPACING: f64 = 28.0;
+/// How far from an edge a click still selects it.
+const HIT_DISTANCE: f64 = 6.0;
+const CURVE_STEPS: usize = 64;
+
+type Point = (f64, f64);
+
+/// Where a node's box sits: reachable maps in columns by depth, unreachable
+/// ones in a column of their own at the end.
+fn node_positions(graph: &SubmapGraph) -> Vec<Point> {
+    let last_column = graph
+        .nodes
+        .iter()
+        .filter_map(|n| n.depth)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let mut rows = vec![0usize; last_column + 1];
+    graph
+        .nodes
+        .iter()
+        .map(|node| {
+            let column = node.depth.unwrap_or(last_column);
+            let row = rows[column];
+            rows[column] += 1;
+            (
+                MARGIN + column as f64 * (NODE_WIDTH + COLUMN_GAP),
+                MARGIN + row as f64 * (NODE_HEIGHT + ROW_GAP),
+            )
+        })
+        .collect()
+}
+
+fn inside_node(p: Point, origin: Point) -> bool {
+    p.0 >= origin.0 - 2.0
+        && p.0 <= origin.0 + NODE_WIDTH + 2.0
+        && p.1 >= origin.1 - 2.0
+        && p.1 <= origin.1 + NODE_HEIGHT + 2.0
+}
+
+/// Samples of each edge's curve between the borders of its two nodes.
+/// Binds between the same two maps get curves bent apart from each other.
+fn edge_curves(graph: &SubmapGraph, positions: &[Point]) -> Vec<Vec<Point>> {
+    let center = |i: usize| {
+        (
+            positions[i].0 + NODE_WIDTH / 2.0,
+            positions[i].1 + NODE_HEIGHT / 2.0,
+        )
+    };
+    let pair = |from: usize, to: usize| (from.min(to), from.max(to));
+
+    graph
+        .edges
+        .iter()
+        .enumerate()
+        .map(|(i, edge)| {
+            let key = pair(edge.from, edge.to);
+            let siblings: Vec<usize> = graph
+                .edges
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| pair(e.from, e.to) == key)
+                .map(|(j, _)| j)
+                .collect();
+            let k = siblings.iter().position(|&j| j == i).unwrap_or(0) as f64;
+            let n = siblings.len() as f64;
+
+            // Bend relative to a fixed direction, so A -> B and B -> A curves
+            // end up on different sides
+            let (a, b) = (center(key.0), center(key.1));
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let length = dx.hypot(dy).max(1.0);
+            let normal = (-dy / length, dx / length);
+            let bend = (k - (n - 1.0) / 2.0) * PARALLEL_SPACING + length * 0.08;
+            let control = (
+                (a.0 + b.0) / 2.0 + normal.0 * bend * 2.0,
+                (a.1 + b.1) / 2.0 + normal.1 * bend * 2.0,
+            );
+
+            let (start, end) = (center(edge.from), center(edge.to));
+            let samples: Vec<Point> = (0..=CURVE_STEPS)
+                .map(|s| {
+                    let t = s as f64 / CURVE_STEPS as f64;
+                    let u = 1.0 - t;
+                    (
+                        u * u * start.0 + 2.0 * u * t * control.0 + t * t * end.0,
+                        u * u * start.1 + 2.0 * u * t * control.1 + t * t * end.1,
+                    )
+                })
+                .collect();
+            let first = samples
+                .iter()
+                .position(|p| !inside_node(*p, positions[edge.from]))
+                .unwrap_or(0);
+            let last = samples
+                .iter()
+                .rposition(|p| !inside_node(*p, positions[edge.to]))
+                .unwrap_or(CURVE_STEPS);
+            if first < last {
+                samples[first..=last].to_vec()
+            } else {
+                samples
+            }
+        })
+        .collect()
+}
+
+fn distance_to_curve(curve: &[Point], p: Point) -> f64 {
+    curve
+        .windows(2)
+        .map(|w| {
+            let (a, b) = (w[0], w[1]);
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let len2 = (dx * dx + dy * dy).max(f64::EPSILON);
+            let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0);
+            (a.0 + t * dx - p.0).hypot(a.1 + t * dy - p.1)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn keys_text(kb: &Keybind) -> String {
+    if kb.clean_mods.is_empty() {
+        kb.key.to_string()
+    } else {
+        format!("{} + {}", kb.clean_mods, kb.key)
+    }
+}
+
+fn create_node(node: &GraphNode, bind_count: usize) -> gtk::Box {
+    let (status, class, tooltip) = if !node.is_reachable() {
+        (
+            "Unreachable".to_string(),
+            Some("unreachable"),
+            "No chain of binds leads here from the default map",
+        )
+    } else if node.dead_end {
+        (
+            "Dead end".to_string(),
+            Some("dead-end"),
+            "No bind leads out of it again",
+        )
+    } else if node.depth == Some(0) {
+        (
+            format!("{} keybinds", bind_count),
+            Some("start"),
+            "Where you start: the default map or your default submap",
+        )
+    } else {
+        (format!("{} keybinds", bind_count), None, "")
+    };
+
+    let card = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .valign(gtk::Align::Center)
+        .width_request(NODE_WIDTH as i32)
+        .height_request(NODE_HEIGHT as i32)
+        .css_classes(["graph-node"])
+        .build();
+    if let Some(class) = class {
+        card.add_css_class(class);
+    }
+    if !tooltip.is_empty() {
+        card.set_tooltip_text(Some(tooltip));
+    }
+    card.append(
+        &gtk::Label::builder()
+            .label(node.title())
+            .css_classes(["heading"])
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .build(),
+    );
+    card.append(
+        &gtk::Label::builder()
+            .label(status)
+            .css_classes(["dim-label", "caption"])
+            .build(),
+    );
+    card
+}
+
+/// Draws the default map and every submap as boxes, with an arrow for each
+/// bind switching between them. Clicking an arrow or its keys opens the bind
+/// with `on_edit`.
+pub fn create_submap_graph_view(
+    stack: &gtk::Stack,
+    model: &gio::ListStore,
+    default_submap: Option<&str>,
+    on_edit: Rc<dyn Fn(KeybindObject)>,
+) -> gtk::Box {
+    let container = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let objects: Vec<KeybindObject> = model
+        .snapshot()
+        .into_iter()
+        .filter_map(|o| o.downcast::<KeybindObject>().ok())
+        .collect();
+    let keybinds: Vec<Keybind> = objects.iter().map(|o| o.to_keybind()).collect();
+    let graph = build_submap_graph(&keybinds, &collect_submaps(model), default_submap);
+
+    let unreachable = graph.nodes.iter().filter(|n| !n.is_reachable()).count();
+    let dead_ends = graph.nodes.iter().filter(|n| n.dead_end).count();
+    let subtitle = format!(
+        "{} submap(s), {} unreachable, {} dead end(s)",
+        graph.nodes.len() - 1,
+        unreachable,
+        dead_ends
+    );
+
+    let stack_weak = stack.downgrade();
+    let header = create_page_header("Submap Flow", Some(&subtitle), "Back", move || {
+        if let Some(s) = stack_weak.upgrade() {
+            s.set_visible_child_name("home");
+        }
+    });
+    container.append(&header);
+
+    if graph.nodes.len() < 2 {
+        let empty = adw::StatusPage::builder()
+            .title("No Submaps")
+            .description("Submaps are defined using 'submap = name' in your config.")
+            .icon_name("view-list-bullet-symbolic")
+            .vexpand(true)
+            .build();
+        container.append(&empty);
+        return container;
+    }
+
+    container.append(
+        &gtk::Label::builder()
+            .label("Arrows are binds switching submaps, dashed ones reset to the default map. Click one to edit its bind.")
+            .css_classes(["dim-label"])
+            .wrap(true)
+            .halign(gtk::Align::Start)
+            .build(),
+    );
+
+    let positions = node_positions(&graph);
+    let curves = Rc::new(edge_curves(&graph, &positions));
+    let width = positions.iter().map(|p| p.0).fold(0.0, f64::max) + NODE_WIDTH + MARGIN;
+    let height = positions.iter().map(|p| p.1).fold(0.0, f64::max) + NODE_HEIGHT + MARGIN;
+
+    let area = gtk::DrawingArea::builder()
+        .content_width(width as i32)
+        .content_height(height as i32)
+        .build();
+    let resets: Vec<bool> = graph.edges.iter().map(|e| e.is_reset).collect();
+    let curves_draw = curves.clone();
+    area.set_draw_func(move |area, cr, _, _| {
+        let fg = area.color();
+        for (curve, is_reset) in curves_draw.iter().zip(&resets) {
+            let (Some(first), Some(last)) = (curve.first(), curve.last()) else {
+                continue;
+            };
+            if *is_reset {
+                cr.set_source_rgba(fg.red() as f64, fg.green() as f64, fg.blue() as f64, 0.45);
+                cr.set_dash(&[6.0, 4.0], 0.0);
+            } else {
+                // Adwaita's default accent blue
+                cr.set_source_rgba(0.21, 0.52, 0.89, 0.9);
+                cr.set_dash(&[], 0.0);
+            }
+            cr.set_line_width(2.0);
+            cr.move_to(first.0, first.1);
+            for p in &curve[1..] {
+                cr.line_to(p.0, p.1);
+            }
+            let _ = cr.stroke();
+
+            // Arrowhead along the curve's last segment
+            let before = curve[curve.len().saturating_sub(3)];
+            let angle = (last.1 - before.1).atan2(last.0 - before.0);
+            cr.set_dash(&[], 0.0);
+            cr.move_to(last.0, last.1);
+            for side in [-0.45, 0.45] {
+                cr.line_to(
+                    last.0 - 10.0 * (angle + side).cos(),
+                    last.1 - 10.0 * (angle + side).sin(),
+                );
+            }
+            cr.close_path();
+            let _ = cr.fill();
+        }
+    });
+
+    let fixed = gtk::Fixed::new();
+    for (node, position) in graph.nodes.iter().zip(&positions) {
+        let bind_count = keybinds
+            .iter()
+            .filter(|kb| kb.submap.as_deref() == node.name.as_deref())
+            .count();
+        fixed.put(&create_node(node, bind_count), position.0, position.1);
+    }
+
+    let objects = Rc::new(objects);
+    for (edge, curve) in graph.edges.iter().zip(curves.iter()) {
+        let kb = &keybinds[edge.bind];
+        let label = gtk::Button::builder()
+            .label(keys_text(kb))
+            .css_classes(["graph-edge-label"])
+            .tooltip_text(format!(
+                "{} → {}\n{} {}",
+                graph.nodes[edge.from].title(),
+                graph.nodes[edge.to].title(),
+                kb.dispatcher,
+                kb.args
+            ))
+            .build();
+        let (_, label_width, _, _) = label.measure(gtk::Orientation::Horizontal, -1);
+        let (_, label_height, _, _) = label.measure(gtk::Orientation::Vertical, -1);
+        let mid = curve[curve.len() / 2];
+        fixed.put(
+            &label,
+            mid.0 - label_width as f64 / 2.0,
+            mid.1 - label_height as f64 / 2.0,
+        );
+
+        let obj = objects[edge.bind].clone();
+        let on_edit = on_edit.clone();
+        label.connect_clicked(move |_| on_edit(obj.clone()));
+    }
+
+    let overlay = gtk::Overlay::builder()
+        .child(&area)
+        .halign(gtk::Align::Start)
+        .valign(gtk::Align::Start)
+        .build();
+    overlay.add_overlay(&fixed);
+
+    // Clicks on the lines themselves; the labels handle their own
+    let click = gtk::GestureClick::new();
+    let binds: Vec<usize> = graph.edges.iter().map(|e| e.bind).collect();
+    click.connect_released(move |_, _, x, y| {
+        let nearest = curves
+            .iter()
+            .map(|curve| distance_to_curve(curve, (x, y)))
+            .enumerate()
+            .filter(|(_, d)| *d <= HIT_DISTANCE)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, _)) = nearest {
+            on_edit(objects[binds[i]].clone());
+        }
+    });
+    overlay.add_controller(click);
+
+    let scroll = gtk::ScrolledWindow::builder()
+        .child(&overlay)
+        .hexpand(true)
+        .vexpand(true)
+        .build();
+    container.append(&scroll);
+
+    container
+}
@@ -0,0 +1,266 @@
+//! The `--which-key` overlay: a popup listing the binds of the submap
+//! Hyprland just entered, gone again once the submap is left.
+//!
+//! Started without a submap it stays in the background and follows
+//! Hyprland's event socket. Started with one, e.g. from the bind that enters
+//! it, it shows that submap right away and exits once the submap is left.
+
+use crate::config::hud::{get_which_key_pid_path, is_which_key_running, load_hud_config};
+use crate::parser::Keybind;
+use crate::ui::hud::{generate_hud_css, watch_submaps};
+use gtk::gio;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+use libadwaita as adw;
+use std::fs;
+use std::time::Duration;
+
+/// Binds per column of the grid.
+const ROWS_PER_COLUMN: usize = 8;
+const MAX_COLUMNS: usize = 4;
+/// What the submap wizard adds to `exec-once`.
+pub const WHICH_KEY_COMMAND: &str = "hyprkcs --which-key";
+/// How long a one-shot popup stays up when submap changes can't be followed.
+const FALLBACK_TIMEOUT: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhichKeyEntry {
+    pub keys: String,
+    pub label: String,
+}
+
+/// The binds of `submap`, labelled with their description where they have
+/// one.
+pub fn which_key_entries(keybinds: &[Keybind], submap: &str) -> Vec<WhichKeyEntry> {
+    keybinds
+        .iter()
+        .filter(|kb| kb.submap.as_deref() == Some(submap))
+        .map(|kb| {
+            let keys = if kb.clean_mods.is_empty() {
+                kb.key.to_string()
+            } else {
+                format!("{} + {}", kb.clean_mods, kb.key)
+            };
+            let label = match kb.description.as_deref().filter(|d| !d.trim().is_empty()) {
+                Some(description) => description.to_string(),
+                None if &*kb.dispatcher == "submap" && &*kb.args == "reset" => {
+                    "Leave submap".to_string()
+                }
+                None if &*kb.dispatcher == "submap" => format!("Go to {}", kb.args),
+                None if kb.args.is_empty() => kb.dispatcher.to_string(),
+                None => format!("{} {}", kb.dispatcher, kb.args),
+            };
+            WhichKeyEntry { keys, label }
+        })
+        .collect()
+}
+
+/// Starts the background overlay as a detached process unless it runs.
+pub fn start_which_key() -> std::io::Result<()> {
+    if is_which_key_running() {
+        return Ok(());
+    }
+    use std::os::unix::process::CommandExt;
+    let mut cmd = std::process::Command::new(std::env::current_exe()?);
+    cmd.arg("--which-key");
+    unsafe {
+        cmd.pre_exec(|| {
+            let _ = libc::setsid();
+            Ok(())
+        });
+    }
+    cmd.spawn().map(|_| ())
+}
+
+fn fill_grid(grid: &gtk::Grid, entries: &[WhichKeyEntry]) {
+    while let Some(child) = grid.first_child() {
+        grid.remove(&child);
+    }
+
+    let columns = entries
+        .len()
+        .div_ceil(ROWS_PER_COLUMN)
+        .clamp(1, MAX_COLUMNS);
+    let rows = entries.len().div_ceil(columns);
+    for (i, entry) in entries.iter().enumerate() {
+        let column = (i / rows) as i32 * 2;
+        let row = (i % rows) as i32;
+        grid.attach(
+            &gtk::Label::builder()
+                .label(&entry.keys)
+                .css_classes(["hud-keys"])
+                .halign(gtk::Align::End)
+                .build(),
+            column,
+            row,
+            1,
+            1,
+        );
+        grid.attach(
+            &gtk::Label::builder()
+                .label(&entry.label)
+                .css_classes(["hud-action"])
+                .halign(gtk::Align::Start)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .max_width_chars(32)
+                .build(),
+            column + 1,
+            row,
+            1,
+            1,
+        );
+    }
+}
+
+/// Runs the overlay. With `submap` set it is a one-shot popup for that
+/// submap; if the background overlay is already running it does nothing.
+pub fn run_which_key(submap: Option<&str>) {
+    if is_which_key_running() {
+        if submap.is_none() {
+            crate::log_warn!("hud", "The which-key overlay is already running");
+        }
+        return;
+    }
+
+    // Only the background overlay claims the PID file, so a one-shot popup
+    // doesn't keep it from starting
+    let pid_path = get_which_key_pid_path().filter(|_| submap.is_none());
+    if let Some(pid_path) = &pid_path {
+        if let Some(parent) = pid_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(pid_path, std::process::id().to_string());
+    }
+
+    adw::init().unwrap_or_else(|e| {
+        crate::log_error!("hud", "Failed to initialize libadwaita: {}", e);
+    });
+
+    let app = adw::Application::builder()
+        .application_id("com.github.hyprkcs.whichkey")
+        .flags(gio::ApplicationFlags::NON_UNIQUE)
+        .build();
+
+    let initial = submap.map(str::to_string);
+    let one_shot = submap.is_some();
+    app.connect_activate(move |app| {
+        // Keeps the background overlay alive while it has no window shown
+        let hold = if one_shot { None } else { Some(app.hold()) };
+
+        let provider = gtk::CssProvider::new();
+        provider.load_from_string(&generate_hud_css(&load_hud_config()));
+        if let Some(display) = gtk::gdk::Display::default() {
+            gtk::style_context_add_provider_for_display(
+                &display,
+                &provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        let window = gtk::ApplicationWindow::builder()
+            .application(app)
+            .title("hyprKCS Which Key")
+            .resizable(false)
+            .decorated(false)
+            .build();
+
+        window.init_layer_shell();
+        window.set_layer(Layer::Overlay);
+        window.set_namespace(Some("hyprkcs-which-key"));
+        window.set_keyboard_mode(KeyboardMode::None);
+        window.set_anchor(Edge::Bottom, true);
+        window.set_margin(Edge::Bottom, 60);
+
+        let container = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .css_classes(["hud-container"])
+            .build();
+        let title = gtk::Label::builder()
+            .css_classes(["hud-title"])
+            .halign(gtk::Align::Start)
+            .build();
+        let grid = gtk::Grid::builder()
+            .column_spacing(16)
+            .row_spacing(6)
+            .build();
+        container.append(&title);
+        container.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+        container.append(&grid);
+        window.set_child(Some(&container));
+
+        let show = {
+            let window = window.clone();
+            move |submap: Option<&str>| {
+                let entries = submap
+                    .map(|s| {
+                        which_key_entries(&crate::parser::parse_config().unwrap_or_default(), s)
+                    })
+                    .unwrap_or_default();
+                if entries.is_empty() {
+                    window.set_visible(false);
+                    return;
+                }
+                title.set_label(submap.unwrap_or_default());
+                fill_grid(&grid, &entries);
+                window.present();
+            }
+        };
+        show(initial.as_deref());
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        watch_submaps(sender);
+        let app = app.clone();
+        glib::timeout_add_local(Duration::from_millis(50), move || {
+            let _hold = &hold;
+            loop {
+                match receiver.try_recv() {
+                    Ok(None) if one_shot => {
+                        app.quit();
+                        return glib::ControlFlow::Break;
+                    }
+                    Ok(submap) => show(submap.as_deref()),
+                    Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        if one_shot {
+                            crate::log_warn!(
+                                "hud",
+                                "Cannot follow submaps, closing the popup after {}s",
+                                FALLBACK_TIMEOUT.as_secs()
+                            );
+                            let app = app.clone();
+                            glib::timeout_add_local_once(FALLBACK_TIMEOUT, move || app.quit());
+                        } else {
+                            crate::log_error!("hud", "Lost Hyprland's event socket");
+                            app.quit();
+                        }
+                        return glib::ControlFlow::Break;
+                    }
+                }
+            }
+            glib::ControlFlow::Continue
+        });
+    });
+
+    app.connect_shutdown(move |_| {
+        if let Some(pid_path) = &pid_path {
+            let _ = fs::remove_file(pid_path);
+        }
+    });
+
+    let app_clone = app.clone();
+    glib::unix_signal_add_local(libc::SIGTERM, move || {
+        app_clone.quit();
+        glib::ControlFlow::Break
+    });
+
+    let app_clone = app.clone();
+    glib::unix_signal_add_local(libc::SIGINT, move || {
+        app_clone.quit();
+        glib::ControlFlow::Break
+    });
+
+    app.run_with_args::<String>(&[]);
+}
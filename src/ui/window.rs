@@ -1,8 +1,10 @@
-use crate::config::favorites::{load_favorites, save_favorites, toggle_favorite, FavoriteKeybind};
+use crate::config::categories::{find_category, load_categories};
+use crate::config::favorites::{load_metadata, save_metadata};
+use crate::config::searches::load_saved_searches;
 use crate::config::StyleConfig;
 use crate::keybind_object::KeybindObject;
 use crate::ui::utils::{create_close_button, create_flat_button, reload_keybinds, SearchQuery};
-use crate::ui::views::{create_add_view, create_edit_view};
+use crate::ui::views::{create_edit_view, open_add_view};
 use crate::ui::wizards::{create_bulk_replace_wizard, create_conflict_wizard};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use gtk::{gio, glib, prelude::*};
@@ -13,6 +15,65 @@ use std::rc::Rc;
 
 type FilterCallback = std::rc::Rc<std::cell::RefCell<Option<Box<dyn Fn()>>>>;
 
+/// The category dropdown entries: "All", the categories from
+/// `categories.json`, "Favorites", then the pinned saved searches.
+fn category_model(show_favorites: bool) -> gtk::StringList {
+    let categories = load_categories();
+    let mut labels: Vec<String> = vec!["All".to_string()];
+    labels.extend(categories.iter().map(|c| c.name.clone()));
+    if show_favorites {
+        labels.push("Favorites".to_string());
+    }
+    labels.extend(
+        load_saved_searches()
+            .into_iter()
+            .filter(|s| s.pinned && !labels.contains(&s.name))
+            .map(|s| s.name)
+            .collect::<Vec<_>>(),
+    );
+    let refs: Vec<&str> = labels.iter().map(|s| s.as_str()).collect();
+    gtk::StringList::new(&refs)
+}
+
+fn selected_label(dropdown: &gtk::DropDown) -> Option<String> {
+    dropdown
+        .model()
+        .and_then(|m| m.item(dropdown.selected()))
+        .and_downcast::<gtk::StringObject>()
+        .map(|s| s.string().to_string())
+}
+
+/// Rebuilds the category dropdown, keeping the selected entry if it is
+/// still there.
+fn refresh_categories(dropdown: &gtk::DropDown, show_favorites: bool) {
+    let selected = selected_label(dropdown);
+    let model = category_model(show_favorites);
+    let index = (0..model.n_items())
+        .find(|&i| model.string(i).map(|s| s.to_string()) == selected)
+        .unwrap_or(0);
+    dropdown.set_model(Some(&model));
+    dropdown.set_selected(index);
+}
+
+/// The query of the category or pinned saved search selected in
+/// `dropdown`, and the search text.
+fn category_query(dropdown: &gtk::DropDown, text: &str) -> (String, String) {
+    let label = selected_label(dropdown).unwrap_or_default();
+    let query = match label.as_str() {
+        "All" => String::new(),
+        "Favorites" => "fav:yes".to_string(),
+        _ => match find_category(&load_categories(), &label) {
+            Some(category) => category.query.clone(),
+            None => load_saved_searches()
+                .into_iter()
+                .find(|s| s.name == label)
+                .map(|s| s.query)
+                .unwrap_or_default(),
+        },
+    };
+    (query, text.to_string())
+}
+
 pub fn build_ui(app: &adw::Application) {
     if let Some(window) = app.active_window() {
         window.present();
@@ -31,12 +92,22 @@ pub fn build_ui(app: &adw::Application) {
     glib::timeout_add_local(std::time::Duration::from_millis(1000), move || {
         let _ = &_watcher;
 
-        let mut reload = false;
-        while receiver.try_recv().is_ok() {
-            reload = true;
+        let mut changed: Vec<std::path::PathBuf> = Vec::new();
+        while let Ok(paths) = receiver.try_recv() {
+            for path in paths {
+                if !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
         }
 
-        if reload {
+        if !changed.is_empty() {
+            crate::parser::mark_files_changed(&changed);
+            crate::log_debug!(
+                "watcher",
+                "{} file(s) changed, re-parsing them",
+                changed.len()
+            );
             reload_keybinds(&model_ipc);
         }
 
@@ -81,17 +152,9 @@ pub fn build_ui(app: &adw::Application) {
         btn.connect_clicked(move |b| {
             if let Some(list_item) = list_item_weak.upgrade() {
                 if let Some(obj) = list_item.item().and_downcast::<KeybindObject>() {
-                    let item = obj.with_data(|d| FavoriteKeybind {
-                        mods: d.clean_mods.to_string(),
-                        key: d.key.to_string(),
-                        submap: d.submap.as_ref().map(|s| s.to_string()).unwrap_or_default(),
-                        dispatcher: d.dispatcher.to_string(),
-                        args: d.args.as_ref().map(|s| s.to_string()).unwrap_or_default(),
-                    });
-
-                    let mut favs = load_favorites();
-                    let new_state = toggle_favorite(&mut favs, item);
-                    let _ = save_favorites(&favs);
+                    let mut metadata = load_metadata();
+                    let new_state = metadata.toggle_favorite(&obj.bind_id());
+                    let _ = save_metadata(&metadata);
 
                     obj.set_property("is-favorite", new_state);
 
@@ -147,6 +210,7 @@ pub fn build_ui(app: &adw::Application) {
         let factory = gtk::SignalListItemFactory::new();
         let prop_name = property_name.to_string();
         let is_mods = property_name == "mods";
+        let is_dispatcher = property_name == "dispatcher";
 
         let prop_name_setup = prop_name.clone();
         factory.connect_setup(move |_, list_item| {
@@ -194,6 +258,22 @@ pub fn build_ui(app: &adw::Application) {
                 box_layout.append(&warning_icon);
                 box_layout.append(&label);
                 list_item.set_child(Some(&box_layout));
+            } else if is_dispatcher {
+                let box_layout = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+
+                // Application launched by exec binds
+                let app_icon = gtk::Image::builder().pixel_size(16).visible(false).build();
+                let app_label = gtk::Label::builder()
+                    .halign(gtk::Align::Start)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .css_classes(["dim-label"])
+                    .visible(false)
+                    .build();
+
+                box_layout.append(&label);
+                box_layout.append(&app_icon);
+                box_layout.append(&app_label);
+                list_item.set_child(Some(&box_layout));
             } else {
                 list_item.set_child(Some(&label));
             }
@@ -218,6 +298,29 @@ pub fn build_ui(app: &adw::Application) {
                     .and_downcast::<gtk::Label>()
                     .unwrap();
                 (label, Some(warning_icon), Some(broken_icon))
+            } else if is_dispatcher {
+                let box_layout = list_item.child().and_downcast::<gtk::Box>().unwrap();
+                let label = box_layout
+                    .first_child()
+                    .and_downcast::<gtk::Label>()
+                    .unwrap();
+                let app_icon = label.next_sibling().and_downcast::<gtk::Image>().unwrap();
+                let app_label = app_icon
+                    .next_sibling()
+                    .and_downcast::<gtk::Label>()
+                    .unwrap();
+
+                keybind.with_data(|data| {
+                    match data.app_icon.as_deref() {
+                        Some(icon) if icon.starts_with('/') => app_icon.set_from_file(Some(icon)),
+                        Some(icon) => app_icon.set_icon_name(Some(icon)),
+                        None => app_icon.set_icon_name(Some("application-x-executable-symbolic")),
+                    }
+                    app_icon.set_visible(data.app_name.is_some());
+                    app_label.set_label(data.app_name.as_deref().unwrap_or(""));
+                    app_label.set_visible(data.app_name.is_some());
+                });
+                (label, None, None)
             } else {
                 let label = list_item.child().and_downcast::<gtk::Label>().unwrap();
                 (label, None, None)
@@ -331,14 +434,13 @@ pub fn build_ui(app: &adw::Application) {
     let backup_button = create_flat_button("document-save-symbolic", "Backup Current Config");
     let settings_button = create_flat_button("emblem-system-symbolic", "Settings");
     let keyboard_button = create_flat_button("input-keyboard-symbolic", "Visual Keyboard");
+    let scripts_button = create_flat_button("text-x-script-symbolic", "Script Library");
+    let graph_button = create_flat_button("media-playlist-repeat-symbolic", "Submap Flow");
+    let sources_button = create_flat_button("folder-documents-symbolic", "Config Files");
+    let searches_button = create_flat_button("bookmark-new-symbolic", "Saved Searches");
 
-    let mut cat_list = vec!["All", "Workspace", "Window", "Media", "Custom", "Mouse"];
-    if config.show_favorites {
-        cat_list.push("Favorites");
-    }
-    let categories = gtk::StringList::new(&cat_list);
     let category_dropdown = gtk::DropDown::builder()
-        .model(&categories)
+        .model(&category_model(config.show_favorites))
         .selected(0)
         .tooltip_text("Filter by Category")
         .build();
@@ -393,10 +495,22 @@ pub fn build_ui(app: &adw::Application) {
         .build();
     top_box.append(&conflict_button);
 
+    // Problems Button (parser diagnostics and lint issues)
+    let problems_button = gtk::Button::builder()
+        .icon_name("emblem-important-symbolic")
+        .css_classes(["flat", "circular", "small", "warning"])
+        .visible(false)
+        .build();
+    top_box.append(&problems_button);
+
     top_box.append(&add_button);
     top_box.append(&bulk_button);
     top_box.append(&backup_button);
     top_box.append(&keyboard_button);
+    top_box.append(&graph_button);
+    top_box.append(&scripts_button);
+    top_box.append(&sources_button);
+    top_box.append(&searches_button);
     top_box.append(&settings_button);
 
     let close_button = create_close_button();
@@ -437,6 +551,17 @@ pub fn build_ui(app: &adw::Application) {
     // Add "Home" page
     root_stack.add_named(&main_vbox, Some("home"));
 
+    // Pick up edits to categories.json when coming back to the list
+    let category_home = category_dropdown.downgrade();
+    root_stack.connect_visible_child_name_notify(move |stack| {
+        if stack.visible_child_name().as_deref() != Some("home") {
+            return;
+        }
+        if let Some(dropdown) = category_home.upgrade() {
+            refresh_categories(&dropdown, StyleConfig::load().show_favorites);
+        }
+    });
+
     // Pages for Add/Edit/Wizard (containers)
     let add_page_container = gtk::Box::new(gtk::Orientation::Vertical, 0);
     root_stack.add_named(&add_page_container, Some("add"));
@@ -466,9 +591,8 @@ pub fn build_ui(app: &adw::Application) {
     let toast_overlay = adw::ToastOverlay::new();
     toast_overlay.set_child(Some(&window_content));
 
-    // Log configuration errors to stderr
     for error in &config.errors {
-        eprintln!("[Config Error] {}", error);
+        crate::log_error!("config", "{}", error);
     }
 
     let window = adw::ApplicationWindow::builder()
@@ -631,9 +755,7 @@ pub fn build_ui(app: &adw::Application) {
     });
 
     let model_clone_add = model.clone();
-    let toast_overlay_add = toast_overlay.clone();
     let root_stack_weak = root_stack.downgrade();
-    let add_page_container_weak = add_page_container.downgrade();
     let default_submap_add = config.default_submap.clone();
 
     add_button.connect_clicked(move |_| {
@@ -641,24 +763,13 @@ pub fn build_ui(app: &adw::Application) {
             Some(w) => w,
             None => return,
         };
-        let add_page_container = match add_page_container_weak.upgrade() {
-            Some(w) => w,
-            None => return,
-        };
-
-        // Clear previous add form (optional but good for reset)
-        while let Some(child) = add_page_container.first_child() {
-            add_page_container.remove(&child);
-        }
 
-        let add_view = create_add_view(
+        open_add_view(
             &root_stack,
             &model_clone_add,
-            &toast_overlay_add,
             default_submap_add.as_deref(),
+            None,
         );
-        add_page_container.append(&add_view);
-        root_stack.set_visible_child_name("add");
     });
 
     let model_bulk = model.clone();
@@ -711,6 +822,7 @@ pub fn build_ui(app: &adw::Application) {
     // Logic to update conflict button visibility AND submap dropdown
     let update_ui_state = {
         let conflict_button_weak = conflict_button.downgrade();
+        let problems_button_weak = problems_button.downgrade();
         let submap_dropdown_weak = submap_dropdown.downgrade();
 
         move |model: &gio::ListStore| {
@@ -733,6 +845,22 @@ pub fn build_ui(app: &adw::Application) {
                 }
             }
 
+            // Update Problems Button
+            if let Some(btn) = problems_button_weak.upgrade() {
+                // Notes alone don't call for the warning button
+                let problem_count = crate::ui::views::current_problems(model)
+                    .iter()
+                    .filter(|p| p.severity != crate::parser::Severity::Note)
+                    .count();
+                btn.set_visible(problem_count > 0);
+                if problem_count > 0 {
+                    btn.set_tooltip_text(Some(&format!(
+                        "Config Problems ({} found)",
+                        problem_count
+                    )));
+                }
+            }
+
             // Update Submap Dropdown
             // Note: Re-creating the model resets selection. We should try to preserve it.
             if let Some(dropdown) = submap_dropdown_weak.upgrade() {
@@ -882,8 +1010,19 @@ pub fn build_ui(app: &adw::Application) {
 
     let matcher = Rc::new(SkimMatcherV2::default());
 
-    let filter_func = move |text: String, category: u32, submap_filter: Option<String>| {
+    let search_entry_errors = search_entry.downgrade();
+    let filter_func = move |text: String, category: String, submap_filter: Option<String>| {
         let query = SearchQuery::parse(&text);
+        let category = SearchQuery::parse(&category);
+        if let Some(entry) = search_entry_errors.upgrade() {
+            if query.errors.is_empty() {
+                entry.remove_css_class("error");
+                entry.set_tooltip_text(None);
+            } else {
+                entry.add_css_class("error");
+                entry.set_tooltip_text(Some(&query.errors.join("\n")));
+            }
+        }
         let m = Rc::clone(&matcher);
 
         filter.set_filter_func(move |obj| {
@@ -904,7 +1043,7 @@ pub fn build_ui(app: &adw::Application) {
                 // "All Submaps" selected: Show everything
             }
 
-            kb.matches_query(&query, category, &*m)
+            kb.matches_query(&query, &category, &*m)
         });
     };
 
@@ -920,8 +1059,7 @@ pub fn build_ui(app: &adw::Application) {
     let submap_refresh = submap_dropdown.clone();
 
     *refresh_filter_callback.borrow_mut() = Some(Box::new(move || {
-        let text = search_entry_refresh.text().to_string();
-        let cat = dropdown_refresh.selected();
+        let (cat, text) = category_query(&dropdown_refresh, &search_entry_refresh.text());
 
         let sub_idx = submap_refresh.selected();
         let sub_val = if let Some(m) = submap_refresh.model() {
@@ -952,8 +1090,7 @@ pub fn build_ui(app: &adw::Application) {
             source.remove();
         }
 
-        let text = entry.text().to_string();
-        let cat = dropdown_ref.selected();
+        let (cat, text) = category_query(&dropdown_ref, &entry.text());
 
         let sub_idx = submap_ref.selected();
         let sub_val = if let Some(m) = submap_ref.model() {
@@ -975,7 +1112,7 @@ pub fn build_ui(app: &adw::Application) {
         let timeout_handle_clone = timeout_handle.clone();
 
         let source = glib::timeout_add_local(std::time::Duration::from_millis(150), move || {
-            filter_func(text.clone(), cat, sub_val.clone());
+            filter_func(text.clone(), cat.clone(), sub_val.clone());
             *timeout_handle_clone.borrow_mut() = None;
             glib::ControlFlow::Break
         });
@@ -985,8 +1122,7 @@ pub fn build_ui(app: &adw::Application) {
     let search_entry_ref = search_entry.clone();
     let submap_ref_2 = submap_dropdown.clone();
     category_dropdown.connect_selected_notify(move |dropdown| {
-        let text = search_entry_ref.text().to_string();
-        let cat = dropdown.selected();
+        let (cat, text) = category_query(dropdown, &search_entry_ref.text());
 
         let sub_idx = submap_ref_2.selected();
         let sub_val = if let Some(m) = submap_ref_2.model() {
@@ -1012,8 +1148,7 @@ pub fn build_ui(app: &adw::Application) {
     let category_ref_3 = category_dropdown.clone();
 
     submap_dropdown.connect_selected_notify(move |dropdown| {
-        let text = search_entry_ref_3.text().to_string();
-        let cat = category_ref_3.selected();
+        let (cat, text) = category_query(&category_ref_3, &search_entry_ref_3.text());
 
         let sub_idx = dropdown.selected();
         let sub_val = if let Some(m) = dropdown.model() {
@@ -1034,8 +1169,49 @@ pub fn build_ui(app: &adw::Application) {
         filter_func_4(text, cat, sub_val);
     });
 
+    // Opens a bind in the edit view from pages other than the list
+    let stack_edit = root_stack.downgrade();
+    let edit_weak = edit_page_container.downgrade();
+    let column_weak = column_view.downgrade();
+    let selection_weak = selection_model.downgrade();
+    let toast_weak = toast_overlay.downgrade();
+    let model_edit = model.clone();
+    let open_edit_bind: Rc<dyn Fn(KeybindObject)> = Rc::new(move |obj| {
+        let (
+            Some(stack),
+            Some(edit_page_container),
+            Some(column_view),
+            Some(selection),
+            Some(toast_overlay),
+        ) = (
+            stack_edit.upgrade(),
+            edit_weak.upgrade(),
+            column_weak.upgrade(),
+            selection_weak.upgrade(),
+            toast_weak.upgrade(),
+        )
+        else {
+            return;
+        };
+        while let Some(child) = edit_page_container.first_child() {
+            edit_page_container.remove(&child);
+        }
+        let edit_view = create_edit_view(
+            &stack,
+            obj,
+            &model_edit,
+            &column_view,
+            &selection,
+            &toast_overlay,
+            &edit_page_container,
+        );
+        edit_page_container.append(&edit_view);
+        stack.set_visible_child_name("edit");
+    });
+
     let stack_weak = root_stack.downgrade();
     let container_weak = settings_page_container.downgrade();
+    let open_edit_settings = open_edit_bind.clone();
     let window_weak = window.downgrade();
     let col_desc_weak = col_desc.downgrade();
     let col_fav_weak = col_fav.downgrade();
@@ -1131,20 +1307,7 @@ pub fn build_ui(app: &adw::Application) {
                 }
                 // Update dropdown options
                 if let Some(dropdown) = dropdown_w.upgrade() {
-                    let mut cat_list =
-                        vec!["All", "Workspace", "Window", "Media", "Custom", "Mouse"];
-                    if s {
-                        cat_list.push("Favorites");
-                    }
-                    // Preserve selection if possible, otherwise default to All
-                    let selected = dropdown.selected();
-                    let model = gtk::StringList::new(&cat_list);
-                    dropdown.set_model(Some(&model));
-                    if selected < model.n_items() {
-                        dropdown.set_selected(selected);
-                    } else {
-                        dropdown.set_selected(0);
-                    }
+                    refresh_categories(&dropdown, s);
                 }
             }),
             std::rc::Rc::new(move |s| {
@@ -1211,6 +1374,7 @@ pub fn build_ui(app: &adw::Application) {
                 }
             }),
             on_focus_submap, // Pass the new callback
+            open_edit_settings.clone(),
             std::rc::Rc::new(move || {
                 let stack = match stack_w.upgrade() {
                     Some(s) => s,
@@ -1263,9 +1427,150 @@ pub fn build_ui(app: &adw::Application) {
         stack.set_visible_child_name("keyboard");
     });
 
+    let stack_weak = root_stack.downgrade();
+    let toast_weak = toast_overlay.downgrade();
+    let model_scripts = model.clone();
+    scripts_button.connect_clicked(move |_| {
+        let (Some(stack), Some(toast_overlay)) = (stack_weak.upgrade(), toast_weak.upgrade())
+        else {
+            return;
+        };
+
+        if let Some(old) = stack.child_by_name("scripts") {
+            stack.remove(&old);
+        }
+        let view = crate::ui::views::create_scripts_view(&stack, &model_scripts, &toast_overlay);
+        stack.add_named(&view, Some("scripts"));
+        stack.set_visible_child_name("scripts");
+    });
+
+    let stack_weak = root_stack.downgrade();
+    let search_weak = search_entry.downgrade();
+    let model_sources = model.clone();
+    sources_button.connect_clicked(move |_| {
+        let Some(stack) = stack_weak.upgrade() else {
+            return;
+        };
+
+        let stack_filter = stack.downgrade();
+        let search_filter = search_weak.clone();
+        let on_filter_file: std::rc::Rc<dyn Fn(&std::path::Path)> = std::rc::Rc::new(move |path| {
+            let (Some(stack), Some(search)) = (stack_filter.upgrade(), search_filter.upgrade())
+            else {
+                return;
+            };
+            let full = path.to_string_lossy();
+            if full.contains(char::is_whitespace) {
+                search.set_text(&format!("file:\"{}\"", full));
+            } else {
+                search.set_text(&format!("file:{}", full));
+            }
+            stack.set_visible_child_name("home");
+        });
+
+        if let Some(old) = stack.child_by_name("sources") {
+            stack.remove(&old);
+        }
+        let view = crate::ui::views::create_sources_view(&stack, &model_sources, on_filter_file);
+        stack.add_named(&view, Some("sources"));
+        stack.set_visible_child_name("sources");
+    });
+
+    let stack_weak = root_stack.downgrade();
+    let search_weak = search_entry.downgrade();
+    let category_weak = category_dropdown.downgrade();
+    let toast_weak = toast_overlay.downgrade();
+    let refresh_weak = std::rc::Rc::downgrade(&refresh_filter_callback);
+    searches_button.connect_clicked(move |_| {
+        let (Some(stack), Some(search), Some(toast_overlay)) = (
+            stack_weak.upgrade(),
+            search_weak.upgrade(),
+            toast_weak.upgrade(),
+        ) else {
+            return;
+        };
+
+        let stack_apply = stack.downgrade();
+        let search_apply = search.downgrade();
+        let category_apply = category_weak.clone();
+        let on_apply: std::rc::Rc<dyn Fn(&str)> = std::rc::Rc::new(move |query| {
+            if let Some(category) = category_apply.upgrade() {
+                category.set_selected(0);
+            }
+            if let Some(search) = search_apply.upgrade() {
+                search.set_text(query);
+            }
+            if let Some(stack) = stack_apply.upgrade() {
+                stack.set_visible_child_name("home");
+            }
+        });
+
+        let category_changed = category_weak.clone();
+        let refresh_changed = refresh_weak.clone();
+        let on_changed: std::rc::Rc<dyn Fn()> = std::rc::Rc::new(move || {
+            if let Some(category) = category_changed.upgrade() {
+                refresh_categories(&category, StyleConfig::load().show_favorites);
+            }
+            // A pinned search can change without the selected index changing
+            if let Some(refresh) = refresh_changed.upgrade() {
+                if let Some(f) = refresh.borrow().as_ref() {
+                    f();
+                }
+            }
+        });
+
+        if let Some(old) = stack.child_by_name("searches") {
+            stack.remove(&old);
+        }
+        let view = crate::ui::views::create_searches_view(
+            &stack,
+            &search.text(),
+            &toast_overlay,
+            on_apply,
+            on_changed,
+        );
+        stack.add_named(&view, Some("searches"));
+        stack.set_visible_child_name("searches");
+    });
+
+    let stack_weak = root_stack.downgrade();
+    let model_graph = model.clone();
+    let open_edit_graph = open_edit_bind.clone();
+    graph_button.connect_clicked(move |_| {
+        let Some(stack) = stack_weak.upgrade() else {
+            return;
+        };
+
+        if let Some(old) = stack.child_by_name("submap_graph") {
+            stack.remove(&old);
+        }
+        let view = crate::ui::views::create_submap_graph_view(
+            &stack,
+            &model_graph,
+            StyleConfig::load().default_submap.as_deref(),
+            open_edit_graph.clone(),
+        );
+        stack.add_named(&view, Some("submap_graph"));
+        stack.set_visible_child_name("submap_graph");
+    });
+
+    let stack_weak = root_stack.downgrade();
+    let model_problems = model.clone();
+    problems_button.connect_clicked(move |_| {
+        let Some(stack) = stack_weak.upgrade() else {
+            return;
+        };
+
+        if let Some(old) = stack.child_by_name("problems") {
+            stack.remove(&old);
+        }
+        let view = crate::ui::views::create_problems_view(&stack, &model_problems);
+        stack.add_named(&view, Some("problems"));
+        stack.set_visible_child_name("problems");
+    });
+
     // Force filter update on startup (delayed until here to ensure filter_func is defined)
-    let startup_text = search_entry.text().to_string();
-    let startup_cat = category_dropdown.selected();
+    let (startup_cat, startup_text) = category_query(&category_dropdown, &search_entry.text());
     let startup_sub_idx = submap_dropdown.selected();
     let startup_sub_val = if let Some(m) = submap_dropdown.model() {
         if let Some(s) = m.item(startup_sub_idx).and_downcast::<gtk::StringObject>() {
@@ -1,3 +1,4 @@
+use crate::config::favorites::{follow_edits, BindEdit};
 use crate::keybind_object::KeybindObject;
 use crate::parser;
 use crate::parser::BatchUpdate;
@@ -292,25 +293,31 @@ pub fn create_bulk_replace_wizard(
 
         // Perform backup ONCE
         if let Err(e) = perform_backup(false) {
-            eprintln!("Backup failed: {}", e);
+            crate::log_error!("backup", "Backup failed: {}", e);
         }
 
         let mut success_count = 0;
         let mut error_count = 0;
 
         let mut updates_by_file: HashMap<PathBuf, Vec<BatchUpdate>> = HashMap::new();
+        let mut edits = Vec::new();
 
         for (obj, new_val) in changes.iter() {
             let file_path = PathBuf::from(obj.property::<String>("file-path"));
             let line_number = obj.property::<u64>("line-number") as usize;
+            edits.push(BindEdit::Moved {
+                old: obj.bind_id(),
+                file: file_path.clone(),
+                line: line_number,
+            });
 
             let mut mods = obj.property::<String>("mods");
             let mut key = obj.property::<String>("key");
             let mut disp = obj.property::<String>("dispatcher");
             let mut args = obj.property::<String>("args");
 
-            // let flags = obj.property::<String>("flags"); // Keep existing flags
-            let _desc = obj.property::<String>("description");
+            let flags = obj.property::<String>("flags");
+            let desc = obj.property::<String>("description");
 
             // Update the specific field
             match target {
@@ -329,7 +336,7 @@ pub fn create_bulk_replace_wizard(
                     new_key: key,
                     new_dispatcher: disp,
                     new_args: args,
-                    description: None, // Pass None to preserve comments
+                    description: parser::kept_description(&flags, Some(&desc)),
                 });
         }
 
@@ -338,12 +345,13 @@ pub fn create_bulk_replace_wizard(
             match parser::update_multiple_lines(path.clone(), updates) {
                 Ok(_) => success_count += count,
                 Err(e) => {
-                    eprintln!("Failed to update file {:?}: {}", path, e);
+                    crate::log_error!("write", "Failed to update file {:?}: {}", path, e);
                     error_count += count;
                 }
             }
         }
 
+        follow_edits(&edits);
         reload_keybinds(&model_apply);
 
         let msg = if error_count > 0 {
@@ -1,14 +1,17 @@
+use crate::config::favorites::{follow_edits, BindEdit, BindId};
 use crate::keybind_object::KeybindObject;
 use crate::parser;
+use crate::ui::utils::conflicts::{apply_resolution_plan, plan_conflict_resolution, RebindReason};
 use crate::ui::utils::{
-    create_destructive_button, create_page_header, create_pill_button, create_suggested_button,
-    normalize, perform_backup,
+    create_card_row, create_destructive_button, create_page_header, create_pill_button,
+    create_suggested_button, model_keybinds, normalize, perform_backup, reload_keybinds,
 };
 use crate::ui::views::create_edit_view;
 use gtk::{gio, prelude::*};
 use gtk4 as gtk;
 use libadwaita as adw;
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::Arc;
 
 pub fn get_conflict_groups(model: &gio::ListStore) -> Vec<Vec<KeybindObject>> {
@@ -148,6 +151,7 @@ pub fn create_conflict_wizard(
 
     // List Items
     for obj in group {
+        let bind_id = obj.bind_id();
         let (dispatcher, args, file_path, line_num) = obj.with_data(|d| {
             (
                 d.dispatcher.to_string(),
@@ -229,10 +233,11 @@ pub fn create_conflict_wizard(
                     .build();
                 toast_overlay.add_toast(toast);
             } else {
+                follow_edits(&[BindEdit::Deleted(bind_id.clone())]);
                 crate::ui::utils::reload_keybinds(&model_c);
 
                 if let Err(e) = perform_backup(false) {
-                    eprintln!("Auto-backup failed: {}", e);
+                    crate::log_error!("backup", "Auto-backup failed: {}", e);
                 }
 
                 refresh_wizard(
@@ -298,13 +303,56 @@ pub fn create_conflict_wizard(
     let bottom_bar = gtk::CenterBox::builder().margin_top(12).build();
 
     let skip_btn = create_pill_button("Next Group", None);
+    let auto_btn = create_pill_button("Auto-Resolve All", Some("emblem-synchronizing-symbolic"));
+    auto_btn.set_tooltip_text(Some(
+        "Preview a plan that rebinds shadowed keybinds across all groups",
+    ));
     let done_btn = create_pill_button("Finish", None);
 
     if groups.len() > 1 {
         bottom_bar.set_start_widget(Some(&skip_btn));
     }
+    bottom_bar.set_center_widget(Some(&auto_btn));
     bottom_bar.set_end_widget(Some(&done_btn));
 
+    let stack_weak = stack.downgrade();
+    let model_c = model.clone();
+    let column_view_weak = column_view.downgrade();
+    let selection_model_weak = selection_model.downgrade();
+    let toast_overlay_weak = toast_overlay.downgrade();
+    let wizard_container_weak = wizard_container.downgrade();
+    auto_btn.connect_clicked(move |_| {
+        let (
+            Some(stack),
+            Some(column_view),
+            Some(selection_model),
+            Some(toast_overlay),
+            Some(wizard_container),
+        ) = (
+            stack_weak.upgrade(),
+            column_view_weak.upgrade(),
+            selection_model_weak.upgrade(),
+            toast_overlay_weak.upgrade(),
+            wizard_container_weak.upgrade(),
+        )
+        else {
+            return;
+        };
+
+        while let Some(child) = wizard_container.first_child() {
+            wizard_container.remove(&child);
+        }
+        let view = create_auto_resolve_view(
+            &stack,
+            &model_c,
+            &column_view,
+            &selection_model,
+            &toast_overlay,
+            &wizard_container,
+        );
+        wizard_container.append(&view);
+    });
+
     let stack_weak = stack.downgrade();
     done_btn.connect_clicked(move |_| {
         if let Some(s) = stack_weak.upgrade() {
@@ -380,3 +428,226 @@ fn refresh_wizard(
     );
     wizard_container.append(&view);
 }
+
+/// Previews an auto-resolve plan for every conflict group and applies it as
+/// one batch.
+fn create_auto_resolve_view(
+    stack: &gtk::Stack,
+    model: &gio::ListStore,
+    column_view: &gtk::ColumnView,
+    selection_model: &gtk::SingleSelection,
+    toast_overlay: &adw::ToastOverlay,
+    wizard_container: &gtk::Box,
+) -> gtk::Widget {
+    let container = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .vexpand(true)
+        .build();
+
+    let stack_weak = stack.downgrade();
+    let model_c = model.clone();
+    let column_view_weak = column_view.downgrade();
+    let selection_model_weak = selection_model.downgrade();
+    let toast_overlay_weak = toast_overlay.downgrade();
+    let wizard_container_weak = wizard_container.downgrade();
+    let back_to_wizard = Rc::new(move || {
+        if let (
+            Some(stack),
+            Some(column_view),
+            Some(selection_model),
+            Some(toast_overlay),
+            Some(wizard_container),
+        ) = (
+            stack_weak.upgrade(),
+            column_view_weak.upgrade(),
+            selection_model_weak.upgrade(),
+            toast_overlay_weak.upgrade(),
+            wizard_container_weak.upgrade(),
+        ) {
+            refresh_wizard(
+                &stack,
+                &model_c,
+                &column_view,
+                &selection_model,
+                &toast_overlay,
+                &wizard_container,
+                0,
+            );
+        }
+    });
+
+    let back = back_to_wizard.clone();
+    let header_box = create_page_header(
+        "Auto-Resolve Plan",
+        Some("The last definition of each combination is kept, since that is the one Hyprland uses. Earlier ones get extra modifiers."),
+        "Back to Conflicts",
+        move || back(),
+    );
+    container.append(&header_box);
+
+    let keep_groups_switch = gtk::Switch::builder()
+        .active(true)
+        .valign(gtk::Align::Center)
+        .build();
+    container.append(&create_card_row(
+        "Keep Related Binds Together",
+        Some("Move binds like workspace 1-9 as a group so they keep one modifier pattern"),
+        &keep_groups_switch,
+    ));
+
+    let summary_label = gtk::Label::builder()
+        .css_classes(["dim-label"])
+        .halign(gtk::Align::Start)
+        .build();
+    container.append(&summary_label);
+
+    let list_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .build();
+    let scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&list_box)
+        .build();
+    container.append(&scroll);
+
+    let bottom_bar = gtk::CenterBox::builder().margin_top(12).build();
+    let cancel_btn = create_pill_button("Cancel", None);
+    let apply_btn = create_suggested_button("Apply Plan", Some("object-select-symbolic"));
+    bottom_bar.set_start_widget(Some(&cancel_btn));
+    bottom_bar.set_end_widget(Some(&apply_btn));
+    container.append(&bottom_bar);
+
+    let back = back_to_wizard.clone();
+    cancel_btn.connect_clicked(move |_| back());
+
+    let keybinds = Rc::new(model_keybinds(model));
+
+    let build_plan = {
+        let keybinds = keybinds.clone();
+        let keep_groups_switch = keep_groups_switch.clone();
+        let apply_btn = apply_btn.clone();
+        move || {
+            while let Some(child) = list_box.first_child() {
+                list_box.remove(&child);
+            }
+
+            let plan = plan_conflict_resolution(&keybinds, keep_groups_switch.is_active());
+
+            let mut summary = match plan.rebinds.len() {
+                1 => "1 keybind will be rebound".to_string(),
+                n => format!("{} keybinds will be rebound", n),
+            };
+            if !plan.unresolved.is_empty() {
+                summary.push_str(&format!(
+                    ", {} could not be resolved automatically",
+                    plan.unresolved.len()
+                ));
+            }
+            summary_label.set_label(&summary);
+            apply_btn.set_sensitive(!plan.rebinds.is_empty());
+
+            let location = |kb: &parser::Keybind| {
+                format!(
+                    "{}:{}",
+                    kb.file_path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                    kb.line_number + 1
+                )
+            };
+            let action = |kb: &parser::Keybind| {
+                if kb.args.is_empty() {
+                    kb.dispatcher.to_string()
+                } else {
+                    format!("{} {}", kb.dispatcher, kb.args)
+                }
+            };
+
+            for rebind in &plan.rebinds {
+                let kb = &keybinds[rebind.index];
+                let title = format!(
+                    "{}, {}  →  {}, {}",
+                    kb.mods, kb.key, rebind.new_mods, kb.key
+                );
+                let mut subtitle = format!("{} · {}", action(kb), location(kb));
+                if rebind.reason == RebindReason::KeepGroupConsistent {
+                    subtitle.push_str(" · moved with related binds");
+                }
+                let icon = gtk::Image::from_icon_name("go-next-symbolic");
+                list_box.append(&create_card_row(&title, Some(&subtitle), &icon));
+            }
+
+            for &idx in &plan.unresolved {
+                let kb = &keybinds[idx];
+                let title = format!("{}, {}", kb.mods, kb.key);
+                let subtitle = format!(
+                    "{} · {} · no free combination, resolve by hand",
+                    action(kb),
+                    location(kb)
+                );
+                let icon = gtk::Image::builder()
+                    .icon_name("dialog-warning-symbolic")
+                    .css_classes(["error-icon"])
+                    .build();
+                list_box.append(&create_card_row(&title, Some(&subtitle), &icon));
+            }
+        }
+    };
+    let build_plan = Rc::new(build_plan);
+
+    let bp = build_plan.clone();
+    keep_groups_switch.connect_active_notify(move |_| bp());
+
+    let model_c = model.clone();
+    let toast_overlay_weak = toast_overlay.downgrade();
+    let back = back_to_wizard.clone();
+    apply_btn.connect_clicked(move |_| {
+        let Some(toast_overlay) = toast_overlay_weak.upgrade() else {
+            return;
+        };
+
+        if let Err(e) = perform_backup(false) {
+            crate::log_error!("backup", "Auto-backup failed: {}", e);
+        }
+
+        let plan = plan_conflict_resolution(&keybinds, keep_groups_switch.is_active());
+        let message = match apply_resolution_plan(&keybinds, &plan) {
+            Ok(count) => {
+                let edits: Vec<BindEdit> = plan
+                    .rebinds
+                    .iter()
+                    .filter_map(|r| keybinds.get(r.index))
+                    .map(|kb| BindEdit::Moved {
+                        old: BindId::of(kb),
+                        file: kb.file_path.clone(),
+                        line: kb.line_number,
+                    })
+                    .collect();
+                follow_edits(&edits);
+                format!("Rebound {} keybinds", count)
+            }
+            Err(e) => format!("Error: {}", e),
+        };
+        toast_overlay.add_toast(
+            adw::Toast::builder()
+                .title(message)
+                .timeout(crate::config::constants::TOAST_TIMEOUT)
+                .build(),
+        );
+
+        reload_keybinds(&model_c);
+        back();
+    });
+
+    build_plan();
+
+    container.upcast()
+}
@@ -1,5 +1,6 @@
 use crate::parser;
 use crate::ui::utils::{create_page_header, create_suggested_button, reload_keybinds};
+use crate::ui::which_key::{start_which_key, WHICH_KEY_COMMAND};
 use gtk::{gio, prelude::*};
 use gtk4 as gtk;
 use libadwaita as adw;
@@ -98,6 +99,13 @@ pub fn create_add_submap_wizard(
     grid.attach(&lbl_reset, 0, 3, 1, 1);
     grid.attach(&entry_reset, 1, 3, 1, 1);
 
+    let which_key_check = gtk::CheckButton::builder()
+        .label("Show a which-key popup with the binds of a submap while it is active")
+        .tooltip_text("Adds 'exec-once = hyprkcs --which-key' to your config and starts it now")
+        .active(crate::config::hud::is_which_key_running())
+        .build();
+    grid.attach(&which_key_check, 0, 4, 2, 1);
+
     form_box.append(&grid);
     container.append(&form_box);
 
@@ -157,6 +165,7 @@ pub fn create_add_submap_wizard(
     let entry_enter_key_c = entry_enter_key.clone();
     let entry_reset_c = entry_reset.clone();
     let default_submap_c = default_submap.clone();
+    let which_key_check_c = which_key_check.clone();
 
     apply_btn.connect_clicked(move |_| {
         let name = entry_name_c.text().trim().to_string();
@@ -176,17 +185,27 @@ pub fn create_add_submap_wizard(
         if let Ok(config_path) = parser::get_config_path() {
             let exit_target = default_submap_c.as_deref().unwrap_or("reset");
 
-            // 1. Create Submap Block
-            if let Err(e) = parser::create_submap_block(
+            // 1. Create Submap Block, in the file the routing rules pick for
+            // binds inside it
+            let block_path = crate::config::routing::destination_for(
+                "",
+                "",
+                "",
+                Some(&name),
                 config_path.clone(),
-                &name,
-                if reset_key.is_empty() {
-                    None
-                } else {
-                    Some(&reset_key)
-                },
-                exit_target,
-            ) {
+            );
+            if let Err(e) = parser::ensure_sourced(&block_path).and_then(|_| {
+                parser::create_submap_block(
+                    block_path,
+                    &name,
+                    if reset_key.is_empty() {
+                        None
+                    } else {
+                        Some(&reset_key)
+                    },
+                    exit_target,
+                )
+            }) {
                 let toast = adw::Toast::builder()
                     .title(format!("Failed to create submap: {}", e))
                     .timeout(crate::config::constants::TOAST_TIMEOUT)
@@ -197,8 +216,16 @@ pub fn create_add_submap_wizard(
 
             // 2. Add Entry Bind (if specified)
             if !enter_key.is_empty() {
-                let _ = parser::add_keybind(
+                let entry_path = crate::config::routing::destination_for(
+                    "submap",
+                    &name,
+                    &enter_key,
+                    default_submap_c.as_deref(),
                     config_path,
+                );
+                let _ = parser::ensure_sourced(&entry_path);
+                let _ = parser::add_keybind(
+                    entry_path,
                     &enter_mods,
                     &enter_key,
                     "submap",
@@ -209,10 +236,21 @@ pub fn create_add_submap_wizard(
                 );
             }
 
+            // 3. Wire up the which-key overlay (if requested)
+            let mut title = format!("Submap '{}' created successfully", name);
+            if which_key_check_c.is_active() {
+                let result = parser::ensure_exec_once(WHICH_KEY_COMMAND)
+                    .and_then(|_| start_which_key().map_err(anyhow::Error::from));
+                if let Err(e) = result {
+                    crate::log_error!("ui", "Failed to set up the which-key overlay: {}", e);
+                    title = format!("Submap '{}' created, but which-key failed: {}", name, e);
+                }
+            }
+
             reload_keybinds(&model_c);
 
             let toast = adw::Toast::builder()
-                .title(format!("Submap '{}' created successfully", name))
+                .title(title)
                 .timeout(crate::config::constants::TOAST_TIMEOUT)
                 .build();
             toast_overlay_c.add_toast(toast);
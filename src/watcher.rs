@@ -2,7 +2,10 @@ use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 use std::path::PathBuf;
 use std::sync::mpsc::Sender;
 
-pub fn create_config_watcher(sender: Sender<()>) -> Option<RecommendedWatcher> {
+/// Watches the directories of every loaded config file and sends the paths of
+/// changes that affect the config, including new files a `source` glob picks
+/// up.
+pub fn create_config_watcher(sender: Sender<Vec<PathBuf>>) -> Option<RecommendedWatcher> {
     let files = match crate::parser::get_loaded_files() {
         Ok(f) if !f.is_empty() => f,
         _ => crate::parser::get_config_path()
@@ -40,35 +43,40 @@ pub fn create_config_watcher(sender: Sender<()>) -> Option<RecommendedWatcher> {
         }
     }
 
-    let files_to_check = files;
     let sender = sender.clone();
 
     let mut watcher = match RecommendedWatcher::new(
         move |res: Result<notify::Event, _>| match res {
             Ok(event) => {
-                let relevant = event
+                let changed: Vec<PathBuf> = event
                     .paths
-                    .iter()
-                    .any(|p| files_to_check.iter().any(|f| f == p));
+                    .into_iter()
+                    .filter(|p| crate::parser::affects_config(p))
+                    .collect();
 
-                if relevant {
-                    let _ = sender.send(());
+                if !changed.is_empty() {
+                    let _ = sender.send(changed);
                 }
             }
-            Err(e) => eprintln!("Watch error: {:?}", e),
+            Err(e) => crate::log_error!("watcher", "Watch error: {:?}", e),
         },
         Config::default(),
     ) {
         Ok(w) => w,
         Err(e) => {
-            eprintln!("Failed to create file watcher: {}", e);
+            crate::log_error!("watcher", "Failed to create file watcher: {}", e);
             return None;
         }
     };
 
     for dir in final_dirs {
         if let Err(e) = watcher.watch(&dir, RecursiveMode::Recursive) {
-            eprintln!("Failed to start recursive watcher on {:?}: {}", dir, e);
+            crate::log_error!(
+                "watcher",
+                "Failed to start recursive watcher on {:?}: {}",
+                dir,
+                e
+            );
         }
     }
 
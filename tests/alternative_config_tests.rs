@@ -156,8 +156,10 @@ fn test_style_config_serialization() {
         fs::create_dir_all(&valid_path).unwrap();
         let path_str = valid_path.to_str().unwrap().to_string();
 
-        let mut config = StyleConfig::default();
-        config.alternative_config_path = Some(path_str.clone());
+        let config = StyleConfig {
+            alternative_config_path: Some(path_str.clone()),
+            ..Default::default()
+        };
 
         // Save
         config.save().unwrap();
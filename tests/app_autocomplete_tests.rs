@@ -82,8 +82,7 @@ Type=Application
 "#;
         fs::write(apps_dir.join("fake-simple.desktop"), simple_desktop).unwrap();
 
-        // 4. Quoted Command (Edge case handling check)
-        // The current logic splits by whitespace, so "my-app" might be kept as ""my-app"" if quoted?
+        // 4. Quoted Command: quotes are removed before taking the binary name
         let quoted_desktop = r#"
 [Desktop Entry]
 Name=Fake Quoted
@@ -107,7 +106,10 @@ Type=Application
             .iter()
             .find(|a| a.name == "Fake Simple")
             .expect("Simple not found");
-        let fake_quoted = apps.iter().find(|a| a.name == "Fake Quoted");
+        let fake_quoted = apps
+            .iter()
+            .find(|a| a.name == "Fake Quoted")
+            .expect("Quoted not found");
 
         // Assertions
         // /usr/lib/firefox/firefox -> firefox
@@ -125,8 +127,8 @@ Type=Application
             "Failed to clean simple command"
         );
 
-        if let Some(q) = fake_quoted {
-            println!("Quoted exec resulted in: {}", q.exec);
-        }
+        // "quoted-app" %F -> quoted-app
+        assert_eq!(fake_quoted.exec, "quoted-app");
+        assert_eq!(fake_quoted.command, vec!["quoted-app"]);
     });
 }
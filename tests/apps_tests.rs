@@ -0,0 +1,237 @@
+use hyprKCS::ui::utils::apps::*;
+use std::path::{Path, PathBuf};
+
+const FIREFOX: &str = r#"
+[Desktop Entry]
+Type=Application
+Name=Firefox
+Name[de]=Feuerfuchs
+Icon=firefox
+TryExec=firefox
+Exec=/usr/lib/firefox/firefox %u
+Actions=new-window;private;
+
+[Desktop Action new-window]
+Name=New Window
+Name[de_DE]=Neues Fenster
+Exec=/usr/lib/firefox/firefox --new-window %u
+
+[Desktop Action private]
+Name=New Private Window
+Exec=/usr/lib/firefox/firefox --private-window %u
+"#;
+
+fn firefox(locales: &[String]) -> AppInfo {
+    parse_desktop_entry(
+        FIREFOX,
+        "firefox",
+        Path::new("/usr/share/applications/firefox.desktop"),
+        locales,
+    )
+    .expect("valid entry")
+}
+
+fn entry(id: &str, name: &str, exec: &str) -> AppInfo {
+    let content = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec={}\n",
+        name, exec
+    );
+    parse_desktop_entry(&content, id, &PathBuf::from(id), &[]).expect("valid entry")
+}
+
+#[test]
+fn test_parse_desktop_entry_actions_and_try_exec() {
+    let app = firefox(&[]);
+    assert_eq!(app.name, "Firefox");
+    assert_eq!(app.exec, "firefox");
+    assert_eq!(app.command, vec!["/usr/lib/firefox/firefox"]);
+    assert_eq!(app.icon.as_deref(), Some("firefox"));
+    assert_eq!(app.try_exec.as_deref(), Some("firefox"));
+
+    let ids: Vec<&str> = app.actions.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["new-window", "private"]);
+    assert_eq!(
+        app.action("private").unwrap().command,
+        vec!["/usr/lib/firefox/firefox", "--private-window"]
+    );
+}
+
+#[test]
+fn test_parse_desktop_entry_localized_names() {
+    assert_eq!(
+        locale_variants("de_DE.UTF-8@euro"),
+        vec!["de_DE@euro", "de_DE", "de@euro", "de"]
+    );
+
+    let app = firefox(&locale_variants("de_DE.UTF-8"));
+    assert_eq!(app.name, "Feuerfuchs");
+    assert_eq!(app.action("new-window").unwrap().name, "Neues Fenster");
+    // Falls back to the unlocalized name
+    assert_eq!(app.action("private").unwrap().name, "New Private Window");
+}
+
+#[test]
+fn test_parse_exec_field_codes() {
+    let path = Path::new("/apps/x.desktop");
+    assert_eq!(
+        parse_exec(
+            "foo %F --name=%c %i 100%% %k",
+            "Foo",
+            Some("foo-icon"),
+            path
+        ),
+        vec![
+            "foo",
+            "--name=Foo",
+            "--icon",
+            "foo-icon",
+            "100%",
+            "/apps/x.desktop"
+        ]
+    );
+    assert_eq!(
+        split_command(r#""/opt/My App/run" --title "a \"b\"" 'c d'"#),
+        vec!["/opt/My App/run", "--title", "a \"b\"", "c d"]
+    );
+}
+
+#[test]
+fn test_parse_desktop_entry_rejects_non_apps() {
+    let path = Path::new("x.desktop");
+    let link = "[Desktop Entry]\nType=Link\nName=Site\nURL=https://example.com\n";
+    assert!(parse_desktop_entry(link, "x", path, &[]).is_none());
+    let hidden = "[Desktop Entry]\nName=Gone\nExec=gone\nHidden=true\n";
+    assert!(parse_desktop_entry(hidden, "x", path, &[]).is_none());
+}
+
+#[test]
+fn test_parse_exec_target_launchers() {
+    assert_eq!(
+        parse_exec_target("[float] gtk-launch org.gnome.Nautilus"),
+        Some(ExecTarget::DesktopId {
+            id: "org.gnome.Nautilus".to_string(),
+            action: None,
+            style: LaunchStyle::GtkLaunch,
+        })
+    );
+    assert_eq!(
+        parse_exec_target("uwsm app -s b -- firefox.desktop:private"),
+        Some(ExecTarget::DesktopId {
+            id: "firefox".to_string(),
+            action: Some("private".to_string()),
+            style: LaunchStyle::Uwsm,
+        })
+    );
+    assert_eq!(
+        parse_exec_target("uwsm-app kitty --single-instance"),
+        Some(ExecTarget::Command {
+            args: vec!["kitty".to_string(), "--single-instance".to_string()],
+            style: LaunchStyle::Uwsm,
+        })
+    );
+    assert_eq!(parse_exec_target("  "), None);
+}
+
+#[test]
+fn test_link_exec_bind() {
+    let entries = vec![
+        firefox(&[]),
+        entry("kitty", "Kitty", "kitty"),
+        entry("kitty-hidden", "Kitty Hidden", "kitty --class x"),
+    ];
+
+    let link = link_exec_bind("/usr/lib/firefox/firefox --private-window", &entries).unwrap();
+    assert_eq!(link.app.id, "firefox");
+    assert_eq!(link.action.map(|a| a.id.as_str()), Some("private"));
+    assert_eq!(link.display_name(), "Firefox: New Private Window");
+
+    let link = link_exec_bind("[workspace 2] kitty -e htop", &entries).unwrap();
+    assert_eq!(link.app.id, "kitty");
+    assert_eq!(link.style, LaunchStyle::Command);
+
+    let link = link_exec_bind("uwsm app -- firefox.desktop", &entries).unwrap();
+    assert_eq!(link.app.id, "firefox");
+    assert_eq!(link.style, LaunchStyle::Uwsm);
+
+    assert!(link_exec_bind("notify-send hi", &entries).is_none());
+    assert!(link_exec_bind("gtk-launch missing", &entries).is_none());
+}
+
+#[test]
+fn test_app_problem() {
+    let entries = vec![entry("sh", "Shell", "sh")];
+
+    assert_eq!(app_problem("gtk-launch sh", &entries), None);
+    assert_eq!(
+        app_problem("gtk-launch org.example.Gone", &entries),
+        Some("Application not installed: org.example.Gone".to_string())
+    );
+    assert_eq!(
+        app_problem("uwsm app -- sh.desktop:nope", &entries),
+        Some("Shell has no action 'nope'".to_string())
+    );
+    assert_eq!(
+        app_problem("uwsm app -- hyprkcs-definitely-missing", &entries),
+        Some("Executable not found: hyprkcs-definitely-missing".to_string())
+    );
+    // Plain commands are left to the PATH check
+    assert_eq!(app_problem("hyprkcs-definitely-missing", &entries), None);
+}
+
+#[test]
+fn test_launch_command_styles() {
+    let app = firefox(&[]);
+    let private = app.action("private");
+
+    assert_eq!(
+        launch_command(&app, None, LaunchStyle::Command),
+        "/usr/lib/firefox/firefox"
+    );
+    assert_eq!(
+        launch_command(&app, None, LaunchStyle::GtkLaunch),
+        "gtk-launch firefox"
+    );
+    // gtk-launch can't start actions
+    assert_eq!(
+        launch_command(&app, private, LaunchStyle::GtkLaunch),
+        "/usr/lib/firefox/firefox --private-window"
+    );
+    assert_eq!(
+        launch_command(&app, private, LaunchStyle::Uwsm),
+        "uwsm app -- firefox.desktop:private"
+    );
+
+    // Every style links back to the same app and action
+    let entries = vec![app.clone()];
+    for (style, _) in LAUNCH_STYLES {
+        let args = launch_command(&app, private, *style);
+        let link = link_exec_bind(&args, &entries).unwrap();
+        assert_eq!(
+            link.action.map(|a| a.id.as_str()),
+            Some("private"),
+            "{}",
+            args
+        );
+    }
+}
+
+#[test]
+fn test_desktop_entries_follow_application_dir_changes() {
+    let root = std::env::temp_dir().join(format!("hyprkcs_test_apps_{}", std::process::id()));
+    let applications = root.join("applications");
+    std::fs::create_dir_all(&applications).unwrap();
+    std::env::set_var("XDG_DATA_DIRS", &root);
+
+    let has = |id: &str| get_desktop_entries().iter().any(|app| app.id == id);
+    std::fs::write(applications.join("hyprkcs-first.desktop"), FIREFOX).unwrap();
+    assert!(has("hyprkcs-first"));
+    assert!(!has("hyprkcs-second"));
+
+    std::fs::write(applications.join("hyprkcs-second.desktop"), FIREFOX).unwrap();
+    assert!(has("hyprkcs-second"));
+
+    std::fs::remove_file(applications.join("hyprkcs-first.desktop")).unwrap();
+    assert!(!has("hyprkcs-first"));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
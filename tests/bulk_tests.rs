@@ -160,7 +160,7 @@ fn test_bulk_replace_dispatcher() {
                 bind.line_number,
                 &bind.mods,
                 &bind.key,
-                &new_disp,
+                new_disp,
                 &bind.args,
                 None,
                 None,
@@ -174,3 +174,38 @@ fn test_bulk_replace_dispatcher() {
     assert!(new_content.contains("bind = SUPER, 2, movetoworkspace, 2"));
     assert!(new_content.contains("bind = SUPER, Q, killactive"));
 }
+
+#[test]
+fn test_bulk_update_keeps_descriptions_and_comments() {
+    let _guard = lock_env();
+    let content = "bindld = SUPER, M, Mute, exec, wpctl set-mute @DEFAULT_AUDIO_SINK@ toggle\n\
+                   bindd = SUPER, Q, Close, killactive,\n\
+                   bind = SUPER, E, exec, nautilus # files\n";
+    let temp = TempFile::new(content);
+    std::env::set_var("HYPRKCS_CONFIG", &temp.path);
+    invalidate_parser_cache();
+
+    let binds = parse_config().expect("Failed to parse config");
+    assert_eq!(binds.len(), 3);
+    let updates = binds
+        .iter()
+        .map(|bind| BatchUpdate {
+            line_number: bind.line_number,
+            new_mods: "ALT".to_string(),
+            new_key: bind.key.to_string(),
+            new_dispatcher: bind.dispatcher.to_string(),
+            new_args: bind.args.to_string(),
+            description: kept_description(&bind.flags, bind.description.as_deref()),
+        })
+        .collect();
+    update_multiple_lines(temp.path.clone(), updates).expect("Failed to update lines");
+
+    let new_content = std::fs::read_to_string(&temp.path).unwrap();
+    let lines: Vec<&str> = new_content.lines().collect();
+    assert_eq!(
+        lines[0],
+        "bindld = ALT, M, Mute, exec, wpctl set-mute @DEFAULT_AUDIO_SINK@ toggle"
+    );
+    assert_eq!(lines[1], "bindd = ALT, Q, Close, killactive");
+    assert_eq!(lines[2], "bind = ALT, E, exec, nautilus # files");
+}
@@ -0,0 +1,151 @@
+use hyprKCS::config::categories::{
+    default_categories, find_category, get_categories_path, load_categories, parse_categories,
+};
+use hyprKCS::parser::Keybind;
+use hyprKCS::ui::utils::search::{KeybindSearch, SearchQuery};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+static ENV_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+    match ENV_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "hyprkcs_categories_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn bind(key: &str, dispatcher: &str, args: &str) -> Keybind {
+    Keybind {
+        mods: "SUPER".into(),
+        clean_mods: "SUPER".into(),
+        flags: "".into(),
+        key: key.into(),
+        dispatcher: dispatcher.into(),
+        args: args.into(),
+        description: None,
+        submap: None,
+        line_number: 1,
+        file_path: PathBuf::from("/home/user/.config/hypr/hyprland.conf"),
+    }
+}
+
+/// Names of the default categories `kb` is in.
+fn categories_of(kb: &Keybind) -> Vec<String> {
+    let target = KeybindSearch {
+        keybind: kb,
+        conflicted: false,
+        broken: false,
+        favorite: false,
+    };
+    default_categories()
+        .into_iter()
+        .filter(|c| SearchQuery::parse(&c.query).matches_exact(&target))
+        .map(|c| c.name)
+        .collect()
+}
+
+#[test]
+fn test_default_categories() {
+    for category in default_categories() {
+        assert!(SearchQuery::parse(&category.query).errors.is_empty());
+    }
+    assert_eq!(
+        categories_of(&bind("2", "movetoworkspace", "2")),
+        ["Workspace"]
+    );
+    assert_eq!(categories_of(&bind("F", "fullscreen", "")), ["Window"]);
+    assert_eq!(
+        categories_of(&bind("XF86AudioPlay", "exec", "playerctl play-pause")),
+        ["Media", "Custom"]
+    );
+    assert_eq!(categories_of(&bind("T", "exec", "kitty")), ["Custom"]);
+    assert!(categories_of(&bind("T", "execr", "kitty")).is_empty());
+    assert_eq!(
+        categories_of(&bind("mouse:272", "movewindow", "")),
+        ["Window", "Mouse"]
+    );
+}
+
+#[test]
+fn test_category_queries_do_not_fuzzy_match() {
+    let kb = bind("T", "exec", "kitty");
+    let target = KeybindSearch {
+        keybind: &kb,
+        conflicted: false,
+        broken: false,
+        favorite: false,
+    };
+    assert!(!SearchQuery::parse("kty").matches_exact(&target));
+    assert!(SearchQuery::parse("kitty").matches_exact(&target));
+}
+
+#[test]
+fn test_parse_categories() {
+    let categories = parse_categories(
+        r#"{"categories": [
+            {"name": "Screenshots", "query": "args:/grim|hyprshot/"},
+            {"name": "screenshots", "query": "action:exec"},
+            {"name": "Favorites", "query": "fav:yes"},
+            {"name": "Empty", "query": ""},
+            {"name": "Launchers", "query": "args:rofi OR args:wofi"}
+        ]}"#,
+    )
+    .unwrap();
+    let names: Vec<&str> = categories.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["Screenshots", "Launchers"]);
+    assert_eq!(
+        find_category(&categories, "LAUNCHERS").map(|c| c.query.as_str()),
+        Some("args:rofi OR args:wofi")
+    );
+    assert!(parse_categories("not json").is_none());
+}
+
+#[test]
+fn test_load_writes_defaults() {
+    let _guard = lock_env();
+    let temp = TempDir::new("load");
+    std::env::set_var("XDG_CONFIG_HOME", &temp.path);
+
+    let path = get_categories_path().unwrap();
+    assert!(!path.exists());
+    assert_eq!(load_categories(), default_categories());
+    assert!(path.exists());
+
+    fs::write(
+        &path,
+        r#"{"categories": [{"name": "Scratchpads", "query": "args:special"}]}"#,
+    )
+    .unwrap();
+    let categories = load_categories();
+    assert_eq!(categories.len(), 1);
+    assert_eq!(categories[0].name, "Scratchpads");
+
+    fs::write(&path, "{broken").unwrap();
+    assert_eq!(load_categories(), default_categories());
+}
@@ -1,4 +1,5 @@
 use hyprKCS::cli::Args;
+use hyprKCS::logging::Level;
 use std::path::PathBuf;
 
 #[test]
@@ -54,3 +55,58 @@ fn test_cli_parsing_empty() {
     assert!(parsed.config.is_none());
     assert!(parsed.search.is_none());
 }
+
+#[test]
+fn test_cli_parsing_lint() {
+    let parsed = Args::parse_from(vec!["hyprkcs", "--lint", "-c", "/tmp/hypr.conf"]);
+    assert!(parsed.lint);
+    assert!(!parsed.doctor);
+    assert_eq!(parsed.config, Some(PathBuf::from("/tmp/hypr.conf")));
+}
+
+#[test]
+fn test_cli_parsing_logging() {
+    let parsed = Args::parse_from(vec!["hyprkcs", "--verbose", "--log-level", "Debug"]);
+    assert!(parsed.verbose);
+    assert_eq!(parsed.log_level, Some(Level::Debug));
+
+    let parsed = Args::parse_from(vec!["hyprkcs", "--log-level", "loud"]);
+    assert!(!parsed.verbose);
+    assert_eq!(parsed.log_level, None);
+}
+
+#[test]
+fn test_cli_parsing_hud_instance() {
+    let parsed = Args::parse_from(vec!["hyprkcs", "--hud"]);
+    assert!(parsed.hud);
+    assert!(parsed.hud_instance.is_none());
+
+    let parsed = Args::parse_from(vec!["hyprkcs", "--hud-instance", "media"]);
+    assert!(parsed.hud);
+    assert_eq!(parsed.hud_instance.as_deref(), Some("media"));
+}
+
+#[test]
+fn test_cli_parsing_which_key() {
+    let parsed = Args::parse_from(vec!["hyprkcs", "--which-key"]);
+    assert!(parsed.which_key);
+    assert!(parsed.which_key_submap.is_none());
+
+    let parsed = Args::parse_from(vec!["hyprkcs", "--which-key", "resize"]);
+    assert!(parsed.which_key);
+    assert_eq!(parsed.which_key_submap.as_deref(), Some("resize"));
+
+    // A following option is not taken as the submap
+    let parsed = Args::parse_from(vec!["hyprkcs", "--which-key", "--verbose"]);
+    assert!(parsed.which_key);
+    assert!(parsed.which_key_submap.is_none());
+    assert!(parsed.verbose);
+}
+
+#[test]
+fn test_cli_parsing_launcher() {
+    let parsed = Args::parse_from(vec!["hyprkcs", "--launcher"]);
+    assert!(parsed.launcher);
+    assert!(!parsed.hud);
+    assert!(!Args::parse_from(vec!["hyprkcs"]).launcher);
+}
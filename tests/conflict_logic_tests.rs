@@ -1,4 +1,7 @@
 use hyprKCS::parser::Keybind;
+use hyprKCS::ui::utils::conflicts::{
+    apply_resolution_plan, plan_conflict_resolution, RebindReason,
+};
 use hyprKCS::ui::utils::detect_conflicts;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -70,3 +73,186 @@ fn test_detect_conflicts_triple() {
     assert!(results[0].as_ref().unwrap().contains("d2"));
     assert!(results[0].as_ref().unwrap().contains("d3"));
 }
+
+fn create_kb_at(mods: &str, key: &str, disp: &str, args: &str, line: usize) -> Keybind {
+    Keybind {
+        line_number: line,
+        ..create_kb(mods, key, disp, args, None)
+    }
+}
+
+#[test]
+fn test_plan_keeps_last_definition() {
+    let kbs = vec![
+        create_kb("SUPER", "Q", "killactive", "", None),
+        create_kb("SUPER SHIFT", "Q", "exit", "", None),
+        create_kb("SUPER", "Q", "exec", "kitty", None),
+    ];
+
+    let plan = plan_conflict_resolution(&kbs, true);
+    assert!(plan.unresolved.is_empty());
+    assert_eq!(plan.rebinds.len(), 1);
+    assert_eq!(plan.rebinds[0].index, 0);
+    assert_eq!(plan.rebinds[0].reason, RebindReason::Conflict);
+    // SHIFT is taken by `exit`, so the next modifier is used
+    assert_eq!(plan.rebinds[0].new_mods, "SUPER CTRL");
+}
+
+#[test]
+fn test_plan_never_creates_new_conflicts() {
+    let kbs = vec![
+        create_kb("SUPER", "E", "d1", "", None),
+        create_kb("SUPER", "E", "d2", "", None),
+        create_kb("SUPER", "E", "d3", "", None),
+        create_kb("ALT", "X", "d4", "", Some("sub")),
+        create_kb("ALT", "X", "d5", "", Some("sub")),
+    ];
+
+    let plan = plan_conflict_resolution(&kbs, true);
+    assert_eq!(plan.rebinds.len(), 3);
+
+    let mut resolved = kbs.clone();
+    for rebind in &plan.rebinds {
+        let mods: std::sync::Arc<str> = Arc::from(rebind.new_mods.as_str());
+        resolved[rebind.index].mods = mods.clone();
+        resolved[rebind.index].clean_mods = mods;
+    }
+    assert!(detect_conflicts(&resolved).iter().all(|c| c.is_none()));
+}
+
+#[test]
+fn test_plan_moves_related_binds_together() {
+    let mut kbs: Vec<Keybind> = (1..=9)
+        .map(|n| create_kb("SUPER", &n.to_string(), "workspace", &n.to_string(), None))
+        .collect();
+    // Shadows `workspace 3`
+    kbs.push(create_kb("SUPER", "3", "exec", "kitty", None));
+    // SUPER SHIFT + digit is already used for moving windows
+    kbs.extend((1..=9).map(|n| {
+        create_kb(
+            "SUPER SHIFT",
+            &n.to_string(),
+            "movetoworkspace",
+            &n.to_string(),
+            None,
+        )
+    }));
+
+    let plan = plan_conflict_resolution(&kbs, true);
+    assert_eq!(plan.rebinds.len(), 9);
+    assert!(plan.rebinds.iter().all(|r| r.new_mods == "SUPER CTRL"));
+    assert_eq!(
+        plan.rebinds
+            .iter()
+            .filter(|r| r.reason == RebindReason::Conflict)
+            .count(),
+        1
+    );
+
+    // Without grouping only the shadowed bind moves
+    let plan = plan_conflict_resolution(&kbs, false);
+    assert_eq!(plan.rebinds.len(), 1);
+    assert_eq!(plan.rebinds[0].index, 2);
+    assert_eq!(plan.rebinds[0].new_mods, "SUPER CTRL");
+}
+
+#[test]
+fn test_plan_reports_unresolvable() {
+    let kbs = vec![
+        create_kb("SUPER SHIFT CTRL", "K", "d1", "", None),
+        create_kb("SUPER SHIFT CTRL", "K", "d2", "", None),
+        create_kb("SUPER SHIFT CTRL ALT", "K", "d3", "", None),
+    ];
+
+    let plan = plan_conflict_resolution(&kbs, true);
+    assert!(plan.rebinds.is_empty());
+    assert_eq!(plan.unresolved, vec![0]);
+}
+
+#[test]
+fn test_apply_plan_rewrites_lines() {
+    let path = std::env::temp_dir().join(format!(
+        "hyprkcs_test_autoresolve_{}_{}.conf",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::write(
+        &path,
+        "$mainMod = SUPER\n\
+         bind = $mainMod, Q, killactive, # close\n\
+         bindd = $mainMod, Q, Terminal, exec, kitty\n",
+    )
+    .unwrap();
+
+    let mut first = create_kb_at("$mainMod", "Q", "killactive", "", 1);
+    first.clean_mods = Arc::from("SUPER");
+    first.file_path = path.clone();
+    let mut second = create_kb_at("$mainMod", "Q", "exec", "kitty", 2);
+    second.clean_mods = Arc::from("SUPER");
+    second.flags = Arc::from("d");
+    second.description = Some(Arc::from("Terminal"));
+    second.file_path = path.clone();
+    let kbs = vec![first, second];
+
+    let plan = plan_conflict_resolution(&kbs, true);
+    let count = apply_resolution_plan(&kbs, &plan).unwrap();
+    assert_eq!(count, 1);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines[1], "bind = $mainMod SHIFT, Q, killactive, # close");
+    assert_eq!(lines[2], "bindd = $mainMod, Q, Terminal, exec, kitty");
+}
+
+#[test]
+fn test_apply_plan_keeps_line_text() {
+    let path = std::env::temp_dir().join(format!(
+        "hyprkcs_test_autoresolve_text_{}_{}.conf",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::write(
+        &path,
+        "$mainMod = SUPER\n\
+         bindld = $mainMod, M, Mute, exec, $volume toggle # audio\n\
+         bind = $mainMod, M, exec, $terminal\n\
+         bind = , Print, exec, grim\n\
+         bind = , Print, exec, hyprshot\n",
+    )
+    .unwrap();
+
+    // As parsed, with variables resolved
+    let mut mute = create_kb_at("SUPER", "M", "exec", "wpctl toggle", 1);
+    mute.flags = Arc::from("ld");
+    mute.description = Some(Arc::from("Mute"));
+    let terminal = create_kb_at("SUPER", "M", "exec", "kitty", 2);
+    let grim = create_kb_at("", "Print", "exec", "grim", 3);
+    let hyprshot = create_kb_at("", "Print", "exec", "hyprshot", 4);
+    let kbs: Vec<Keybind> = [mute, terminal, grim, hyprshot]
+        .into_iter()
+        .map(|kb| Keybind {
+            file_path: path.clone(),
+            ..kb
+        })
+        .collect();
+
+    let plan = plan_conflict_resolution(&kbs, false);
+    assert_eq!(apply_resolution_plan(&kbs, &plan).unwrap(), 2);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(
+        lines[1],
+        "bindld = $mainMod SHIFT, M, Mute, exec, $volume toggle # audio"
+    );
+    assert_eq!(lines[2], "bind = $mainMod, M, exec, $terminal");
+    assert_eq!(lines[3], "bind = SHIFT, Print, exec, grim");
+}
@@ -0,0 +1,186 @@
+use hyprKCS::parser::devices::*;
+use hyprKCS::parser::input::InputConfig;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+static ENV_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+    match ENV_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn new(suffix: &str, content: &str) -> Self {
+        let mut path = std::env::temp_dir();
+        let filename = format!(
+            "hyprkcs_test_devices_{}_{}.{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos(),
+            suffix
+        );
+        path.push(filename);
+        let mut file = std::fs::File::create(&path).expect("Failed to create temp file");
+        file.write_all(content.as_bytes())
+            .expect("Failed to write temp content");
+        Self { path }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+const DEVICES_JSON: &str = r#"{
+    "mice": [
+        { "address": "0x1", "name": "logitech-mouse", "defaultSpeed": 0.0 }
+    ],
+    "keyboards": [
+        {
+            "address": "0x2",
+            "name": "at-translated-set-2-keyboard",
+            "rules": "", "model": "", "layout": "us", "variant": "", "options": "",
+            "active_keymap": "English (US)",
+            "main": true
+        },
+        {
+            "address": "0x3",
+            "name": "keychron-k2",
+            "rules": "", "model": "pc105", "layout": "de", "variant": "nodeadkeys", "options": "",
+            "active_keymap": "German (no dead keys)",
+            "main": false
+        }
+    ]
+}"#;
+
+#[test]
+fn test_parse_devices_json() {
+    let keyboards = parse_devices_json(DEVICES_JSON);
+    assert_eq!(keyboards.len(), 2);
+
+    assert_eq!(keyboards[0].name, "at-translated-set-2-keyboard");
+    assert_eq!(keyboards[0].layout, "us");
+    assert!(keyboards[0].main);
+
+    assert_eq!(keyboards[1].name, "keychron-k2");
+    assert_eq!(keyboards[1].layout, "de");
+    assert_eq!(keyboards[1].variant, "nodeadkeys");
+    assert_eq!(keyboards[1].model, "pc105");
+    assert_eq!(keyboards[1].active_keymap, "German (no dead keys)");
+    assert!(!keyboards[1].main);
+}
+
+#[test]
+fn test_parse_devices_json_invalid() {
+    assert!(parse_devices_json("").is_empty());
+    assert!(parse_devices_json("not json").is_empty());
+    assert!(parse_devices_json(r#"{ "mice": [] }"#).is_empty());
+}
+
+#[test]
+fn test_parse_device_blocks_both_syntaxes() {
+    let content = r#"
+device {
+    name = keychron-k2
+    kb_layout = fr
+    kb_variant = azerty # comment
+}
+
+device:old-style-board {
+    kb_layout = jp
+    kb_file = ~/.config/xkb/custom.xkb
+}
+
+input {
+    kb_layout = us
+}
+"#;
+    let blocks = parse_device_blocks(content);
+    assert_eq!(blocks.len(), 2);
+
+    assert_eq!(blocks[0].name, "keychron-k2");
+    assert_eq!(blocks[0].kb_layout.as_deref(), Some("fr"));
+    assert_eq!(blocks[0].kb_variant.as_deref(), Some("azerty"));
+    assert_eq!(blocks[0].kb_model, None);
+
+    assert_eq!(blocks[1].name, "old-style-board");
+    assert_eq!(blocks[1].kb_layout.as_deref(), Some("jp"));
+    assert_eq!(
+        blocks[1].kb_file.as_deref(),
+        Some("~/.config/xkb/custom.xkb")
+    );
+}
+
+#[test]
+fn test_apply_overrides_and_global_fallback() {
+    let mut keyboards = parse_devices_json(DEVICES_JSON);
+    keyboards[0].layout.clear();
+
+    let overrides = vec![DeviceOverride {
+        name: "keychron-k2".to_string(),
+        kb_layout: Some("fr".to_string()),
+        kb_variant: Some(String::new()),
+        ..Default::default()
+    }];
+    let global = InputConfig {
+        kb_layout: "gb".to_string(),
+        kb_variant: "extd".to_string(),
+        kb_options: "caps:escape".to_string(),
+        ..Default::default()
+    };
+
+    apply_overrides(&mut keyboards, &overrides, &global);
+
+    // No layout reported and no override: global input block applies
+    assert_eq!(keyboards[0].layout, "gb");
+    assert_eq!(keyboards[0].variant, "extd");
+    assert_eq!(keyboards[0].options, "caps:escape");
+
+    // Device block wins, but its layout is kept apart from the global variant
+    assert_eq!(keyboards[1].layout, "fr");
+    assert_eq!(keyboards[1].variant, "");
+    assert_eq!(keyboards[1].model, "pc105");
+}
+
+#[test]
+fn test_list_keyboards_with_stubbed_devices() {
+    let _guard = lock_env();
+
+    let config = TempFile::new(
+        "conf",
+        r#"
+input {
+    kb_layout = us
+}
+
+device {
+    name = keychron-k2
+    kb_layout = jp
+}
+"#,
+    );
+    let devices = TempFile::new("json", DEVICES_JSON);
+    std::env::set_var("HYPRKCS_CONFIG", &config.path);
+    std::env::set_var("HYPRKCS_DEVICES_JSON", &devices.path);
+
+    let keyboards = list_keyboards(&InputConfig::default());
+
+    std::env::remove_var("HYPRKCS_DEVICES_JSON");
+
+    assert_eq!(keyboards.len(), 2);
+    assert_eq!(keyboards[0].layout, "us");
+    assert_eq!(keyboards[1].name, "keychron-k2");
+    assert_eq!(keyboards[1].layout, "jp");
+}
@@ -0,0 +1,157 @@
+use hyprKCS::lint::collect_problems;
+use hyprKCS::parser::diagnostics::check_bind_flags;
+use hyprKCS::parser::*;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+static ENV_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+    match ENV_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "hyprkcs_diagnostics_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    fn use_config(&self, content: &str) -> PathBuf {
+        let config = self.path.join("hyprland.conf");
+        fs::write(&config, content).unwrap();
+        std::env::set_var("HYPRKCS_CONFIG", &config);
+        invalidate_parser_cache();
+        config
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[test]
+fn test_check_bind_flags() {
+    assert_eq!(check_bind_flags(""), None);
+    assert_eq!(check_bind_flags("eld"), None);
+    assert_eq!(
+        check_bind_flags("-x").map(|(s, _)| s),
+        Some(Severity::Error)
+    );
+    let (severity, message) = check_bind_flags("ez").unwrap();
+    assert_eq!(severity, Severity::Warning);
+    assert!(message.contains("'z'"));
+}
+
+#[test]
+fn test_malformed_binds_are_reported() {
+    let _guard = lock_env();
+    let temp = TempDir::new("malformed");
+    let config = temp.use_config(
+        "bind = SUPER, Q, exec, kitty\n\
+         bind = SUPER, W\n\
+         bind-x = SUPER, E, exec, foot\n\
+         bindz = SUPER, R, exec, wofi\n\
+         bind SUPER, T, exec, thunar\n\
+         bind = SUPER, Y, , arg\n\
+         binds {\n\
+         \x20   scroll_event_delay = 0\n\
+         }\n\
+         binds:workspace_back_and_forth = true\n",
+    );
+
+    let (binds, diagnostics) = parse_config_with_diagnostics().unwrap();
+    let keys: Vec<&str> = binds.iter().map(|b| b.key.as_ref()).collect();
+    assert_eq!(keys, vec!["Q", "R", "Y"]);
+
+    let found: Vec<(usize, Severity)> = diagnostics.iter().map(|d| (d.line, d.severity)).collect();
+    assert_eq!(
+        found,
+        vec![
+            (2, Severity::Error),
+            (3, Severity::Error),
+            (4, Severity::Warning),
+            (5, Severity::Error),
+            (6, Severity::Warning),
+        ]
+    );
+    assert!(diagnostics.iter().all(|d| d.file == config));
+    assert!(diagnostics[0].message.contains("found 2 fields"));
+    assert_eq!(
+        diagnostics[0].to_string(),
+        format!("{}:2: error: {}", config.display(), diagnostics[0].message)
+    );
+
+    // Served from the cache on the next call
+    assert_eq!(get_diagnostics().unwrap(), diagnostics);
+}
+
+#[test]
+fn test_source_problems_are_reported() {
+    let _guard = lock_env();
+    let temp = TempDir::new("source");
+    fs::write(temp.path.join("broken.conf"), [0xff, 0xfe, 0x00]).unwrap();
+    let config = temp.use_config(&format!(
+        "source = {dir}/missing.conf\n\
+         source = {dir}/extra/*.conf\n\
+         source = {dir}/broken.conf\n\
+         bind = SUPER, Q, exec, kitty\n",
+        dir = temp.path.display()
+    ));
+
+    let (binds, diagnostics) = parse_config_with_diagnostics().unwrap();
+    assert_eq!(binds.len(), 1);
+    assert_eq!(diagnostics.len(), 3);
+
+    assert_eq!(diagnostics[0].file, config);
+    assert_eq!(diagnostics[0].line, 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert!(diagnostics[0].message.contains("missing.conf"));
+
+    assert_eq!(diagnostics[1].line, 2);
+    assert_eq!(diagnostics[1].severity, Severity::Warning);
+
+    // Unreadable files are reported against the file itself
+    assert_eq!(diagnostics[2].file, temp.path.join("broken.conf"));
+    assert_eq!(diagnostics[2].line, 0);
+    assert_eq!(diagnostics[2].severity, Severity::Error);
+    assert!(!diagnostics[2].to_string().contains(":0:"));
+}
+
+#[test]
+fn test_problems_include_lint_issues() {
+    let _guard = lock_env();
+    let temp = TempDir::new("lint");
+    temp.use_config(
+        "bind = SUPER, Q, notadispatcher, x\n\
+         bind = SUPER\n\
+         bind = SUPER, W, killactive, now\n",
+    );
+
+    let (binds, diagnostics) = parse_config_with_diagnostics().unwrap();
+    let problems = collect_problems(&binds, &diagnostics);
+    assert_eq!(problems.len(), 3);
+    assert_eq!(problems[0].line, 2);
+    assert_eq!(problems[0].severity, Severity::Error);
+    // Only noted, Hyprland may know it
+    assert_eq!(problems[1].line, 1);
+    assert_eq!(problems[1].severity, Severity::Note);
+    assert_eq!(problems[2].line, 3);
+    assert_eq!(problems[2].severity, Severity::Warning);
+}
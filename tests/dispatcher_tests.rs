@@ -0,0 +1,196 @@
+use hyprKCS::lint::lint_keybinds;
+use hyprKCS::parser::dispatchers::{lookup, validate_dispatcher, ArgKind, DISPATCHERS};
+use hyprKCS::parser::{Keybind, Severity};
+use std::path::PathBuf;
+
+fn is_valid(dispatcher: &str, args: &str) -> bool {
+    validate_dispatcher(dispatcher, args).is_none()
+}
+
+#[test]
+fn test_catalog_lookup() {
+    assert_eq!(lookup("workspace").unwrap().arg, ArgKind::Workspace);
+    assert_eq!(lookup("  MoveFocus ").unwrap().arg, ArgKind::Direction);
+    assert!(lookup("notadispatcher").is_none());
+
+    let mut names: Vec<&str> = DISPATCHERS.iter().map(|d| d.name).collect();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), DISPATCHERS.len(), "duplicate catalog entries");
+}
+
+#[test]
+fn test_workspace_specifiers() {
+    for ws in [
+        "1",
+        "10",
+        "+1",
+        "-1",
+        "m+1",
+        "m-2",
+        "r+1",
+        "e-1",
+        "m~3",
+        "name:web",
+        "special",
+        "special:magic",
+        "previous",
+        "empty",
+        "emptynm",
+    ] {
+        assert!(is_valid("workspace", ws), "{} should be valid", ws);
+    }
+
+    for ws in ["0", "web", "+x", "m+", "name:", "emptyx"] {
+        assert!(!is_valid("workspace", ws), "{} should be invalid", ws);
+    }
+
+    let reason = validate_dispatcher("workspace", "").unwrap();
+    assert!(reason.contains("required"), "{}", reason);
+}
+
+#[test]
+fn test_workspace_with_window() {
+    assert!(is_valid("movetoworkspace", "3"));
+    assert!(is_valid(
+        "movetoworkspace",
+        "special:scratch,class:^(kitty)$"
+    ));
+    assert!(is_valid("movetoworkspacesilent", "2, pid:1234"));
+    assert!(!is_valid("movetoworkspacesilent", "2, pid:abc"));
+    assert!(!is_valid("movetoworkspace", "nope"));
+}
+
+#[test]
+fn test_window_selectors() {
+    assert!(is_valid("pass", "^(com\\.obsproject\\.Studio)$"));
+    assert!(is_valid("focuswindow", "title:Firefox"));
+    assert!(is_valid("focuswindow", "address:0x55d1f0a0"));
+    assert!(!is_valid("focuswindow", "address:zz"));
+    assert!(!is_valid("focuswindow", "class:"));
+    assert!(!is_valid("pass", ""));
+
+    // Optional window
+    assert!(is_valid("togglefloating", ""));
+    assert!(is_valid("pin", "active"));
+}
+
+#[test]
+fn test_deltas_and_ratios() {
+    assert!(is_valid("resizeactive", "10 -10"));
+    assert!(is_valid("resizeactive", "exact 50% 50%"));
+    assert!(is_valid("moveactive", "-20 0"));
+    assert!(!is_valid("resizeactive", "10"));
+    assert!(!is_valid("resizeactive", "10 tall"));
+    assert!(is_valid("resizewindowpixel", "10 10,class:kitty"));
+    assert!(!is_valid("resizewindowpixel", "10 10"));
+
+    assert!(is_valid("splitratio", "+0.1"));
+    assert!(is_valid("splitratio", "exact 0.5"));
+    assert!(!is_valid("splitratio", "more"));
+}
+
+#[test]
+fn test_choices_directions_and_integers() {
+    assert!(is_valid("movefocus", "l"));
+    assert!(!is_valid("movefocus", "sideways"));
+    assert!(is_valid("movewindow", "mon:DP-1 silent"));
+    assert!(is_valid("movewindow", "r"));
+    // bindm drag action
+    assert!(is_valid("movewindow", ""));
+
+    assert!(is_valid("lockgroups", "toggle"));
+    assert!(!is_valid("lockgroups", "maybe"));
+    assert!(is_valid("dpms", "off eDP-1"));
+    assert!(is_valid("alterzorder", "top,class:kitty"));
+    assert!(is_valid("cyclenext", "prev tiled"));
+    assert!(!is_valid("cyclenext", "sideways"));
+    assert!(is_valid("changegroupactive", "f"));
+    assert!(is_valid("changegroupactive", "2"));
+    assert!(!is_valid("changegroupactive", "0"));
+
+    assert!(is_valid("fullscreen", "1"));
+    assert!(is_valid("fullscreen", ""));
+    let reason = validate_dispatcher("fullscreen", "5").unwrap();
+    assert!(reason.contains("outside 0-2"), "{}", reason);
+    assert!(!is_valid("movecursortocorner", "x"));
+    assert!(is_valid("movecursor", "100 200"));
+}
+
+#[test]
+fn test_no_args_and_unknown() {
+    assert!(is_valid("killactive", ""));
+    let reason = validate_dispatcher("killactive", "now").unwrap();
+    assert!(reason.contains("takes no arguments"), "{}", reason);
+
+    let reason = validate_dispatcher("wokspace", "1").unwrap();
+    assert!(reason.contains("not in the catalog"), "{}", reason);
+
+    assert!(is_valid("forceidle", "10"));
+    assert!(is_valid("signalwindow", "class:kitty,9"));
+
+    // Plugin dispatchers and unresolved variables are not checked
+    assert!(is_valid("hyprexpo:expo", "toggle"));
+    assert!(is_valid("workspace", "$ws"));
+    assert!(is_valid("global", "discord:mute"));
+    assert!(!is_valid("global", "mute"));
+}
+
+#[test]
+fn test_lint_keybinds_reports_lines() {
+    let kb = |dispatcher: &str, args: &str, line: usize| Keybind {
+        mods: "SUPER".into(),
+        clean_mods: "SUPER".into(),
+        flags: "".into(),
+        key: "Q".into(),
+        dispatcher: dispatcher.into(),
+        args: args.into(),
+        description: None,
+        submap: None,
+        line_number: line,
+        file_path: PathBuf::from("/tmp/hyprland.conf"),
+    };
+
+    let issues = lint_keybinds(&[
+        kb("exec", "kitty", 0),
+        kb("workspace", "web", 4),
+        kb("movefocus", "l", 5),
+        kb("resizeactive", "wide", 9),
+        kb("newdispatcher", "", 11),
+    ]);
+
+    assert_eq!(issues.len(), 3);
+    assert_eq!(issues[0].line, 5);
+    assert_eq!(
+        issues[0].to_string(),
+        "/tmp/hyprland.conf:5: Invalid arguments for workspace: 'web' is not a workspace specifier"
+    );
+    assert_eq!(issues[1].line, 10);
+    assert_eq!(issues[0].severity, Severity::Warning);
+    // Hyprland may have added it since
+    assert_eq!(issues[2].severity, Severity::Note);
+}
+
+#[test]
+fn test_workspace_editor_parses_current_args() {
+    use hyprKCS::ui::utils::arg_editor::parse_workspace;
+
+    assert_eq!(parse_workspace("3"), Some((0, "3".to_string())));
+    assert_eq!(parse_workspace("-1"), Some((1, "-1".to_string())));
+    assert_eq!(parse_workspace("m+1"), Some((2, "+1".to_string())));
+    assert_eq!(parse_workspace("r+1"), Some((3, "+1".to_string())));
+    assert_eq!(parse_workspace("e-2"), Some((4, "-2".to_string())));
+    assert_eq!(parse_workspace("name:web"), Some((5, "web".to_string())));
+    assert_eq!(parse_workspace("special"), Some((6, String::new())));
+    assert_eq!(
+        parse_workspace("special:magic"),
+        Some((6, "magic".to_string()))
+    );
+    assert_eq!(parse_workspace("previous"), Some((7, String::new())));
+    assert_eq!(parse_workspace("empty"), Some((8, String::new())));
+
+    // Left to the text entry
+    assert_eq!(parse_workspace("r~1"), None);
+    assert_eq!(parse_workspace("emptynm"), None);
+    assert_eq!(parse_workspace("previous_per_monitor"), None);
+}
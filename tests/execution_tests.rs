@@ -0,0 +1,121 @@
+use hyprKCS::json;
+use hyprKCS::ui::utils::execution::*;
+use std::collections::HashMap;
+
+fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_dry_run_substitutes_variables() {
+    let vars = vars(&[
+        ("$term", "foot"),
+        ("$terminal", "kitty"),
+        ("$mainMod", "SUPER"),
+    ]);
+    let run = prepare_dry_run("exec", "[float] $terminal -e $term", &vars);
+
+    assert_eq!(run.args, "[float] kitty -e foot");
+    assert_eq!(run.command.as_deref(), Some("kitty -e foot"));
+    assert_eq!(run.program.as_deref(), Some("kitty"));
+    assert_eq!(run.target, DryRunTarget::NewProcess);
+    assert_eq!(
+        run.hyprctl_args(),
+        vec!["dispatch", "exec", "[float] kitty -e foot"]
+    );
+    assert_eq!(
+        run.command_line(),
+        "hyprctl dispatch exec '[float] kitty -e foot'"
+    );
+}
+
+#[test]
+fn test_dry_run_program_check() {
+    let run = prepare_dry_run("exec", "sh -c 'true'", &HashMap::new());
+    assert!(run.program_exists);
+    assert_eq!(run.terminal_command(), "sh -c 'true'");
+
+    let run = prepare_dry_run("exec", "hyprkcs-definitely-missing --flag", &HashMap::new());
+    assert_eq!(run.program.as_deref(), Some("hyprkcs-definitely-missing"));
+    assert!(!run.program_exists);
+}
+
+#[test]
+fn test_dry_run_targets() {
+    let empty = HashMap::new();
+    let target = |d: &str, a: &str| prepare_dry_run(d, a, &empty).target;
+
+    assert_eq!(target("killactive", ""), DryRunTarget::FocusedWindow);
+    assert_eq!(
+        target("movetoworkspace", "3,class:kitty"),
+        DryRunTarget::Selector("class:kitty".to_string())
+    );
+    assert_eq!(target("movetoworkspace", "3"), DryRunTarget::FocusedWindow);
+    assert_eq!(
+        target("focuswindow", "title:Firefox"),
+        DryRunTarget::Selector("title:Firefox".to_string())
+    );
+
+    // Non-exec binds run the dispatch call in the terminal
+    let run = prepare_dry_run("workspace", "2", &empty);
+    assert_eq!(run.program, None);
+    assert_eq!(run.terminal_command(), "hyprctl dispatch workspace 2");
+}
+
+#[test]
+fn test_parse_active_window() {
+    let window = parse_active_window(
+        r#"{"address": "0x55d1", "class": "kitty", "title": "~",
+            "workspace": {"id": 2, "name": "2"}, "floating": true}"#,
+    )
+    .unwrap();
+    assert_eq!(window.class, "kitty");
+    assert_eq!(window.workspace, "2");
+    assert!(window.floating);
+
+    assert_eq!(parse_active_window("{}"), None);
+    assert_eq!(parse_active_window("Invalid"), None);
+}
+
+#[test]
+fn test_terminal_exec_args() {
+    let args = terminal_exec_args("kitty", "notify-send 'hi there'");
+    assert!(args.starts_with("[float] kitty -e sh -c '"));
+    assert!(args.contains("notify-send"));
+    assert!(args.contains("read -r _"));
+
+    let vars = vars(&[("$terminal", "foot")]);
+    assert_eq!(find_terminal(&vars).as_deref(), Some("foot"));
+}
+
+#[test]
+fn test_execution_record_is_one_json_line() {
+    let record = ExecutionRecord {
+        timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+        origin: "dry-run".to_string(),
+        command: vec![
+            "hyprctl".to_string(),
+            "dispatch".to_string(),
+            "exec".to_string(),
+            "echo \"a\nb\"".to_string(),
+        ],
+        status: Some(0),
+        stdout: "ok".to_string(),
+        stderr: String::new(),
+        error: None,
+    };
+    let line = record.to_json().to_compact_string();
+    assert!(!line.contains('\n'));
+
+    let parsed = json::parse(&line).unwrap();
+    assert_eq!(parsed.str_field("origin"), "dry-run");
+    assert_eq!(parsed.get("status").and_then(|s| s.as_f64()), Some(0.0));
+    assert_eq!(
+        parsed.get("command").and_then(|c| c.as_array()).unwrap()[3].as_str(),
+        Some("echo \"a\nb\"")
+    );
+    assert_eq!(parsed, record.to_json());
+}
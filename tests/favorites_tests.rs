@@ -1,4 +1,5 @@
 use hyprKCS::config::favorites::*;
+use hyprKCS::parser::{delete_keybind, invalidate_parser_cache, update_line};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{LazyLock, Mutex};
@@ -38,62 +39,151 @@ fn test_favorites_round_trip() {
     let _guard = lock_env();
     let _temp = TempConfig::new();
 
-    let favs = vec![
-        FavoriteKeybind {
-            mods: "SUPER".to_string(),
-            key: "Q".to_string(),
-            submap: "".to_string(),
-            dispatcher: "exec".to_string(),
-            args: "kitty".to_string(),
-        },
-        FavoriteKeybind {
-            mods: "CTRL ALT".to_string(),
-            key: "T".to_string(),
-            submap: "resize".to_string(),
-            dispatcher: "exec".to_string(),
-            args: "notify-send test".to_string(),
-        },
-    ];
-
-    save_favorites(&favs).expect("Save failed");
-
-    let loaded = load_favorites();
-    assert_eq!(loaded.len(), 2);
-    assert_eq!(loaded[0].mods, "SUPER");
-    assert_eq!(loaded[1].submap, "resize");
-    assert_eq!(loaded[1].args, "notify-send test");
+    let mut store = MetadataStore::default();
+    store.toggle_favorite(&BindId::new("SUPER", "Q", "", "exec", "kitty"));
+    store.toggle_favorite(&BindId::new(
+        "CTRL ALT",
+        "T",
+        "resize",
+        "exec",
+        "notify-send test",
+    ));
+
+    save_metadata(&store).expect("Save failed");
+
+    let loaded = load_metadata();
+    assert_eq!(loaded, store);
+    assert_eq!(loaded.entries[0].id.mods, "SUPER");
+    assert_eq!(loaded.entries[1].id.submap, "resize");
+    assert_eq!(loaded.entries[1].id.args, "notify-send test");
+
+    let content = fs::read_to_string(get_favorites_path().unwrap()).unwrap();
+    assert!(content.contains("\"version\": 2"));
 }
 
 #[test]
 fn test_toggle_favorite() {
-    let mut favs = Vec::new();
-    let item = FavoriteKeybind {
-        mods: "SUPER".to_string(),
-        key: "A".to_string(),
-        submap: "".to_string(),
-        dispatcher: "exec".to_string(),
-        args: "cmd".to_string(),
-    };
-
-    let result = toggle_favorite(&mut favs, item.clone());
-    assert!(result);
-    assert_eq!(favs.len(), 1);
-
-    let result = toggle_favorite(&mut favs, item);
-    assert!(!result);
-    assert_eq!(favs.len(), 0);
+    let mut store = MetadataStore::default();
+    let id = BindId::new("SUPER", "A", "", "exec", "cmd");
+
+    assert!(store.toggle_favorite(&id));
+    assert_eq!(store.entries.len(), 1);
+
+    assert!(!store.toggle_favorite(&id));
+    assert_eq!(store.entries.len(), 0);
 }
 
 #[test]
 fn test_is_favorite() {
-    let favs = vec![FavoriteKeybind {
-        mods: "SUPER".to_string(),
-        key: "Q".to_string(),
-        submap: "".to_string(),
-        dispatcher: "exec".to_string(),
-        args: "kitty".to_string(),
-    }];
-
-    assert!(is_favorite(&favs, "SUPER", "Q", "", "exec", "kitty"));
-    assert!(!is_favorite(&favs, "SUPER", "W", "", "exec", "kitty"));
+    let mut store = MetadataStore::default();
+    store.toggle_favorite(&BindId::new("SUPER", "Q", "", "exec", "kitty"));
+
+    assert!(store.is_favorite(&BindId::new("SUPER", "Q", "", "exec", "kitty")));
+    assert!(!store.is_favorite(&BindId::new("SUPER", "W", "", "exec", "kitty")));
+    // Modifier case and order don't matter
+    store.toggle_favorite(&BindId::new("SHIFT super", "E", "", "exit", ""));
+    assert!(store.is_favorite(&BindId::new("SUPER SHIFT", "E", "", "exit", "")));
+}
+
+#[test]
+fn test_legacy_format_is_converted() {
+    let _guard = lock_env();
+    let _temp = TempConfig::new();
+
+    let path = get_favorites_path().unwrap();
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(
+        &path,
+        "SUPER|Q||exec|kitty\nSUPER|P||exec|echo a | wc -c\nbroken line\n",
+    )
+    .unwrap();
+
+    let (parsed, converted) = parse_metadata(&fs::read_to_string(&path).unwrap());
+    assert!(converted);
+    assert_eq!(parsed.entries.len(), 2);
+    assert!(parsed.is_favorite(&BindId::new("SUPER", "P", "", "exec", "echo a | wc -c")));
+
+    let store = load_metadata();
+    assert_eq!(store, parsed);
+    let (reloaded, converted) = parse_metadata(&fs::read_to_string(&path).unwrap());
+    assert!(!converted);
+    assert_eq!(reloaded, store);
+}
+
+#[test]
+fn test_unknown_fields_are_kept() {
+    let (store, _) = parse_metadata(
+        r#"{"version": 2, "binds": [
+            {"mods": "SUPER", "key": "Q", "submap": "", "dispatcher": "exec",
+             "args": "kitty", "favorite": true, "note": "terminal"}
+        ]}"#,
+    );
+    let id = BindId::new("SUPER", "Q", "", "exec", "kitty");
+    assert_eq!(
+        store
+            .get(&id)
+            .unwrap()
+            .extra
+            .get("note")
+            .and_then(|v| v.as_str()),
+        Some("terminal")
+    );
+    assert!(store.to_json().contains("\"note\": \"terminal\""));
+}
+
+#[test]
+fn test_migrate_and_copy() {
+    let old = BindId::new("SUPER", "Q", "", "exec", "kitty");
+    let new = BindId::new("SUPER", "T", "", "exec", "kitty");
+    let mut store = MetadataStore::default();
+    store.toggle_favorite(&old);
+
+    assert!(store.migrate(&old, &new));
+    assert!(!store.is_favorite(&old));
+    assert!(store.is_favorite(&new));
+    assert!(!store.migrate(&old, &new));
+
+    let clone = BindId::new("SUPER SHIFT", "T", "", "exec", "kitty");
+    assert!(store.copy(&new, &clone));
+    assert!(store.is_favorite(&new) && store.is_favorite(&clone));
+
+    assert!(store.remove(&clone));
+    assert_eq!(store.entries.len(), 1);
+}
+
+#[test]
+fn test_follow_edits() {
+    let _guard = lock_env();
+    let temp = TempConfig::new();
+    let config = temp.path.join("hyprland.conf");
+    fs::write(
+        &config,
+        "bind = SUPER, Q, exec, kitty\nbind = SUPER, M, exit\n",
+    )
+    .unwrap();
+    std::env::set_var("HYPRKCS_CONFIG", &config);
+    invalidate_parser_cache();
+
+    let old = BindId::new("SUPER", "Q", "", "exec", "kitty");
+    let mut store = MetadataStore::default();
+    store.toggle_favorite(&old);
+    save_metadata(&store).unwrap();
+
+    update_line(config.clone(), 0, "SUPER", "T", "exec", "kitty", None, None).unwrap();
+    follow_edits(&[BindEdit::Moved {
+        old: old.clone(),
+        file: config.clone(),
+        line: 0,
+    }]);
+    let new = BindId::new("SUPER", "T", "", "exec", "kitty");
+    let store = load_metadata();
+    assert!(store.is_favorite(&new));
+    assert!(!store.is_favorite(&old));
+
+    delete_keybind(config.clone(), 0).unwrap();
+    follow_edits(&[BindEdit::Deleted(new)]);
+    assert!(load_metadata().entries.is_empty());
+
+    std::env::remove_var("HYPRKCS_CONFIG");
+    invalidate_parser_cache();
 }
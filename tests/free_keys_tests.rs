@@ -0,0 +1,135 @@
+use hyprKCS::parser::Keybind;
+use hyprKCS::ui::utils::free_keys::{find_free_keys, FreeKeyOptions, KeyClass};
+use hyprKCS::ui::utils::normalize;
+use hyprKCS::ui::views::keyboard_geometry::{standard_geometry, GeometryKey};
+use std::path::PathBuf;
+
+fn kb(mods: &str, key: &str, submap: Option<&str>) -> Keybind {
+    Keybind {
+        mods: mods.into(),
+        clean_mods: mods.into(),
+        flags: "".into(),
+        key: key.into(),
+        dispatcher: "exec".into(),
+        args: "true".into(),
+        description: None,
+        submap: submap.map(Into::into),
+        line_number: 0,
+        file_path: PathBuf::from("/tmp/hyprland.conf"),
+    }
+}
+
+fn resolve(k: &GeometryKey) -> (String, String) {
+    (k.label.clone(), normalize("", &k.hypr_name).1)
+}
+
+fn opts(mods: &str) -> FreeKeyOptions {
+    FreeKeyOptions {
+        mods: mods.into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_free_keys_ranked_from_home_row() {
+    let free = find_free_keys(&[], &standard_geometry("ANSI"), &opts("SUPER"), resolve);
+
+    // Home-row keys are distance zero and come first
+    let first: Vec<&str> = free.iter().take(8).map(|f| f.key.as_str()).collect();
+    for key in ["a", "s", "d", "f", "j", "k", "l", "semicolon"] {
+        assert!(first.contains(&key), "{} missing from {:?}", key, first);
+    }
+    assert!(free.windows(2).all(|w| w[0].distance <= w[1].distance));
+
+    // Modifier keys are never offered
+    assert!(!free.iter().any(|f| f.key.starts_with("shift")));
+    assert!(!free.iter().any(|f| f.key.starts_with("super")));
+
+    // Keys never repeat
+    let mut keys: Vec<&str> = free.iter().map(|f| f.key.as_str()).collect();
+    keys.sort();
+    keys.dedup();
+    assert_eq!(keys.len(), free.len());
+}
+
+#[test]
+fn test_free_keys_skip_bound_combos() {
+    let binds = vec![
+        kb("SUPER", "F", None),
+        kb("super", "J", None),
+        kb("SUPER SHIFT", "K", None),
+        kb("SUPER", "L", Some("resize")),
+        kb("SUPER", "D", Some("reset")),
+    ];
+    let geometry = standard_geometry("ANSI");
+
+    let free = find_free_keys(&binds, &geometry, &opts("SUPER"), resolve);
+    let keys: Vec<&str> = free.iter().map(|f| f.key.as_str()).collect();
+
+    assert!(!keys.contains(&"f"));
+    assert!(!keys.contains(&"j"));
+    // "reset" belongs to the global map
+    assert!(!keys.contains(&"d"));
+    // Different modifiers or a different submap leave the key free
+    assert!(keys.contains(&"k"));
+    assert!(keys.contains(&"l"));
+
+    let mut in_resize = opts("SUPER");
+    in_resize.submap = Some("resize".into());
+    let free = find_free_keys(&binds, &geometry, &in_resize, resolve);
+    let keys: Vec<&str> = free.iter().map(|f| f.key.as_str()).collect();
+    assert!(!keys.contains(&"l"));
+    assert!(keys.contains(&"f"));
+}
+
+#[test]
+fn test_free_keys_class_filters() {
+    let geometry = standard_geometry("ANSI");
+
+    let mut letters_only = opts("ALT");
+    letters_only.digits = false;
+    letters_only.function_keys = false;
+    letters_only.other = false;
+    let free = find_free_keys(&[], &geometry, &letters_only, resolve);
+    assert_eq!(free.len(), 26);
+    assert!(free.iter().all(|f| f.class == KeyClass::Letter));
+
+    let mut fkeys_only = opts("ALT");
+    fkeys_only.letters = false;
+    fkeys_only.digits = false;
+    fkeys_only.other = false;
+    let free = find_free_keys(&[], &geometry, &fkeys_only, resolve);
+    assert_eq!(free.len(), 12);
+    assert!(free
+        .iter()
+        .all(|f| f.class == KeyClass::Function && f.bind_key().starts_with('F')));
+}
+
+#[test]
+fn test_free_keys_near_home_row() {
+    let mut near = opts("CTRL ALT");
+    near.near_home_row = true;
+    let free = find_free_keys(&[], &standard_geometry("ANSI"), &near, resolve);
+    let keys: Vec<&str> = free.iter().map(|f| f.key.as_str()).collect();
+
+    assert!(keys.contains(&"q"));
+    assert!(keys.contains(&"z"));
+    assert!(!keys.contains(&"1"));
+    assert!(!keys.contains(&"f1"));
+    assert!(!keys.contains(&"space"));
+}
+
+#[test]
+fn test_key_class_and_bind_key() {
+    assert_eq!(KeyClass::of("q"), KeyClass::Letter);
+    assert_eq!(KeyClass::of("7"), KeyClass::Digit);
+    assert_eq!(KeyClass::of("f11"), KeyClass::Function);
+    assert_eq!(KeyClass::of("fn"), KeyClass::Other);
+    assert_eq!(KeyClass::of("return"), KeyClass::Other);
+
+    let free = find_free_keys(&[], &standard_geometry("ANSI"), &opts(""), resolve);
+    let q = free.iter().find(|f| f.key == "q").unwrap();
+    assert_eq!(q.bind_key(), "Q");
+    let comma = free.iter().find(|f| f.key == "comma").unwrap();
+    assert_eq!(comma.bind_key(), "comma");
+}
@@ -0,0 +1,265 @@
+use hyprKCS::config::favorites::{BindId, MetadataStore};
+use hyprKCS::config::hud::{
+    format_hud_configs, get_hud_instance_pid_path, get_hud_pid_path, parse_hud_configs, HudConfig,
+    HudContent, HudKeybind, HudPosition, DEFAULT_HUD,
+};
+use hyprKCS::parser::Keybind;
+use hyprKCS::ui::hud::{hud_entries, parse_submap_event, HudSources};
+use hyprKCS::ui::utils::execution::parse_execution_counts;
+use hyprKCS::ui::which_key::which_key_entries;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn bind(key: &str, dispatcher: &str, args: &str, submap: Option<&str>) -> Keybind {
+    Keybind {
+        mods: "SUPER".into(),
+        clean_mods: "SUPER".into(),
+        flags: "".into(),
+        key: key.into(),
+        dispatcher: dispatcher.into(),
+        args: args.into(),
+        description: None,
+        submap: submap.map(Into::into),
+        line_number: 1,
+        file_path: PathBuf::from("/home/user/.config/hypr/hyprland.conf"),
+    }
+}
+
+fn keys(entries: &[HudKeybind]) -> Vec<&str> {
+    entries.iter().map(|e| e.key.as_ref()).collect()
+}
+
+#[test]
+fn test_parse_hud_instances() {
+    let configs = parse_hud_configs(
+        "enabled=true\n\
+         position=bottom-left\n\
+         SUPER|Q|killactive|\n\
+         \n\
+         [resize]\n\
+         content=submap\n\
+         monitor=DP-1\n\
+         limit=0\n\
+         \n\
+         [launchers]\n\
+         content=search\n\
+         query=action:exec args:/rofi|wofi/\n",
+    );
+    assert_eq!(configs.len(), 3);
+    assert_eq!(configs[0].name, DEFAULT_HUD);
+    assert!(configs[0].enabled);
+    assert_eq!(configs[0].position, HudPosition::BottomLeft);
+    assert_eq!(configs[0].content, HudContent::Manual);
+    assert_eq!(configs[0].keybinds.len(), 1);
+
+    assert_eq!(configs[1].name, "resize");
+    assert_eq!(configs[1].content, HudContent::Submap);
+    assert_eq!(configs[1].monitor.as_deref(), Some("DP-1"));
+    assert_eq!(configs[1].limit, 0);
+    assert!(configs[1].keybinds.is_empty());
+
+    assert_eq!(configs[2].content, HudContent::Search);
+    assert_eq!(configs[2].query, "action:exec args:/rofi|wofi/");
+
+    // Written back the same way
+    let reparsed = parse_hud_configs(&format_hud_configs(&configs));
+    assert_eq!(reparsed.len(), 3);
+    assert_eq!(reparsed[2].query, configs[2].query);
+    assert_eq!(reparsed[1].monitor, configs[1].monitor);
+    assert_eq!(reparsed[0].keybinds, configs[0].keybinds);
+
+    // Files from before instances existed are the default HUD
+    let old = parse_hud_configs("enabled=false\nposition=top-left\n");
+    assert_eq!(old.len(), 1);
+    assert_eq!(old[0].content, HudContent::Manual);
+    assert_eq!(old[0].limit, 10);
+}
+
+#[test]
+fn test_hud_content_modes() {
+    let keybinds = vec![
+        bind("T", "exec", "kitty", None),
+        bind("Q", "killactive", "", None),
+        bind("R", "exec", "rofi -show drun", None),
+        bind("H", "resizeactive", "-10 0", Some("resize")),
+    ];
+    let mut metadata = MetadataStore::default();
+    metadata.toggle_favorite(&BindId::of(&keybinds[1]));
+    let mut usage = HashMap::new();
+    usage.insert(("exec".to_string(), "rofi -show drun".to_string()), 5);
+    usage.insert(("exec".to_string(), "kitty".to_string()), 2);
+    let sources = |submap| HudSources {
+        keybinds: &keybinds,
+        metadata: &metadata,
+        usage: &usage,
+        submap,
+    };
+    let config = |content, query: &str| HudConfig {
+        content,
+        query: query.to_string(),
+        ..HudConfig::named("test")
+    };
+
+    assert_eq!(
+        keys(&hud_entries(
+            &config(HudContent::Favorites, ""),
+            &sources(None)
+        )),
+        ["Q"]
+    );
+    assert_eq!(
+        keys(&hud_entries(
+            &config(HudContent::Search, "action:exec"),
+            &sources(None)
+        )),
+        ["T", "R"]
+    );
+    assert!(hud_entries(&config(HudContent::Search, ""), &sources(None)).is_empty());
+    assert!(hud_entries(&config(HudContent::Submap, ""), &sources(None)).is_empty());
+    assert_eq!(
+        keys(&hud_entries(
+            &config(HudContent::Submap, ""),
+            &sources(Some("resize"))
+        )),
+        ["H"]
+    );
+    assert_eq!(
+        keys(&hud_entries(
+            &config(HudContent::MostUsed, ""),
+            &sources(None)
+        )),
+        ["R", "T"]
+    );
+
+    let mut limited = config(HudContent::Search, "mod:super");
+    limited.limit = 2;
+    assert_eq!(keys(&hud_entries(&limited, &sources(None))), ["T", "Q"]);
+
+    let mut manual = config(HudContent::Manual, "");
+    manual.keybinds = vec![HudKeybind::new("SUPER", "X", "exit", "")];
+    manual.limit = 0;
+    assert_eq!(keys(&hud_entries(&manual, &sources(None))), ["X"]);
+}
+
+#[test]
+fn test_parse_submap_event() {
+    assert_eq!(
+        parse_submap_event("submap>>resize"),
+        Some(Some("resize".to_string()))
+    );
+    assert_eq!(parse_submap_event("submap>>"), Some(None));
+    assert_eq!(parse_submap_event("workspace>>2"), None);
+}
+
+#[test]
+fn test_execution_counts() {
+    let log = concat!(
+        r#"{"command":["hyprctl","dispatch","exec","rofi","-show","drun"],"status":0,"error":null}"#,
+        "\n",
+        r#"{"command":["hyprctl","dispatch","exec","rofi","-show","drun"],"status":0,"error":null}"#,
+        "\n",
+        r#"{"command":["hyprctl","dispatch","killactive"],"status":0,"error":null}"#,
+        "\n",
+        r#"{"command":["hyprctl","dispatch","killactive"],"status":1,"error":null}"#,
+        "\n",
+        r#"{"command":["hyprctl","dispatch","exec","kitty"],"status":null,"error":"not found"}"#,
+        "\n",
+        r#"{"command":["hyprctl","reload"],"status":0,"error":null}"#,
+        "\nnot json\n",
+    );
+    let counts = parse_execution_counts(log);
+    assert_eq!(counts.len(), 2);
+    assert_eq!(
+        counts.get(&("exec".to_string(), "rofi -show drun".to_string())),
+        Some(&2)
+    );
+    assert_eq!(
+        counts.get(&("killactive".to_string(), String::new())),
+        Some(&1)
+    );
+}
+
+#[test]
+fn test_instance_pid_paths() {
+    let default = get_hud_pid_path().unwrap();
+    assert_eq!(get_hud_instance_pid_path(DEFAULT_HUD).unwrap(), default);
+
+    let other = get_hud_instance_pid_path("left screen/2").unwrap();
+    assert_ne!(other, default);
+    assert_eq!(other.parent(), default.parent());
+    assert_eq!(
+        other.file_name().unwrap().to_str().unwrap(),
+        "hyprkcs-hud-left_screen_2.pid"
+    );
+}
+
+#[test]
+fn test_which_key_entries() {
+    let mut shrink = bind("H", "resizeactive", "-10 0", Some("resize"));
+    shrink.clean_mods = "".into();
+    shrink.description = Some("Shrink".into());
+    let mut grow = bind("L", "resizeactive", "10 0", Some("resize"));
+    grow.clean_mods = "SHIFT".into();
+    let mut leave = bind("escape", "submap", "reset", Some("resize"));
+    leave.clean_mods = "".into();
+    let keybinds = vec![
+        bind("R", "submap", "resize", None),
+        shrink,
+        grow,
+        leave,
+        bind("M", "submap", "move", Some("resize")),
+    ];
+
+    let entries = which_key_entries(&keybinds, "resize");
+    let shown: Vec<(&str, &str)> = entries
+        .iter()
+        .map(|e| (e.keys.as_str(), e.label.as_str()))
+        .collect();
+    assert_eq!(
+        shown,
+        [
+            ("H", "Shrink"),
+            ("SHIFT + L", "resizeactive 10 0"),
+            ("escape", "Leave submap"),
+            ("SUPER + M", "Go to move"),
+        ]
+    );
+    assert!(which_key_entries(&keybinds, "launch").is_empty());
+}
+
+#[test]
+fn test_execution_counts_follow_appends_and_rotation() {
+    use hyprKCS::ui::utils::execution::execution_counts_in;
+    use std::io::Write;
+
+    let dir = std::env::temp_dir().join(format!("hyprkcs_test_counts_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let log = dir.join("executions.jsonl");
+    let record = r#"{"command":["hyprctl","dispatch","killactive"],"status":0,"error":null}"#;
+    let key = ("killactive".to_string(), String::new());
+    let append = |text: &str| {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    };
+
+    append(&format!("{}\n", record));
+    assert_eq!(execution_counts_in(&log).get(&key), Some(&1));
+
+    // A line that is still being written waits for its newline
+    append(&format!("{}\n{}", record, &record[..10]));
+    assert_eq!(execution_counts_in(&log).get(&key), Some(&2));
+    append(&format!("{}\n", &record[10..]));
+    assert_eq!(execution_counts_in(&log).get(&key), Some(&3));
+
+    // Rotated records still count
+    hyprKCS::logging::rotate(&log, hyprKCS::logging::KEEP_ROTATED);
+    append(&format!("{}\n", record));
+    assert_eq!(execution_counts_in(&log).get(&key), Some(&4));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
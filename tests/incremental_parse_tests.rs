@@ -0,0 +1,194 @@
+use hyprKCS::parser::*;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+static ENV_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+    match ENV_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "hyprkcs_incremental_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(path.join("binds")).unwrap();
+        Self { path }
+    }
+
+    fn write(&self, name: &str, content: &str) -> PathBuf {
+        let path = self.path.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// `hyprland.conf` sourcing `vars.conf` and `binds/*.conf`.
+    fn setup(&self) -> PathBuf {
+        self.write("vars.conf", "$mod = SUPER\n");
+        self.write("binds/apps.conf", "bind = $mod, T, exec, kitty\n");
+        self.write("binds/windows.conf", "bind = $mod, Q, killactive\n");
+        let main = self.write(
+            "hyprland.conf",
+            &format!(
+                "source = {dir}/vars.conf\nsource = {dir}/binds/*.conf\nbind = $mod, M, exit\n",
+                dir = self.path.display()
+            ),
+        );
+        std::env::set_var("HYPRKCS_CONFIG", &main);
+        invalidate_parser_cache();
+        main
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn combos() -> Vec<String> {
+    parse_config()
+        .unwrap()
+        .iter()
+        .map(|kb| format!("{} {} {}", kb.mods, kb.key, kb.dispatcher))
+        .collect()
+}
+
+#[test]
+fn test_source_graph() {
+    let _guard = lock_env();
+    let temp = TempDir::new("graph");
+    let main = temp.setup();
+    let apps = temp.path.join("binds/apps.conf");
+
+    let graph = get_source_graph().unwrap();
+    assert_eq!(graph.root, main);
+    assert_eq!(graph.files().len(), 4);
+    assert_eq!(graph.sources_of(&main).len(), 2);
+    assert_eq!(graph.sources_of(&main)[1].line, 2);
+
+    // New files matching a glob are picked up, unrelated ones are not
+    let new_file = temp.path.join("binds/media.conf");
+    assert!(graph.is_relevant(&new_file));
+    assert_eq!(graph.includers_of_new(&new_file), vec![main.clone()]);
+    assert!(!graph.is_relevant(&temp.path.join("binds/notes.txt")));
+    assert!(affects_config(&apps));
+    assert!(!affects_config(&temp.path.join("hyprland.conf.swp")));
+}
+
+#[test]
+fn test_mark_files_changed_reparses_changed_file_only() {
+    let _guard = lock_env();
+    let temp = TempDir::new("changed");
+    temp.setup();
+    assert_eq!(
+        combos(),
+        vec!["SUPER T exec", "SUPER Q killactive", "SUPER M exit"]
+    );
+
+    let apps = temp.path.join("binds/apps.conf");
+    let windows = temp.path.join("binds/windows.conf");
+    let windows_mtime = fs::metadata(&windows).unwrap().modified().unwrap();
+
+    // Same size and mtime: only a watcher event can tell this file changed
+    temp.write("binds/windows.conf", "bind = $mod, W, killactive\n");
+    fs::File::options()
+        .write(true)
+        .open(&windows)
+        .unwrap()
+        .set_modified(windows_mtime)
+        .unwrap();
+    temp.write("binds/apps.conf", "bind = $mod, B, exec, firefox\n");
+
+    mark_files_changed(std::slice::from_ref(&apps));
+
+    // apps.conf is re-read, windows.conf is reused as it was
+    assert_eq!(
+        combos(),
+        vec!["SUPER B exec", "SUPER Q killactive", "SUPER M exit"]
+    );
+
+    mark_files_changed(std::slice::from_ref(&windows));
+    assert_eq!(
+        combos(),
+        vec!["SUPER B exec", "SUPER W killactive", "SUPER M exit"]
+    );
+}
+
+#[test]
+fn test_variable_change_reaches_other_files() {
+    let _guard = lock_env();
+    let temp = TempDir::new("variables");
+    temp.setup();
+    combos();
+
+    let vars = temp.write("vars.conf", "$mod = ALT\n");
+    mark_files_changed(&[vars]);
+    assert_eq!(
+        combos(),
+        vec!["ALT T exec", "ALT Q killactive", "ALT M exit"]
+    );
+}
+
+#[test]
+fn test_new_glob_match_is_picked_up() {
+    let _guard = lock_env();
+    let temp = TempDir::new("new_file");
+    temp.setup();
+    combos();
+
+    let media = temp.write("binds/media.conf", "bind = , XF86AudioMute, exec, mute\n");
+    mark_files_changed(&[media]);
+    assert!(combos().contains(&" XF86AudioMute exec".to_string()));
+    assert_eq!(get_source_graph().unwrap().files().len(), 5);
+}
+
+#[test]
+fn test_in_app_writes_keep_other_files_cached() {
+    let _guard = lock_env();
+    let temp = TempDir::new("in_app");
+    temp.setup();
+    combos();
+
+    // Same size and mtime, so only a re-read from scratch would see it
+    let windows = temp.path.join("binds/windows.conf");
+    let windows_mtime = fs::metadata(&windows).unwrap().modified().unwrap();
+    temp.write("binds/windows.conf", "bind = $mod, W, killactive\n");
+    fs::File::options()
+        .write(true)
+        .open(&windows)
+        .unwrap()
+        .set_modified(windows_mtime)
+        .unwrap();
+
+    let apps = temp.path.join("binds/apps.conf");
+    write_lines(&apps, &["bind = $mod, B, exec, firefox".to_string()]).unwrap();
+    assert_eq!(
+        combos(),
+        vec!["SUPER B exec", "SUPER Q killactive", "SUPER M exit"]
+    );
+
+    // Every file uses $mod, so every file is rewritten and read again
+    rename_variable_references("mod", "mainMod").unwrap();
+    assert_eq!(
+        combos(),
+        vec!["SUPER B exec", "SUPER W killactive", "SUPER M exit"]
+    );
+    assert_eq!(
+        get_defined_variables().unwrap()[0].name.as_ref(),
+        "$mainMod"
+    );
+}
@@ -0,0 +1,245 @@
+use hyprKCS::ui::views::keyboard_geometry::*;
+use std::path::PathBuf;
+
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "hyprkcs_test_layouts_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+#[test]
+fn test_bundled_layouts_parse() {
+    let layouts = bundled_layouts();
+    let names: Vec<&str> = layouts.iter().map(|l| l.name.as_str()).collect();
+    assert!(names.contains(&"Planck"));
+    assert!(names.contains(&"Corne"));
+
+    let planck = layouts.iter().find(|l| l.name == "Planck").unwrap();
+    // 4x12 grid, with a 2u space bar on the bottom row
+    assert_eq!(planck.keys.len(), 47);
+    assert!(planck
+        .keys
+        .iter()
+        .all(|k| k.x + k.width <= 12.0 && k.y < 4.0));
+
+    let corne = layouts.iter().find(|l| l.name == "Corne").unwrap();
+    assert_eq!(corne.keys.len(), 42);
+    let y_key = corne.keys.iter().find(|k| k.hypr_name == "Y").unwrap();
+    assert_eq!(y_key.x, 7.5);
+}
+
+#[test]
+fn test_parse_layout_positions_and_options() {
+    let content = r##"
+# comment
+name = Test Board
+
+row {
+    key = Esc, Escape, 1
+    gap = 0.5
+    key = ",", comma, 51, w=1.5   # trailing comment
+    key = "#", numbersign, 0
+}
+
+row {
+    key = Space, space, 57, w=2, h=2, x=3, y=1.25
+}
+"##;
+    let geo = parse_layout(content).expect("Should parse");
+    assert_eq!(geo.name, "Test Board");
+    assert_eq!(geo.keys.len(), 4);
+
+    assert_eq!(geo.keys[0].x, 0.0);
+    assert_eq!(geo.keys[1].label, ",");
+    assert_eq!(geo.keys[1].x, 1.5);
+    assert_eq!(geo.keys[1].width, 1.5);
+    assert_eq!(geo.keys[2].label, "#");
+    assert_eq!(geo.keys[2].x, 3.0);
+
+    let space = &geo.keys[3];
+    assert_eq!((space.x, space.y), (3.0, 1.25));
+    assert_eq!((space.width, space.height), (2.0, 2.0));
+}
+
+#[test]
+fn test_parse_layout_errors() {
+    assert!(parse_layout("row {\n key = A, A, 30\n}").is_err()); // no name
+    assert!(parse_layout("name = X\n").is_err()); // no keys
+    assert!(parse_layout("name = X\nrow {\n key = A, A, 30\n").is_err()); // unterminated
+    assert!(parse_layout("name = X\nrow {\n key = A, A\n}").is_err()); // missing keycode
+    assert!(parse_layout("name = X\nrow {\n key = A, A, abc\n}").is_err());
+    assert!(parse_layout("name = X\nrow {\n key = A, A, 30, z=1\n}").is_err());
+    assert!(parse_layout("name = X\nrow {\n key = A, A, 30, w=0\n}").is_err());
+
+    let err = parse_layout("name = X\nrow {\n key = A, A, 30\n bogus = 1\n}").unwrap_err();
+    assert!(err.to_string().contains("line 4"));
+}
+
+#[test]
+fn test_serialize_round_trip() {
+    for geo in bundled_layouts()
+        .into_iter()
+        .chain([standard_geometry("ISO"), get_geometry("60%")])
+    {
+        let text = serialize_layout(&geo);
+        let reparsed = parse_layout(&text).expect("Serialized layout should parse");
+        assert_eq!(reparsed.name, geo.name);
+
+        let mut expected = geo.keys.clone();
+        let mut actual = reparsed.keys.clone();
+        for keys in [&mut expected, &mut actual] {
+            keys.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+        }
+        assert_eq!(actual, expected, "Round trip mismatch for {}", geo.name);
+    }
+}
+
+#[test]
+fn test_standard_and_sixty_percent_geometry() {
+    let ansi = standard_geometry("ANSI");
+    let left = ansi.keys.iter().find(|k| k.hypr_name == "Left").unwrap();
+    assert_eq!((left.x, left.y), (5.5, 6.0));
+    assert!(ansi.keys.iter().any(|k| k.hypr_name == "F1"));
+
+    let sixty = get_geometry("60%");
+    assert!(!sixty.keys.iter().any(|k| k.hypr_name == "F1"));
+    assert!(!sixty.keys.iter().any(|k| k.hypr_name == "Left"));
+    assert_eq!(sixty.keys.iter().map(|k| k.y as i32).max(), Some(4));
+
+    // Unknown names fall back to ANSI, legacy Hungarian name still works
+    assert_eq!(get_geometry("does-not-exist").name, "ANSI");
+    assert_eq!(get_geometry("hungarian").name, "HUNGARIAN");
+    assert_eq!(get_geometry("corne").name, "Corne");
+}
+
+#[test]
+fn test_import_qmk_info_with_labels() {
+    let info = r#"{
+        "keyboard_name": "Tiny Pad",
+        "layouts": {
+            "LAYOUT_alt": { "layout": [ { "matrix": [0, 0], "x": 0, "y": 0 } ] },
+            "LAYOUT": {
+                "layout": [
+                    { "matrix": [0, 0], "x": 0, "y": 0, "label": "Esc" },
+                    { "matrix": [0, 1], "x": 1, "y": 0, "label": "Q" },
+                    { "matrix": [1, 0], "x": 0, "y": 1, "w": 2, "label": "Space" },
+                    { "matrix": [1, 1], "x": 2, "y": 1, "label": "Fn" }
+                ]
+            }
+        }
+    }"#;
+
+    let geo = import_qmk_info(info, None).expect("Should import");
+    assert_eq!(geo.name, "Tiny Pad");
+    assert_eq!(geo.keys.len(), 4);
+    assert_eq!(geo.keys[0].keycode, 1);
+    assert_eq!(geo.keys[1].hypr_name, "Q");
+    assert_eq!(geo.keys[2].keycode, 57);
+    assert_eq!(geo.keys[2].width, 2.0);
+    assert_eq!(geo.keys[3].keycode, 0); // unknown legend left unassigned
+
+    let alt = import_qmk_info(info, Some("LAYOUT_alt")).unwrap();
+    assert_eq!(alt.keys.len(), 1);
+    assert!(import_qmk_info(info, Some("LAYOUT_missing")).is_err());
+    assert!(import_qmk_info("{}", None).is_err());
+    assert!(import_qmk_info("not json", None).is_err());
+}
+
+#[test]
+fn test_import_unlabeled_split_assigns_by_position() {
+    // 3x6 split with staggered columns and 3 thumb keys per side
+    let mut entries = Vec::new();
+    for row in 0..3 {
+        for col in 0..12 {
+            let x = if col < 6 {
+                col as f64
+            } else {
+                col as f64 + 1.5
+            };
+            let stagger = if col % 6 == 2 { 0.125 } else { 0.0 };
+            entries.push(format!(
+                r#"{{ "matrix": [{}, {}], "x": {}, "y": {} }}"#,
+                row,
+                col,
+                x,
+                row as f64 + stagger
+            ));
+        }
+    }
+    for x in [3.5, 4.5, 5.5, 7.5, 8.5, 9.5] {
+        entries.push(format!(r#"{{ "matrix": [3, 0], "x": {}, "y": 3.7 }}"#, x));
+    }
+    let info = format!(
+        r#"{{ "keyboard_name": "crkbd", "layouts": {{ "LAYOUT_split_3x6_3": {{ "layout": [{}] }} }} }}"#,
+        entries.join(",")
+    );
+
+    let geo = import_qmk_info(&info, None).expect("Should import");
+    assert_eq!(geo.keys.len(), 42);
+
+    let find = |name: &str| geo.keys.iter().find(|k| k.hypr_name == name);
+    assert_eq!(find("Q").map(|k| k.keycode), Some(16));
+    assert_eq!(find("A").map(|k| (k.x, k.y)), Some((1.0, 1.0)));
+    assert_eq!(find("H").map(|k| k.x), Some(7.5));
+    assert_eq!(find("slash").map(|k| k.keycode), Some(53));
+    // Thumb keys have no obvious mapping
+    assert_eq!(geo.keys.iter().filter(|k| k.keycode == 0).count(), 6);
+}
+
+#[test]
+fn test_import_via_kle_keymap() {
+    let via = r#"{
+        "name": "Via Board",
+        "layouts": {
+            "keymap": [
+                ["0,0", {"w": 1.5}, "0,1", "0,2"],
+                [{"x": 0.25, "y": 0.5}, "1,0", {"w": 2}, "1,1"]
+            ]
+        }
+    }"#;
+    let geo = import_qmk_info(via, None).expect("Should import VIA");
+    assert_eq!(geo.name, "Via Board");
+    assert_eq!(geo.keys.len(), 5);
+    assert_eq!(geo.keys[1].x, 1.0);
+    assert_eq!(geo.keys[1].width, 1.5);
+    assert_eq!(geo.keys[2].x, 2.5);
+    assert_eq!((geo.keys[3].x, geo.keys[3].y), (0.25, 1.5));
+    assert_eq!(geo.keys[4].width, 2.0);
+}
+
+#[test]
+fn test_save_and_load_user_layouts() {
+    let dir = TempDir::new();
+    let geo = get_geometry("Planck");
+
+    let first = save_layout_to(&dir.path, &geo).unwrap();
+    let second = save_layout_to(&dir.path, &geo).unwrap();
+    assert_eq!(first.file_name().unwrap(), "planck.conf");
+    assert_eq!(second.file_name().unwrap(), "planck-2.conf");
+
+    std::fs::write(dir.path.join("broken.conf"), "name = Broken\n").unwrap();
+    std::fs::write(dir.path.join("notes.txt"), "ignored").unwrap();
+
+    let loaded = load_layouts_from(&dir.path);
+    assert_eq!(loaded.len(), 2);
+    assert!(loaded.iter().all(|l| l.name == "Planck"));
+}
@@ -0,0 +1,83 @@
+use fuzzy_matcher::skim::SkimMatcherV2;
+use hyprKCS::parser::Keybind;
+use hyprKCS::ui::launcher::{
+    launcher_candidates, launcher_title, parse_current_submap, rank_launcher,
+};
+use std::path::PathBuf;
+
+fn bind(key: &str, dispatcher: &str, args: &str, description: Option<&str>) -> Keybind {
+    Keybind {
+        mods: "SUPER".into(),
+        clean_mods: "SUPER".into(),
+        flags: "".into(),
+        key: key.into(),
+        dispatcher: dispatcher.into(),
+        args: args.into(),
+        description: description.map(Into::into),
+        submap: None,
+        line_number: 1,
+        file_path: PathBuf::from("/home/user/.config/hypr/hyprland.conf"),
+    }
+}
+
+fn titles(keybinds: &[Keybind], query: &str) -> Vec<String> {
+    rank_launcher(keybinds, query, &SkimMatcherV2::default())
+        .into_iter()
+        .map(|i| launcher_title(&keybinds[i]))
+        .collect()
+}
+
+#[test]
+fn test_parse_current_submap() {
+    assert_eq!(parse_current_submap("resize\n"), Some("resize".to_string()));
+    assert_eq!(parse_current_submap("default\n"), None);
+    assert_eq!(parse_current_submap(""), None);
+}
+
+#[test]
+fn test_candidates_follow_submap() {
+    let mut resize = bind("H", "resizeactive", "-10 0", None);
+    resize.submap = Some("resize".into());
+    let mut drag = bind("mouse:272", "movewindow", "", None);
+    drag.flags = "m".into();
+    let keybinds = vec![bind("T", "exec", "kitty", None), resize, drag];
+
+    let root = launcher_candidates(keybinds.clone(), None);
+    assert_eq!(root.len(), 1);
+    assert_eq!(root[0].key.as_ref(), "T");
+
+    let inside = launcher_candidates(keybinds, Some("resize"));
+    assert_eq!(inside.len(), 1);
+    assert_eq!(inside[0].key.as_ref(), "H");
+}
+
+#[test]
+fn test_rank_launcher() {
+    let keybinds = vec![
+        bind("T", "exec", "kitty", Some("Open terminal")),
+        bind("B", "exec", "firefox", Some("Open browser")),
+        bind("Q", "killactive", "", None),
+        bind("F", "fullscreen", "0", None),
+    ];
+
+    // No query keeps the config order
+    assert_eq!(
+        titles(&keybinds, ""),
+        [
+            "Open terminal",
+            "Open browser",
+            "killactive",
+            "fullscreen 0"
+        ]
+    );
+    // Fuzzy words over description and action
+    assert_eq!(titles(&keybinds, "brwsr"), ["Open browser"]);
+    assert_eq!(titles(&keybinds, "kill"), ["killactive"]);
+    assert_eq!(titles(&keybinds, "open term")[0], "Open terminal");
+    // Query fields filter
+    assert_eq!(
+        titles(&keybinds, "action:exec"),
+        ["Open terminal", "Open browser"]
+    );
+    assert!(titles(&keybinds, "zzzz").is_empty());
+}
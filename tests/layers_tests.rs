@@ -0,0 +1,84 @@
+use hyprKCS::parser::Keybind;
+use hyprKCS::ui::utils::layers::build_layers;
+use std::path::PathBuf;
+
+fn kb(mods: &str, key: &str, dispatcher: &str, args: &str, submap: Option<&str>) -> Keybind {
+    Keybind {
+        mods: mods.into(),
+        clean_mods: mods.into(),
+        flags: "".into(),
+        key: key.into(),
+        dispatcher: dispatcher.into(),
+        args: args.into(),
+        description: None,
+        submap: submap.map(Into::into),
+        line_number: 0,
+        file_path: PathBuf::from("/tmp/hyprland.conf"),
+    }
+}
+
+#[test]
+fn test_layers_grouped_and_ordered() {
+    let binds = vec![
+        kb("SUPER SHIFT", "Q", "exit", "", None),
+        kb("CTRL ALT", "T", "exec", "kitty", None),
+        kb("", "Escape", "submap", "reset", Some("resize")),
+        kb("SUPER", "Q", "killactive", "", None),
+        kb("SHIFT SUPER", "1", "movetoworkspace", "1", None),
+        kb("", "L", "resizeactive", "10 0", Some("resize")),
+        kb("SUPER", "R", "submap", "resize", Some("reset")),
+    ];
+
+    let layers = build_layers(&binds);
+    let titles: Vec<String> = layers.iter().map(|l| l.title()).collect();
+
+    assert_eq!(
+        titles,
+        vec![
+            "SUPER",
+            "SUPER + SHIFT",
+            "CTRL + ALT",
+            "No Modifier — submap resize",
+        ]
+    );
+
+    // "reset" submap counts as the global map
+    assert!(layers[0].keys.contains_key("r"));
+    assert_eq!(layers[0].keys.len(), 2);
+
+    // Modifier order in the source does not split layers
+    assert_eq!(layers[1].keys.len(), 2);
+    assert_eq!(layers[1].keys["1"][0].action_text(), "movetoworkspace 1");
+
+    assert_eq!(layers[3].submap.as_deref(), Some("resize"));
+    assert_eq!(layers[3].keys.len(), 2);
+}
+
+#[test]
+fn test_layer_labels_and_conflicts() {
+    let mut described = kb("SUPER", "Return", "exec", "kitty", None);
+    described.description = Some("Open terminal".into());
+
+    let binds = vec![
+        described,
+        kb("SUPER", "RETURN", "exec", "alacritty", None),
+        kb("SUPER", "E", "exec", "nautilus", None),
+    ];
+
+    let layers = build_layers(&binds);
+    assert_eq!(layers.len(), 1);
+
+    let ret = &layers[0].keys["return"];
+    assert_eq!(ret.len(), 2);
+    assert_eq!(ret[0].label(true), "Open terminal");
+    assert_eq!(ret[0].label(false), "exec kitty");
+    assert_eq!(ret[1].label(true), "exec alacritty");
+
+    assert_eq!(layers[0].keys["e"][0].label(true), "exec nautilus");
+}
+
+#[test]
+fn test_layers_empty() {
+    assert!(build_layers(&[]).is_empty());
+    assert!(build_layers(&[kb("SUPER", "", "exec", "foo", None)]).is_empty());
+}
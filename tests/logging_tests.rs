@@ -0,0 +1,148 @@
+use hyprKCS::logging::*;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+static LOG_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn lock_log() -> std::sync::MutexGuard<'static, ()> {
+    match LOG_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let mut path = std::env::temp_dir();
+        path.push(format!("hyprkcs_logging_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn record(level: Level, target: &str, message: &str) -> LogRecord {
+    LogRecord {
+        timestamp: "2026-01-01T12:00:00.000+00:00".to_string(),
+        level,
+        target: target.to_string(),
+        message: message.to_string(),
+    }
+}
+
+#[test]
+fn test_level_parse_and_order() {
+    assert_eq!(Level::parse("WARNING"), Some(Level::Warn));
+    assert_eq!(Level::parse(" trace "), Some(Level::Trace));
+    assert_eq!(Level::parse("verbose"), None);
+    assert!(Level::Error < Level::Warn && Level::Debug < Level::Trace);
+}
+
+#[test]
+fn test_record_line_round_trip() {
+    let original = record(Level::Warn, "parser", "line 3: bad bind\nsecond line");
+    let line = original.to_line();
+    assert!(!line.contains('\n'));
+    assert!(line.contains("WARN  [parser] line 3"));
+    assert_eq!(LogRecord::parse_line(&line), Some(original));
+    assert_eq!(LogRecord::parse_line("garbage"), None);
+}
+
+#[test]
+fn test_filter_records() {
+    let records = vec![
+        record(Level::Error, "write", "a"),
+        record(Level::Info, "hyprctl", "b"),
+        record(Level::Debug, "parser", "c"),
+    ];
+    assert_eq!(filter_records(&records, Level::Info, None).len(), 2);
+    let writes = filter_records(&records, Level::Trace, Some("write"));
+    assert_eq!(writes.len(), 1);
+    assert_eq!(writes[0].message, "a");
+}
+
+#[test]
+fn test_rotate_keeps_limited_history() {
+    let temp = TempDir::new("rotate");
+    let log = temp.path.join(LOG_FILE);
+
+    for generation in 0..5 {
+        fs::write(&log, format!("generation {}", generation)).unwrap();
+        rotate(&log, 2);
+    }
+
+    assert!(!log.exists());
+    assert_eq!(
+        fs::read_to_string(temp.path.join("hyprkcs.1.log")).unwrap(),
+        "generation 4"
+    );
+    assert_eq!(
+        fs::read_to_string(temp.path.join("hyprkcs.2.log")).unwrap(),
+        "generation 3"
+    );
+    assert!(!temp.path.join("hyprkcs.3.log").exists());
+}
+
+#[test]
+fn test_log_writes_file_and_respects_level() {
+    let _guard = lock_log();
+    let temp = TempDir::new("write");
+
+    init_in(&temp.path, Level::Info, false);
+    hyprKCS::log_info!("write", "Wrote {} lines", 3);
+    hyprKCS::log_debug!("parser", "hidden");
+    log(Level::Trace, "parser", "also hidden");
+
+    let records = read_log_file(&temp.path.join(LOG_FILE));
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].target, "write");
+    assert_eq!(records[0].message, "Wrote 3 lines");
+    assert!(recent_records()
+        .iter()
+        .any(|r| r.message == "Wrote 3 lines"));
+
+    clear();
+    assert!(read_log_file(&temp.path.join(LOG_FILE)).is_empty());
+    assert!(recent_records().is_empty());
+}
+
+#[test]
+fn test_init_rotates_oversized_log() {
+    let _guard = lock_log();
+    let temp = TempDir::new("init");
+    let log = temp.path.join(LOG_FILE);
+    fs::write(&log, vec![b'x'; MAX_LOG_BYTES as usize + 1]).unwrap();
+
+    init_in(&temp.path, Level::Info, false);
+
+    assert!(!log.exists());
+    assert!(temp.path.join("hyprkcs.1.log").exists());
+    assert_eq!(log_file(), Some(log));
+}
+
+#[test]
+fn test_rotate_if_large_keeps_extension() {
+    let temp = TempDir::new("rotate_jsonl");
+    let log = temp.path.join("executions.jsonl");
+
+    fs::write(&log, "{}\n").unwrap();
+    rotate_if_large(&log);
+    assert!(log.exists());
+
+    fs::write(&log, "x".repeat(MAX_LOG_BYTES as usize + 1)).unwrap();
+    rotate_if_large(&log);
+    assert!(!log.exists());
+    assert_eq!(rotated_path(&log, 1), temp.path.join("executions.1.jsonl"));
+    assert!(temp.path.join("executions.1.jsonl").exists());
+}
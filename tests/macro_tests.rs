@@ -0,0 +1,272 @@
+use hyprKCS::parser::{invalidate_parser_cache, parse_config};
+use hyprKCS::ui::utils::macro_builder::{
+    compile_steps, parse_macro, shell_quote, step_from_fields, MacroStep,
+};
+
+fn dispatch(dispatcher: &str, args: &str) -> MacroStep {
+    MacroStep::Dispatch {
+        dispatcher: dispatcher.to_string(),
+        args: args.to_string(),
+    }
+}
+
+fn keyword(name: &str, value: &str) -> MacroStep {
+    MacroStep::Keyword {
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn round_trip(steps: &[MacroStep]) -> Option<Vec<MacroStep>> {
+    let (dispatcher, args) = compile_steps(steps);
+    parse_macro(&dispatcher, &args)
+}
+
+/// Small xorshift generator so the property test is reproducible without
+/// pulling in a dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+
+    fn text(&mut self) -> String {
+        const CHARS: &[char] = &[
+            'a', 'b', 'z', '1', '9', ' ', ' ', '\'', '"', ';', '\\', '$', ',', ':', '-', '{', '}',
+            '#', '*', '(', ')', '&', '|', '~',
+        ];
+        let len = self.below(12);
+        let text: String = (0..len).map(|_| CHARS[self.below(CHARS.len())]).collect();
+        text.trim().to_string()
+    }
+
+    fn step(&mut self, allow_conditional: bool) -> MacroStep {
+        const DISPATCHERS: &[&str] = &["workspace", "exec", "movetoworkspace", "killactive"];
+        const KEYWORDS: &[&str] = &["general:gaps_in", "decoration:rounding", "$var"];
+        const SHELL: &[&str] = &[
+            "notify-send 'a;b'",
+            "echo \"it's here\"",
+            "pkill waybar; waybar",
+            "sleep 2.5",
+            "echo }",
+            "echo } ; ls",
+            "hyprctl dispatch exec kitty",
+            "if true; then echo x; fi",
+            "{ echo grouped; }",
+            "printf '%s\\n' \"$HOME\"",
+        ];
+        const CONDITIONS: &[&str] = &[
+            "pgrep waybar",
+            "test -f \"$HOME/.cache/x\"",
+            "[ \"$(hyprctl activewindow -j)\" != '{}' ]",
+        ];
+
+        match self.below(if allow_conditional { 5 } else { 4 }) {
+            0 => dispatch(self.pick(DISPATCHERS), &self.text()),
+            1 => keyword(self.pick(KEYWORDS), &self.text()),
+            2 => MacroStep::Delay(self.below(5000) as u32),
+            3 => MacroStep::Shell(self.pick(SHELL).to_string()),
+            _ => MacroStep::Conditional {
+                condition: self.pick(CONDITIONS).to_string(),
+                step: Box::new(self.step(false)),
+            },
+        }
+    }
+}
+
+#[test]
+fn test_macro_compile_parse_identity_property() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let mut stored = Vec::new();
+    for _ in 0..2000 {
+        let len = 1 + rng.below(5);
+        let mut steps: Vec<MacroStep> = (0..len).map(|_| rng.step(true)).collect();
+        if !steps
+            .iter()
+            .any(|s| matches!(s, MacroStep::Dispatch { .. } | MacroStep::Keyword { .. }))
+        {
+            steps.push(dispatch("workspace", &rng.text()));
+        }
+
+        let compiled = compile_steps(&steps);
+        assert_eq!(
+            parse_macro(&compiled.0, &compiled.1).as_ref(),
+            Some(&steps),
+            "compiled as: {}",
+            compiled.1
+        );
+        // The builder refuses these, since the rest of the line would be
+        // read as a comment
+        if !steps.iter().any(MacroStep::has_comment) {
+            stored.push((steps, compiled));
+        }
+    }
+
+    // What the builder accepts survives being written to a config file
+    let content: String = stored
+        .iter()
+        .map(|(_, (dispatcher, args))| format!("bind = SUPER, M, {}, {}\n", dispatcher, args))
+        .collect();
+    let mut path = std::env::temp_dir();
+    path.push(format!("hyprkcs_test_macro_{}.conf", std::process::id()));
+    std::fs::write(&path, content).unwrap();
+    std::env::set_var("HYPRKCS_CONFIG", &path);
+    invalidate_parser_cache();
+    let keybinds = parse_config();
+    let _ = std::fs::remove_file(&path);
+
+    let keybinds = keybinds.expect("Parse failed");
+    assert_eq!(keybinds.len(), stored.len());
+    for (kb, (steps, _)) in keybinds.iter().zip(&stored) {
+        assert_eq!(
+            parse_macro(&kb.dispatcher, &kb.args).as_ref(),
+            Some(steps),
+            "stored as: {}",
+            kb.args
+        );
+    }
+}
+
+#[test]
+fn test_macro_steps_with_hash_are_flagged() {
+    assert!(MacroStep::Shell("notify-send \"#1\"".to_string()).has_comment());
+    assert!(dispatch("exec", "echo #").has_comment());
+    assert!(MacroStep::Conditional {
+        condition: "true".to_string(),
+        step: Box::new(keyword("general:col.active_border", "rgb(#fff)")),
+    }
+    .has_comment());
+    assert!(!dispatch("workspace", "1").has_comment());
+}
+
+#[test]
+fn test_macro_compiles_to_single_batch() {
+    let steps = vec![
+        dispatch("workspace", "2"),
+        keyword("general:gaps_in", "10"),
+        dispatch("killactive", ""),
+    ];
+    let (dispatcher, args) = compile_steps(&steps);
+
+    assert_eq!(dispatcher, "exec");
+    assert_eq!(
+        args,
+        "hyprctl --batch 'dispatch workspace 2 ; keyword general:gaps_in 10 ; dispatch killactive'"
+    );
+    assert_eq!(round_trip(&steps), Some(steps));
+}
+
+#[test]
+fn test_macro_semicolon_args_leave_the_batch() {
+    let steps = vec![
+        dispatch("workspace", "1"),
+        dispatch("exec", "notify-send \"a; b\""),
+    ];
+    let (_, args) = compile_steps(&steps);
+
+    assert!(args.starts_with("bash -c "));
+    assert!(args.contains("hyprctl --batch"));
+    assert!(args.contains("hyprctl dispatch exec"));
+    assert_eq!(round_trip(&steps), Some(steps));
+}
+
+#[test]
+fn test_macro_delay_shell_and_conditional_steps() {
+    let steps = vec![
+        dispatch("workspace", "3"),
+        MacroStep::Delay(250),
+        MacroStep::Shell("notify-send Done".to_string()),
+        MacroStep::Conditional {
+            condition: "pgrep waybar".to_string(),
+            step: Box::new(dispatch("exec", "pkill waybar")),
+        },
+    ];
+    let (_, args) = compile_steps(&steps);
+
+    assert!(args.contains("sleep 0.25"));
+    assert!(args.contains("if pgrep waybar; then hyprctl dispatch exec"));
+    assert_eq!(round_trip(&steps), Some(steps));
+}
+
+#[test]
+fn test_macro_shell_step_with_separators_is_grouped() {
+    let steps = vec![
+        MacroStep::Shell("pkill waybar; waybar".to_string()),
+        dispatch("workspace", "1"),
+    ];
+    let (_, args) = compile_steps(&steps);
+
+    assert!(args.contains("{ pkill waybar; waybar; }"));
+    assert_eq!(round_trip(&steps), Some(steps));
+}
+
+#[test]
+fn test_parse_macro_legacy_and_batch_forms() {
+    let legacy = parse_macro(
+        "exec",
+        "bash -c \"hyprctl dispatch workspace 1; sleep 1; hyprctl keyword general:gaps_in 5\"",
+    )
+    .expect("legacy script");
+    assert_eq!(
+        legacy,
+        vec![
+            dispatch("workspace", "1"),
+            MacroStep::Delay(1000),
+            keyword("general:gaps_in", "5"),
+        ]
+    );
+
+    let batch = parse_macro(
+        "exec",
+        "hyprctl --batch \"dispatch workspace 1; dispatch togglefloating\"",
+    )
+    .expect("batch");
+    assert_eq!(
+        batch,
+        vec![dispatch("workspace", "1"), dispatch("togglefloating", "")]
+    );
+
+    assert!(parse_macro("exec", "bash -c 'echo \"unterminated'").is_none());
+    assert!(parse_macro("exec", "bash -c 'notify-send hi; sleep 1'").is_none());
+}
+
+#[test]
+fn test_shell_quote() {
+    assert_eq!(shell_quote("general:gaps_in"), "general:gaps_in");
+    assert_eq!(shell_quote(""), "''");
+    assert_eq!(shell_quote("a b"), "'a b'");
+    assert_eq!(shell_quote("it's"), "'it'\\''s'");
+}
+
+#[test]
+fn test_invalid_delay_is_an_error() {
+    assert_eq!(
+        step_from_fields(2, " 250 ", "", None).unwrap(),
+        Some(MacroStep::Delay(250))
+    );
+    let err = step_from_fields(2, "0.5s", "", None).unwrap_err();
+    assert!(err.to_string().contains("0.5s"), "{}", err);
+    assert!(step_from_fields(2, "-1", "", Some("pgrep waybar")).is_err());
+
+    // Empty rows are skipped, not errors
+    assert_eq!(step_from_fields(2, "  ", "", None).unwrap(), None);
+    assert_eq!(
+        step_from_fields(3, "notify-send hi", "", Some("pgrep waybar")).unwrap(),
+        Some(MacroStep::Conditional {
+            condition: "pgrep waybar".to_string(),
+            step: Box::new(MacroStep::Shell("notify-send hi".to_string())),
+        })
+    );
+}
@@ -159,3 +159,42 @@ fn test_update_from_bindd_to_bind() {
     assert!(new_content.contains("bind = SUPER, T, exec, alacritty # Open Terminal"));
     assert!(!new_content.contains("bindd ="));
 }
+
+#[test]
+fn test_description_flag_combined_with_others() {
+    let _guard = lock_env();
+
+    let temp = TempFile::new(
+        "bindde = , XF86AudioRaiseVolume, Volume up, exec, wpctl set-volume @DEFAULT_AUDIO_SINK@ 5%+\n\
+         bindld = , XF86AudioMute, Mute, exec, wpctl set-mute @DEFAULT_AUDIO_SINK@ toggle\n",
+    );
+    std::env::set_var("HYPRKCS_CONFIG", &temp.path);
+
+    let binds = parse_config().expect("Failed to parse combined flags");
+    assert_eq!(binds.len(), 2);
+    assert_eq!(binds[0].description.as_deref(), Some("Volume up"));
+    assert_eq!(binds[0].dispatcher.as_ref(), "exec");
+    assert_eq!(binds[1].description.as_deref(), Some("Mute"));
+    assert_eq!(
+        binds[1].args.as_ref(),
+        "wpctl set-mute @DEFAULT_AUDIO_SINK@ toggle"
+    );
+}
+
+#[test]
+fn test_split_bind_fields() {
+    let code = r#" SUPER, Q, exec, notify-send "a, b", c "#;
+    let (fields, open_quote) = split_bind_fields(code, false);
+    let parts: Vec<&str> = fields.into_iter().map(|r| &code[r]).collect();
+    assert_eq!(parts, ["SUPER", "Q", "exec", r#"notify-send "a, b", c"#]);
+    assert!(!open_quote);
+
+    let code = " SUPER, Q, Close, killactive,";
+    let (fields, _) = split_bind_fields(code, true);
+    let parts: Vec<&str> = fields.into_iter().map(|r| &code[r]).collect();
+    assert_eq!(parts, ["SUPER", "Q", "Close", "killactive", ""]);
+
+    let (fields, open_quote) = split_bind_fields(r#"SUPER, "Q, exec"#, false);
+    assert_eq!(fields.len(), 2);
+    assert!(open_quote);
+}
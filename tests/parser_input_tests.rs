@@ -90,8 +90,10 @@ input_device {
     let temp = TempFile::new(content);
     std::env::set_var("HYPRKCS_CONFIG", &temp.path);
 
-    let mut input = InputConfig::default();
-    input.kb_layout = "gb".to_string();
+    let input = InputConfig {
+        kb_layout: "gb".to_string(),
+        ..Default::default()
+    };
     let gestures = GesturesConfig::default();
 
     save_input_config(&input, &gestures).expect("Failed to save");
@@ -0,0 +1,387 @@
+use hyprKCS::parser::refactor::*;
+use hyprKCS::parser::{invalidate_parser_cache, LoadOrder, SourceEdge, SourceGraph, Variable};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+
+static ENV_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+    match ENV_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn new(content: &str) -> Self {
+        let mut path = std::env::temp_dir();
+        let filename = format!(
+            "hyprkcs_test_refactor_{}_{}.conf",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        );
+        path.push(filename);
+        let mut file = std::fs::File::create(&path).expect("Failed to create temp file");
+        file.write_all(content.as_bytes())
+            .expect("Failed to write temp content");
+        invalidate_parser_cache();
+        Self { path }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn var(name: &str, value: &str) -> Variable {
+    Variable {
+        name: Arc::from(name),
+        value: Arc::from(value),
+        line_number: 0,
+        file_path: PathBuf::from("/tmp/vars.conf"),
+    }
+}
+
+/// /tmp/binds.conf, sourcing /tmp/vars.conf on its first line and
+/// /tmp/late.conf on its last.
+fn load_order() -> LoadOrder {
+    let source = |line: usize, target: &str| SourceEdge {
+        from: PathBuf::from("/tmp/binds.conf"),
+        line,
+        pattern: target.to_string(),
+        remapped_from: None,
+        targets: vec![PathBuf::from(target)],
+    };
+    SourceGraph {
+        root: PathBuf::from("/tmp/binds.conf"),
+        edges: vec![source(1, "/tmp/vars.conf"), source(100, "/tmp/late.conf")],
+    }
+    .load_order()
+}
+
+fn suggestions_for(lines: &[&str], defined: &[Variable]) -> Vec<VariableSuggestion> {
+    let path = Path::new("/tmp/binds.conf");
+    let bind_lines: Vec<(&Path, usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| (path, i + 1, *line))
+        .collect();
+    suggest_variables(&bind_lines, defined, &load_order())
+}
+
+fn find<'a>(
+    suggestions: &'a [VariableSuggestion],
+    kind: LiteralKind,
+    value: &str,
+) -> Option<&'a VariableSuggestion> {
+    suggestions
+        .iter()
+        .find(|s| s.kind == kind && s.value == value)
+}
+
+#[test]
+fn test_suggests_repeated_modifiers_and_programs() {
+    let suggestions = suggestions_for(
+        &[
+            "bind = SUPER, Return, exec, kitty",
+            "bind = SUPER SHIFT, Return, exec, kitty -e htop",
+            "bind = SUPER SHIFT, Q, killactive,",
+            "bind = SUPER, E, exec, nautilus",
+            "bind = ALT, Tab, cyclenext,",
+        ],
+        &[],
+    );
+
+    let main = find(&suggestions, LiteralKind::Modifiers, "SUPER").unwrap();
+    assert_eq!(main.name, "mainMod");
+    assert_eq!(main.occurrences.len(), 2);
+    assert_eq!(
+        find(&suggestions, LiteralKind::Modifiers, "SUPER SHIFT")
+            .unwrap()
+            .name,
+        "superShiftMod"
+    );
+    let terminal = find(&suggestions, LiteralKind::Program, "kitty").unwrap();
+    assert_eq!(terminal.name, "terminal");
+    assert_eq!(terminal.occurrences[1].line_number, 2);
+
+    // Seen once
+    assert!(find(&suggestions, LiteralKind::Modifiers, "ALT").is_none());
+    assert!(find(&suggestions, LiteralKind::Program, "nautilus").is_none());
+    // A bare program is the Program suggestion, not a Command one
+    assert!(find(&suggestions, LiteralKind::Command, "kitty").is_none());
+}
+
+#[test]
+fn test_suggests_repeated_commands() {
+    let suggestions = suggestions_for(
+        &[
+            "bind = , Print, exec, grim -g \"$(slurp)\" - | wl-copy",
+            "bind = SUPER, S, exec, grim -g \"$(slurp)\" - | wl-copy",
+            "binddr = SUPER, R, Run, exec, [float] rofi -show drun",
+            "bind = SUPER, D, exec, [float] rofi -show drun",
+        ],
+        &[],
+    );
+
+    let screenshot = find(
+        &suggestions,
+        LiteralKind::Command,
+        "grim -g \"$(slurp)\" - | wl-copy",
+    )
+    .unwrap();
+    assert_eq!(screenshot.name, "screenshotCmd");
+    let menu = find(
+        &suggestions,
+        LiteralKind::Command,
+        "[float] rofi -show drun",
+    )
+    .unwrap();
+    assert_eq!(menu.name, "menuCmd");
+    assert_eq!(
+        find(&suggestions, LiteralKind::Program, "rofi")
+            .unwrap()
+            .name,
+        "menu"
+    );
+}
+
+#[test]
+fn test_skips_values_using_variables_and_ignored_programs() {
+    let suggestions = suggestions_for(
+        &[
+            "bind = $mainMod, Q, exec, $terminal",
+            "bind = $mainMod, W, exec, $terminal",
+            "bind = SUPER, 1, exec, hyprctl dispatch workspace 1",
+            "bind = SUPER, 2, exec, hyprctl dispatch workspace 2",
+        ],
+        &[],
+    );
+    assert!(suggestions.iter().all(|s| !s.value.contains('$')));
+    assert!(find(&suggestions, LiteralKind::Program, "hyprctl").is_none());
+    assert!(find(&suggestions, LiteralKind::Modifiers, "SUPER").is_some());
+}
+
+#[test]
+fn test_reuses_existing_variables_and_avoids_taken_names() {
+    let suggestions = suggestions_for(
+        &[
+            "bind = SUPER, Q, exec, kitty",
+            "bind = SUPER, W, exec, kitty",
+        ],
+        &[var("$mod", "SUPER"), var("$terminal", "foot")],
+    );
+
+    let mods = find(&suggestions, LiteralKind::Modifiers, "SUPER").unwrap();
+    assert_eq!(mods.name, "mod");
+    assert!(mods.existing);
+    let terminal = find(&suggestions, LiteralKind::Program, "kitty").unwrap();
+    assert_eq!(terminal.name, "terminal2");
+    assert!(!terminal.existing);
+}
+
+#[test]
+fn test_reuses_only_variables_defined_before_use() {
+    let lines = [
+        "bind = SUPER, Q, exec, kitty",
+        "bind = SUPER, W, exec, kitty",
+    ];
+    let at = |name: &str, value: &str, file: &str, line: usize| Variable {
+        line_number: line,
+        file_path: PathBuf::from(file),
+        ..var(name, value)
+    };
+
+    // Defined after the binds that would use it
+    let suggestions = suggestions_for(&lines, &[at("$mod", "SUPER", "/tmp/late.conf", 0)]);
+    let mods = find(&suggestions, LiteralKind::Modifiers, "SUPER").unwrap();
+    assert!(!mods.existing);
+    assert_eq!(mods.name, "mainMod");
+
+    // Defined below them in the same file
+    let suggestions = suggestions_for(&lines, &[at("$mod", "SUPER", "/tmp/binds.conf", 50)]);
+    assert!(
+        !find(&suggestions, LiteralKind::Modifiers, "SUPER")
+            .unwrap()
+            .existing
+    );
+
+    // Redefined to something else later
+    let suggestions = suggestions_for(
+        &lines,
+        &[
+            at("$mod", "SUPER", "/tmp/vars.conf", 0),
+            at("$mod", "ALT", "/tmp/late.conf", 0),
+        ],
+    );
+    assert!(
+        !find(&suggestions, LiteralKind::Modifiers, "SUPER")
+            .unwrap()
+            .existing
+    );
+
+    // Defined at the top of the same file
+    let suggestions = suggestions_for(&lines, &[at("$mod", "SUPER", "/tmp/binds.conf", 0)]);
+    assert!(
+        find(&suggestions, LiteralKind::Modifiers, "SUPER")
+            .unwrap()
+            .existing
+    );
+}
+
+#[test]
+fn test_plan_rewrites_fields_and_defines_variables() {
+    let path = PathBuf::from("/tmp/hyprland.conf");
+    let lines = [
+        "# Keybinds",
+        "",
+        "bind = SUPER, Q, exec, kitty # terminal",
+        "bind = SUPER SHIFT, SUPER_L, exec, kitty -e htop",
+        "bind = SUPER, W, killactive,",
+    ];
+    let mut files = BTreeMap::new();
+    files.insert(
+        path.clone(),
+        lines.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+    );
+    let bind_lines: Vec<(&Path, usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| (path.as_path(), i, *line))
+        .collect();
+    let suggestions: Vec<VariableSuggestion> = suggest_variables(&bind_lines, &[], &load_order())
+        .into_iter()
+        .filter(|s| s.kind != LiteralKind::Command)
+        .collect();
+
+    let plan = plan_refactor(&files, &suggestions);
+    let new_lines = &plan.files[&path];
+    assert_eq!(new_lines[0], "# Keybinds");
+    assert!(new_lines.contains(&"$mainMod = SUPER".to_string()));
+    assert!(new_lines.contains(&"$terminal = kitty".to_string()));
+    assert!(new_lines.contains(&"bind = $mainMod, Q, exec, $terminal # terminal".to_string()));
+    // Only the modifier field changes, not the SUPER_L key
+    assert!(new_lines.contains(&"bind = SUPER SHIFT, SUPER_L, exec, $terminal -e htop".to_string()));
+    assert!(new_lines.contains(&"bind = $mainMod, W, killactive,".to_string()));
+
+    // Definitions come before the first use
+    let definition = new_lines
+        .iter()
+        .position(|l| l == "$terminal = kitty")
+        .unwrap();
+    let first_use = new_lines
+        .iter()
+        .position(|l| l.contains("$terminal #"))
+        .unwrap();
+    assert!(definition < first_use);
+
+    let added = plan.changes.iter().filter(|c| c.before.is_none()).count();
+    let edited = plan.changes.iter().filter(|c| c.before.is_some()).count();
+    assert_eq!(added, 2);
+    assert_eq!(edited, 3);
+}
+
+#[test]
+fn test_plan_skips_lines_that_changed() {
+    let path = PathBuf::from("/tmp/hyprland.conf");
+    let suggestion = VariableSuggestion {
+        kind: LiteralKind::Program,
+        value: "kitty".to_string(),
+        name: "terminal".to_string(),
+        existing: false,
+        occurrences: vec![
+            Occurrence {
+                file_path: path.clone(),
+                line_number: 0,
+            },
+            Occurrence {
+                file_path: path.clone(),
+                line_number: 1,
+            },
+        ],
+    };
+    let mut files = BTreeMap::new();
+    files.insert(
+        path.clone(),
+        vec![
+            "bind = SUPER, Q, exec, foot".to_string(),
+            "bind = SUPER, W, exec, kitty".to_string(),
+        ],
+    );
+
+    let plan = plan_refactor(&files, &[suggestion]);
+    assert_eq!(
+        plan.files[&path],
+        vec![
+            "$terminal = kitty",
+            "bind = SUPER, Q, exec, foot",
+            "bind = SUPER, W, exec, $terminal",
+        ]
+    );
+}
+
+#[test]
+fn test_apply_variable_refactor() {
+    let _lock = lock_env();
+    let temp = TempFile::new(
+        "bind = SUPER, Q, exec, kitty\nbind = SUPER, W, exec, kitty --single-instance\n",
+    );
+    std::env::set_var("HYPRKCS_CONFIG", &temp.path);
+
+    let suggestions: Vec<VariableSuggestion> = variable_suggestions()
+        .expect("Scan failed")
+        .into_iter()
+        .filter(|s| s.kind == LiteralKind::Program)
+        .collect();
+    assert_eq!(suggestions.len(), 1);
+
+    let plan = plan_variable_refactor(&suggestions).expect("Plan failed");
+    assert_eq!(apply_refactor(&plan).expect("Apply failed"), 1);
+
+    let content = std::fs::read_to_string(&temp.path).unwrap();
+    assert_eq!(
+        content,
+        "$terminal = kitty\nbind = SUPER, Q, exec, $terminal\nbind = SUPER, W, exec, $terminal --single-instance\n"
+    );
+    assert!(variable_suggestions()
+        .unwrap()
+        .iter()
+        .all(|s| s.kind != LiteralKind::Program));
+}
+
+#[test]
+fn test_apply_refuses_stale_plan() {
+    let _lock = lock_env();
+    let temp = TempFile::new("bind = SUPER, Q, exec, kitty\nbind = SUPER, W, exec, kitty\n");
+    std::env::set_var("HYPRKCS_CONFIG", &temp.path);
+
+    let suggestions: Vec<VariableSuggestion> = variable_suggestions()
+        .expect("Scan failed")
+        .into_iter()
+        .filter(|s| s.kind == LiteralKind::Program)
+        .collect();
+    let plan = plan_variable_refactor(&suggestions).expect("Plan failed");
+
+    // Edited elsewhere after the plan was made
+    let edited = "bind = SUPER, Q, exec, kitty\nbind = SUPER, W, exec, foot\n";
+    std::fs::write(&temp.path, edited).unwrap();
+    assert!(apply_refactor(&plan).is_err());
+    assert_eq!(std::fs::read_to_string(&temp.path).unwrap(), edited);
+
+    // Lines added above the planned ones shift them
+    let shifted = "# Apps\nbind = SUPER, Q, exec, kitty\nbind = SUPER, W, exec, kitty\n";
+    std::fs::write(&temp.path, shifted).unwrap();
+    assert!(apply_refactor(&plan).is_err());
+    assert_eq!(std::fs::read_to_string(&temp.path).unwrap(), shifted);
+}
@@ -0,0 +1,192 @@
+use hyprKCS::config::categories::{default_categories, Category};
+use hyprKCS::config::routing::{format_rules, parse_rules, route, RouteMatch, RouteRule};
+use hyprKCS::config::StyleConfig;
+use hyprKCS::parser::{ensure_sourced, get_loaded_files, invalidate_parser_cache};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+static ENV_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+    match ENV_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let mut path = std::env::temp_dir();
+        path.push(format!("hyprkcs_routing_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[test]
+fn test_parse_and_format_rules() {
+    let (rules, invalid) = parse_rules(
+        "dispatcher:exec -> apps.conf; submap:* -> submaps/{submap}.conf; \
+         category:Media -> media.conf; category: -> x.conf; garbage",
+    );
+    assert_eq!(invalid, vec!["category: -> x.conf", "garbage"]);
+    assert_eq!(rules.len(), 3);
+    assert_eq!(rules[0].matcher, RouteMatch::Dispatcher("exec".to_string()));
+    assert_eq!(rules[2].matcher, RouteMatch::Category("media".to_string()));
+
+    let formatted = format_rules(&rules);
+    assert_eq!(
+        formatted,
+        "dispatcher:exec -> apps.conf; submap:* -> submaps/{submap}.conf; category:media -> media.conf"
+    );
+    assert_eq!(parse_rules(&formatted), (rules, vec![]));
+}
+
+#[test]
+fn test_first_matching_rule_wins() {
+    let (rules, _) = parse_rules(
+        "submap:resize -> resize.conf; submap:* -> submaps/{submap}.conf; \
+         dispatcher:exec -> apps.conf; category:workspace -> workspaces.conf",
+    );
+    let dir = Path::new("/cfg");
+    let categories = default_categories();
+
+    assert_eq!(
+        route(
+            &rules,
+            &categories,
+            dir,
+            "exec",
+            "kitty",
+            "T",
+            Some("resize")
+        ),
+        Some(PathBuf::from("/cfg/resize.conf"))
+    );
+    assert_eq!(
+        route(
+            &rules,
+            &categories,
+            dir,
+            "exec",
+            "kitty",
+            "T",
+            Some("launch")
+        ),
+        Some(PathBuf::from("/cfg/submaps/launch.conf"))
+    );
+    assert_eq!(
+        route(&rules, &categories, dir, "exec", "kitty", "T", None),
+        Some(PathBuf::from("/cfg/apps.conf"))
+    );
+    // "reset" is the global scope, not a submap
+    assert_eq!(
+        route(
+            &rules,
+            &categories,
+            dir,
+            "EXEC",
+            "kitty",
+            "T",
+            Some("reset")
+        ),
+        Some(PathBuf::from("/cfg/apps.conf"))
+    );
+    assert_eq!(
+        route(&rules, &categories, dir, "movetoworkspace", "2", "2", None),
+        Some(PathBuf::from("/cfg/workspaces.conf"))
+    );
+    assert_eq!(
+        route(&rules, &categories, dir, "killactive", "", "Q", None),
+        None
+    );
+
+    // User-defined categories are looked up by name
+    let (rules, _) = parse_rules("category:Screenshots -> shots.conf");
+    let shots = vec![Category {
+        name: "Screenshots".to_string(),
+        query: "args:/grim|hyprshot/".to_string(),
+    }];
+    assert_eq!(
+        route(
+            &rules,
+            &shots,
+            dir,
+            "exec",
+            "grim -g \"$(slurp)\"",
+            "Print",
+            None
+        ),
+        Some(PathBuf::from("/cfg/shots.conf"))
+    );
+    assert_eq!(route(&rules, &shots, dir, "exec", "kitty", "T", None), None);
+    assert_eq!(
+        route(&rules, &categories, dir, "exec", "grim", "Print", None),
+        None
+    );
+
+    let absolute = RouteRule::parse("dispatcher:exec -> /etc/hypr/apps.conf").unwrap();
+    assert_eq!(
+        absolute.destination(dir, None),
+        PathBuf::from("/etc/hypr/apps.conf")
+    );
+}
+
+#[test]
+fn test_invalid_rules_are_reported() {
+    let _guard = lock_env();
+    let temp = TempDir::new("config");
+    std::env::set_var("XDG_CONFIG_HOME", &temp.path);
+    fs::create_dir_all(temp.path.join("hyprkcs")).unwrap();
+    fs::write(
+        temp.path.join("hyprkcs/hyprkcs.conf"),
+        "bindRoutes = dispatcher:exec -> apps.conf; window -> win.conf\n",
+    )
+    .unwrap();
+    StyleConfig::invalidate_cache();
+
+    let config = StyleConfig::load();
+    assert_eq!(config.bind_routes.len(), 1);
+    assert!(config
+        .errors
+        .iter()
+        .any(|e| e.contains("window -> win.conf")));
+    StyleConfig::invalidate_cache();
+}
+
+#[test]
+fn test_ensure_sourced_adds_source_line_once() {
+    let _guard = lock_env();
+    let temp = TempDir::new("sourced");
+    let main = temp.path.join("hyprland.conf");
+    fs::write(&main, "bind = SUPER, M, exit\n").unwrap();
+    std::env::set_var("HYPRKCS_CONFIG", &main);
+    invalidate_parser_cache();
+
+    assert!(!ensure_sourced(&main).unwrap());
+
+    let target = temp.path.join("submaps/resize.conf");
+    assert!(ensure_sourced(&target).unwrap());
+    assert!(target.exists());
+    assert_eq!(
+        fs::read_to_string(&main).unwrap(),
+        "bind = SUPER, M, exit\nsource = submaps/resize.conf\n"
+    );
+    assert!(get_loaded_files().unwrap().contains(&target));
+
+    assert!(!ensure_sourced(&target).unwrap());
+    std::env::remove_var("HYPRKCS_CONFIG");
+    invalidate_parser_cache();
+}
@@ -0,0 +1,188 @@
+use hyprKCS::parser::Keybind;
+use hyprKCS::ui::utils::scripts::*;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+
+static ENV_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+    match ENV_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let mut path = std::env::temp_dir();
+        path.push(format!("hyprkcs_scripts_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn create_kb(dispatcher: &str, args: &str) -> Keybind {
+    Keybind {
+        mods: Arc::from("SUPER"),
+        clean_mods: Arc::from("SUPER"),
+        flags: Arc::from(""),
+        key: Arc::from("S"),
+        dispatcher: Arc::from(dispatcher),
+        args: Arc::from(args),
+        description: None,
+        submap: None,
+        line_number: 0,
+        file_path: PathBuf::from("test.conf"),
+    }
+}
+
+#[test]
+fn test_scripts_dir_follows_config() {
+    let _guard = lock_env();
+    let temp = TempDir::new("dir");
+    let config = temp.path.join("hyprland.conf");
+    fs::write(&config, "").unwrap();
+    std::env::set_var("HYPRKCS_CONFIG", &config);
+
+    assert_eq!(
+        scripts_dir().unwrap(),
+        temp.path.join("scripts").join("hyprkcs")
+    );
+}
+
+#[test]
+fn test_script_body() {
+    assert_eq!(
+        script_body("exec", "kitty --single-instance"),
+        "kitty --single-instance"
+    );
+    assert_eq!(
+        script_body(
+            "exec",
+            "[float] bash -c 'grim - | wl-copy; notify-send Copied'"
+        ),
+        "grim - | wl-copy; notify-send Copied"
+    );
+    assert_eq!(
+        script_body(
+            "exec",
+            "hyprctl --batch 'dispatch workspace 2 ; keyword general:gaps_in 0'"
+        ),
+        "hyprctl dispatch workspace 2\nhyprctl keyword general:gaps_in 0"
+    );
+}
+
+#[test]
+fn test_extract_to_script() {
+    let temp = TempDir::new("extract");
+    let dir = temp.path.join("scripts");
+
+    let (path, new_args) = extract_to_script(
+        &dir,
+        "My Screenshot!",
+        "exec",
+        "[float] grim -g \"$(slurp)\"",
+    )
+    .unwrap();
+    assert_eq!(path, dir.join("my-screenshot.sh"));
+    assert_eq!(new_args, format!("[float] {}", script_call(&path)));
+    assert_eq!(
+        referenced_scripts(&dir, &new_args),
+        std::slice::from_ref(&path)
+    );
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.starts_with("#!/usr/bin/env bash\n"));
+    assert!(content.ends_with("grim -g \"$(slurp)\"\n"));
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_ne!(mode & 0o111, 0);
+
+    // Existing scripts are never overwritten
+    let (second, _) = extract_to_script(&dir, "my-screenshot", "exec", "true").unwrap();
+    assert_eq!(second, dir.join("my-screenshot-2.sh"));
+}
+
+#[test]
+fn test_script_call_is_quoted() {
+    let home = dirs::home_dir().unwrap();
+    let dir = home.join("my scripts");
+    let path = dir.join("it's.sh");
+    let call = script_call(&path);
+    assert_eq!(call, "\"$HOME\"/'my scripts/it'\\''s.sh'");
+    assert_eq!(referenced_scripts(&dir, &call), [path]);
+
+    assert_eq!(
+        script_call(&PathBuf::from("/opt/hypr scripts/a.sh")),
+        "'/opt/hypr scripts/a.sh'"
+    );
+    assert_eq!(
+        script_call(&home.join(".config/hypr/scripts/a.sh")),
+        "\"$HOME\"/.config/hypr/scripts/a.sh"
+    );
+}
+
+#[test]
+fn test_orphaned_and_missing_scripts() {
+    let temp = TempDir::new("usage");
+    let dir = temp.path.clone();
+    create_script(&dir.join("used.sh"), "echo used").unwrap();
+    create_script(&dir.join("orphan.sh"), "echo orphan").unwrap();
+
+    let used = dir.join("used.sh");
+    let missing = dir.join("gone.sh");
+    let keybinds = vec![
+        create_kb("exec", &used.display().to_string()),
+        create_kb("exec", &format!("bash -c '{} --flag'", missing.display())),
+        create_kb("exec", "kitty"),
+        // Only exec binds run scripts
+        create_kb("workspace", &missing.display().to_string()),
+    ];
+
+    let usage = script_usage(&dir, &keybinds);
+    assert_eq!(usage.len(), 2);
+    let orphan = usage
+        .iter()
+        .find(|u| u.path.ends_with("orphan.sh"))
+        .unwrap();
+    assert!(orphan.is_orphaned());
+    let used_entry = usage.iter().find(|u| u.path.ends_with("used.sh")).unwrap();
+    assert_eq!(used_entry.used_by, vec![0]);
+
+    assert_eq!(missing_scripts(&dir, &keybinds), vec![(1, missing.clone())]);
+}
+
+#[test]
+fn test_script_problem() {
+    let temp = TempDir::new("problem");
+    let dir = temp.path.clone();
+    let script = dir.join("ok.sh");
+    create_script(&script, "true").unwrap();
+
+    assert_eq!(script_problem(&dir, &script.display().to_string()), None);
+    assert_eq!(
+        script_problem(&dir, &dir.join("nope.sh").display().to_string()),
+        Some("Script not found: nope.sh".to_string())
+    );
+
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+    assert_eq!(
+        script_problem(&dir, &format!("[float] {}", script.display())),
+        Some("Script is not executable: ok.sh".to_string())
+    );
+
+    // Commands outside the library are left to the PATH check
+    assert_eq!(script_problem(&dir, "/usr/bin/definitely-missing"), None);
+}
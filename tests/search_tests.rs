@@ -1,61 +1,310 @@
-use hyprKCS::ui::utils::search::SearchQuery;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use hyprKCS::parser::Keybind;
+use hyprKCS::ui::utils::search::{
+    filter_keybinds, Field, KeybindSearch, Pattern, SearchQuery, Term,
+};
+use std::path::PathBuf;
+
+/// Terms as `field=value` (`any=` for bare words, `/.../` for regexes).
+fn terms(query: &str) -> Vec<String> {
+    SearchQuery::parse(query)
+        .terms()
+        .iter()
+        .map(|t| {
+            let pattern = |p: &Pattern| match p {
+                Pattern::Text(s) => s.clone(),
+                Pattern::Regex(r) => format!("/{}/", r.pattern()),
+            };
+            match t {
+                Term::Any { pattern: p, .. } => format!("any={}", pattern(p)),
+                Term::Field { field, pattern: p } => format!("{:?}={}", field, pattern(p)),
+                Term::State { field, value } => format!("{:?}={}", field, value),
+            }
+        })
+        .collect()
+}
+
+fn bind(mods: &str, key: &str, dispatcher: &str, args: &str) -> Keybind {
+    Keybind {
+        mods: mods.into(),
+        clean_mods: mods.into(),
+        flags: "".into(),
+        key: key.into(),
+        dispatcher: dispatcher.into(),
+        args: args.into(),
+        description: None,
+        submap: None,
+        line_number: 1,
+        file_path: PathBuf::from("/home/user/.config/hypr/hyprland.conf"),
+    }
+}
+
+fn matches(query: &str, kb: &Keybind) -> bool {
+    let target = KeybindSearch {
+        keybind: kb,
+        conflicted: false,
+        broken: false,
+        favorite: false,
+    };
+    SearchQuery::parse(query).matches(&target, &SkimMatcherV2::default())
+}
 
 #[test]
 fn test_parse_simple() {
-    let q = SearchQuery::parse("mod:SUPER key:Q exec");
-    assert_eq!(q.mods.as_deref(), Some("super"));
-    assert_eq!(q.key.as_deref(), Some("q"));
-    assert_eq!(q.general_query, "exec");
+    assert_eq!(
+        terms("mod:SUPER key:Q exec"),
+        vec!["Mods=super", "Key=q", "any=exec"]
+    );
+    assert!(matches(
+        "mod:SUPER key:Q exec",
+        &bind("SUPER", "Q", "exec", "kitty")
+    ));
+    assert!(!matches(
+        "mod:SUPER key:Q exec",
+        &bind("SUPER", "W", "exec", "kitty")
+    ));
 }
 
 #[test]
 fn test_parse_aliases() {
-    let q = SearchQuery::parse("mods:SHIFT action:kill disp:ignore arg:something");
-    assert_eq!(q.mods.as_deref(), Some("shift"));
-    assert_eq!(q.action.as_deref(), Some("ignore"));
-    assert_eq!(q.args.as_deref(), Some("something"));
+    assert_eq!(
+        terms("mods:SHIFT action:kill disp:ignore arg:something"),
+        vec![
+            "Mods=shift",
+            "Action=kill",
+            "Action=ignore",
+            "Args=something"
+        ]
+    );
 }
 
 #[test]
 fn test_parse_general_only() {
-    let q = SearchQuery::parse("JUST SEARCHING STUFF");
-    assert_eq!(q.mods, None);
-    assert_eq!(q.general_query, "just searching stuff");
+    assert_eq!(
+        terms("JUST SEARCHING STUFF"),
+        vec!["any=just", "any=searching", "any=stuff"]
+    );
 }
 
 #[test]
 fn test_parse_mixed() {
-    let q = SearchQuery::parse("Firefox mod:SUPER --private");
-    assert_eq!(q.mods.as_deref(), Some("super"));
-    assert_eq!(q.general_query, "firefox --private");
+    // `--private` is an argument, not a negation
+    assert_eq!(
+        terms("Firefox mod:SUPER --private"),
+        vec!["any=firefox", "Mods=super", "any=--private"]
+    );
+    assert!(matches(
+        "Firefox mod:SUPER --private",
+        &bind("SUPER", "P", "exec", "firefox --private-window")
+    ));
 }
 
 #[test]
 fn test_parse_empty() {
-    let q = SearchQuery::parse("");
-    assert!(q.mods.is_none());
-    assert!(q.key.is_none());
-    assert!(q.general_query.is_empty());
+    let q = SearchQuery::parse("   ");
+    assert!(q.is_empty());
+    assert!(q.errors.is_empty());
+    assert!(matches("", &bind("SUPER", "Q", "killactive", "")));
 }
 
 #[test]
 fn test_parse_multiple_same_tag() {
-    // Last one wins currently
-    let q = SearchQuery::parse("mod:SUPER mod:SHIFT");
-    assert_eq!(q.mods.as_deref(), Some("shift"));
+    // Every term has to match
+    assert_eq!(
+        terms("mod:SUPER mod:SHIFT"),
+        vec!["Mods=super", "Mods=shift"]
+    );
+    assert!(matches(
+        "mod:SUPER mod:SHIFT",
+        &bind("SUPER SHIFT", "Q", "exit", "")
+    ));
+    assert!(!matches(
+        "mod:SUPER mod:SHIFT",
+        &bind("SUPER", "Q", "exit", "")
+    ));
 }
 
 #[test]
 fn test_parse_empty_value_tag() {
-    let q = SearchQuery::parse("mod: key:Q");
-    assert_eq!(q.mods, None); // "mod:" should be treated as general if empty
-    assert_eq!(q.key.as_deref(), Some("q"));
-    assert_eq!(q.general_query, "mod:");
+    // "mod:" without a value is searched as text
+    assert_eq!(terms("mod: key:Q"), vec!["any=mod:", "Key=q"]);
 }
 
 #[test]
 fn test_parse_colon_in_general_query() {
-    let q = SearchQuery::parse("unknown:tag mod:SUPER");
-    assert_eq!(q.mods.as_deref(), Some("super"));
-    assert_eq!(q.general_query, "unknown:tag");
+    assert_eq!(
+        terms("unknown:tag mod:SUPER"),
+        vec!["any=unknown:tag", "Mods=super"]
+    );
+}
+
+#[test]
+fn test_parse_file_tag() {
+    assert_eq!(
+        terms("file:~/.config/hypr/Binds.conf exec"),
+        vec!["File=~/.config/hypr/binds.conf", "any=exec"]
+    );
+    assert_eq!(
+        terms("file:\"/home/me/My Binds.conf\""),
+        vec!["File=/home/me/my binds.conf"]
+    );
+}
+
+#[test]
+fn test_quoted_phrase() {
+    let mut kb = bind("SUPER", "H", "movewindow", "l");
+    kb.description = Some("Move window left".into());
+    assert_eq!(
+        terms("desc:\"Move Window\""),
+        vec!["Description=move window"]
+    );
+    assert!(matches("desc:\"move window\"", &kb));
+    assert!(!matches("desc:\"window move\"", &kb));
+    assert!(matches("\"window left\"", &kb));
+}
+
+#[test]
+fn test_negation() {
+    let exec = bind("SUPER", "T", "exec", "kitty");
+    let kill = bind("SUPER", "Q", "killactive", "");
+    assert!(!matches("-action:exec", &exec));
+    assert!(matches("-action:exec", &kill));
+    assert!(matches("mod:super -kitty", &kill));
+    assert!(!matches("mod:super -kitty", &exec));
+
+    // Excluding a word drops substring matches only, not fuzzy ones
+    let close = bind("SUPER", "C", "closewindow", "");
+    assert!(matches("clwin", &close));
+    assert!(matches("-clwin", &close));
+    assert!(matches("-(super clwin)", &close));
+    assert!(!matches("-closewin", &close));
+    // Negated twice it includes again, fuzzy matches too
+    assert!(matches("-(-clwin)", &close));
+
+    // Negative numbers stay plain words
+    assert_eq!(terms("-10"), vec!["any=-10"]);
+    assert!(matches(
+        "-10",
+        &bind("SUPER", "left", "resizeactive", "-10 0")
+    ));
+}
+
+#[test]
+fn test_or_and_groups() {
+    let q = bind("SUPER", "Q", "killactive", "");
+    let w = bind("SUPER", "W", "togglefloating", "");
+    let e = bind("SUPER", "E", "exec", "nautilus");
+    assert!(matches("key:q OR key:w", &q));
+    assert!(matches("key:q OR key:w", &w));
+    assert!(!matches("key:q OR key:w", &e));
+
+    // AND binds tighter than OR
+    assert!(matches("action:exec key:x OR key:w", &w));
+    assert!(!matches("action:exec key:x OR key:w", &e));
+
+    assert!(matches("mod:super (key:q OR key:e) -action:exec", &q));
+    assert!(!matches("mod:super (key:q OR key:e) -action:exec", &e));
+    assert!(!matches("-(key:q OR key:w)", &w));
+    assert!(matches("-(key:q OR key:w)", &e));
+}
+
+#[test]
+fn test_regex_values() {
+    let ws = bind("SUPER", "3", "workspace", "3");
+    let named = bind("SUPER", "S", "workspace", "special:magic");
+    assert_eq!(terms(r"args:/^\d+$/"), vec![r"Args=/^\d+$/"]);
+    assert!(matches(r"args:/^\d+$/", &ws));
+    assert!(!matches(r"args:/^\d+$/", &named));
+    // Case-insensitive, and usable without a field
+    assert!(matches("/^SPECIAL:/", &named));
+
+    // A slash-delimited path is not a regex
+    assert_eq!(terms("/usr/bin/kitty"), vec!["any=/usr/bin/kitty"]);
+
+    let q = SearchQuery::parse("args:/[unclosed/");
+    assert_eq!(q.errors.len(), 1);
+    assert!(q.is_empty());
+}
+
+#[test]
+fn test_flag_and_submap() {
+    let mut kb = bind("", "XF86AudioRaiseVolume", "exec", "wpctl set-volume @ 5%+");
+    kb.flags = "el".into();
+    kb.submap = Some("Media".into());
+    assert!(matches("flag:e", &kb));
+    assert!(matches("flag:binde", &kb));
+    assert!(!matches("flag:r", &kb));
+    assert!(matches("submap:media", &kb));
+    assert!(!matches("-submap:/./", &kb));
+    assert!(matches(
+        "-submap:/./",
+        &bind("SUPER", "Q", "killactive", "")
+    ));
+}
+
+#[test]
+fn test_state_fields() {
+    let kb = bind("SUPER", "Q", "killactive", "");
+    let target = KeybindSearch {
+        keybind: &kb,
+        conflicted: true,
+        broken: false,
+        favorite: true,
+    };
+    let matcher = SkimMatcherV2::default();
+    let check = |q: &str| SearchQuery::parse(q).matches(&target, &matcher);
+    assert!(check("conflict:yes"));
+    assert!(check("fav:yes broken:no"));
+    assert!(!check("broken:yes"));
+    assert!(!check("-fav:yes"));
+
+    let q = SearchQuery::parse("conflict:maybe");
+    assert_eq!(q.errors.len(), 1);
+    assert!(SearchQuery::parse("conflict:yes").uses(Field::Conflict));
+}
+
+#[test]
+fn test_filter_keybinds() {
+    let mut other = bind("SUPER", "Q", "killactive", "");
+    other.file_path = PathBuf::from("/home/user/.config/hypr/windows.conf");
+    let binds = vec![
+        bind("SUPER", "T", "exec", "kitty"),
+        bind("SUPER", "T", "exec", "foot"),
+        other,
+    ];
+
+    let found = filter_keybinds(binds.clone(), &SearchQuery::parse("file:windows"));
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].key.as_ref(), "Q");
+
+    let found = filter_keybinds(binds, &SearchQuery::parse("conflict:yes"));
+    assert_eq!(found.len(), 2);
+}
+
+#[test]
+fn test_saved_searches_parse() {
+    use hyprKCS::config::searches::{parse_saved_searches, upsert_saved_search, SavedSearch};
+
+    let mut searches = parse_saved_searches(
+        r#"{"searches": [
+            {"name": "Screenshots", "query": "args:/grim|slurp/", "pinned": true},
+            {"name": "Unpinned", "query": "mod:alt"},
+            {"name": "", "query": "ignored"}
+        ]}"#,
+    );
+    assert_eq!(searches.len(), 2);
+    assert!(searches[0].pinned);
+    assert!(!searches[1].pinned);
+
+    upsert_saved_search(
+        &mut searches,
+        SavedSearch {
+            name: "Unpinned".to_string(),
+            query: "mod:ctrl".to_string(),
+            pinned: true,
+        },
+    );
+    assert_eq!(searches.len(), 2);
+    assert_eq!(searches[1].query, "mod:ctrl");
+    assert!(parse_saved_searches("not json").is_empty());
 }
@@ -0,0 +1,75 @@
+use hyprKCS::parser::{IncludeKind, SourceEdge, SourceGraph};
+use std::path::PathBuf;
+
+fn edge(from: &str, line: usize, pattern: &str, targets: &[&str]) -> SourceEdge {
+    SourceEdge {
+        from: PathBuf::from(from),
+        line,
+        pattern: pattern.to_string(),
+        remapped_from: None,
+        targets: targets.iter().map(PathBuf::from).collect(),
+    }
+}
+
+fn graph() -> SourceGraph {
+    SourceGraph {
+        root: PathBuf::from("/hypr/hyprland.conf"),
+        edges: vec![
+            edge("/hypr/hyprland.conf", 1, "/hypr/a.conf", &["/hypr/a.conf"]),
+            edge(
+                "/hypr/hyprland.conf",
+                2,
+                "/hypr/conf.d/*.conf",
+                &["/hypr/conf.d/b.conf", "/hypr/conf.d/c.conf"],
+            ),
+            edge("/hypr/hyprland.conf", 3, "/hypr/gone.conf", &[]),
+            edge(
+                "/hypr/a.conf",
+                4,
+                "/hypr/hyprland.conf",
+                &["/hypr/hyprland.conf"],
+            ),
+            edge("/hypr/conf.d/c.conf", 1, "/hypr/a.conf", &["/hypr/a.conf"]),
+        ],
+    }
+}
+
+#[test]
+fn test_tree_marks_cycles_duplicates_and_missing() {
+    let tree = graph().tree();
+    let rows: Vec<(IncludeKind, &str, usize, usize)> = tree
+        .iter()
+        .map(|n| (n.kind, n.path.to_str().unwrap(), n.depth, n.line))
+        .collect();
+
+    assert_eq!(
+        rows,
+        vec![
+            (IncludeKind::File, "/hypr/hyprland.conf", 0, 0),
+            (IncludeKind::File, "/hypr/a.conf", 1, 1),
+            (IncludeKind::Cycle, "/hypr/hyprland.conf", 2, 4),
+            (IncludeKind::Glob, "/hypr/conf.d/*.conf", 1, 2),
+            (IncludeKind::File, "/hypr/conf.d/b.conf", 2, 0),
+            (IncludeKind::File, "/hypr/conf.d/c.conf", 2, 0),
+            (IncludeKind::Duplicate, "/hypr/a.conf", 3, 1),
+            (IncludeKind::Missing, "/hypr/gone.conf", 1, 3),
+        ]
+    );
+    assert_eq!(tree[3].matched, 2);
+}
+
+#[test]
+fn test_load_order_follows_source_lines() {
+    let order = graph().load_order();
+    let at = |path: &str, line: usize| order.position(&PathBuf::from(path), line).unwrap();
+
+    // a.conf is read at line 1 of the root, before the root's line 1
+    assert!(at("/hypr/hyprland.conf", 0) < at("/hypr/a.conf", 0));
+    assert!(at("/hypr/a.conf", 50) < at("/hypr/hyprland.conf", 1));
+    // Glob matches in order, and c.conf sourcing a.conf again changes nothing
+    assert!(at("/hypr/conf.d/b.conf", 50) < at("/hypr/conf.d/c.conf", 0));
+    assert!(at("/hypr/a.conf", 0) < at("/hypr/conf.d/c.conf", 0));
+    assert!(order
+        .position(&PathBuf::from("/hypr/gone.conf"), 0)
+        .is_none());
+}
@@ -0,0 +1,81 @@
+use hyprKCS::parser::Keybind;
+use hyprKCS::ui::utils::submap_graph::build_submap_graph;
+use std::path::PathBuf;
+
+fn kb(key: &str, dispatcher: &str, args: &str, submap: Option<&str>) -> Keybind {
+    Keybind {
+        mods: "SUPER".into(),
+        clean_mods: "SUPER".into(),
+        flags: "".into(),
+        key: key.into(),
+        dispatcher: dispatcher.into(),
+        args: args.into(),
+        description: None,
+        submap: submap.map(Into::into),
+        line_number: 0,
+        file_path: PathBuf::from("/tmp/hyprland.conf"),
+    }
+}
+
+fn submaps(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn test_graph_edges_and_depth() {
+    let binds = vec![
+        kb("R", "submap", "resize", None),
+        kb("L", "resizeactive", "10 0", Some("resize")),
+        kb("escape", "submap", "reset", Some("resize")),
+        kb("M", "submap", "move", Some("resize")),
+        kb(
+            "escape",
+            "exec",
+            "hyprctl dispatch submap reset",
+            Some("move"),
+        ),
+        kb("X", "submap", "move", Some("move")),
+    ];
+    let graph = build_submap_graph(&binds, &submaps(&["move", "resize"]), None);
+
+    let titles: Vec<&str> = graph.nodes.iter().map(|n| n.title()).collect();
+    assert_eq!(titles, ["Default", "resize", "move"]);
+    assert_eq!(graph.node(Some("resize")).unwrap().depth, Some(1));
+    assert_eq!(graph.node(Some("move")).unwrap().depth, Some(2));
+
+    // Switching to the submap you are in is not an edge
+    assert_eq!(graph.edges.len(), 4);
+    let reset = graph.edges.iter().find(|e| e.bind == 4).unwrap();
+    assert!(reset.is_reset);
+    assert_eq!((reset.from, reset.to), (2, 0));
+    let entry = graph.edges.iter().find(|e| e.bind == 0).unwrap();
+    assert_eq!((entry.from, entry.to), (0, 1));
+    assert!(!entry.is_reset);
+
+    assert!(graph.nodes.iter().all(|n| n.is_reachable() && !n.dead_end));
+}
+
+#[test]
+fn test_graph_dead_ends_and_unreachable() {
+    let binds = vec![
+        kb("T", "submap", "trap", None),
+        kb("H", "movefocus", "l", Some("trap")),
+        kb("O", "movefocus", "r", Some("orphan")),
+        kb("escape", "submap", "reset", Some("orphan")),
+        kb("G", "submap", "global", None),
+    ];
+    let graph = build_submap_graph(&binds, &submaps(&["orphan", "trap"]), None);
+
+    let trap = graph.node(Some("trap")).unwrap();
+    assert!(trap.is_reachable() && trap.dead_end);
+    let orphan = graph.node(Some("orphan")).unwrap();
+    assert!(!orphan.is_reachable() && !orphan.dead_end);
+    // Switched to, but not defined anywhere
+    assert!(graph.node(Some("global")).unwrap().dead_end);
+    // Unreachable submaps come last
+    assert_eq!(graph.nodes.last().unwrap().title(), "orphan");
+
+    // The default submap from the settings is a starting point
+    let graph = build_submap_graph(&binds, &submaps(&["orphan", "trap"]), Some("orphan"));
+    assert_eq!(graph.node(Some("orphan")).unwrap().depth, Some(0));
+}
@@ -162,3 +162,242 @@ fn test_submap_reset_logic() {
     assert_eq!(binds.len(), 1);
     assert_eq!(binds[0].submap.as_deref(), Some("open_ended"));
 }
+
+fn lines(content: &str) -> Vec<String> {
+    content.lines().map(|l| l.to_string()).collect()
+}
+
+#[test]
+fn test_rename_submap_lines() {
+    let mut config = lines(
+        "bind = SUPER, R, submap, resize # enter\n\
+         bindd = SUPER, M, Move mode, submap, resize\n\
+         bind = SUPER, X, exec, notify-send resize\n\
+         submap = resize # resizing\n\
+         binde = , right, resizeactive, 10 0\n\
+         bind = , escape, submap, reset\n\
+         submap = reset\n\
+         submap = resizer\n\
+         submap = reset",
+    );
+    assert_eq!(
+        submaps::rename_submap_lines(&mut config, "resize", "size"),
+        3
+    );
+    assert_eq!(config[0], "bind = SUPER, R, submap, size # enter");
+    assert_eq!(config[1], "bindd = SUPER, M, Move mode, submap, size");
+    assert_eq!(config[2], "bind = SUPER, X, exec, notify-send resize");
+    assert_eq!(config[3], "submap = size # resizing");
+    assert_eq!(config[7], "submap = resizer");
+}
+
+#[test]
+fn test_submap_dispatch_fields() {
+    assert_eq!(
+        submaps::submap_dispatch("bindld = SUPER, R, Resize mode, submap, resize"),
+        Some("resize")
+    );
+    assert_eq!(
+        submaps::submap_dispatch("bindl = SUPER, R, submap, resize # enter"),
+        Some("resize")
+    );
+    assert_eq!(submaps::submap_dispatch("bind = SUPER, R, submap"), None);
+}
+
+#[test]
+fn test_rename_and_delete_follow_exec_switches() {
+    let config_text =
+        "bind = SUPER, R, exec, hyprctl dispatch submap resize && hyprkcs --which-key resize\n\
+         bind = SUPER, P, exec, hyprctl dispatch submap resizer\n\
+         bind = SUPER, W, exec, hyprkcs --which-key\n\
+         submap = resize\n\
+         bind = , escape, exec, hyprctl dispatch submap reset\n\
+         submap = reset";
+
+    let mut config = lines(config_text);
+    assert_eq!(
+        submaps::rename_submap_lines(&mut config, "resize", "size"),
+        2
+    );
+    assert_eq!(
+        config[0],
+        "bind = SUPER, R, exec, hyprctl dispatch submap size && hyprkcs --which-key size"
+    );
+    assert_eq!(
+        config[1],
+        "bind = SUPER, P, exec, hyprctl dispatch submap resizer"
+    );
+    assert_eq!(config[2], "bind = SUPER, W, exec, hyprkcs --which-key");
+
+    let mut config = lines(config_text);
+    assert_eq!(submaps::delete_submap_lines(&mut config, "resize"), 2);
+    assert_eq!(
+        config,
+        [
+            "bind = SUPER, P, exec, hyprctl dispatch submap resizer",
+            "bind = SUPER, W, exec, hyprkcs --which-key"
+        ]
+    );
+}
+
+#[test]
+fn test_delete_submap_lines() {
+    let mut config = lines(
+        "bind = SUPER, Q, exec, kitty\n\
+         bind = SUPER, R, submap, resize\n\
+         \n\
+         submap = resize\n\
+         binde = , right, resizeactive, 10 0\n\
+         bind = , escape, submap, reset\n\
+         submap = reset\n\
+         bind = SUPER, E, exec, dolphin",
+    );
+    assert_eq!(submaps::delete_submap_lines(&mut config, "resize"), 3);
+    assert_eq!(
+        config,
+        [
+            "bind = SUPER, Q, exec, kitty",
+            "bind = SUPER, E, exec, dolphin"
+        ]
+    );
+}
+
+#[test]
+fn test_move_lines_to_submap() {
+    let mut config = lines(
+        "# Terminal\n\
+         bind = SUPER, T, exec, kitty\n\
+         bind = SUPER, Q, killactive\n\
+         submap = resize\n\
+         bind = , escape, submap, reset\n\
+         submap = reset",
+    );
+    // Into an existing block, with the description above it
+    let moved = submaps::move_lines_to_submap(&mut config, &[1], Some("resize"));
+    assert_eq!(moved, [4]);
+    assert_eq!(
+        config,
+        [
+            "bind = SUPER, Q, killactive",
+            "submap = resize",
+            "bind = , escape, submap, reset",
+            "# Terminal",
+            "bind = SUPER, T, exec, kitty",
+            "submap = reset",
+        ]
+    );
+
+    // Into a new block
+    let moved = submaps::move_lines_to_submap(&mut config, &[0], Some("apps"));
+    assert_eq!(moved, [7]);
+    assert_eq!(
+        config[5..],
+        [
+            "",
+            "submap = apps",
+            "bind = SUPER, Q, killactive",
+            "submap = reset"
+        ]
+    );
+
+    // Back out of any submap
+    let moved = submaps::move_lines_to_submap(&mut config, &[3], None);
+    assert_eq!(moved, [8]);
+    assert_eq!(config[7..], ["# Terminal", "bind = SUPER, T, exec, kitty"]);
+    assert!(parse_lines_submaps(&config)[8].is_none());
+}
+
+/// The submap each line is in, following `submap =` lines.
+fn parse_lines_submaps(config: &[String]) -> Vec<Option<String>> {
+    let mut current = None;
+    config
+        .iter()
+        .map(|line| {
+            if let Some(name) = submaps::submap_declaration(line) {
+                current = (name != "reset").then(|| name.to_string());
+            }
+            current.clone()
+        })
+        .collect()
+}
+
+fn bind(key: &str, dispatcher: &str, args: &str, submap: Option<&str>) -> Keybind {
+    Keybind {
+        mods: "SUPER".into(),
+        clean_mods: "SUPER".into(),
+        flags: "".into(),
+        key: key.into(),
+        dispatcher: dispatcher.into(),
+        args: args.into(),
+        description: None,
+        submap: submap.map(Into::into),
+        line_number: 0,
+        file_path: PathBuf::new(),
+    }
+}
+
+#[test]
+fn test_submap_health() {
+    let keybinds = vec![
+        bind("R", "submap", "resize", None),
+        bind("right", "resizeactive", "10 0", Some("resize")),
+        bind("escape", "submap", "reset", Some("resize")),
+        bind("G", "submap", "gaps", None),
+        bind(
+            "up",
+            "exec",
+            "hyprctl keyword general:gaps_in 10",
+            Some("gaps"),
+        ),
+        bind("L", "submap", "launch", None),
+        bind(
+            "F",
+            "exec",
+            "firefox; hyprctl dispatch submap reset",
+            Some("launch"),
+        ),
+        bind("H", "movefocus", "l", Some("orphan")),
+        bind("P", "submap", "planned", Some("resize")),
+    ];
+    let health = submaps::submap_health(&keybinds, None);
+    let find = |name: &str| health.iter().find(|h| h.name == name).unwrap();
+
+    assert!(find("resize").problems().is_empty());
+    assert_eq!(find("resize").bind_count, 3);
+    assert!(find("gaps").traps());
+    assert!(find("launch").problems().is_empty());
+    assert!(!find("orphan").has_entry);
+    assert!(!find("orphan").traps());
+    assert!(find("planned").is_undefined());
+    assert_eq!(find("planned").problems(), ["No binds are defined in it"]);
+
+    // The default submap is where the user starts
+    let health = submaps::submap_health(&keybinds, Some("orphan"));
+    assert!(health
+        .iter()
+        .find(|h| h.name == "orphan")
+        .unwrap()
+        .problems()
+        .is_empty());
+}
+
+#[test]
+fn test_rename_submap_in_file() {
+    let _guard = lock_env();
+    let temp = TempFile::new(
+        "bind = SUPER, R, submap, resize\n\
+         submap = resize\n\
+         bind = , escape, submap, reset\n\
+         submap = reset\n",
+    );
+    std::env::set_var("HYPRKCS_CONFIG", &temp.path);
+
+    assert!(submaps::rename_submap("resize", "reset").is_err());
+    assert!(submaps::rename_submap("resize", "a,b").is_err());
+    assert_eq!(submaps::rename_submap("resize", "size").unwrap(), 2);
+
+    invalidate_parser_cache();
+    let binds = parse_config().unwrap();
+    assert_eq!(&*binds[0].args, "size");
+    assert_eq!(binds[1].submap.as_deref(), Some("size"));
+}
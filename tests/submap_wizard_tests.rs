@@ -154,3 +154,21 @@ fn test_wizard_standard_behavior() {
     assert_eq!(exit_bind.submap.as_deref(), Some("resize"));
     assert_eq!(exit_bind.args.as_ref(), "reset");
 }
+
+#[test]
+fn test_wizard_wires_which_key() {
+    let _guard = lock_env();
+    let temp = TempFile::new("bind = SUPER, Q, exec, kitty\nexec-once = waybar\n");
+    std::env::set_var("HYPRKCS_CONFIG", &temp.path);
+    invalidate_parser_cache();
+
+    assert!(ensure_exec_once("hyprkcs --which-key").unwrap());
+    let content = std::fs::read_to_string(&temp.path).unwrap();
+    assert!(content.ends_with("exec-once = hyprkcs --which-key\n"));
+
+    // Not added twice, also when started through a full path
+    assert!(!ensure_exec_once("hyprkcs --which-key").unwrap());
+    std::fs::write(&temp.path, "exec-once=/usr/bin/hyprkcs --which-key\n").unwrap();
+    invalidate_parser_cache();
+    assert!(!ensure_exec_once("hyprkcs --which-key").unwrap());
+}
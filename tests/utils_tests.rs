@@ -77,10 +77,8 @@ fn test_parse_macro_simple() {
     let result = parse_macro(dispatcher, args).expect("Failed to parse valid macro");
 
     assert_eq!(result.len(), 2);
-    assert_eq!(result[0].0, "workspace");
-    assert_eq!(result[0].1, "1");
-    assert_eq!(result[1].0, "fullscreen");
-    assert_eq!(result[1].1, "1");
+    assert_eq!(result[0].as_dispatch(), Some(("workspace", "1")));
+    assert_eq!(result[1].as_dispatch(), Some(("fullscreen", "1")));
 }
 
 #[test]
@@ -98,8 +96,10 @@ fn test_parse_macro_quoted_args() {
     let result = parse_macro(dispatcher, args).expect("Failed to parse quoted macro");
 
     assert_eq!(result.len(), 1);
-    assert_eq!(result[0].0, "notify-send");
-    assert_eq!(result[0].1, "\"Hello World\"");
+    assert_eq!(
+        result[0].as_dispatch(),
+        Some(("notify-send", "Hello World"))
+    );
 }
 
 #[test]
@@ -109,8 +109,6 @@ fn test_parse_macro_multiple_commands_whitespace() {
     let result = parse_macro("exec", args).expect("Failed to parse messy whitespace");
 
     assert_eq!(result.len(), 2);
-    assert_eq!(result[0].0, "workspace");
-    assert_eq!(result[0].1, "2");
-    assert_eq!(result[1].0, "killactive");
-    assert_eq!(result[1].1, "");
+    assert_eq!(result[0].as_dispatch(), Some(("workspace", "2")));
+    assert_eq!(result[1].as_dispatch(), Some(("killactive", "")));
 }
@@ -427,3 +427,67 @@ fn test_variable_naming_conventions() {
     let binds = parse_config().expect("Parse failed");
     assert_eq!(binds[0].args.as_ref(), "echo underscore");
 }
+
+#[test]
+fn test_variable_names_in_line() {
+    use hyprKCS::parser::variables::variable_names_in_line;
+
+    assert_eq!(
+        variable_names_in_line("bind = $mainMod SHIFT, Q, exec, $term # uses $mod"),
+        ["$mainMod", "$term"]
+    );
+    // Only the value of a definition refers to variables
+    assert_eq!(
+        variable_names_in_line("$browser = $prefix firefox"),
+        ["$prefix"]
+    );
+    // Shell syntax is not a variable
+    assert!(
+        variable_names_in_line("bind = , Print, exec, grim -g \"$(slurp)\" ${DIR}/$1").is_empty()
+    );
+}
+
+#[test]
+fn test_variable_report() {
+    let _guard = lock_env();
+    let content = "$mod = SUPER\n\
+                   $term = kitty\n\
+                   $unused = foo\n\
+                   bind = $mod, T, exec, $term\n\
+                   bind = $modShift, Q, killactive\n\
+                   bind = $mod, E, exec, $files\n\
+                   bind = $mod, H, exec, ls $HOME\n\
+                   $term = foot\n";
+    let temp = TempFile::new(content);
+    std::env::set_var("HYPRKCS_CONFIG", &temp.path);
+
+    let defined = get_defined_variables().unwrap();
+    let references = variables::collect_variable_references().unwrap();
+    let report = variables::analyze_variables(&defined, references, |name| name == "HOME");
+
+    let unused: Vec<&str> = report.unused.iter().map(|v| &*v.name).collect();
+    assert_eq!(unused, ["$unused"]);
+
+    let undefined: Vec<(&str, usize)> = report
+        .undefined
+        .iter()
+        .map(|r| (r.name.as_str(), r.line_number))
+        .collect();
+    assert_eq!(undefined, [("$files", 5)]);
+
+    assert_eq!(report.shadowed.len(), 1);
+    assert_eq!(report.shadowed[0].shadowed.line_number, 1);
+    assert_eq!(report.shadowed[0].by.line_number, 7);
+    assert!(report.shadowed_by(&defined[1]).is_some());
+    assert!(report.shadowed_by(&defined[3]).is_none());
+
+    // `$modShift` resolves through `$mod`, like Hyprland does
+    let mod_lines: Vec<usize> = report
+        .references_to("$mod")
+        .map(|r| r.line_number)
+        .collect();
+    assert_eq!(mod_lines, [3, 4, 5, 6]);
+    assert!(report
+        .references_to("$term")
+        .all(|r| r.file_path == temp.path && r.line.starts_with("bind")));
+}